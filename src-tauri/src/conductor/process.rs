@@ -36,17 +36,32 @@ impl Drop for TempFileGuard {
 }
 
 /// Delivers CLI events to the Tauri frontend via emit.
-pub struct EventSink(AppHandle);
+pub struct EventSink {
+    app: AppHandle,
+    /// Optional copy of every event for a backend observer (e.g. scheduler run capture).
+    /// The channel closes when the sink is dropped at the end of the session.
+    tap: Option<tokio::sync::mpsc::UnboundedSender<CliEvent>>,
+}
 
 impl EventSink {
     pub fn new(app: AppHandle) -> Self {
-        Self(app)
+        Self { app, tap: None }
+    }
+
+    /// Like `new`, but also forwards every event to `tap`.
+    pub fn with_tap(app: AppHandle, tap: tokio::sync::mpsc::UnboundedSender<CliEvent>) -> Self {
+        Self { app, tap: Some(tap) }
     }
 
     fn emit(&self, event: &CliEvent) {
-        if let Err(e) = self.0.emit("cli-event", event) {
+        if let Err(e) = self.app.emit("cli-event", event) {
             eprintln!("[conductor] Failed to emit event: {e}");
         }
+        if let Some(tap) = &self.tap {
+            if tap.send(event.clone()).is_err() {
+                eprintln!("[conductor] Event tap receiver dropped");
+            }
+        }
     }
 }
 
@@ -121,6 +136,7 @@ pub async fn run_cli_session(
                 allowed_tools: Vec::new(),
                 can_manage: false,
                 start_message: None,
+                extends: None,
             });
         if let Some(mcp) = crate::teamwork::mcp_server::get_state() {
             mcp.register_project_agent(&agent_id, pp, slug, resolved_role)
@@ -465,9 +481,14 @@ pub async fn run_cli_session(
                     }
 
                     // Build combined text: [Сообщение от {from}]: {text}
+                    // (channel messages carry a language-neutral tag naming the
+                    // channel to reply through: [#{channel} ← {from}]: {text})
                     let text: String = msgs
                         .iter()
-                        .map(|m| format!("[Сообщение от {}]: {}", m.from, m.text))
+                        .map(|m| match m.channel {
+                            Some(ref ch) => format!("[#{ch} ← {}]: {}", m.from, m.text),
+                            None => format!("[Сообщение от {}]: {}", m.from, m.text),
+                        })
                         .collect::<Vec<_>>()
                         .join("\n\n");
 
@@ -1,8 +1,8 @@
 use crate::files::validate_path_safe;
-use notify_debouncer_mini::{new_debouncer, DebouncedEventKind};
+use notify_debouncer_mini::{new_debouncer, DebouncedEvent, DebouncedEventKind, Debouncer};
 use serde::Serialize;
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use tokio::sync::Mutex;
 use tauri::{AppHandle, Emitter, Manager};
@@ -21,10 +21,38 @@ pub struct WatcherState {
 }
 
 struct WatcherHandle {
-    _debouncer: notify_debouncer_mini::Debouncer<notify::RecommendedWatcher>,
+    _debouncer: Watcher,
     _watched_paths: Vec<PathBuf>,
 }
 
+/// A debounced file watcher; stops when dropped.
+pub(crate) type Watcher = Debouncer<notify::RecommendedWatcher>;
+
+/// Create a debounced watcher that hands each batch of changes to `on_events`.
+/// Watcher errors are logged with `label` as prefix.
+pub(crate) fn new_watcher(
+    delay: Duration,
+    label: &'static str,
+    on_events: impl Fn(&[DebouncedEvent]) + Send + 'static,
+) -> Result<Watcher, String> {
+    new_debouncer(
+        delay,
+        move |result: Result<Vec<DebouncedEvent>, notify::Error>| match result {
+            Ok(events) => on_events(&events),
+            Err(e) => eprintln!("[{label}] error: {e}"),
+        },
+    )
+    .map_err(|e| format!("Failed to create watcher: {e}"))
+}
+
+/// Watch `dir` and everything below it.
+pub(crate) fn watch_recursive(watcher: &mut Watcher, dir: &Path) -> Result<(), String> {
+    watcher
+        .watcher()
+        .watch(dir, notify::RecursiveMode::Recursive)
+        .map_err(|e| format!("Failed to watch {}: {e}", dir.display()))
+}
+
 impl WatcherState {
     pub fn new() -> Self {
         Self {
@@ -45,40 +73,28 @@ pub async fn watch_directories(
     // Build the watcher on a blocking thread (notify setup may touch fs)
     let handle = tokio::task::spawn_blocking(move || {
         let app_for_cb = app_clone;
-        let mut debouncer = new_debouncer(
-            Duration::from_millis(500),
-            move |result: Result<Vec<notify_debouncer_mini::DebouncedEvent>, notify::Error>| {
-                let events = match result {
-                    Ok(evts) => evts,
-                    Err(e) => {
-                        eprintln!("[watcher] error: {e}");
-                        return;
-                    }
-                };
-
-                // Collect unique parent directories that changed
-                let mut changed = HashSet::new();
-                for evt in &events {
-                    if evt.kind != DebouncedEventKind::Any {
-                        continue;
-                    }
-                    let parent = evt
-                        .path
-                        .parent()
-                        .unwrap_or(&evt.path)
-                        .to_string_lossy()
-                        .into_owned();
-                    changed.insert(parent);
+        let mut debouncer = new_watcher(Duration::from_millis(500), "watcher", move |events| {
+            // Collect unique parent directories that changed
+            let mut changed = HashSet::new();
+            for evt in events {
+                if evt.kind != DebouncedEventKind::Any {
+                    continue;
                 }
+                let parent = evt
+                    .path
+                    .parent()
+                    .unwrap_or(&evt.path)
+                    .to_string_lossy()
+                    .into_owned();
+                changed.insert(parent);
+            }
 
-                for path in changed {
-                    if let Err(e) = app_for_cb.emit("fs-change", FsChangeEvent { path }) {
-                        eprintln!("[file_watcher] Failed to emit fs-change event: {e}");
-                    }
+            for path in changed {
+                if let Err(e) = app_for_cb.emit("fs-change", FsChangeEvent { path }) {
+                    eprintln!("[file_watcher] Failed to emit fs-change event: {e}");
                 }
-            },
-        )
-        .map_err(|e| format!("Failed to create watcher: {e}"))?;
+            }
+        })?;
 
         let mut watched_paths = Vec::new();
         for p in &paths {
@@ -88,10 +104,7 @@ pub async fn watch_directories(
                 continue;
             }
             if path.is_dir() {
-                debouncer
-                    .watcher()
-                    .watch(&path, notify::RecursiveMode::Recursive)
-                    .map_err(|e| format!("Failed to watch {p}: {e}"))?;
+                watch_recursive(&mut debouncer, &path)?;
                 watched_paths.push(path);
             }
         }
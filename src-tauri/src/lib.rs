@@ -17,6 +17,7 @@ mod mcp_transport;
 mod named_mutex_pool;
 mod plugins;
 mod projects;
+mod prompt_template;
 mod rag;
 mod scheduler;
 mod secrets;
@@ -200,6 +201,10 @@ pub fn run() {
             teamwork::roles::roles_delete,
             teamwork::roles::roles_get_default,
             teamwork::roles::roles_set_default,
+            teamwork::role_library::roles_history,
+            teamwork::role_library::roles_rollback,
+            teamwork::role_library::roles_export,
+            teamwork::role_library::roles_import,
             teamwork::presets::presets_list,
             teamwork::presets::presets_save,
             teamwork::presets::presets_delete,
@@ -208,6 +213,16 @@ pub fn run() {
             teamwork::mailbox::team_read_all_messages,
             teamwork::mailbox::team_clear_messages,
             teamwork::mailbox::team_broadcast,
+            teamwork::history::team_search_messages,
+            teamwork::history::team_compact_messages,
+            teamwork::history::team_load_retention,
+            teamwork::history::team_save_retention,
+            teamwork::channels::team_channels_list,
+            teamwork::channels::team_channels_save,
+            teamwork::channels::team_channels_delete,
+            teamwork::approvals::team_pending_approvals,
+            teamwork::approvals::team_answer_approval,
+            teamwork::approvals::team_approval_log,
             teamwork::mcp_server::team_list_agents,
             projects::get_teamwork_slug,
             rag::commands::rag_list_bases,
@@ -223,13 +238,30 @@ pub fn run() {
             rag::commands::rag_add_youtube,
             rag::commands::rag_reindex_base,
             rag::commands::rag_get_available_models,
+            rag::commands::rag_get_available_rerankers,
             rag::commands::rag_load_settings,
             rag::commands::rag_save_settings,
+            rag::commands::rag_migrate_base_model,
+            rag::commands::rag_add_source,
+            rag::commands::rag_remove_source,
+            rag::commands::rag_sync_base,
+            rag::commands::rag_sync_status,
+            rag::commands::rag_set_document_labels,
+            rag::commands::rag_find_duplicates,
+            rag::commands::rag_export_base,
+            rag::commands::rag_import_base,
             scheduler::commands::scheduler_list_tasks,
             scheduler::commands::scheduler_save_task,
             scheduler::commands::scheduler_delete_task,
             scheduler::commands::scheduler_toggle_task,
             scheduler::commands::scheduler_run_now,
+            scheduler::history::scheduler_list_runs,
+            scheduler::preview::scheduler_preview,
+            scheduler::preview::scheduler_validate_schedule,
+            scheduler::settings::scheduler_load_settings,
+            scheduler::settings::scheduler_save_settings,
+            prompt_template::prompt_template_variables,
+            prompt_template::prompt_template_preview,
         ])
         .setup(move |app| {
             // --- System tray ---
@@ -294,6 +326,9 @@ pub fn run() {
             let scheduler_handle = app.handle().clone();
             tauri::async_runtime::spawn(scheduler::runner::start_scheduler(scheduler_handle));
 
+            // Keep knowledge bases in sync with their source folders
+            tauri::async_runtime::spawn(rag::sync::start(app.handle().clone()));
+
             let sessions_for_mcp = sessions;
             tauri::async_runtime::spawn(async move {
                 // Start MCP server for team agent communication
@@ -324,6 +359,7 @@ pub fn run() {
                     }
 
                     attachments::cleanup_old_temp(3600);
+                    teamwork::history::compact_all_teams();
                     if let Err(e) = projects::ensure_projects_file() {
                         eprintln!("[aitherflow] ensure_projects_file failed: {e}");
                     }
@@ -0,0 +1,432 @@
+//! Prompt templates for scheduled task prompts and role start messages.
+//!
+//! Syntax:
+//! - `{{name}}` — insert a variable (see `VARIABLES`);
+//! - `{{#if name}}...{{else}}...{{/if}}` — keep a section only if the variable
+//!   is non-empty (`{{else}}` is optional, blocks nest);
+//! - `\{{` — a literal `{{`.
+//!
+//! Text without `{{` renders unchanged.
+
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::process::Command;
+
+/// Variables a template may use, with a short description for the UI.
+pub const VARIABLES: &[(&str, &str)] = &[
+    ("date", "Today's date, YYYY-MM-DD"),
+    ("time", "Current time, HH:MM"),
+    ("weekday", "Day of the week, e.g. Monday"),
+    ("project", "Project folder name"),
+    ("project_path", "Full project path"),
+    ("branch", "Current git branch"),
+    ("task", "Scheduled task name"),
+    ("last_status", "Status of the task's previous run"),
+    ("last_output", "Final message of the task's previous run"),
+    (
+        "changed_files",
+        "Files changed since the previous run, one per line",
+    ),
+    ("trigger", "What fired the run (event-triggered tasks)"),
+    (
+        "trigger_payload",
+        "Commit list or webhook body of the trigger",
+    ),
+];
+
+/// Longest `last_output` inserted into a prompt.
+const MAX_OUTPUT_CHARS: usize = 16_000;
+
+/// Values for template variables. Empty fields render as empty strings.
+#[derive(Default, Debug, Clone, Serialize)]
+pub struct PromptVars {
+    pub date: String,
+    pub time: String,
+    pub weekday: String,
+    pub project: String,
+    pub project_path: String,
+    pub branch: String,
+    pub task: String,
+    pub last_status: String,
+    pub last_output: String,
+    pub changed_files: Vec<String>,
+    pub trigger: String,
+    pub trigger_payload: String,
+}
+
+impl PromptVars {
+    /// Date, project and branch for a project directory (runs git).
+    pub fn for_project(project_path: &str) -> Self {
+        let now = chrono::Local::now();
+        let project = Path::new(project_path)
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Self {
+            date: now.format("%Y-%m-%d").to_string(),
+            time: now.format("%H:%M").to_string(),
+            weekday: now.format("%A").to_string(),
+            project,
+            project_path: project_path.to_string(),
+            branch: git_output(project_path, &["branch", "--show-current"]).unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the previous run's final message, cut to a sane length.
+    pub fn set_last_output(&mut self, output: &str) {
+        self.last_output = match output.char_indices().nth(MAX_OUTPUT_CHARS) {
+            Some((end, _)) => format!("{}\n[truncated]", &output[..end]),
+            None => output.to_string(),
+        };
+    }
+
+    fn get(&self, name: &str) -> Option<String> {
+        let value = match name {
+            "date" => &self.date,
+            "time" => &self.time,
+            "weekday" => &self.weekday,
+            "project" => &self.project,
+            "project_path" => &self.project_path,
+            "branch" => &self.branch,
+            "task" => &self.task,
+            "last_status" => &self.last_status,
+            "last_output" => &self.last_output,
+            "changed_files" => return Some(self.changed_files.join("\n")),
+            "trigger" => &self.trigger,
+            "trigger_payload" => &self.trigger_payload,
+            _ => return None,
+        };
+        Some(value.clone())
+    }
+}
+
+fn git_output(dir: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Files committed since `since` (RFC 3339) plus uncommitted changes, sorted.
+pub fn changed_files_since(project_path: &str, since: Option<&str>) -> Vec<String> {
+    let mut files = BTreeSet::new();
+    if let Some(since) = since {
+        let since_arg = format!("--since={since}");
+        if let Some(log) = git_output(
+            project_path,
+            &["log", &since_arg, "--name-only", "--pretty=format:"],
+        ) {
+            files.extend(log.lines().filter(|l| !l.is_empty()).map(String::from));
+        }
+    }
+    if let Some(status) = git_output(project_path, &["status", "--porcelain"]) {
+        for line in status.lines().filter(|l| l.len() > 3) {
+            // Renames are shown as "old -> new"
+            let path = &line[3..];
+            let path = path.rsplit(" -> ").next().unwrap_or(path);
+            files.insert(path.trim_matches('"').to_string());
+        }
+    }
+    files.into_iter().collect()
+}
+
+#[derive(Debug, PartialEq)]
+enum Node {
+    Text(String),
+    Var(String),
+    If {
+        name: String,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+}
+
+enum Tag<'a> {
+    Var(&'a str),
+    If(&'a str),
+    Else,
+    EndIf,
+}
+
+fn parse_tag<'a>(tag: &'a str, variables: &[(&str, &str)]) -> Result<Tag<'a>, String> {
+    let tag = tag.trim();
+    let known = |name: &str| {
+        if variables.iter().any(|(v, _)| *v == name) {
+            Ok(())
+        } else {
+            Err(format!("Unknown variable '{name}'"))
+        }
+    };
+    if let Some(name) = tag.strip_prefix("#if") {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("'{{#if}}' needs a variable name".into());
+        }
+        known(name)?;
+        return Ok(Tag::If(name));
+    }
+    match tag {
+        "else" => Ok(Tag::Else),
+        "/if" => Ok(Tag::EndIf),
+        "" => Err("Empty '{{}}' tag".into()),
+        name => {
+            known(name)?;
+            Ok(Tag::Var(name))
+        }
+    }
+}
+
+/// Parse a template over `variables`. `{{` without a matching `}}` is an error,
+/// `\{{` is kept as text.
+fn parse(src: &str, variables: &[(&str, &str)]) -> Result<Vec<Node>, String> {
+    // Stack of open blocks: (if-name, then-branch, else-branch, in-else)
+    let mut stack: Vec<(String, Vec<Node>, Vec<Node>, bool)> = Vec::new();
+    let mut root = Vec::new();
+    let mut rest = src;
+
+    fn current<'a>(
+        root: &'a mut Vec<Node>,
+        stack: &'a mut [(String, Vec<Node>, Vec<Node>, bool)],
+    ) -> &'a mut Vec<Node> {
+        match stack.last_mut() {
+            Some((_, then, _, false)) => then,
+            Some((_, _, otherwise, true)) => otherwise,
+            None => root,
+        }
+    }
+
+    while let Some(start) = rest.find("{{") {
+        if rest[..start].ends_with('\\') {
+            let text = format!("{}{{{{", &rest[..start - 1]);
+            current(&mut root, &mut stack).push(Node::Text(text));
+            rest = &rest[start + 2..];
+            continue;
+        }
+        if start > 0 {
+            current(&mut root, &mut stack).push(Node::Text(rest[..start].to_string()));
+        }
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| "Unclosed '{{' in template (write '\\{{' for literal braces)".to_string())?;
+        match parse_tag(&after[..end], variables)? {
+            Tag::Var(name) => current(&mut root, &mut stack).push(Node::Var(name.to_string())),
+            Tag::If(name) => stack.push((name.to_string(), Vec::new(), Vec::new(), false)),
+            Tag::Else => match stack.last_mut() {
+                Some((_, _, _, in_else @ false)) => *in_else = true,
+                Some(_) => return Err("Duplicate '{{else}}'".into()),
+                None => return Err("'{{else}}' outside of '{{#if}}'".into()),
+            },
+            Tag::EndIf => {
+                let (name, then, otherwise, _) = stack
+                    .pop()
+                    .ok_or_else(|| "'{{/if}}' without '{{#if}}'".to_string())?;
+                current(&mut root, &mut stack).push(Node::If {
+                    name,
+                    then,
+                    otherwise,
+                });
+            }
+        }
+        rest = &after[end + 2..];
+    }
+    if !rest.is_empty() {
+        current(&mut root, &mut stack).push(Node::Text(rest.to_string()));
+    }
+    if let Some((name, ..)) = stack.last() {
+        return Err(format!("'{{{{#if {name}}}}}' is not closed"));
+    }
+    Ok(root)
+}
+
+fn render_nodes(nodes: &[Node], get: &dyn Fn(&str) -> Option<String>, out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Var(name) => out.push_str(&get(name).unwrap_or_default()),
+            Node::If {
+                name,
+                then,
+                otherwise,
+            } => {
+                let set = get(name).is_some_and(|v| !v.trim().is_empty());
+                render_nodes(if set { then } else { otherwise }, get, out);
+            }
+        }
+    }
+}
+
+/// Check template syntax and variable names.
+pub fn validate(src: &str) -> Result<(), String> {
+    validate_with(src, VARIABLES)
+}
+
+/// Render a template with `vars`.
+pub fn render(src: &str, vars: &PromptVars) -> Result<String, String> {
+    render_with(src, VARIABLES, &|name| vars.get(name))
+}
+
+/// `validate` for templates with their own variable set (e.g. notifications).
+pub fn validate_with(src: &str, variables: &[(&str, &str)]) -> Result<(), String> {
+    parse(src, variables).map(|_| ())
+}
+
+/// `render` for templates with their own variable set; `get` returns a
+/// variable's value.
+pub fn render_with(
+    src: &str,
+    variables: &[(&str, &str)],
+    get: &dyn Fn(&str) -> Option<String>,
+) -> Result<String, String> {
+    let nodes = parse(src, variables)?;
+    let mut out = String::with_capacity(src.len());
+    render_nodes(&nodes, get, &mut out);
+    Ok(out)
+}
+
+/// Whether a template refers to any of `names` (used to decide if trigger
+/// details still need to be appended).
+pub fn uses_any(src: &str, names: &[&str]) -> bool {
+    fn walk(nodes: &[Node], names: &[&str]) -> bool {
+        nodes.iter().any(|n| match n {
+            Node::Text(_) => false,
+            Node::Var(name) => names.contains(&name.as_str()),
+            Node::If {
+                name,
+                then,
+                otherwise,
+            } => names.contains(&name.as_str()) || walk(then, names) || walk(otherwise, names),
+        })
+    }
+    parse(src, VARIABLES).is_ok_and(|nodes| walk(&nodes, names))
+}
+
+#[derive(Serialize)]
+pub struct TemplateVariable {
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+/// Variables available in prompt templates.
+#[tauri::command]
+pub fn prompt_template_variables() -> Vec<TemplateVariable> {
+    VARIABLES
+        .iter()
+        .map(|(name, description)| TemplateVariable { name, description })
+        .collect()
+}
+
+/// Render a template as it would be sent now. With `task_id`, task variables
+/// (previous run, changed files) come from that scheduled task.
+#[tauri::command]
+pub async fn prompt_template_preview(
+    template: String,
+    project_path: String,
+    task_id: Option<String>,
+) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        validate(&template)?;
+        crate::files::validate_path_safe(Path::new(&project_path))?;
+        let vars = match task_id {
+            Some(id) => {
+                let task = crate::scheduler::store::load_task(&id)?
+                    .ok_or_else(|| format!("Task not found: {id}"))?;
+                crate::scheduler::runner::task_prompt_vars(&task, &project_path, None)
+            }
+            None => PromptVars::for_project(&project_path),
+        };
+        render(&template, &vars)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> PromptVars {
+        PromptVars {
+            date: "2026-05-04".into(),
+            project: "aitherflow".into(),
+            branch: "main".into(),
+            changed_files: vec!["a.rs".into(), "b.rs".into()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn renders_variables_and_plain_text() {
+        assert_eq!(render("no tags here", &vars()).unwrap(), "no tags here");
+        assert_eq!(
+            render(
+                "Review {{project}} on {{ branch }} ({{date}}):\n{{changed_files}}",
+                &vars()
+            )
+            .unwrap(),
+            "Review aitherflow on main (2026-05-04):\na.rs\nb.rs"
+        );
+    }
+
+    #[test]
+    fn if_blocks_follow_variable_presence() {
+        let tpl = "Start.{{#if last_output}} Previous: {{last_output}}{{else}} First run.{{/if}}";
+        assert_eq!(render(tpl, &vars()).unwrap(), "Start. First run.");
+        let mut v = vars();
+        v.set_last_output("all green");
+        assert_eq!(render(tpl, &v).unwrap(), "Start. Previous: all green");
+
+        let nested = "{{#if branch}}[{{#if trigger}}t{{else}}{{branch}}{{/if}}]{{/if}}";
+        assert_eq!(render(nested, &vars()).unwrap(), "[main]");
+    }
+
+    #[test]
+    fn rejects_broken_templates() {
+        assert!(validate("{{nope}}").is_err());
+        assert!(validate("{{date").is_err());
+        assert!(validate("{{#if date}}open").is_err());
+        assert!(validate("{{/if}}").is_err());
+        assert!(validate("{{else}}").is_err());
+        assert!(validate("{{#if date}}a{{else}}b{{else}}c{{/if}}").is_err());
+        assert!(validate("{{}}").is_err());
+        assert!(validate("{ single braces } are fine").is_ok());
+    }
+
+    #[test]
+    fn escaped_braces_stay_literal() {
+        assert_eq!(
+            render(r"Use \{{name}} in {{project}}: \{{ unclosed", &vars()).unwrap(),
+            "Use {{name}} in aitherflow: {{ unclosed"
+        );
+        assert!(validate(r"\{{#if date}}").is_ok());
+        assert!(!uses_any(r"\{{trigger}}", &["trigger"]));
+    }
+
+    #[test]
+    fn detects_variable_use() {
+        assert!(uses_any("x {{#if trigger}}y{{/if}}", &["trigger"]));
+        assert!(uses_any(
+            "{{#if date}}{{trigger_payload}}{{/if}}",
+            &["trigger_payload"]
+        ));
+        assert!(!uses_any("{{date}} trigger", &["trigger"]));
+    }
+
+    #[test]
+    fn last_output_is_truncated() {
+        let mut v = PromptVars::default();
+        v.set_last_output(&"ж".repeat(MAX_OUTPUT_CHARS + 10));
+        assert!(v.last_output.ends_with("[truncated]"));
+        assert_eq!(
+            v.last_output.chars().count(),
+            MAX_OUTPUT_CHARS + "\n[truncated]".len()
+        );
+    }
+}
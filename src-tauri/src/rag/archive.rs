@@ -0,0 +1,552 @@
+//! Export a knowledge base to a single file and import it elsewhere.
+//!
+//! The archive is a zip with:
+//! - `manifest.json`: format version, embedding model and vector size
+//! - `meta.json`: the base's metadata and documents, as stored
+//! - `documents/{id}.txt`: each document's text, rebuilt from its chunks
+//! - `chunks.jsonl`: one chunk per line, with its provenance
+//! - `embeddings.f32`: the chunk vectors in `chunks.jsonl` order, as
+//!   little-endian f32
+//!
+//! Import restores the index from the chunks and vectors, so nothing is
+//! parsed or downloaded again; the document texts are there for reading and
+//! for tools other than this app.
+
+use std::collections::HashMap;
+use std::io::{Read, Seek, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::chunker::{ByteRange, Chunk, LineRange};
+use super::config as rag_config;
+use super::parser::Location;
+use super::{embedder, filter, index, migrate, rag_settings, store};
+
+const FORMAT: &str = "aitherflow-knowledge-base";
+const VERSION: u32 = 1;
+/// Largest uncompressed entry read from an archive, so a crafted file can't
+/// exhaust memory.
+const MAX_ENTRY_BYTES: u64 = 1 << 30;
+
+/// What an archive holds, written to `manifest.json`.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    format: String,
+    version: u32,
+    exported_at: u64,
+    embedding_model: String,
+    dimension: usize,
+    chunk_count: usize,
+}
+
+/// A line of `chunks.jsonl`.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct ArchivedChunk {
+    document_id: String,
+    chunk_index: u32,
+    text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    lines: Option<LineRange>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    bytes: Option<ByteRange>,
+    #[serde(default)]
+    location: Location,
+}
+
+/// Contents of an archive.
+struct Archive {
+    manifest: Manifest,
+    meta: store::BaseMeta,
+    /// Document id and text; only written, import doesn't need them.
+    texts: Vec<(String, String)>,
+    chunks: Vec<ArchivedChunk>,
+    vectors: Vec<Vec<f32>>,
+}
+
+/// Outcome of an export or import.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveSummary {
+    pub base_id: String,
+    pub name: String,
+    pub documents: usize,
+    pub chunks: usize,
+    pub model: String,
+    /// The chunks were embedded again because the model changed.
+    pub reembedded: bool,
+}
+
+fn encode_vectors(vectors: &[Vec<f32>]) -> Vec<u8> {
+    vectors
+        .iter()
+        .flatten()
+        .flat_map(|x| x.to_le_bytes())
+        .collect()
+}
+
+fn decode_vectors(bytes: &[u8], dim: usize, count: usize) -> Result<Vec<Vec<f32>>, String> {
+    if dim == 0 || bytes.len() != dim * count * 4 {
+        return Err(format!(
+            "Archive embeddings are {} bytes, expected {count} vectors of {dim} floats",
+            bytes.len()
+        ));
+    }
+    Ok(bytes
+        .chunks_exact(dim * 4)
+        .map(|row| {
+            row.chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect()
+        })
+        .collect())
+}
+
+fn write_archive(writer: impl Write + Seek, archive: &Archive) -> Result<(), String> {
+    let err = |e: zip::result::ZipError| format!("Failed to write archive: {e}");
+    let io_err = |e: std::io::Error| format!("Failed to write archive: {e}");
+    let json_err = |e: serde_json::Error| format!("Failed to serialize archive: {e}");
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    let mut zip = zip::ZipWriter::new(writer);
+    zip.start_file("manifest.json", options).map_err(err)?;
+    serde_json::to_writer_pretty(&mut zip, &archive.manifest).map_err(json_err)?;
+    zip.start_file("meta.json", options).map_err(err)?;
+    serde_json::to_writer_pretty(&mut zip, &archive.meta).map_err(json_err)?;
+
+    for (id, text) in &archive.texts {
+        zip.start_file(format!("documents/{id}.txt"), options).map_err(err)?;
+        zip.write_all(text.as_bytes()).map_err(io_err)?;
+    }
+
+    zip.start_file("chunks.jsonl", options).map_err(err)?;
+    for chunk in &archive.chunks {
+        serde_json::to_writer(&mut zip, chunk).map_err(json_err)?;
+        zip.write_all(b"\n").map_err(io_err)?;
+    }
+    zip.start_file("embeddings.f32", options).map_err(err)?;
+    zip.write_all(&encode_vectors(&archive.vectors)).map_err(io_err)?;
+
+    zip.finish().map_err(err)?;
+    Ok(())
+}
+
+fn read_entry(
+    zip: &mut zip::ZipArchive<impl Read + Seek>,
+    name: &str,
+    max_bytes: u64,
+) -> Result<Vec<u8>, String> {
+    let file = zip
+        .by_name(name)
+        .map_err(|e| format!("Archive has no {name}: {e}"))?;
+    let too_large = || format!("{name} in archive is larger than {} MB", max_bytes >> 20);
+    if file.size() > max_bytes {
+        return Err(too_large());
+    }
+    // The header's size can lie, so stop reading past the limit too
+    let mut data = Vec::with_capacity(file.size() as usize);
+    file.take(max_bytes + 1)
+        .read_to_end(&mut data)
+        .map_err(|e| format!("Failed to read {name} from archive: {e}"))?;
+    if data.len() as u64 > max_bytes {
+        return Err(too_large());
+    }
+    Ok(data)
+}
+
+/// Read and check an archive. Document texts are skipped.
+fn read_archive(reader: impl Read + Seek) -> Result<Archive, String> {
+    let mut zip =
+        zip::ZipArchive::new(reader).map_err(|e| format!("Not a knowledge base archive: {e}"))?;
+
+    let manifest: Manifest = serde_json::from_slice(&read_entry(&mut zip, "manifest.json", MAX_ENTRY_BYTES)?)
+        .map_err(|e| format!("Invalid archive manifest: {e}"))?;
+    if manifest.format != FORMAT {
+        return Err("Not a knowledge base archive".into());
+    }
+    if manifest.version > VERSION {
+        return Err(format!(
+            "Archive format version {} is newer than this app supports ({VERSION})",
+            manifest.version
+        ));
+    }
+
+    let meta: store::BaseMeta = serde_json::from_slice(&read_entry(&mut zip, "meta.json", MAX_ENTRY_BYTES)?)
+        .map_err(|e| format!("Invalid archive meta.json: {e}"))?;
+    let chunks = String::from_utf8(read_entry(&mut zip, "chunks.jsonl", MAX_ENTRY_BYTES)?)
+        .map_err(|e| format!("Invalid archive chunks.jsonl: {e}"))?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(i, line)| {
+            serde_json::from_str::<ArchivedChunk>(line)
+                .map_err(|e| format!("Invalid chunk on line {} of chunks.jsonl: {e}", i + 1))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if chunks.len() != manifest.chunk_count {
+        return Err(format!(
+            "Archive has {} chunks, its manifest says {}",
+            chunks.len(),
+            manifest.chunk_count
+        ));
+    }
+    let vectors = decode_vectors(
+        &read_entry(&mut zip, "embeddings.f32", MAX_ENTRY_BYTES)?,
+        manifest.dimension,
+        chunks.len(),
+    )?;
+
+    Ok(Archive {
+        manifest,
+        meta,
+        texts: Vec::new(),
+        chunks,
+        vectors,
+    })
+}
+
+/// Write a base with its chunks and vectors to an archive at `path`.
+pub async fn export_base(base_id: &str, path: &Path) -> Result<ArchiveSummary, String> {
+    // Shared with other writers; only keeps a migration from swapping the
+    // model and index between reading the metadata and the vectors
+    let _access = migrate::write_access(base_id)?;
+    let bid = base_id.to_string();
+    let meta = tokio::task::spawn_blocking(move || store::get_base(&bid))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))??;
+    let (stored, vectors): (Vec<index::StoredChunk>, Vec<Vec<f32>>) =
+        index::all_chunks_with_vectors(base_id).await?.into_iter().unzip();
+
+    // Chunks come ordered by document
+    let texts: Vec<(String, String)> = stored
+        .chunk_by(|a, b| a.document_id == b.document_id)
+        .map(|chunks| (chunks[0].document_id.clone(), index::stitch_chunks(chunks)))
+        .collect();
+    let dimension = vectors.first().map_or_else(
+        || rag_settings::model_dimension(&meta.embedding_model),
+        Vec::len,
+    );
+    let chunks: Vec<ArchivedChunk> = stored
+        .into_iter()
+        .map(|c| ArchivedChunk {
+            document_id: c.document_id,
+            chunk_index: c.chunk_index,
+            text: c.text,
+            lines: c.lines,
+            bytes: c.bytes,
+            location: c.location,
+        })
+        .collect();
+
+    let summary = ArchiveSummary {
+        base_id: meta.id.clone(),
+        name: meta.name.clone(),
+        documents: meta.documents.len(),
+        chunks: chunks.len(),
+        model: meta.embedding_model.clone(),
+        reembedded: false,
+    };
+    let archive = Archive {
+        manifest: Manifest {
+            format: FORMAT.into(),
+            version: VERSION,
+            exported_at: store::now_millis(),
+            embedding_model: meta.embedding_model.clone(),
+            dimension,
+            chunk_count: chunks.len(),
+        },
+        meta,
+        texts,
+        chunks,
+        vectors,
+    };
+
+    // Written next to the target and renamed, so a failed export leaves no
+    // truncated archive behind
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let partial = path.with_extension("part");
+        let file = std::fs::File::create(&partial)
+            .map_err(|e| format!("Failed to create {}: {e}", partial.display()))?;
+        let written = write_archive(std::io::BufWriter::new(file), &archive)
+            .and_then(|()| {
+                std::fs::rename(&partial, &path)
+                    .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+            });
+        if written.is_err() {
+            let _ = std::fs::remove_file(&partial);
+        }
+        written
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??;
+
+    eprintln!(
+        "[rag] Exported base {} ({} chunks)",
+        summary.base_id, summary.chunks
+    );
+    Ok(summary)
+}
+
+/// Restore an archive as a new base. The chunks keep their vectors when the
+/// base stays on the archive's model, and are embedded again when `model`
+/// names another one (or this app doesn't know the archive's model).
+/// Folder sources are not restored, their paths belong to another machine.
+/// `on_progress(processed, total, filename)` is called before each document.
+pub async fn import_base(
+    path: &Path,
+    model: Option<&str>,
+    on_progress: impl Fn(usize, usize, &str),
+) -> Result<ArchiveSummary, String> {
+    let p = path.to_path_buf();
+    let archive = tokio::task::spawn_blocking(move || {
+        let file = std::fs::File::open(&p)
+            .map_err(|e| format!("Failed to open {}: {e}", p.display()))?;
+        read_archive(std::io::BufReader::new(file))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??;
+
+    let archived_model = archive.manifest.embedding_model.clone();
+    let model = match model {
+        Some(m) => m.to_string(),
+        None if rag_settings::validate_model(&archived_model).is_ok() => archived_model.clone(),
+        None => rag_settings::load().embedding_model,
+    };
+    rag_settings::validate_model(&model)?;
+    let reembed = model != archived_model;
+    if !reembed && archive.manifest.dimension != rag_settings::model_dimension(&model) {
+        return Err(format!(
+            "Archive vectors have {} dimensions, {model} produces {}",
+            archive.manifest.dimension,
+            rag_settings::model_dimension(&model)
+        ));
+    }
+
+    let base_id = uuid::Uuid::new_v4().to_string();
+    let result = restore(&base_id, archive, &model, reembed, on_progress).await;
+    if result.is_err() {
+        let dir = rag_config::base_dir(&base_id);
+        let removed = tokio::task::spawn_blocking(move || {
+            std::fs::remove_dir_all(&dir)
+                .map_err(|e| format!("Failed to clean up failed import {}: {e}", dir.display()))
+        })
+        .await;
+        match removed {
+            Ok(Err(e)) => eprintln!("[rag] {e}"),
+            Err(e) => eprintln!("[rag] Task join error: {e}"),
+            Ok(Ok(())) => {}
+        }
+    }
+    result
+}
+
+/// Build the index of an imported base, then record its metadata. Until
+/// `meta.json` is written the base isn't listed.
+async fn restore(
+    base_id: &str,
+    archive: Archive,
+    model: &str,
+    reembed: bool,
+    on_progress: impl Fn(usize, usize, &str),
+) -> Result<ArchiveSummary, String> {
+    let Archive {
+        mut meta,
+        chunks,
+        vectors,
+        ..
+    } = archive;
+
+    let mut by_document: HashMap<String, Vec<(ArchivedChunk, Vec<f32>)>> = HashMap::new();
+    for (chunk, vector) in chunks.into_iter().zip(vectors) {
+        by_document
+            .entry(chunk.document_id.clone())
+            .or_default()
+            .push((chunk, vector));
+    }
+
+    // add_chunks_at creates it, this covers archives without chunks
+    let lance_dir = rag_config::base_lance_dir(base_id);
+    let dir = lance_dir.clone();
+    tokio::task::spawn_blocking(move || std::fs::create_dir_all(&dir))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+        .map_err(|e| format!("Failed to create lance dir: {e}"))?;
+
+    let total = meta.documents.len();
+    let mut chunk_total = 0;
+    for (i, doc) in meta.documents.iter_mut().enumerate() {
+        on_progress(i, total, &doc.filename);
+        // Ids end up in index filter expressions
+        super::validate_uuid(&doc.id, "document id in archive")?;
+        doc.source_id = None;
+        // Labels end up in index filter expressions as well
+        doc.labels = filter::normalize_labels(std::mem::take(&mut doc.labels))
+            .map_err(|e| format!("Invalid labels on {} in archive: {e}", doc.filename))?;
+
+        let mut rows = by_document.remove(&doc.id).unwrap_or_default();
+        rows.sort_by_key(|(c, _)| c.chunk_index);
+        doc.chunk_count = rows.len();
+        if rows.is_empty() {
+            continue;
+        }
+        let (chunks, vectors): (Vec<Chunk>, Vec<Vec<f32>>) = rows
+            .into_iter()
+            .map(|(c, vector)| {
+                let chunk = Chunk {
+                    text: c.text,
+                    lines: c.lines,
+                    bytes: c.bytes,
+                    location: c.location,
+                };
+                (chunk, vector)
+            })
+            .unzip();
+
+        let embeddings = if reembed {
+            let m = model.to_string();
+            let texts: Vec<String> = chunks.iter().map(|c| c.text.clone()).collect();
+            tokio::task::spawn_blocking(move || embedder::embed_texts(&m, &texts))
+                .await
+                .map_err(|e| format!("Task join error: {e}"))??
+        } else {
+            vectors
+        };
+        index::add_chunks_at(&lance_dir, &doc.id, &doc.labels, &chunks, &embeddings)
+            .await
+            .map_err(|e| format!("Failed to import {}: {e}", doc.filename))?;
+        chunk_total += chunks.len();
+    }
+    on_progress(total, total, "");
+    if !by_document.is_empty() {
+        eprintln!(
+            "[rag] Skipped chunks of {} documents missing from the archive's meta.json",
+            by_document.len()
+        );
+    }
+
+    meta.id = base_id.to_string();
+    meta.created_at = store::now_millis();
+    meta.embedding_model = model.to_string();
+    meta.sources.clear();
+    let meta = tokio::task::spawn_blocking(move || store::add_imported_base(meta))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))??;
+
+    eprintln!(
+        "[rag] Imported base {} as {base_id} ({chunk_total} chunks, model {model})",
+        meta.name
+    );
+    Ok(ArchiveSummary {
+        base_id: meta.id,
+        name: meta.name,
+        documents: total,
+        chunks: chunk_total,
+        model: model.to_string(),
+        reembedded: reembed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta() -> store::BaseMeta {
+        serde_json::from_value(serde_json::json!({
+            "id": "base",
+            "name": "Docs",
+            "description": "",
+            "createdAt": 0,
+            "embeddingModel": "model",
+            "documents": [],
+        }))
+        .unwrap()
+    }
+
+    fn chunk(index: u32, text: &str) -> ArchivedChunk {
+        ArchivedChunk {
+            document_id: "doc".into(),
+            chunk_index: index,
+            text: text.into(),
+            lines: None,
+            bytes: Some(ByteRange {
+                start: 0,
+                end: text.len() as u64,
+            }),
+            location: Location {
+                page: Some(index + 1),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn archive(dimension: usize, vectors: Vec<Vec<f32>>) -> Archive {
+        let chunks = vec![chunk(0, "first"), chunk(1, "second\nline")];
+        Archive {
+            manifest: Manifest {
+                format: FORMAT.into(),
+                version: VERSION,
+                exported_at: 1,
+                embedding_model: "model".into(),
+                dimension,
+                chunk_count: chunks.len(),
+            },
+            meta: meta(),
+            texts: vec![("doc".into(), "first\n\nsecond\nline".into())],
+            chunks,
+            vectors,
+        }
+    }
+
+    fn roundtrip(archive: &Archive) -> Result<Archive, String> {
+        let mut buf = std::io::Cursor::new(Vec::new());
+        write_archive(&mut buf, archive)?;
+        buf.set_position(0);
+        read_archive(buf)
+    }
+
+    #[test]
+    fn archive_roundtrips() {
+        let vectors = vec![vec![0.5, -1.25, 3.0], vec![f32::MIN_POSITIVE, 0.0, 1e9]];
+        let out = roundtrip(&archive(3, vectors.clone())).unwrap();
+        assert_eq!(out.manifest, archive(3, Vec::new()).manifest);
+        assert_eq!(out.meta.name, "Docs");
+        assert_eq!(out.chunks, [chunk(0, "first"), chunk(1, "second\nline")]);
+        assert_eq!(out.vectors, vectors);
+    }
+
+    #[test]
+    fn rejects_inconsistent_archives() {
+        // Vectors don't match the declared size
+        assert!(roundtrip(&archive(2, vec![vec![1.0; 3], vec![1.0; 3]])).is_err());
+
+        let mut newer = archive(1, vec![vec![1.0], vec![2.0]]);
+        newer.manifest.version = VERSION + 1;
+        assert!(matches!(roundtrip(&newer), Err(e) if e.contains("newer")));
+
+        assert!(read_archive(std::io::Cursor::new(b"not a zip".to_vec())).is_err());
+    }
+
+    #[test]
+    fn caps_entry_size() {
+        let mut buf = std::io::Cursor::new(Vec::new());
+        write_archive(&mut buf, &archive(1, vec![vec![1.0], vec![2.0]])).unwrap();
+        buf.set_position(0);
+        let mut zip = zip::ZipArchive::new(buf).unwrap();
+        assert_eq!(read_entry(&mut zip, "embeddings.f32", 8).unwrap().len(), 8);
+        let err = read_entry(&mut zip, "embeddings.f32", 7).unwrap_err();
+        assert!(err.contains("larger than"), "{err}");
+    }
+
+    #[test]
+    fn vectors_decode_little_endian() {
+        let bytes = encode_vectors(&[vec![1.0, 2.0]]);
+        assert_eq!(&bytes[..4], &1.0f32.to_le_bytes());
+        assert_eq!(decode_vectors(&bytes, 2, 1).unwrap(), [vec![1.0, 2.0]]);
+        assert!(decode_vectors(&bytes, 0, 0).is_err());
+        assert!(decode_vectors(&bytes, 2, 2).is_err());
+    }
+}
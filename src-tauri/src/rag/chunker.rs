@@ -1,21 +1,70 @@
+use serde::{Deserialize, Serialize};
 use text_splitter::{ChunkConfig, MarkdownSplitter, TextSplitter};
 
+use super::code;
+use super::parser::{Location, ParsedDocument};
 use super::rag_settings;
 
+/// Lines a chunk spans in its source file, 1-based and inclusive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineRange {
+    pub start: u32,
+    pub end: u32,
+}
+
+/// Bytes a chunk spans in its document's parsed text, end exclusive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ByteRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl ByteRange {
+    fn of(start: usize, text: &str) -> Self {
+        Self {
+            start: start as u64,
+            end: (start + text.len()) as u64,
+        }
+    }
+}
+
 /// A single text chunk from the original document.
 pub struct Chunk {
     pub text: String,
+    /// Set for source code.
+    pub lines: Option<LineRange>,
+    pub bytes: Option<ByteRange>,
+    /// Where the chunk starts in the source (page, chapter, heading, time).
+    pub location: Location,
 }
 
-/// Split text into overlapping chunks for embedding.
-/// Uses markdown-aware splitting for markdown content, plain text splitting otherwise.
-/// Split text using defaults from RAG settings.
-pub fn split_text(text: &str, is_markdown: bool) -> Result<Vec<Chunk>, String> {
-    let settings = rag_settings::load();
-    split_text_with_params(text, is_markdown, settings.chunk_size, settings.chunk_overlap)
+/// Split a parsed document: source code along its structure, everything
+/// else as text or markdown. `params` overrides the (size, overlap) settings.
+pub fn split_document(
+    doc: &ParsedDocument,
+    params: Option<(usize, usize)>,
+) -> Result<Vec<Chunk>, String> {
+    let (chunk_size, overlap) = params.unwrap_or_else(|| {
+        let settings = rag_settings::load();
+        (settings.chunk_size, settings.chunk_overlap)
+    });
+    if let Some(lang) = doc.language {
+        return Ok(code::split_code(&doc.text, lang, chunk_size));
+    }
+    let mut chunks = split_text_with_params(&doc.text, doc.is_markdown, chunk_size, overlap)?;
+    if !doc.sections.is_empty() {
+        for chunk in &mut chunks {
+            if let Some(bytes) = chunk.bytes {
+                chunk.location = doc.location_at(bytes.start as usize);
+            }
+        }
+    }
+    Ok(chunks)
 }
 
-/// Split text with custom chunk size and overlap parameters.
+/// Split text into overlapping chunks for embedding.
+/// Uses markdown-aware splitting for markdown content, plain text splitting otherwise.
+/// Chunks record their byte range in `text`.
 pub fn split_text_with_params(
     text: &str,
     is_markdown: bool,
@@ -26,19 +75,22 @@ pub fn split_text_with_params(
         .with_overlap(overlap)
         .map_err(|e| format!("Invalid chunk config: {e}"))?;
 
-    let pieces: Vec<&str> = if is_markdown {
+    let pieces: Vec<(usize, &str)> = if is_markdown {
         let splitter = MarkdownSplitter::new(chunk_config);
-        splitter.chunks(text).collect()
+        splitter.chunk_indices(text).collect()
     } else {
         let splitter = TextSplitter::new(chunk_config);
-        splitter.chunks(text).collect()
+        splitter.chunk_indices(text).collect()
     };
 
     Ok(pieces
         .into_iter()
-        .filter(|t| !t.trim().is_empty())
-        .map(|t| Chunk {
+        .filter(|(_, t)| !t.trim().is_empty())
+        .map(|(start, t)| Chunk {
             text: t.to_string(),
+            lines: None,
+            bytes: Some(ByteRange::of(start, t)),
+            location: Location::default(),
         })
         .collect())
 }
@@ -84,6 +136,28 @@ mod tests {
         assert_eq!(chunks[0].text, "Short text.");
     }
 
+    #[test]
+    fn chunks_record_offsets_and_locations() {
+        let doc = ParsedDocument::from_pieces(
+            [1, 2].map(|page| {
+                let location = Location {
+                    page: Some(page),
+                    ..Location::default()
+                };
+                (location, format!("Page {page} text. More words on page {page}."))
+            }),
+            "\n\n",
+        );
+        let chunks = split_document(&doc, Some((40, 0))).unwrap();
+        assert!(chunks.len() >= 2);
+        for chunk in &chunks {
+            let bytes = chunk.bytes.unwrap();
+            assert_eq!(&doc.text[bytes.start as usize..bytes.end as usize], chunk.text);
+        }
+        assert_eq!(chunks[0].location.page, Some(1));
+        assert_eq!(chunks.last().unwrap().location.page, Some(2));
+    }
+
     #[test]
     fn split_invalid_overlap() {
         // overlap > chunk_size should error
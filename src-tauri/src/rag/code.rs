@@ -0,0 +1,394 @@
+//! Code-aware splitting for source files.
+//!
+//! A file is cut at its top-level items (functions, impl blocks, classes,
+//! ...), items too large for one chunk are cut again at their members, and
+//! small neighbouring pieces are packed back together up to the chunk size.
+//! Item starts are recognised per line from keywords and indentation, which
+//! holds for conventionally formatted code without a parser per language.
+//! Every chunk records the lines it spans in the file.
+
+use std::ops::Range;
+
+use super::chunker::{ByteRange, Chunk, LineRange};
+use super::parser::Location;
+
+/// Source language, as far as splitting is concerned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Language {
+    Rust,
+    /// TypeScript and JavaScript.
+    TypeScript,
+    Python,
+    /// Code without item detection; split at blank lines.
+    Other,
+}
+
+/// Extensions of source files indexed by default in codebase bases.
+pub const SOURCE_EXTENSIONS: &[&str] = &[
+    "rs", "ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs", "py", "pyi", "go", "java", "kt",
+    "kts", "c", "h", "cc", "cpp", "hpp", "cs", "rb", "php", "swift", "scala", "lua", "sh", "bash",
+    "zsh", "sql",
+];
+
+impl Language {
+    /// Language of a file by its (lowercase) extension; None for non-code files.
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext {
+            "rs" => Some(Self::Rust),
+            "ts" | "tsx" | "mts" | "cts" | "js" | "jsx" | "mjs" | "cjs" => Some(Self::TypeScript),
+            "py" | "pyi" => Some(Self::Python),
+            _ if SOURCE_EXTENSIONS.contains(&ext) => Some(Self::Other),
+            _ => None,
+        }
+    }
+
+    /// Whether a trimmed line starts an item (or a member inside one).
+    fn starts_item(self, line: &str) -> bool {
+        match self {
+            Self::Rust => rust_item(line),
+            Self::TypeScript => ts_item(line),
+            Self::Python => python_item(line),
+            Self::Other => false,
+        }
+    }
+
+    /// Whether a trimmed line belongs to the item below it (doc comments,
+    /// attributes, decorators).
+    fn leads_item(self, line: &str) -> bool {
+        match self {
+            Self::Rust => ["//", "#[", "/*", "*"].iter().any(|p| line.starts_with(p)),
+            Self::TypeScript => ["//", "/*", "*", "@"].iter().any(|p| line.starts_with(p)),
+            Self::Python => line.starts_with('#') || line.starts_with('@'),
+            Self::Other => false,
+        }
+    }
+}
+
+/// `s` starts with `word` as a whole word.
+fn starts_with_word(s: &str, word: &str) -> bool {
+    s.strip_prefix(word).is_some_and(|rest| {
+        !rest.starts_with(|c: char| c.is_alphanumeric() || c == '_' || c == '$')
+    })
+}
+
+/// Skip leading modifier words; returns the rest of the line.
+fn skip_modifiers<'a>(mut s: &'a str, modifiers: &[&str]) -> &'a str {
+    loop {
+        let Some(m) = modifiers.iter().find(|m| starts_with_word(s, m)) else {
+            return s;
+        };
+        s = s[m.len()..].trim_start();
+    }
+}
+
+fn rust_item(line: &str) -> bool {
+    const ITEMS: &[&str] = &[
+        "fn",
+        "impl",
+        "struct",
+        "enum",
+        "trait",
+        "mod",
+        "type",
+        "union",
+        "const",
+        "static",
+        "macro_rules!",
+    ];
+    let mut s = line;
+    if let Some(rest) = s.strip_prefix("pub(") {
+        s = rest.split_once(')').map_or("", |(_, r)| r).trim_start();
+    }
+    let s = skip_modifiers(s, &["pub", "async", "unsafe", "default", "extern"]);
+    // extern "C" fn
+    let s = match s.strip_prefix('"') {
+        Some(rest) => rest.split_once('"').map_or("", |(_, r)| r).trim_start(),
+        None => s,
+    };
+    ITEMS.iter().any(|w| starts_with_word(s, w))
+}
+
+fn python_item(line: &str) -> bool {
+    let s = skip_modifiers(line, &["async"]);
+    starts_with_word(s, "def") || starts_with_word(s, "class")
+}
+
+fn ts_item(line: &str) -> bool {
+    const ITEMS: &[&str] = &[
+        "function",
+        "class",
+        "interface",
+        "type",
+        "enum",
+        "namespace",
+        "module",
+        "const",
+        "let",
+        "var",
+    ];
+    const CONTROL: &[&str] = &[
+        "if", "for", "while", "switch", "catch", "return", "else", "do", "try", "with",
+    ];
+    let s = skip_modifiers(line, &["export", "default", "declare", "abstract", "async"]);
+    if ITEMS.iter().any(|w| starts_with_word(s, w)) || s.starts_with("function*") {
+        return true;
+    }
+
+    // Class members: `[modifiers] name(…) {` or `name = (…) => {`
+    let s = skip_modifiers(
+        s,
+        &[
+            "public",
+            "private",
+            "protected",
+            "static",
+            "readonly",
+            "override",
+            "async",
+            "get",
+            "set",
+        ],
+    );
+    let name_len = s
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$' || c == '#'))
+        .unwrap_or(s.len());
+    if name_len == 0 || CONTROL.iter().any(|w| starts_with_word(s, w)) {
+        return false;
+    }
+    let rest = s[name_len..].trim_start();
+    let opens_body = line.trim_end().ends_with('{');
+    opens_body
+        && (rest.starts_with('(')
+            || rest.starts_with('<')
+            || (rest.starts_with('=') && rest.contains("=>")))
+}
+
+fn indent(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+fn size(lines: &[&str], range: Range<usize>) -> usize {
+    lines[range].iter().map(|l| l.chars().count() + 1).sum()
+}
+
+/// Split source code into chunks of about `max_chars`, along its structure
+/// where possible. Chunks don't overlap: item boundaries make it unnecessary.
+pub fn split_code(text: &str, lang: Language, max_chars: usize) -> Vec<Chunk> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut pieces = Vec::new();
+    split_range(&lines, lang, 0..lines.len(), max_chars.max(1), &mut pieces);
+    pack(text, &lines, pieces, max_chars)
+}
+
+/// Cut `range` at its outermost item starts, recursing into pieces still too large.
+fn split_range(
+    lines: &[&str],
+    lang: Language,
+    range: Range<usize>,
+    max: usize,
+    out: &mut Vec<Range<usize>>,
+) {
+    if range.is_empty() {
+        return;
+    }
+    if size(lines, range.clone()) <= max {
+        out.push(range);
+        return;
+    }
+
+    // The item the range opens with (after its doc comments) is not a cut point
+    let mut head = range.start;
+    while head + 1 < range.end
+        && (lines[head].trim().is_empty() || lang.leads_item(lines[head].trim_start()))
+    {
+        head += 1;
+    }
+    let starts: Vec<(usize, usize)> = (head + 1..range.end)
+        .filter(|&i| lang.starts_item(lines[i].trim_start()))
+        .map(|i| (i, indent(lines[i])))
+        .collect();
+    let Some(outer) = starts.iter().map(|&(_, ind)| ind).min() else {
+        split_lines(lines, range, max, out);
+        return;
+    };
+
+    let mut cut = range.start;
+    for (i, ind) in starts.into_iter().filter(|&(_, ind)| ind == outer) {
+        // Doc comments and attributes go with the item they precede
+        let mut start = i;
+        while start > cut + 1
+            && indent(lines[start - 1]) == ind
+            && lang.leads_item(lines[start - 1].trim_start())
+        {
+            start -= 1;
+        }
+        split_range(lines, lang, cut..start, max, out);
+        cut = start;
+    }
+    split_range(lines, lang, cut..range.end, max, out);
+}
+
+/// Split a range without items, preferring to cut at blank lines.
+fn split_lines(lines: &[&str], range: Range<usize>, max: usize, out: &mut Vec<Range<usize>>) {
+    let mut start = range.start;
+    let mut current = 0;
+    let mut last_blank = None;
+    for i in range.clone() {
+        let len = lines[i].chars().count() + 1;
+        if current + len > max && i > start {
+            let cut = match last_blank {
+                Some(b) if b > start && size(lines, b..i) + len <= max => b,
+                _ => i,
+            };
+            out.push(start..cut);
+            start = cut;
+            current = size(lines, cut..i);
+            last_blank = None;
+        }
+        if lines[i].trim().is_empty() {
+            last_blank = Some(i);
+        }
+        current += len;
+    }
+    if start < range.end {
+        out.push(start..range.end);
+    }
+}
+
+/// Merge adjacent pieces up to `max` and turn them into chunks without
+/// leading or trailing blank lines. `lines` are slices of `text`.
+fn pack(text: &str, lines: &[&str], pieces: Vec<Range<usize>>, max: usize) -> Vec<Chunk> {
+    let offset = |line: &str| (line.as_ptr() as usize - text.as_ptr() as usize) as u64;
+    let mut merged: Vec<Range<usize>> = Vec::new();
+    for piece in pieces {
+        if let Some(last) = merged.last_mut() {
+            if size(lines, last.start..piece.end) <= max {
+                last.end = piece.end;
+                continue;
+            }
+        }
+        merged.push(piece);
+    }
+
+    merged
+        .into_iter()
+        .filter_map(|mut r| {
+            while r.start < r.end && lines[r.start].trim().is_empty() {
+                r.start += 1;
+            }
+            while r.end > r.start && lines[r.end - 1].trim().is_empty() {
+                r.end -= 1;
+            }
+            if r.is_empty() {
+                return None;
+            }
+            let last = lines[r.end - 1];
+            Some(Chunk {
+                text: lines[r.clone()].join("\n"),
+                lines: Some(LineRange {
+                    start: r.start as u32 + 1,
+                    end: r.end as u32,
+                }),
+                bytes: Some(ByteRange {
+                    start: offset(lines[r.start]),
+                    end: offset(last) + last.len() as u64,
+                }),
+                location: Location::default(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranges(chunks: &[Chunk]) -> Vec<(u32, u32)> {
+        chunks
+            .iter()
+            .map(|c| {
+                let l = c.lines.unwrap();
+                (l.start, l.end)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn detects_items() {
+        assert!(rust_item("pub(crate) async fn run() {"));
+        assert!(rust_item("impl<T> Foo for Bar<T> {"));
+        assert!(rust_item("extern \"C\" fn cb() {}"));
+        assert!(rust_item("macro_rules! m {"));
+        assert!(!rust_item("let fn_name = 1;"));
+        assert!(!rust_item("implement();"));
+
+        assert!(python_item("async def fetch(self):"));
+        assert!(python_item("class Foo(Base):"));
+        assert!(!python_item("define = 1"));
+
+        assert!(ts_item("export default async function main() {"));
+        assert!(ts_item("export interface Props {"));
+        assert!(ts_item("private async load(id: string): Promise<void> {"));
+        assert!(ts_item("handleClick = (e) => {"));
+        assert!(!ts_item("if (x) {"));
+        assert!(!ts_item("foo(bar);"));
+    }
+
+    #[test]
+    fn splits_rust_at_items_with_doc_comments() {
+        let src = "\
+use std::io;
+
+/// Adds.
+fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[derive(Debug)]
+struct Point {
+    x: i32,
+}
+";
+        let chunks = split_code(src, Language::Rust, 60);
+        assert_eq!(ranges(&chunks), [(1, 1), (3, 6), (8, 11)]);
+        assert!(chunks[1].text.starts_with("/// Adds."));
+        assert!(chunks[2].text.starts_with("#[derive(Debug)]"));
+    }
+
+    #[test]
+    fn large_items_split_at_members() {
+        let src = "\
+class Store:
+    def load(self):
+        return self.read()
+
+    def save(self):
+        self.write()
+        self.flush()
+";
+        let chunks = split_code(src, Language::Python, 70);
+        assert_eq!(ranges(&chunks), [(1, 3), (5, 7)]);
+        assert!(chunks[1].text.trim_start().starts_with("def save"));
+    }
+
+    #[test]
+    fn small_files_stay_whole_and_blank_lines_are_trimmed() {
+        let src = "\n\nconst a = 1;\nconst b = 2;\n\n";
+        let chunks = split_code(src, Language::TypeScript, 1000);
+        assert_eq!(ranges(&chunks), [(3, 4)]);
+        assert_eq!(chunks[0].text, "const a = 1;\nconst b = 2;");
+        let bytes = chunks[0].bytes.unwrap();
+        assert_eq!(&src[bytes.start as usize..bytes.end as usize], chunks[0].text);
+        assert!(split_code("", Language::Rust, 100).is_empty());
+    }
+
+    #[test]
+    fn unknown_structure_splits_at_blank_lines() {
+        let src = "aaaa\nbbbb\n\ncccc\ndddd\n";
+        let chunks = split_code(src, Language::Other, 16);
+        assert_eq!(ranges(&chunks), [(1, 2), (4, 5)]);
+    }
+}
@@ -5,7 +5,10 @@ use tauri::{AppHandle, Emitter};
 
 use crate::files::validate_path_safe;
 
-use super::{chunker, embedder, index, parser, rag_settings, store, validate_uuid, web, youtube};
+use super::{
+    archive, chunker, dedup, embedder, filter, index, migrate, parser, rag_settings, reranker, store,
+    sync, validate_uuid, web, youtube,
+};
 
 /// Playlist progress event emitted after each video is processed.
 #[derive(Clone, Serialize)]
@@ -29,6 +32,14 @@ pub struct PlaylistSummary {
 
 const DEFAULT_SEARCH_LIMIT: usize = 10;
 const MAX_PLAYLIST_VIDEOS: usize = 300;
+/// Cosine similarity above which two chunks count as near-duplicates.
+const DEFAULT_DUPLICATE_THRESHOLD: f32 = 0.95;
+const DEFAULT_DUPLICATE_LIMIT: usize = 100;
+/// Every distinct chunk is compared with every other (n² dot products), so cap
+/// the scan to keep it around a second.
+const MAX_DUPLICATE_SCAN_CHUNKS: u64 = 3_000;
+/// Characters of chunk text shown per duplicate.
+const DUPLICATE_PREVIEW_CHARS: usize = 300;
 
 /// Reindex progress event emitted after each document.
 #[derive(Clone, Serialize)]
@@ -55,19 +66,68 @@ pub async fn rag_list_bases() -> Result<Vec<store::BaseInfo>, String> {
         .map_err(|e| format!("Task join error: {e}"))?
 }
 
+/// Create a base embedded with `embedding_model`, or the configured default.
+/// A codebase base needs the project `root`, which becomes its first folder
+/// source and is indexed in the background.
 #[tauri::command]
-pub async fn rag_create_base(name: String, description: String) -> Result<store::BaseMeta, String> {
-    tokio::task::spawn_blocking(move || store::create_base(&name, &description))
-        .await
-        .map_err(|e| format!("Task join error: {e}"))?
+pub async fn rag_create_base(
+    name: String,
+    description: String,
+    embedding_model: Option<String>,
+    kind: Option<store::BaseKind>,
+    root: Option<String>,
+) -> Result<store::BaseMeta, String> {
+    let kind = kind.unwrap_or_default();
+    let meta = tokio::task::spawn_blocking(move || {
+        let root = match (kind, root) {
+            (store::BaseKind::Codebase, Some(root)) => {
+                let dir = Path::new(&root);
+                validate_path_safe(dir)?;
+                if !dir.is_dir() {
+                    return Err(format!("Not a folder: {root}"));
+                }
+                Some(root)
+            }
+            (store::BaseKind::Codebase, None) => {
+                return Err("A codebase needs its project folder".into());
+            }
+            (store::BaseKind::Documents, _) => None,
+        };
+
+        let model = embedding_model.unwrap_or_else(|| rag_settings::load().embedding_model);
+        let meta = store::create_base(&name, &description, &model, kind)?;
+        let Some(root) = root else {
+            return Ok(meta);
+        };
+        if let Err(e) = store::add_source(&meta.id, &root, Vec::new(), Vec::new()) {
+            if let Err(e) = store::delete_base(&meta.id) {
+                eprintln!("[rag] Failed to clean up base {}: {e}", meta.id);
+            }
+            return Err(e);
+        }
+        sync::refresh_watchers();
+        store::get_base(&meta.id)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??;
+
+    if !meta.sources.is_empty() {
+        sync::queue_sync(&meta.id);
+    }
+    Ok(meta)
 }
 
 #[tauri::command]
 pub async fn rag_delete_base(base_id: String) -> Result<(), String> {
     validate_uuid(&base_id, "base_id")?;
-    tokio::task::spawn_blocking(move || store::delete_base(&base_id))
-        .await
-        .map_err(|e| format!("Task join error: {e}"))?
+    let _access = migrate::write_access(&base_id)?;
+    tokio::task::spawn_blocking(move || {
+        store::delete_base(&base_id)?;
+        sync::refresh_watchers();
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
 }
 
 #[tauri::command]
@@ -78,20 +138,47 @@ pub async fn rag_get_base(base_id: String) -> Result<store::BaseMeta, String> {
         .map_err(|e| format!("Task join error: {e}"))?
 }
 
+/// Embed chunk texts with the base's own embedding model.
+async fn embed_for_base(base_id: &str, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+    let bid = base_id.to_string();
+    let texts = texts.to_vec();
+    tokio::task::spawn_blocking(move || {
+        let model = store::get_base(&bid)?.embedding_model;
+        embedder::embed_texts(&model, &texts)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
 /// Process a single file: parse → chunk → embed → store metadata → index.
-async fn add_single_document(
+/// `source_id` links the document to the folder source that found it.
+/// Callers must hold `migrate::write_access` for the base.
+pub(crate) async fn add_single_document(
     base_id: &str,
     file_path: &str,
+    source_id: Option<&str>,
 ) -> Result<String, String> {
     let p = file_path.to_string();
+    let bid = base_id.to_string();
+    let from_source = source_id.is_some();
 
     // Parse and chunk in a blocking task
-    let (texts, filename, size) = tokio::task::spawn_blocking(move || {
+    let (chunks, filename, size, hash) = tokio::task::spawn_blocking(move || {
         let path = Path::new(&p);
         validate_path_safe(path)?;
+        let hash = std::fs::read(path)
+            .map(|bytes| store::content_hash(&bytes))
+            .ok();
+        // Skip parsing and embedding a file that is already in the base
+        // (add_document_meta checks again, this is the cheap early exit)
+        if !from_source {
+            if let Some(existing) = store::get_base(&bid)?.find_duplicate(hash.as_deref(), &p) {
+                return Err(store::duplicate_error(existing));
+            }
+        }
+
         let parsed = parser::parse_file(path)?;
-        let chunks = chunker::split_text(&parsed.text, parsed.is_markdown)?;
-        let texts: Vec<String> = chunks.into_iter().map(|c| c.text).collect();
+        let chunks = chunker::split_document(&parsed, None)?;
 
         let filename = path
             .file_name()
@@ -101,34 +188,33 @@ async fn add_single_document(
             .map(|m| m.len())
             .unwrap_or(0);
 
-        Ok::<_, String>((texts, filename, size))
+        Ok::<_, String>((chunks, filename, size, hash))
     })
     .await
     .map_err(|e| format!("Task join error: {e}"))??;
 
-    if texts.is_empty() {
+    if chunks.is_empty() {
         return Err(format!("No content extracted from file: {file_path}"));
     }
 
     // Generate embeddings (CPU-bound, blocking)
-    let texts_for_embed = texts.clone();
-    let embeddings = tokio::task::spawn_blocking(move || embedder::embed_texts(&texts_for_embed))
-        .await
-        .map_err(|e| format!("Task join error: {e}"))??;
+    let texts: Vec<String> = chunks.iter().map(|c| c.text.clone()).collect();
+    let embeddings = embed_for_base(base_id, &texts).await?;
 
     // Save document metadata
     let bid = base_id.to_string();
     let fname = filename.clone();
     let fp = file_path.to_string();
-    let chunk_count = texts.len();
+    let chunk_count = chunks.len();
+    let source_id = source_id.map(str::to_string);
     let doc_id = tokio::task::spawn_blocking(move || {
-        store::add_document_meta(&bid, &fname, &fp, size, chunk_count)
+        store::add_document_meta(&bid, &fname, &fp, size, chunk_count, hash, source_id)
     })
     .await
     .map_err(|e| format!("Task join error: {e}"))??;
 
     // Add to vector index
-    index::add_chunks(base_id, &doc_id, &texts, &embeddings).await?;
+    index::add_chunks(base_id, &doc_id, &Default::default(), &chunks, &embeddings).await?;
 
     Ok(doc_id)
 }
@@ -149,6 +235,7 @@ pub async fn rag_add_documents(
     paths: Vec<String>,
 ) -> Result<Vec<String>, String> {
     validate_uuid(&base_id, "base_id")?;
+    let _access = migrate::write_access(&base_id)?;
 
     // Verify base exists
     let bid = base_id.clone();
@@ -175,7 +262,7 @@ pub async fn rag_add_documents(
             eprintln!("[rag] Failed to emit add progress: {e}");
         }
 
-        match add_single_document(&base_id, file_path).await {
+        match add_single_document(&base_id, file_path, None).await {
             Ok(doc_id) => doc_ids.push(doc_id),
             Err(e) => {
                 eprintln!("[rag] Failed to add document {}/{}: {} — {e}", i + 1, total, file_path);
@@ -202,56 +289,62 @@ pub async fn rag_add_documents(
     Ok(doc_ids)
 }
 
-/// Process raw text content: sanitize → chunk → embed → store → index.
+/// Process fetched content: sanitize → chunk → embed → store → index.
 /// Used by URL and YouTube importers.
 async fn add_text_document(
     base_id: &str,
     filename: &str,
     source: &str,
-    text: &str,
+    doc: parser::ParsedDocument,
 ) -> Result<String, String> {
-    let sanitized = parser::sanitize_text(text);
-    let is_markdown = false;
+    let (sanitized, chunks) = tokio::task::spawn_blocking(move || {
+        let doc = parser::sanitize_document(doc);
+        chunker::split_document(&doc, None).map(|chunks| (doc.text, chunks))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??;
     let size = sanitized.len() as u64;
 
-    let text_owned = sanitized.clone();
-    let chunks = tokio::task::spawn_blocking(move || chunker::split_text(&text_owned, is_markdown))
-        .await
-        .map_err(|e| format!("Task join error: {e}"))??;
-
-    let texts: Vec<String> = chunks.into_iter().map(|c| c.text).collect();
-    if texts.is_empty() {
+    if chunks.is_empty() {
         return Err(format!("No content extracted from: {source}"));
     }
 
-    let texts_for_embed = texts.clone();
-    let embeddings = tokio::task::spawn_blocking(move || embedder::embed_texts(&texts_for_embed))
-        .await
-        .map_err(|e| format!("Task join error: {e}"))??;
+    let texts: Vec<String> = chunks.iter().map(|c| c.text.clone()).collect();
+    let embeddings = embed_for_base(base_id, &texts).await?;
 
     let bid = base_id.to_string();
     let fname = filename.to_string();
     let src = source.to_string();
-    let chunk_count = texts.len();
+    let chunk_count = chunks.len();
+    let hash = store::content_hash(sanitized.as_bytes());
     let doc_id = tokio::task::spawn_blocking(move || {
-        store::add_document_meta(&bid, &fname, &src, size, chunk_count)
+        store::add_document_meta(&bid, &fname, &src, size, chunk_count, Some(hash), None)
     })
     .await
     .map_err(|e| format!("Task join error: {e}"))??;
 
-    index::add_chunks(base_id, &doc_id, &texts, &embeddings).await?;
+    index::add_chunks(base_id, &doc_id, &Default::default(), &chunks, &embeddings).await?;
     Ok(doc_id)
 }
 
+/// Fail if the page or video at `url` is already in the base (or the base
+/// doesn't exist), before anything is downloaded.
+async fn ensure_not_added(base_id: &str, url: &str) -> Result<(), String> {
+    let bid = base_id.to_string();
+    let url = url.to_string();
+    tokio::task::spawn_blocking(move || match store::get_base(&bid)?.find_duplicate(None, &url) {
+        Some(existing) => Err(store::duplicate_error(existing)),
+        None => Ok(()),
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
 #[tauri::command]
 pub async fn rag_add_url(base_id: String, url: String) -> Result<String, String> {
     validate_uuid(&base_id, "base_id")?;
-
-    // Verify base exists
-    let bid = base_id.clone();
-    tokio::task::spawn_blocking(move || store::get_base(&bid))
-        .await
-        .map_err(|e| format!("Task join error: {e}"))??;
+    let _access = migrate::write_access(&base_id)?;
+    ensure_not_added(&base_id, &url).await?;
 
     let text = web::fetch_article(&url).await?;
 
@@ -263,7 +356,7 @@ pub async fn rag_add_url(base_id: String, url: String) -> Result<String, String>
         .take(80)
         .collect::<String>();
 
-    add_text_document(&base_id, &filename, &url, &text).await
+    add_text_document(&base_id, &filename, &url, parser::ParsedDocument::plain(text)).await
 }
 
 #[tauri::command]
@@ -273,6 +366,7 @@ pub async fn rag_add_youtube(
     url: String,
 ) -> Result<PlaylistSummary, String> {
     validate_uuid(&base_id, "base_id")?;
+    let _access = migrate::write_access(&base_id)?;
 
     // Verify base exists
     let bid = base_id.clone();
@@ -290,16 +384,18 @@ pub async fn rag_add_youtube(
 
 /// Add a single YouTube video to the knowledge base.
 async fn add_single_youtube(base_id: &str, url: &str) -> Result<PlaylistSummary, String> {
+    ensure_not_added(base_id, url).await?;
+
     let u = url.to_string();
-    let text = tokio::task::spawn_blocking(move || youtube::fetch_youtube_transcript(&u))
+    let transcript = tokio::task::spawn_blocking(move || youtube::fetch_youtube_transcript(&u))
         .await
         .map_err(|e| format!("Task join error: {e}"))??;
 
-    let filename = extract_youtube_id(url)
+    let filename = youtube::video_id(url)
         .map(|id| format!("youtube-{id}"))
         .unwrap_or_else(|| "youtube-video".into());
 
-    add_text_document(base_id, &filename, url, &text).await?;
+    add_text_document(base_id, &filename, url, transcript).await?;
 
     Ok(PlaylistSummary {
         added: 1,
@@ -347,6 +443,12 @@ async fn add_youtube_playlist(
             eprintln!("[rag] Failed to emit playlist progress: {e}");
         }
 
+        if let Err(e) = ensure_not_added(base_id, &entry.url).await {
+            eprintln!("[rag] Skipping playlist video {}/{}: {e}", i + 1, total);
+            skipped += 1;
+            continue;
+        }
+
         let video_url = entry.url.clone();
         let result = tokio::task::spawn_blocking(move || {
             youtube::fetch_youtube_transcript(&video_url)
@@ -355,12 +457,12 @@ async fn add_youtube_playlist(
         .map_err(|e| format!("Task join error: {e}"))?;
 
         match result {
-            Ok(text) => {
-                let filename = extract_youtube_id(&entry.url)
+            Ok(transcript) => {
+                let filename = youtube::video_id(&entry.url)
                     .map(|id| format!("youtube-{id}"))
                     .unwrap_or_else(|| format!("youtube-playlist-{}", i + 1));
 
-                match add_text_document(base_id, &filename, &entry.url, &text).await {
+                match add_text_document(base_id, &filename, &entry.url, transcript).await {
                     Ok(_) => added += 1,
                     Err(e) => {
                         eprintln!(
@@ -400,7 +502,7 @@ async fn add_youtube_playlist(
 
     if added == 0 {
         return Err(format!(
-            "No videos could be added from playlist ({skipped} skipped, no subtitles)"
+            "No videos could be added from playlist ({skipped} skipped: no subtitles or already added)"
         ));
     }
 
@@ -412,24 +514,11 @@ async fn add_youtube_playlist(
     })
 }
 
-fn extract_youtube_id(url: &str) -> Option<String> {
-    // Handle youtu.be/ID and youtube.com/watch?v=ID
-    if let Some(rest) = url.strip_prefix("https://youtu.be/").or_else(|| url.strip_prefix("http://youtu.be/")) {
-        return Some(rest.split(['?', '&', '/']).next()?.to_string());
-    }
-    if url.contains("youtube.com") {
-        if let Some(pos) = url.find("v=") {
-            let id = &url[pos + 2..];
-            return Some(id.split(['&', '#']).next()?.to_string());
-        }
-    }
-    None
-}
-
 #[tauri::command]
 pub async fn rag_remove_document(base_id: String, document_id: String) -> Result<(), String> {
     validate_uuid(&base_id, "base_id")?;
     validate_uuid(&document_id, "document_id")?;
+    let _access = migrate::write_access(&base_id)?;
 
     // Remove from vector index first
     index::remove_document_chunks(&base_id, &document_id).await?;
@@ -460,51 +549,218 @@ pub async fn enrich_results(
         .await
         .map_err(|e| format!("Task join error: {e}"))??;
 
-    let doc_map: std::collections::HashMap<&str, &str> = docs
-        .iter()
-        .map(|d| (d.id.as_str(), d.filename.as_str()))
-        .collect();
+    let doc_map: std::collections::HashMap<&str, &store::DocumentMeta> =
+        docs.iter().map(|d| (d.id.as_str(), d)).collect();
 
     Ok(raw_results
         .into_iter()
         .map(|r| {
-            let doc_name = doc_map
-                .get(r.document_id.as_str())
-                .map(|s| (*s).to_owned())
+            let doc = doc_map.get(r.document_id.as_str());
+            let doc_name = doc
+                .map(|d| d.filename.clone())
                 .unwrap_or_else(|| "unknown".into());
+            // Code cites path:line, the form editors and agents jump to
+            let citation = match (doc, r.lines) {
+                (Some(d), Some(lines)) => Some(format!("{}:{}", d.path, lines.start)),
+                _ => r.location.label(),
+            };
+            let link = doc.and_then(|d| r.location.link(&d.path));
             index::SearchResult {
                 chunk_text: r.chunk_text,
+                base_id: base_id.to_string(),
                 document_id: r.document_id,
                 document_name: doc_name,
                 chunk_index: r.chunk_index,
+                lines: r.lines,
+                bytes: r.bytes,
+                location: r.location,
+                citation,
+                link,
                 score: r.score,
+                similarity: r.similarity,
             }
         })
         .collect())
 }
 
+/// Search one base, embedding the query with the base's own model.
+/// Keyword mode skips embedding the query.
+pub async fn search_base(
+    base_id: &str,
+    query: &str,
+    mode: index::SearchMode,
+    limit: usize,
+    filter: Option<&filter::Filter>,
+) -> Result<Vec<index::SearchResult>, String> {
+    let query_vec = match mode {
+        index::SearchMode::Keyword => None,
+        _ => {
+            let embeddings = embed_for_base(base_id, &[query.to_string()]).await?;
+            Some(embeddings.into_iter().next().ok_or("Failed to embed query")?)
+        }
+    };
+    let filter = filter.map(filter::Filter::to_sql);
+    let raw_results = index::search_with_mode(
+        base_id,
+        query,
+        query_vec.as_deref(),
+        mode,
+        limit,
+        filter.as_deref(),
+    )
+    .await?;
+    if raw_results.is_empty() {
+        return Ok(Vec::new());
+    }
+    enrich_results(base_id, raw_results).await
+}
+
+/// How many candidates to fetch for `limit` results: with the reranker on,
+/// its whole candidate pool.
+pub fn candidate_count(settings: &rag_settings::RagSettings, limit: usize) -> usize {
+    if settings.reranker_enabled {
+        limit.max(settings.rerank_candidates)
+    } else {
+        limit
+    }
+}
+
+/// Rescore the top candidates with the reranker (if enabled) and keep the
+/// best `limit`. If reranking fails, the original order is kept.
+pub async fn rerank_results(
+    settings: &rag_settings::RagSettings,
+    query: &str,
+    results: Vec<index::SearchResult>,
+    limit: usize,
+) -> Vec<index::SearchResult> {
+    if !settings.reranker_enabled || results.len() < 2 {
+        let mut results = results;
+        results.truncate(limit);
+        return results;
+    }
+
+    let model = settings.reranker_model.clone();
+    let q = query.to_string();
+    let texts: Vec<String> = results
+        .iter()
+        .take(settings.rerank_candidates)
+        .map(|r| r.chunk_text.clone())
+        .collect();
+    let scores = tokio::task::spawn_blocking(move || reranker::rerank(&model, &q, &texts))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))
+        .and_then(|r| r);
+
+    match scores {
+        Ok(scores) => reranker::apply_scores(results, &scores, limit),
+        Err(e) => {
+            eprintln!("[rag] Reranking skipped: {e}");
+            let mut results = results;
+            results.truncate(limit);
+            results
+        }
+    }
+}
+
 #[tauri::command]
 pub async fn rag_search(
     base_id: String,
     query: String,
     limit: Option<usize>,
+    mode: Option<index::SearchMode>,
+    filter: Option<String>,
 ) -> Result<Vec<index::SearchResult>, String> {
     validate_uuid(&base_id, "base_id")?;
     let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+    let mode = mode.unwrap_or_default();
+    let filter = filter::Filter::parse(filter.as_deref().unwrap_or_default())?;
+    let settings = tokio::task::spawn_blocking(rag_settings::load)
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?;
+
+    let candidates = candidate_count(&settings, limit);
+    let results = search_base(&base_id, &query, mode, candidates, filter.as_ref()).await?;
+    Ok(rerank_results(&settings, &query, results, limit).await)
+}
 
-    let embeddings = tokio::task::spawn_blocking(move || {
-        embedder::embed_texts(&[query])
+/// Replace a document's tags and metadata, in `meta.json` and on its chunks.
+/// Returns the normalized labels.
+#[tauri::command]
+pub async fn rag_set_document_labels(
+    base_id: String,
+    document_id: String,
+    labels: store::DocumentLabels,
+) -> Result<store::DocumentLabels, String> {
+    validate_uuid(&base_id, "base_id")?;
+    validate_uuid(&document_id, "document_id")?;
+    let _access = migrate::write_access(&base_id)?;
+    let labels = filter::normalize_labels(labels)?;
+
+    let (bid, did, l) = (base_id.clone(), document_id.clone(), labels.clone());
+    tokio::task::spawn_blocking(move || {
+        store::update_document_meta(&bid, &did, |d| d.labels = l)
     })
     .await
     .map_err(|e| format!("Task join error: {e}"))??;
 
-    let query_vec = embeddings
-        .into_iter()
-        .next()
-        .ok_or("Failed to embed query")?;
+    index::set_document_labels(&base_id, &document_id, &labels).await?;
+    Ok(labels)
+}
 
-    let raw_results = index::search(&base_id, &query_vec, limit).await?;
-    enrich_results(&base_id, raw_results).await
+/// Report chunks of a base that repeat each other: identical text, or
+/// embeddings at least `threshold` similar (default 0.95). Most similar first.
+#[tauri::command]
+pub async fn rag_find_duplicates(
+    base_id: String,
+    threshold: Option<f32>,
+    limit: Option<usize>,
+) -> Result<Vec<dedup::DuplicatePair>, String> {
+    validate_uuid(&base_id, "base_id")?;
+    let threshold = threshold.unwrap_or(DEFAULT_DUPLICATE_THRESHOLD);
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err(format!("Threshold must be between 0 and 1, got {threshold}"));
+    }
+    let limit = limit.unwrap_or(DEFAULT_DUPLICATE_LIMIT);
+
+    // Count first so a large base isn't loaded with all its vectors for nothing
+    let total = index::get_status(&base_id).await?.total_chunks;
+    if total > MAX_DUPLICATE_SCAN_CHUNKS {
+        return Err(format!(
+            "Base has {total} chunks, duplicate search is limited to {MAX_DUPLICATE_SCAN_CHUNKS}"
+        ));
+    }
+    let chunks = index::chunk_fingerprints(&base_id).await?;
+
+    tokio::task::spawn_blocking(move || {
+        let names: std::collections::HashMap<String, String> = store::get_base(&base_id)?
+            .documents
+            .into_iter()
+            .map(|d| (d.id, d.filename))
+            .collect();
+        let hashes: Vec<&str> = chunks.iter().map(|c| c.hash.as_str()).collect();
+        let vectors: Vec<&[f32]> = chunks.iter().map(|c| c.vector.as_slice()).collect();
+
+        let side = |i: usize| {
+            let c = &chunks[i];
+            dedup::DuplicateChunk {
+                document_id: c.document_id.clone(),
+                document_name: names.get(&c.document_id).cloned().unwrap_or_default(),
+                chunk_index: c.chunk_index,
+                text: c.text.chars().take(DUPLICATE_PREVIEW_CHARS).collect(),
+            }
+        };
+        Ok(dedup::near_duplicates(&hashes, &vectors, threshold, limit)
+            .into_iter()
+            .map(|(a, b, score)| dedup::DuplicatePair {
+                score,
+                exact: chunks[a].hash == chunks[b].hash,
+                first: side(a),
+                second: side(b),
+            })
+            .collect())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
 }
 
 #[tauri::command]
@@ -532,6 +788,23 @@ pub fn rag_get_available_models() -> Vec<ModelInfo> {
         .collect()
 }
 
+#[derive(Clone, Serialize)]
+pub struct RerankerInfo {
+    pub id: String,
+    pub label: String,
+}
+
+#[tauri::command]
+pub fn rag_get_available_rerankers() -> Vec<RerankerInfo> {
+    rag_settings::AVAILABLE_RERANKERS
+        .iter()
+        .map(|(id, label)| RerankerInfo {
+            id: id.to_string(),
+            label: label.to_string(),
+        })
+        .collect()
+}
+
 #[tauri::command]
 pub async fn rag_load_settings() -> Result<rag_settings::RagSettings, String> {
     tokio::task::spawn_blocking(|| Ok(rag_settings::load()))
@@ -539,24 +812,181 @@ pub async fn rag_load_settings() -> Result<rag_settings::RagSettings, String> {
         .map_err(|e| format!("Task join error: {e}"))?
 }
 
-/// Returns true if the embedding model was changed (requires restart + reindex).
 #[tauri::command]
-pub async fn rag_save_settings(settings: rag_settings::RagSettings) -> Result<bool, String> {
+pub async fn rag_save_settings(settings: rag_settings::RagSettings) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || rag_settings::save(&settings))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+/// Re-embed a base with another embedding model. Searches keep using the old
+/// model until the new index is complete. Emits "rag-migrate-progress".
+#[tauri::command]
+pub async fn rag_migrate_base_model(
+    app: AppHandle,
+    base_id: String,
+    model: String,
+) -> Result<migrate::MigrateSummary, String> {
+    validate_uuid(&base_id, "base_id")?;
+    migrate::migrate_base(&base_id, &model, |processed, total, filename| {
+        if let Err(e) = app.emit(
+            "rag-migrate-progress",
+            ReindexProgress {
+                processed,
+                total,
+                current_filename: filename.to_string(),
+            },
+        ) {
+            eprintln!("[rag] Failed to emit migrate progress: {e}");
+        }
+    })
+    .await
+}
+
+/// Write a base, with its chunks and embeddings, to a single archive file.
+#[tauri::command]
+pub async fn rag_export_base(
+    base_id: String,
+    path: String,
+) -> Result<archive::ArchiveSummary, String> {
+    validate_uuid(&base_id, "base_id")?;
+    let target = Path::new(&path);
+    validate_path_safe(target)?;
+    archive::export_base(&base_id, target).await
+}
+
+/// Restore an exported base as a new base. Embeddings are reused unless
+/// `model` differs from the archive's, in which case the chunks are
+/// re-embedded in the background, reporting "rag-import-progress".
+#[tauri::command]
+pub async fn rag_import_base(
+    app: AppHandle,
+    path: String,
+    model: Option<String>,
+) -> Result<archive::ArchiveSummary, String> {
+    let source = Path::new(&path);
+    validate_path_safe(source)?;
+    archive::import_base(source, model.as_deref(), |processed, total, filename| {
+        if let Err(e) = app.emit(
+            "rag-import-progress",
+            ReindexProgress {
+                processed,
+                total,
+                current_filename: filename.to_string(),
+            },
+        ) {
+            eprintln!("[rag] Failed to emit import progress: {e}");
+        }
+    })
+    .await
+}
+
+// --- Folder sources ---
+
+/// Register a folder whose files are kept in sync with the base. Empty
+/// `include` means all supported document types. The first sync runs in the
+/// background and ends with a "rag-sync-finished" event.
+#[tauri::command]
+pub async fn rag_add_source(
+    base_id: String,
+    path: String,
+    include: Vec<String>,
+    exclude: Vec<String>,
+) -> Result<store::FolderSource, String> {
+    validate_uuid(&base_id, "base_id")?;
+    let _access = migrate::write_access(&base_id)?;
+    sync::validate_patterns(&include)?;
+    sync::validate_patterns(&exclude)?;
+
+    let bid = base_id.clone();
+    let source = tokio::task::spawn_blocking(move || {
+        let dir = Path::new(&path);
+        validate_path_safe(dir)?;
+        if !dir.is_dir() {
+            return Err(format!("Not a folder: {path}"));
+        }
+        let source = store::add_source(&bid, &path, include, exclude)?;
+        sync::refresh_watchers();
+        Ok(source)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??;
+
+    sync::queue_sync(&base_id);
+    Ok(source)
+}
+
+/// Stop syncing a folder. Its documents are deleted with `remove_documents`,
+/// otherwise they stay in the base as regular documents.
+#[tauri::command]
+pub async fn rag_remove_source(
+    base_id: String,
+    source_id: String,
+    remove_documents: bool,
+) -> Result<(), String> {
+    validate_uuid(&base_id, "base_id")?;
+    validate_uuid(&source_id, "source_id")?;
+    let _access = migrate::write_access(&base_id)?;
+    if sync::is_syncing(&base_id) {
+        return Err("This knowledge base is syncing its folders; try again when it finishes".into());
+    }
+
+    if remove_documents {
+        let bid = base_id.clone();
+        let documents = tokio::task::spawn_blocking(move || store::list_documents(&bid))
+            .await
+            .map_err(|e| format!("Task join error: {e}"))??;
+        for doc in documents
+            .iter()
+            .filter(|d| d.source_id.as_deref() == Some(source_id.as_str()))
+        {
+            index::remove_document_chunks(&base_id, &doc.id).await?;
+            let (bid, did) = (base_id.clone(), doc.id.clone());
+            tokio::task::spawn_blocking(move || store::remove_document_meta(&bid, &did))
+                .await
+                .map_err(|e| format!("Task join error: {e}"))??;
+        }
+    }
+
     tokio::task::spawn_blocking(move || {
-        let old = rag_settings::load();
-        let model_changed = old.embedding_model != settings.embedding_model;
-        rag_settings::save(&settings)?;
-        Ok(model_changed)
+        store::remove_source(&base_id, &source_id)?;
+        sync::refresh_watchers();
+        Ok(())
     })
     .await
     .map_err(|e| format!("Task join error: {e}"))?
 }
 
+/// Sync all folder sources of a base now and return the result.
+#[tauri::command]
+pub async fn rag_sync_base(
+    app: AppHandle,
+    base_id: String,
+) -> Result<sync::BaseSyncStatus, String> {
+    validate_uuid(&base_id, "base_id")?;
+    sync::sync_base(&base_id).await?;
+    sync::emit_finished(&app, &base_id);
+
+    tokio::task::spawn_blocking(move || sync::status(&base_id))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+/// Folder sources of a base with their last sync results.
+#[tauri::command]
+pub async fn rag_sync_status(base_id: String) -> Result<sync::BaseSyncStatus, String> {
+    validate_uuid(&base_id, "base_id")?;
+    tokio::task::spawn_blocking(move || sync::status(&base_id))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
 // --- Reindex ---
 
 /// Reindex a single document: remove old chunks → parse → chunk → embed → re-add.
 /// Returns new chunk count on success. Used by both rag_reindex_base and mcp tool.
 /// If chunk_size/chunk_overlap are None, uses defaults from rag settings.
+/// Callers must hold `migrate::write_access` for the base.
 pub async fn reindex_single_document(
     base_id: &str,
     doc: &store::DocumentMeta,
@@ -571,34 +1001,29 @@ pub async fn reindex_single_document(
 
     // Re-parse with optional custom chunk params
     let file_path = doc.path.clone();
-    let (texts, new_chunk_count) = tokio::task::spawn_blocking(move || {
+    let (chunks, new_chunk_count, hash) = tokio::task::spawn_blocking(move || {
         let path = std::path::Path::new(&file_path);
         let parsed = parser::parse_file(path)?;
-        let chunks = match (chunk_size, chunk_overlap) {
-            (Some(size), Some(overlap)) => {
-                chunker::split_text_with_params(&parsed.text, parsed.is_markdown, size, overlap)?
-            }
-            _ => chunker::split_text(&parsed.text, parsed.is_markdown)?,
-        };
-        let texts: Vec<String> = chunks.into_iter().map(|c| c.text).collect();
-        let count = texts.len();
-        Ok::<_, String>((texts, count))
+        let hash = std::fs::read(path)
+            .map(|bytes| store::content_hash(&bytes))
+            .ok();
+        let chunks = chunker::split_document(&parsed, chunk_size.zip(chunk_overlap))?;
+        let count = chunks.len();
+        Ok::<_, String>((chunks, count, hash))
     })
     .await
     .map_err(|e| format!("Task join error: {e}"))??;
 
-    if texts.is_empty() {
+    if chunks.is_empty() {
         return Err("No content after re-parsing".into());
     }
 
     // Re-embed
-    let texts_for_embed = texts.clone();
-    let embeddings = tokio::task::spawn_blocking(move || embedder::embed_texts(&texts_for_embed))
-        .await
-        .map_err(|e| format!("Task join error: {e}"))??;
+    let texts: Vec<String> = chunks.iter().map(|c| c.text.clone()).collect();
+    let embeddings = embed_for_base(base_id, &texts).await?;
 
     // Re-add to index
-    index::add_chunks(&bid, &did, &texts, &embeddings).await?;
+    index::add_chunks(&bid, &did, &doc.labels, &chunks, &embeddings).await?;
 
     // Update chunk count in metadata
     let bid2 = base_id.to_string();
@@ -608,6 +1033,7 @@ pub async fn reindex_single_document(
         let mut meta = store::get_base(&bid2)?;
         if let Some(d) = meta.documents.iter_mut().find(|d| d.id == did2) {
             d.chunk_count = count;
+            d.content_hash = hash;
         }
         crate::file_ops::write_json(&super::config::base_meta_path(&bid2), &meta)
     })
@@ -623,6 +1049,7 @@ pub async fn rag_reindex_base(
     base_id: String,
 ) -> Result<ReindexSummary, String> {
     validate_uuid(&base_id, "base_id")?;
+    let _access = migrate::write_access(&base_id)?;
 
     let bid = base_id.clone();
     let documents = tokio::task::spawn_blocking(move || store::list_documents(&bid))
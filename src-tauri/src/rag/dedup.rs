@@ -0,0 +1,161 @@
+//! Duplicate detection: canonical IDs for fetched sources and near-duplicate
+//! chunks within a base.
+//!
+//! Files are recognized by the SHA-256 of their bytes (`content_hash` on the
+//! document), fetched pages and videos also by where they come from, so the
+//! same article or video is not downloaded twice. Chunks carry the SHA-256 of
+//! their text in the index; identical chunks are grouped by it, near-identical
+//! ones found by comparing embeddings.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use super::youtube;
+
+/// Query parameters that only track where a visitor came from.
+const TRACKING_PARAMS: &[&str] = &["fbclid", "gclid", "igshid", "mc_cid", "mc_eid", "ref", "si"];
+
+/// Canonical ID of a fetched source (`youtube:ID` for videos, `url:host/path?query`
+/// for web pages), or None for local paths.
+pub fn canonical_source(source: &str) -> Option<String> {
+    if let Some(id) = youtube::video_id(source) {
+        return Some(format!("youtube:{id}"));
+    }
+    let url = reqwest::Url::parse(source).ok()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+    // http/https, "www.", the fragment, trailing slashes and tracking
+    // parameters don't change the page
+    let host = url.host_str()?;
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    let mut canonical = format!("url:{host}");
+    if let Some(port) = url.port() {
+        canonical.push_str(&format!(":{port}"));
+    }
+    canonical.push_str(url.path().trim_end_matches('/'));
+
+    let mut query: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(k, _)| !k.starts_with("utm_") && !TRACKING_PARAMS.contains(&k.as_ref()))
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    if !query.is_empty() {
+        query.sort();
+        let query: Vec<String> = query.iter().map(|(k, v)| format!("{k}={v}")).collect();
+        canonical.push('?');
+        canonical.push_str(&query.join("&"));
+    }
+    Some(canonical)
+}
+
+/// One side of a duplicate pair.
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateChunk {
+    pub document_id: String,
+    pub document_name: String,
+    pub chunk_index: u32,
+    pub text: String,
+}
+
+/// Two chunks with (nearly) the same content.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicatePair {
+    /// Cosine similarity of the embeddings; 1.0 for identical text.
+    pub score: f32,
+    /// Same text (equal chunk hashes).
+    pub exact: bool,
+    pub first: DuplicateChunk,
+    pub second: DuplicateChunk,
+}
+
+/// Pairs of chunks (indices into the inputs) that are identical or whose
+/// vectors have a cosine similarity of at least `threshold`, most similar
+/// first. Identical chunks are paired with the first of their group, and only
+/// that one is compared with the rest, so a group isn't reported n² times.
+pub fn near_duplicates(
+    hashes: &[&str],
+    vectors: &[&[f32]],
+    threshold: f32,
+    limit: usize,
+) -> Vec<(usize, usize, f32)> {
+    let mut pairs = Vec::new();
+    let mut first_with_hash: HashMap<&str, usize> = HashMap::new();
+    let mut distinct = Vec::new();
+    for (i, hash) in hashes.iter().enumerate() {
+        match first_with_hash.get(hash) {
+            Some(&first) => pairs.push((first, i, 1.0)),
+            None => {
+                first_with_hash.insert(hash, i);
+                distinct.push(i);
+            }
+        }
+    }
+
+    let normalized: Vec<Vec<f32>> = distinct.iter().map(|&i| normalize(vectors[i])).collect();
+    for (a, va) in normalized.iter().enumerate() {
+        for (b, vb) in normalized.iter().enumerate().skip(a + 1) {
+            let score: f32 = va.iter().zip(vb).map(|(x, y)| x * y).sum();
+            if score >= threshold {
+                pairs.push((distinct[a], distinct[b], score.min(1.0)));
+            }
+        }
+    }
+
+    pairs.sort_by(|a, b| b.2.total_cmp(&a.2).then((a.0, a.1).cmp(&(b.0, b.1))));
+    pairs.truncate(limit);
+    pairs
+}
+
+fn normalize(v: &[f32]) -> Vec<f32> {
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm == 0.0 {
+        return v.to_vec();
+    }
+    v.iter().map(|x| x / norm).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical_source_ignores_cosmetic_url_differences() {
+        let id = canonical_source("https://www.example.com/blog/post/?utm_source=x&b=2&a=1#top");
+        assert_eq!(id.as_deref(), Some("url:example.com/blog/post?a=1&b=2"));
+        assert_eq!(canonical_source("http://example.com/blog/post?a=1&b=2"), id);
+        assert_eq!(
+            canonical_source("https://youtu.be/dQw4w9WgXcQ?si=abc").as_deref(),
+            Some("youtube:dQw4w9WgXcQ")
+        );
+        assert_eq!(
+            canonical_source("https://www.youtube.com/watch?v=dQw4w9WgXcQ"),
+            canonical_source("https://youtu.be/dQw4w9WgXcQ")
+        );
+        assert_eq!(canonical_source("/home/me/book.pdf"), None);
+        assert_eq!(canonical_source("file:///home/me/book.pdf"), None);
+    }
+
+    #[test]
+    fn finds_exact_and_near_duplicates() {
+        let hashes = ["a", "b", "a", "c", "a"];
+        let v: [&[f32]; 5] = [
+            &[1.0, 0.0],
+            &[0.0, 2.0],
+            &[1.0, 0.0],
+            &[0.1, 1.0],
+            &[1.0, 0.0],
+        ];
+        let pairs = near_duplicates(&hashes, &v, 0.99, 10);
+        let keys: Vec<(usize, usize)> = pairs.iter().map(|p| (p.0, p.1)).collect();
+        assert_eq!(keys, [(0, 2), (0, 4), (1, 3)]);
+        assert!(pairs[2].2 > 0.99 && pairs[2].2 < 1.0);
+
+        assert_eq!(near_duplicates(&hashes, &v, 0.999, 10).len(), 2);
+        assert_eq!(near_duplicates(&hashes, &v, 0.99, 1).len(), 1);
+        assert!(near_duplicates(&[], &[], 0.9, 10).is_empty());
+    }
+}
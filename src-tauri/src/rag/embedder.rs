@@ -1,75 +1,63 @@
-use std::sync::{Mutex, OnceLock};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
 
 use fastembed::{TextEmbedding, TextInitOptions};
 
 use super::config as rag_config;
 use super::rag_settings;
 
-/// Global embedding model instance (initialized once on first use).
+/// Loaded embedding models, keyed by model name. Each knowledge base embeds
+/// with its own model, so several can be loaded at once.
 /// NOTE: embed_texts must always be called from tokio::task::spawn_blocking.
-/// Model is determined at init time from rag_settings. Changing model requires app restart.
-/// Mutex is needed because fastembed v5 embed() requires &mut self.
-static EMBEDDER: OnceLock<Mutex<EmbedderInfo>> = OnceLock::new();
+/// A model is loaded on first use inside its own slot, so loading one model
+/// doesn't block embedding with another.
+/// Slot Mutex is needed because fastembed v5 embed() requires &mut self.
+static EMBEDDERS: OnceLock<Mutex<HashMap<String, Slot>>> = OnceLock::new();
 
-struct EmbedderInfo {
-    model: TextEmbedding,
-    dimension: usize,
-}
+type Slot = Arc<Mutex<Option<TextEmbedding>>>;
 
-fn get_or_init() -> Result<&'static Mutex<EmbedderInfo>, String> {
-    if let Some(m) = EMBEDDER.get() {
-        return Ok(m);
-    }
+fn slot(model_name: &str) -> Result<Slot, String> {
+    let mut pool = EMBEDDERS
+        .get_or_init(Default::default)
+        .lock()
+        .map_err(|e| format!("Embedder pool lock poisoned: {e}"))?;
+    Ok(pool.entry(model_name.to_string()).or_default().clone())
+}
 
-    let settings = rag_settings::load();
+fn load_model(model_name: &str) -> Result<TextEmbedding, String> {
     let cache_dir = rag_config::models_dir();
     std::fs::create_dir_all(&cache_dir)
         .map_err(|e| format!("Failed to create models dir: {e}"))?;
 
-    let fastembed_model = rag_settings::resolve_model(&settings.embedding_model);
-    let dimension = rag_settings::model_dimension(&settings.embedding_model);
-
     eprintln!(
         "[rag] Initializing embedding model: {} (dim={})",
-        settings.embedding_model, dimension
+        model_name,
+        rag_settings::model_dimension(model_name)
     );
 
-    let model = TextEmbedding::try_new(
-        TextInitOptions::new(fastembed_model).with_cache_dir(cache_dir),
+    TextEmbedding::try_new(
+        TextInitOptions::new(rag_settings::resolve_model(model_name)).with_cache_dir(cache_dir),
     )
-    .map_err(|e| format!("Failed to initialize embedding model: {e}"))?;
-
-    let info = Mutex::new(EmbedderInfo { model, dimension });
-    // OnceLock::set returns Err if already initialized by a concurrent caller —
-    // that's fine, we just use whichever value won the race.
-    let _ = EMBEDDER.set(info);
-    Ok(EMBEDDER.get().expect("just initialized"))
+    .map_err(|e| format!("Failed to initialize embedding model: {e}"))
 }
 
-/// Generate embeddings for a batch of text chunks.
-pub fn embed_texts(texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+/// Generate embeddings for a batch of text chunks with the given model.
+pub fn embed_texts(model_name: &str, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
     if texts.is_empty() {
         return Ok(Vec::new());
     }
+    rag_settings::validate_model(model_name)?;
 
-    let mutex = get_or_init()?;
-    let mut info = mutex
+    let slot = slot(model_name)?;
+    let mut model = slot
         .lock()
         .map_err(|e| format!("Embedder lock poisoned: {e}"))?;
-    info.model
+    if model.is_none() {
+        *model = Some(load_model(model_name)?);
+    }
+    model
+        .as_mut()
+        .expect("just initialized")
         .embed(texts, None)
         .map_err(|e| format!("Embedding failed: {e}"))
 }
-
-/// Get the dimension of the current embedding model.
-pub fn embedding_dimension() -> usize {
-    EMBEDDER
-        .get()
-        .and_then(|m| {
-            m.lock()
-                .map_err(|e| eprintln!("[rag] Embedder mutex poisoned in embedding_dimension: {e}"))
-                .ok()
-        })
-        .map(|info| info.dimension)
-        .unwrap_or_else(|| rag_settings::model_dimension(&rag_settings::load().embedding_model))
-}
@@ -0,0 +1,323 @@
+//! Document labels (tags and key/value metadata) as chunk columns, and the
+//! filter expressions search accepts.
+//!
+//! Every chunk carries its document's labels in two string columns, `tags`
+//! (`|rust|async|`) and `metadata` (`|author=Knuth|lang=en|`), which filters
+//! match with ILIKE. Labels can't contain `|`, so the delimiters hold.
+//!
+//! A filter is a list of whitespace-separated terms that must all match:
+//! `tag:rust` requires a tag, `author=Knuth` a metadata value, `tag:a,b`
+//! accepts either value and a leading `-` negates a term. Values with spaces
+//! are quoted: `author="Donald Knuth"`. Matching ignores case.
+
+use std::collections::BTreeMap;
+
+use super::store::DocumentLabels;
+
+/// Check and normalize labels: tags and keys are trimmed and lowercased,
+/// duplicate tags and empty values dropped.
+pub fn normalize_labels(labels: DocumentLabels) -> Result<DocumentLabels, String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in labels.tags {
+        let tag = tag.trim().to_lowercase();
+        if tag.is_empty() || tags.contains(&tag) {
+            continue;
+        }
+        check_label(&tag, "Tag")?;
+        tags.push(tag);
+    }
+
+    let mut metadata = BTreeMap::new();
+    for (key, value) in labels.metadata {
+        let key = key.trim().to_lowercase();
+        let value = value.trim().to_string();
+        if value.is_empty() {
+            continue;
+        }
+        if key.is_empty() {
+            return Err(format!("Metadata value '{value}' has no key"));
+        }
+        if !key.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '.') {
+            return Err(format!(
+                "Metadata key '{key}' may only contain letters, digits, '_', '-' and '.'"
+            ));
+        }
+        check_label(&value, "Metadata value")?;
+        metadata.insert(key, value);
+    }
+
+    Ok(DocumentLabels { tags, metadata })
+}
+
+fn check_label(s: &str, what: &str) -> Result<(), String> {
+    if s.contains('|') || s.chars().any(char::is_control) {
+        return Err(format!("{what} '{s}' contains '|' or control characters"));
+    }
+    Ok(())
+}
+
+/// Value of the `tags` column.
+pub fn encode_tags(labels: &DocumentLabels) -> String {
+    encode(labels.tags.iter().cloned())
+}
+
+/// Value of the `metadata` column.
+pub fn encode_metadata(labels: &DocumentLabels) -> String {
+    encode(labels.metadata.iter().map(|(k, v)| format!("{k}={v}")))
+}
+
+fn encode(items: impl Iterator<Item = String>) -> String {
+    let items: Vec<String> = items.collect();
+    if items.is_empty() {
+        String::new()
+    } else {
+        format!("|{}|", items.join("|"))
+    }
+}
+
+/// A SQL string literal.
+pub fn sql_string(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Field {
+    Tag,
+    Meta(String),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Term {
+    negate: bool,
+    field: Field,
+    /// Any of these matches.
+    values: Vec<String>,
+}
+
+/// A parsed search filter.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Filter {
+    terms: Vec<Term>,
+}
+
+impl Filter {
+    /// Parse a filter expression; None if it is blank.
+    pub fn parse(s: &str) -> Result<Option<Self>, String> {
+        let terms = split_unquoted(s, char::is_whitespace, false)?
+            .iter()
+            .map(|t| parse_term(t))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((!terms.is_empty()).then_some(Self { terms }))
+    }
+
+    /// The filter as a LanceDB (DataFusion SQL) predicate.
+    pub fn to_sql(&self) -> String {
+        self.terms
+            .iter()
+            .map(|term| {
+                let (column, prefix) = match &term.field {
+                    Field::Tag => ("tags", String::new()),
+                    Field::Meta(key) => ("metadata", format!("{key}=")),
+                };
+                let any = term
+                    .values
+                    .iter()
+                    .map(|v| {
+                        let pattern = format!("%|{}|%", escape_like(&format!("{prefix}{v}")));
+                        format!(
+                            "coalesce({column}, '') ILIKE {} ESCAPE '\\'",
+                            sql_string(&pattern)
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" OR ");
+                if term.negate {
+                    format!("NOT ({any})")
+                } else {
+                    format!("({any})")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" AND ")
+    }
+}
+
+fn parse_term(token: &str) -> Result<Term, String> {
+    let (negate, rest) = match token.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, token),
+    };
+    let (field, values) = if rest.get(..4).is_some_and(|p| p.eq_ignore_ascii_case("tag:")) {
+        (Field::Tag, &rest[4..])
+    } else if let Some((key, values)) = rest.split_once('=') {
+        let key = key.trim().to_lowercase();
+        if key.is_empty() || key.contains('"') {
+            return Err(format!("Invalid filter term '{token}': missing key"));
+        }
+        (Field::Meta(key), values)
+    } else {
+        return Err(format!(
+            "Invalid filter term '{token}' (expected tag:NAME or key=value)"
+        ));
+    };
+
+    let values = split_unquoted(values, |c| c == ',', true)?;
+    if values.is_empty() {
+        return Err(format!("Invalid filter term '{token}': missing value"));
+    }
+    let values = values
+        .into_iter()
+        .map(|v| match field {
+            Field::Tag => v.to_lowercase(),
+            Field::Meta(_) => v,
+        })
+        .collect();
+    Ok(Term {
+        negate,
+        field,
+        values,
+    })
+}
+
+/// Split at characters matching `sep` outside double quotes, dropping empty
+/// pieces and, with `strip_quotes`, the quotes.
+fn split_unquoted(
+    s: &str,
+    sep: impl Fn(char) -> bool,
+    strip_quotes: bool,
+) -> Result<Vec<String>, String> {
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in s.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                if !strip_quotes {
+                    current.push(c);
+                }
+            }
+            c if !quoted && sep(c) => {
+                let piece = std::mem::take(&mut current);
+                if !piece.trim().is_empty() {
+                    pieces.push(piece.trim().to_string());
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if quoted {
+        return Err(format!("Unclosed quote in filter: {s}"));
+    }
+    if !current.trim().is_empty() {
+        pieces.push(current.trim().to_string());
+    }
+    Ok(pieces)
+}
+
+/// Escape LIKE wildcards, with `\` as the escape character.
+fn escape_like(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(tags: &[&str], metadata: &[(&str, &str)]) -> DocumentLabels {
+        DocumentLabels {
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            metadata: metadata
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn labels_are_normalized_and_encoded() {
+        let l = normalize_labels(labels(
+            &[" Rust ", "rust", "", "async"],
+            &[("Author", " Knuth "), ("lang", "")],
+        ))
+        .unwrap();
+        assert_eq!(l, labels(&["rust", "async"], &[("author", "Knuth")]));
+        assert_eq!(encode_tags(&l), "|rust|async|");
+        assert_eq!(encode_metadata(&l), "|author=Knuth|");
+        assert_eq!(encode_tags(&DocumentLabels::default()), "");
+
+        assert!(normalize_labels(labels(&["a|b"], &[])).is_err());
+        assert!(normalize_labels(labels(&[], &[("a=b", "c")])).is_err());
+        assert!(normalize_labels(labels(&[], &[("", "c")])).is_err());
+    }
+
+    #[test]
+    fn parses_filter_terms() {
+        let f = Filter::parse(r#"tag:Rust,async -lang=de author="Knuth, Donald""#)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            f.terms,
+            [
+                Term {
+                    negate: false,
+                    field: Field::Tag,
+                    values: vec!["rust".into(), "async".into()],
+                },
+                Term {
+                    negate: true,
+                    field: Field::Meta("lang".into()),
+                    values: vec!["de".into()],
+                },
+                Term {
+                    negate: false,
+                    field: Field::Meta("author".into()),
+                    values: vec!["Knuth, Donald".into()],
+                },
+            ]
+        );
+        assert_eq!(Filter::parse("  ").unwrap(), None);
+        assert!(Filter::parse("rust").is_err());
+        assert!(Filter::parse("tag:").is_err());
+        assert!(Filter::parse("=x").is_err());
+        assert!(Filter::parse("author=\"Knuth").is_err());
+    }
+
+    #[test]
+    fn parses_non_ascii_terms() {
+        let f = Filter::parse("作者=高德纳 tag:日本語").unwrap().unwrap();
+        assert_eq!(
+            f.terms,
+            [
+                Term {
+                    negate: false,
+                    field: Field::Meta("作者".into()),
+                    values: vec!["高德纳".into()],
+                },
+                Term {
+                    negate: false,
+                    field: Field::Tag,
+                    values: vec!["日本語".into()],
+                },
+            ]
+        );
+        assert!(Filter::parse("日本").is_err());
+    }
+
+    #[test]
+    fn filter_sql_escapes_values() {
+        let f = Filter::parse("tag:c_sharp -author=O'Brien%").unwrap().unwrap();
+        assert_eq!(
+            f.to_sql(),
+            "(coalesce(tags, '') ILIKE '%|c\\_sharp|%' ESCAPE '\\') AND \
+             NOT (coalesce(metadata, '') ILIKE '%|author=O''Brien\\%|%' ESCAPE '\\')"
+        );
+    }
+}
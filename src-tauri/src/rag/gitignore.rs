@@ -0,0 +1,145 @@
+//! `.gitignore` support for walking codebases: the rules of the `.gitignore`
+//! files met on the way down, matched with `glob`. Covers comments, negation,
+//! directory-only and anchored patterns; global excludes and
+//! `.git/info/exclude` are not read.
+
+use std::path::{Path, PathBuf};
+
+use glob::{MatchOptions, Pattern};
+
+const OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+struct Rule {
+    /// Directory of the `.gitignore`, relative to the walk root.
+    dir: PathBuf,
+    pattern: Pattern,
+    negate: bool,
+    dir_only: bool,
+}
+
+/// Ignore rules in effect at some point of a walk. Later rules win.
+#[derive(Default)]
+pub struct Gitignore {
+    rules: Vec<Rule>,
+}
+
+impl Gitignore {
+    /// Add the rules of `root/dir/.gitignore`, if there is one. Returns the
+    /// previous rule count, to `truncate` to when leaving the directory.
+    pub fn push_dir(&mut self, root: &Path, dir: &Path) -> usize {
+        let len = self.rules.len();
+        if let Ok(content) = std::fs::read_to_string(root.join(dir).join(".gitignore")) {
+            self.rules.extend(parse(dir, &content));
+        }
+        len
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        self.rules.truncate(len);
+    }
+
+    /// Whether the file or directory at `rel` (relative to the walk root) is ignored.
+    pub fn is_ignored(&self, rel: &Path, is_dir: bool) -> bool {
+        let mut ignored = false;
+        for rule in &self.rules {
+            if rule.dir_only && !is_dir {
+                continue;
+            }
+            let Ok(sub) = rel.strip_prefix(&rule.dir) else {
+                continue;
+            };
+            if rule.pattern.matches_path_with(sub, OPTIONS) {
+                ignored = !rule.negate;
+            }
+        }
+        ignored
+    }
+
+    /// Whether a file is ignored itself or lies in an ignored directory.
+    pub fn is_file_ignored(&self, rel: &Path) -> bool {
+        rel.ancestors()
+            .skip(1)
+            .filter(|a| !a.as_os_str().is_empty())
+            .any(|a| self.is_ignored(a, true))
+            || self.is_ignored(rel, false)
+    }
+}
+
+fn parse(dir: &Path, content: &str) -> Vec<Rule> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim_end();
+            if line.is_empty() || line.starts_with('#') {
+                return None;
+            }
+            let (negate, line) = match line.strip_prefix('!') {
+                Some(rest) => (true, rest),
+                None => (false, line.strip_prefix('\\').unwrap_or(line)),
+            };
+            let (dir_only, line) = match line.strip_suffix('/') {
+                Some(rest) => (true, rest),
+                None => (false, line),
+            };
+            // A slash anywhere but the end anchors the pattern to the .gitignore's directory
+            let glob = if line.contains('/') {
+                line.trim_start_matches('/').to_string()
+            } else {
+                format!("**/{line}")
+            };
+            let pattern = Pattern::new(&glob).ok()?;
+            Some(Rule {
+                dir: dir.to_path_buf(),
+                pattern,
+                negate,
+                dir_only,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ignore(rules: &[(&str, &str)]) -> Gitignore {
+        Gitignore {
+            rules: rules
+                .iter()
+                .flat_map(|(dir, content)| parse(Path::new(dir), content))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn matches_gitignore_patterns() {
+        let gi = ignore(&[(
+            "",
+            "# build output\ntarget/\n*.log\n!keep.log\n/dist\ndocs/*.tmp\n",
+        )]);
+        assert!(gi.is_ignored(Path::new("target"), true));
+        assert!(gi.is_ignored(Path::new("crates/a/target"), true));
+        assert!(!gi.is_ignored(Path::new("target"), false));
+        assert!(gi.is_ignored(Path::new("src/debug.log"), false));
+        assert!(!gi.is_ignored(Path::new("src/keep.log"), false));
+        assert!(gi.is_ignored(Path::new("dist"), true));
+        assert!(!gi.is_ignored(Path::new("web/dist"), true));
+        assert!(gi.is_ignored(Path::new("docs/a.tmp"), false));
+        assert!(!gi.is_ignored(Path::new("docs/sub/a.tmp"), false));
+        assert!(!gi.is_ignored(Path::new("src/main.rs"), false));
+        assert!(gi.is_file_ignored(Path::new("target/debug/build.rs")));
+    }
+
+    #[test]
+    fn nested_rules_apply_below_their_directory() {
+        let gi = ignore(&[("", "*.gen.ts\n"), ("web", "/out\n!keep.gen.ts\n")]);
+        assert!(gi.is_ignored(Path::new("web/out"), true));
+        assert!(!gi.is_ignored(Path::new("out"), true));
+        assert!(gi.is_ignored(Path::new("api/x.gen.ts"), false));
+        assert!(!gi.is_ignored(Path::new("web/keep.gen.ts"), false));
+    }
+}
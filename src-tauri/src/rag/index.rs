@@ -1,15 +1,68 @@
 use arrow_array::{
     cast::AsArray, Array, FixedSizeListArray, Float32Array, RecordBatch, RecordBatchIterator,
-    RecordBatchReader, StringArray, UInt32Array,
+    RecordBatchReader, StringArray, UInt32Array, UInt64Array,
 };
 use arrow_schema::{ArrowError, DataType, Field, Schema};
-use lancedb::query::{ExecutableQuery, QueryBase};
+use futures_util::TryStreamExt;
+use lancedb::index::scalar::{FtsIndexBuilder, FullTextSearchQuery};
+use lancedb::index::{Index, IndexType};
+use lancedb::query::{ExecutableQuery, QueryBase, Select};
+use lancedb::table::NewColumnTransform;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::path::Path;
 use std::sync::Arc;
 
+use super::chunker::{ByteRange, Chunk, LineRange};
 use super::config as rag_config;
-use super::embedder;
+use super::filter;
+use super::parser::Location;
+use super::store::{self, DocumentLabels};
 
 const TABLE_NAME: &str = "chunks";
+/// Reciprocal rank fusion constant; damps the weight of the very top ranks.
+const RRF_K: f32 = 60.0;
+/// Hybrid search fetches this many candidates per requested result from each
+/// side before fusing, and at least `HYBRID_MIN_CANDIDATES`.
+const HYBRID_CANDIDATE_FACTOR: usize = 3;
+const HYBRID_MIN_CANDIDATES: usize = 20;
+/// Columns locating a chunk in its source, read with `ProvenanceColumns`.
+const PROVENANCE_COLUMNS: &[&str] = &[
+    "start_line",
+    "end_line",
+    "byte_start",
+    "byte_end",
+    "page",
+    "chapter",
+    "heading",
+    "timestamp",
+];
+
+/// How chunks are ranked against a query.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchMode {
+    /// Embedding similarity only.
+    Vector,
+    /// Full-text (BM25) match only: exact identifiers, error codes, names.
+    Keyword,
+    /// Both rankings merged with reciprocal rank fusion.
+    #[default]
+    Hybrid,
+}
+
+impl SearchMode {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "vector" => Ok(Self::Vector),
+            "keyword" => Ok(Self::Keyword),
+            "hybrid" => Ok(Self::Hybrid),
+            other => Err(format!(
+                "Unknown search mode '{other}' (expected vector, keyword or hybrid)"
+            )),
+        }
+    }
+}
 
 /// A single search result from the vector index.
 #[derive(Clone)]
@@ -17,7 +70,12 @@ pub struct RawSearchResult {
     pub chunk_text: String,
     pub document_id: String,
     pub chunk_index: u32,
+    pub lines: Option<LineRange>,
+    pub bytes: Option<ByteRange>,
+    pub location: Location,
     pub score: f32,
+    /// Cosine similarity to the query, for chunks found by vector search.
+    pub similarity: Option<f32>,
 }
 
 /// Search result enriched with document metadata, sent to the frontend.
@@ -25,10 +83,23 @@ pub struct RawSearchResult {
 #[serde(rename_all = "camelCase")]
 pub struct SearchResult {
     pub chunk_text: String,
+    pub base_id: String,
     pub document_id: String,
     pub document_name: String,
     pub chunk_index: u32,
+    pub lines: Option<LineRange>,
+    pub bytes: Option<ByteRange>,
+    pub location: Location,
+    /// Where the chunk is in its source, e.g. `src/main.rs:42` or `p. 42, § Setup`.
+    pub citation: Option<String>,
+    /// Link to the spot in the source, for videos with a timestamp.
+    pub link: Option<String>,
+    /// Ranking score: cosine similarity in vector mode, scaled rank fusion
+    /// (see `fuse_rankings`) in keyword and hybrid mode, relevance when
+    /// reranked. Only comparable within one search.
     pub score: f32,
+    /// Cosine similarity to the query; None for keyword-only hits.
+    pub similarity: Option<f32>,
 }
 
 /// Index status info.
@@ -39,12 +110,25 @@ pub struct IndexStatus {
     pub has_index: bool,
 }
 
-fn schema() -> Arc<Schema> {
-    let dim = embedder::embedding_dimension() as i32;
+fn schema(dim: i32) -> Arc<Schema> {
     Arc::new(Schema::new(vec![
         Field::new("text", DataType::Utf8, false),
         Field::new("document_id", DataType::Utf8, false),
         Field::new("chunk_index", DataType::UInt32, false),
+        // Columns added later must be nullable, see add_missing_columns
+        Field::new("start_line", DataType::UInt32, true),
+        Field::new("end_line", DataType::UInt32, true),
+        Field::new("byte_start", DataType::UInt64, true),
+        Field::new("byte_end", DataType::UInt64, true),
+        Field::new("page", DataType::UInt32, true),
+        Field::new("chapter", DataType::Utf8, true),
+        Field::new("heading", DataType::Utf8, true),
+        Field::new("timestamp", DataType::UInt32, true),
+        // The document's labels, see filter
+        Field::new("tags", DataType::Utf8, true),
+        Field::new("metadata", DataType::Utf8, true),
+        // SHA-256 of the text, see dedup
+        Field::new("chunk_hash", DataType::Utf8, true),
         Field::new(
             "vector",
             DataType::FixedSizeList(Arc::new(Field::new("item", DataType::Float32, true)), dim),
@@ -63,8 +147,11 @@ fn make_reader(
 }
 
 async fn open_db(base_id: &str) -> Result<lancedb::Connection, String> {
-    let lance_dir = rag_config::base_lance_dir(base_id);
-    let dir = lance_dir.clone();
+    open_db_at(&rag_config::base_lance_dir(base_id)).await
+}
+
+async fn open_db_at(lance_dir: &Path) -> Result<lancedb::Connection, String> {
+    let dir = lance_dir.to_path_buf();
     tokio::task::spawn_blocking(move || {
         std::fs::create_dir_all(&dir)
             .map_err(|e| format!("Failed to create lance dir: {e}"))
@@ -80,6 +167,7 @@ async fn open_db(base_id: &str) -> Result<lancedb::Connection, String> {
 
 async fn get_or_create_table(
     db: &lancedb::Connection,
+    dim: i32,
 ) -> Result<lancedb::Table, String> {
     let names = db
         .table_names()
@@ -88,12 +176,15 @@ async fn get_or_create_table(
         .map_err(|e| format!("Failed to list tables: {e}"))?;
 
     if names.contains(&TABLE_NAME.to_string()) {
-        db.open_table(TABLE_NAME)
+        let table = db
+            .open_table(TABLE_NAME)
             .execute()
             .await
-            .map_err(|e| format!("Failed to open table: {e}"))
+            .map_err(|e| format!("Failed to open table: {e}"))?;
+        add_missing_columns(&table).await?;
+        Ok(table)
     } else {
-        let s = schema();
+        let s = schema(dim);
         let batch = RecordBatch::new_empty(s.clone());
         let reader = make_reader(vec![batch], s);
         db.create_table(TABLE_NAME, reader)
@@ -103,6 +194,37 @@ async fn get_or_create_table(
     }
 }
 
+/// Bring a table created by an older version up to the current schema. New
+/// columns are empty for existing rows; this only touches table metadata.
+async fn add_missing_columns(table: &lancedb::Table) -> Result<(), String> {
+    let current = table
+        .schema()
+        .await
+        .map_err(|e| format!("Failed to read table schema: {e}"))?;
+    let dim = match current.field_with_name("vector").map(|f| f.data_type()) {
+        Ok(DataType::FixedSizeList(_, dim)) => *dim,
+        _ => return Err("Index table has no vector column".into()),
+    };
+    let missing: Vec<Field> = schema(dim)
+        .fields()
+        .iter()
+        .filter(|f| current.field_with_name(f.name()).is_err())
+        .map(|f| f.as_ref().clone())
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    table
+        .add_columns(
+            NewColumnTransform::AllNulls(Arc::new(Schema::new(missing))),
+            None,
+        )
+        .await
+        .map_err(|e| format!("Failed to upgrade index table: {e}"))?;
+    Ok(())
+}
+
 /// Build a FixedSizeListArray from flat f32 values.
 fn build_vector_array(
     embeddings: &[Vec<f32>],
@@ -116,37 +238,100 @@ fn build_vector_array(
     Ok(Arc::new(arr))
 }
 
-/// Add chunks with their embeddings to the index.
+/// Add chunks with their embeddings to the index, labelled with their
+/// document's tags and metadata.
 pub async fn add_chunks(
     base_id: &str,
     document_id: &str,
-    texts: &[String],
+    labels: &DocumentLabels,
+    chunks: &[Chunk],
+    embeddings: &[Vec<f32>],
+) -> Result<(), String> {
+    add_chunks_at(
+        &rag_config::base_lance_dir(base_id),
+        document_id,
+        labels,
+        chunks,
+        embeddings,
+    )
+    .await
+}
+
+/// Add chunks to the index stored in `lance_dir`. The table's vector size
+/// is taken from the embeddings when it is created.
+pub async fn add_chunks_at(
+    lance_dir: &Path,
+    document_id: &str,
+    labels: &DocumentLabels,
+    chunks: &[Chunk],
     embeddings: &[Vec<f32>],
 ) -> Result<(), String> {
-    if texts.is_empty() {
+    if chunks.is_empty() {
         return Ok(());
     }
+    if embeddings.len() != chunks.len() {
+        return Err(format!(
+            "Got {} embeddings for {} chunks",
+            embeddings.len(),
+            chunks.len()
+        ));
+    }
+    let dim = embeddings[0].len();
+    if embeddings.iter().any(|e| e.len() != dim) {
+        return Err("Embeddings have different dimensions".into());
+    }
+    let dim = dim as i32;
 
-    let db = open_db(base_id).await?;
-    let table = get_or_create_table(&db).await?;
-    let dim = embedder::embedding_dimension() as i32;
+    let db = open_db_at(lance_dir).await?;
+    let table = get_or_create_table(&db, dim).await?;
 
     let text_array = Arc::new(StringArray::from(
-        texts.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
+        chunks.iter().map(|c| c.text.as_str()).collect::<Vec<_>>(),
     )) as Arc<dyn Array>;
 
-    let doc_ids: Vec<&str> = vec![document_id; texts.len()];
+    let doc_ids: Vec<&str> = vec![document_id; chunks.len()];
     let doc_id_array = Arc::new(StringArray::from(doc_ids)) as Arc<dyn Array>;
 
-    let indices: Vec<u32> = (0..texts.len() as u32).collect();
+    let indices: Vec<u32> = (0..chunks.len() as u32).collect();
     let index_array = Arc::new(UInt32Array::from(indices)) as Arc<dyn Array>;
 
+    let u32_column = |f: &dyn Fn(&Chunk) -> Option<u32>| {
+        Arc::new(UInt32Array::from(chunks.iter().map(f).collect::<Vec<_>>())) as Arc<dyn Array>
+    };
+    let u64_column = |f: &dyn Fn(&Chunk) -> Option<u64>| {
+        Arc::new(UInt64Array::from(chunks.iter().map(f).collect::<Vec<_>>())) as Arc<dyn Array>
+    };
+    let str_column = |f: &dyn Fn(&Chunk) -> Option<&str>| {
+        Arc::new(StringArray::from(chunks.iter().map(f).collect::<Vec<_>>())) as Arc<dyn Array>
+    };
+
     let vector_array = build_vector_array(embeddings, dim)?;
 
-    let s = schema();
+    let s = schema(dim);
     let batch = RecordBatch::try_new(
         s.clone(),
-        vec![text_array, doc_id_array, index_array, vector_array],
+        vec![
+            text_array,
+            doc_id_array,
+            index_array,
+            u32_column(&|c| c.lines.map(|l| l.start)),
+            u32_column(&|c| c.lines.map(|l| l.end)),
+            u64_column(&|c| c.bytes.map(|b| b.start)),
+            u64_column(&|c| c.bytes.map(|b| b.end)),
+            u32_column(&|c| c.location.page),
+            str_column(&|c| c.location.chapter.as_deref()),
+            str_column(&|c| c.location.heading.as_deref()),
+            u32_column(&|c| c.location.timestamp),
+            Arc::new(StringArray::from(vec![filter::encode_tags(labels); chunks.len()])),
+            Arc::new(StringArray::from(vec![filter::encode_metadata(labels); chunks.len()])),
+            Arc::new(StringArray::from(
+                chunks
+                    .iter()
+                    .map(|c| store::content_hash(c.text.as_bytes()))
+                    .collect::<Vec<_>>(),
+            )),
+            vector_array,
+        ],
     )
     .map_err(|e| format!("Failed to create record batch: {e}"))?;
 
@@ -157,6 +342,11 @@ pub async fn add_chunks(
         .await
         .map_err(|e| format!("Failed to add chunks to index: {e}"))?;
 
+    // Keyword search builds it on demand too; not worth failing the upload over
+    if let Err(e) = ensure_fts_index(&table).await {
+        eprintln!("[rag] {e}");
+    }
+
     Ok(())
 }
 
@@ -167,6 +357,43 @@ pub async fn remove_document_chunks(base_id: &str, document_id: &str) -> Result<
     uuid::Uuid::parse_str(document_id)
         .map_err(|_| format!("Invalid document_id: '{document_id}' is not a valid UUID"))?;
 
+    let Some(table) = open_existing_table(base_id).await? else {
+        return Ok(());
+    };
+
+    table
+        .delete(&format!("document_id = '{document_id}'"))
+        .await
+        .map_err(|e| format!("Failed to delete chunks: {e}"))?;
+
+    Ok(())
+}
+
+/// Relabel all chunks of a document after its tags or metadata changed.
+pub async fn set_document_labels(
+    base_id: &str,
+    document_id: &str,
+    labels: &DocumentLabels,
+) -> Result<(), String> {
+    uuid::Uuid::parse_str(document_id)
+        .map_err(|_| format!("Invalid document_id: '{document_id}' is not a valid UUID"))?;
+
+    let Some(table) = open_existing_table(base_id).await? else {
+        return Ok(());
+    };
+    table
+        .update()
+        .only_if(format!("document_id = '{document_id}'"))
+        .column("tags", filter::sql_string(&filter::encode_tags(labels)))
+        .column("metadata", filter::sql_string(&filter::encode_metadata(labels)))
+        .execute()
+        .await
+        .map_err(|e| format!("Failed to update document labels: {e}"))?;
+    Ok(())
+}
+
+/// Open the chunks table, or None if nothing has been indexed yet.
+async fn open_existing_table(base_id: &str) -> Result<Option<lancedb::Table>, String> {
     let db = open_db(base_id).await?;
     let names = db
         .table_names()
@@ -175,7 +402,7 @@ pub async fn remove_document_chunks(base_id: &str, document_id: &str) -> Result<
         .map_err(|e| format!("Failed to list tables: {e}"))?;
 
     if !names.contains(&TABLE_NAME.to_string()) {
-        return Ok(());
+        return Ok(None);
     }
 
     let table = db
@@ -183,51 +410,356 @@ pub async fn remove_document_chunks(base_id: &str, document_id: &str) -> Result<
         .execute()
         .await
         .map_err(|e| format!("Failed to open table: {e}"))?;
+    add_missing_columns(&table).await?;
+    Ok(Some(table))
+}
+
+/// Build the full-text index on `text` if the table doesn't have one yet.
+/// Chunks added after it was built are still found (LanceDB scans them unindexed).
+async fn ensure_fts_index(table: &lancedb::Table) -> Result<(), String> {
+    let indices = table
+        .list_indices()
+        .await
+        .map_err(|e| format!("Failed to list indices: {e}"))?;
+    let has_fts = indices
+        .iter()
+        .any(|i| i.index_type == IndexType::FTS && i.columns.iter().any(|c| c == "text"));
+    if has_fts {
+        return Ok(());
+    }
 
     table
-        .delete(&format!("document_id = '{document_id}'"))
+        .create_index(&["text"], Index::FTS(FtsIndexBuilder::default()))
+        .execute()
         .await
-        .map_err(|e| format!("Failed to delete chunks: {e}"))?;
+        .map_err(|e| format!("Failed to create full-text index: {e}"))
+}
 
-    Ok(())
+/// The columns of a result batch locating each chunk in its source.
+struct ProvenanceColumns<'a> {
+    start_line: &'a UInt32Array,
+    end_line: &'a UInt32Array,
+    byte_start: &'a UInt64Array,
+    byte_end: &'a UInt64Array,
+    page: &'a UInt32Array,
+    chapter: &'a StringArray,
+    heading: &'a StringArray,
+    timestamp: &'a UInt32Array,
 }
 
-/// Search for similar chunks using vector similarity.
+impl<'a> ProvenanceColumns<'a> {
+    fn of(batch: &'a RecordBatch) -> Result<Self, String> {
+        let column = |name: &str| {
+            batch
+                .column_by_name(name)
+                .ok_or_else(|| format!("Missing '{name}' column"))
+        };
+        let u32_column = |name: &str| {
+            column(name).map(|c| c.as_primitive::<arrow_array::types::UInt32Type>())
+        };
+        let u64_column = |name: &str| {
+            column(name).map(|c| c.as_primitive::<arrow_array::types::UInt64Type>())
+        };
+        Ok(Self {
+            start_line: u32_column("start_line")?,
+            end_line: u32_column("end_line")?,
+            byte_start: u64_column("byte_start")?,
+            byte_end: u64_column("byte_end")?,
+            page: u32_column("page")?,
+            chapter: column("chapter")?.as_string::<i32>(),
+            heading: column("heading")?.as_string::<i32>(),
+            timestamp: u32_column("timestamp")?,
+        })
+    }
+
+    fn lines(&self, row: usize) -> Option<LineRange> {
+        (self.start_line.is_valid(row) && self.end_line.is_valid(row)).then(|| LineRange {
+            start: self.start_line.value(row),
+            end: self.end_line.value(row),
+        })
+    }
+
+    fn bytes(&self, row: usize) -> Option<ByteRange> {
+        (self.byte_start.is_valid(row) && self.byte_end.is_valid(row)).then(|| ByteRange {
+            start: self.byte_start.value(row),
+            end: self.byte_end.value(row),
+        })
+    }
+
+    fn location(&self, row: usize) -> Location {
+        let string = |a: &StringArray| a.is_valid(row).then(|| a.value(row).to_string());
+        Location {
+            page: self.page.is_valid(row).then(|| self.page.value(row)),
+            chapter: string(self.chapter),
+            heading: string(self.heading),
+            timestamp: self.timestamp.is_valid(row).then(|| self.timestamp.value(row)),
+        }
+    }
+}
+
+/// Turn result batches into search results, scoring each row from `score_column`.
+fn collect_results(
+    batches: &[RecordBatch],
+    score_column: &str,
+    to_score: impl Fn(f32) -> f32,
+) -> Result<Vec<RawSearchResult>, String> {
+    let mut out = Vec::new();
+    for batch in batches {
+        let texts = batch
+            .column_by_name("text")
+            .ok_or("Missing 'text' column")?
+            .as_string::<i32>();
+        let doc_ids = batch
+            .column_by_name("document_id")
+            .ok_or("Missing 'document_id' column")?
+            .as_string::<i32>();
+        let chunk_indices = batch
+            .column_by_name("chunk_index")
+            .ok_or("Missing 'chunk_index' column")?
+            .as_primitive::<arrow_array::types::UInt32Type>();
+        let scores = batch
+            .column_by_name(score_column)
+            .ok_or_else(|| format!("Missing '{score_column}' column"))?
+            .as_primitive::<arrow_array::types::Float32Type>();
+        let provenance = ProvenanceColumns::of(batch)?;
+
+        for i in 0..batch.num_rows() {
+            out.push(RawSearchResult {
+                chunk_text: texts.value(i).to_string(),
+                document_id: doc_ids.value(i).to_string(),
+                chunk_index: chunk_indices.value(i),
+                lines: provenance.lines(i),
+                bytes: provenance.bytes(i),
+                location: provenance.location(i),
+                score: to_score(scores.value(i)),
+                similarity: None,
+            });
+        }
+    }
+    Ok(out)
+}
+
+/// Search for similar chunks using vector similarity. `filter` is a SQL
+/// predicate on the chunk columns (see `filter::Filter::to_sql`).
 pub async fn search(
     base_id: &str,
     query_embedding: &[f32],
     limit: usize,
+    filter: Option<&str>,
 ) -> Result<Vec<RawSearchResult>, String> {
-    let db = open_db(base_id).await?;
-    let names = db
-        .table_names()
+    let Some(table) = open_existing_table(base_id).await? else {
+        return Ok(Vec::new());
+    };
+
+    let mut query = table
+        .vector_search(query_embedding)
+        .map_err(|e| format!("Failed to create search query: {e}"))?
+        .limit(limit);
+    if let Some(filter) = filter {
+        query = query.only_if(filter);
+    }
+    let results = query
         .execute()
         .await
-        .map_err(|e| format!("Failed to list tables: {e}"))?;
+        .map_err(|e| format!("Failed to execute search: {e}"))?;
 
-    if !names.contains(&TABLE_NAME.to_string()) {
+    let batches: Vec<RecordBatch> = results
+        .try_collect()
+        .await
+        .map_err(|e| format!("Failed to collect search results: {e}"))?;
+
+    let mut results = collect_results(&batches, "_distance", |d| 1.0 - d)?;
+    for r in &mut results {
+        r.similarity = Some(r.score);
+    }
+    Ok(results)
+}
+
+/// Search for chunks containing the query terms (BM25 over the full-text index).
+/// Scores are raw BM25, not comparable to vector scores.
+pub async fn keyword_search(
+    base_id: &str,
+    query: &str,
+    limit: usize,
+    filter: Option<&str>,
+) -> Result<Vec<RawSearchResult>, String> {
+    if query.trim().is_empty() {
         return Ok(Vec::new());
     }
+    let Some(table) = open_existing_table(base_id).await? else {
+        return Ok(Vec::new());
+    };
+    ensure_fts_index(&table).await?;
 
-    let table = db
-        .open_table(TABLE_NAME)
+    let mut fts = table
+        .query()
+        .full_text_search(FullTextSearchQuery::new(query.to_string()))
+        .limit(limit);
+    if let Some(filter) = filter {
+        fts = fts.only_if(filter);
+    }
+    let results = fts
         .execute()
         .await
-        .map_err(|e| format!("Failed to open table: {e}"))?;
+        .map_err(|e| format!("Failed to execute keyword search: {e}"))?;
 
-    let results = table
-        .vector_search(query_embedding)
-        .map_err(|e| format!("Failed to create search query: {e}"))?
-        .limit(limit)
+    let batches: Vec<RecordBatch> = results
+        .try_collect()
+        .await
+        .map_err(|e| format!("Failed to collect search results: {e}"))?;
+
+    collect_results(&batches, "_score", |s| s)
+}
+
+/// Merge ranked lists with reciprocal rank fusion: a chunk scores
+/// sum(1 / (k + rank)) over the lists it appears in. Scores are scaled so a
+/// chunk ranked first in every list gets 1.0, which keeps them comparable
+/// across bases. Keeps each chunk's text from the first list that has it and
+/// its similarity from whichever list has one.
+fn fuse_rankings(lists: Vec<Vec<RawSearchResult>>, limit: usize) -> Vec<RawSearchResult> {
+    if lists.is_empty() {
+        return Vec::new();
+    }
+    let best = lists.len() as f32 / (RRF_K + 1.0);
+    let mut fused: Vec<RawSearchResult> = Vec::new();
+    let mut positions: HashMap<(String, u32), usize> = HashMap::new();
+    for list in lists {
+        for (rank, result) in list.into_iter().enumerate() {
+            let score = 1.0 / (RRF_K + rank as f32 + 1.0) / best;
+            let key = (result.document_id.clone(), result.chunk_index);
+            match positions.get(&key) {
+                Some(&pos) => {
+                    fused[pos].score += score;
+                    fused[pos].similarity = fused[pos].similarity.or(result.similarity);
+                }
+                None => {
+                    positions.insert(key, fused.len());
+                    fused.push(RawSearchResult { score, ..result });
+                }
+            }
+        }
+    }
+    fused.sort_by(|a, b| b.score.total_cmp(&a.score));
+    fused.truncate(limit);
+    fused
+}
+
+/// Search a base in the given mode. `query_embedding` is needed for vector
+/// and hybrid search. Keyword and hybrid scores are rank-based (see
+/// `fuse_rankings`). If keyword search fails, hybrid falls back to vector results.
+pub async fn search_with_mode(
+    base_id: &str,
+    query: &str,
+    query_embedding: Option<&[f32]>,
+    mode: SearchMode,
+    limit: usize,
+    filter: Option<&str>,
+) -> Result<Vec<RawSearchResult>, String> {
+    let embedding = || query_embedding.ok_or("Query embedding is required for vector search");
+    match mode {
+        SearchMode::Vector => search(base_id, embedding()?, limit, filter).await,
+        SearchMode::Keyword => {
+            let results = keyword_search(base_id, query, limit, filter).await?;
+            Ok(fuse_rankings(vec![results], limit))
+        }
+        SearchMode::Hybrid => {
+            let pool = (limit * HYBRID_CANDIDATE_FACTOR).max(HYBRID_MIN_CANDIDATES);
+            let vector = search(base_id, embedding()?, pool, filter).await?;
+            match keyword_search(base_id, query, pool, filter).await {
+                Ok(keyword) => Ok(fuse_rankings(vec![vector, keyword], limit)),
+                Err(e) => {
+                    eprintln!("[rag] Keyword search failed, using vector results only: {e}");
+                    let mut vector = vector;
+                    vector.truncate(limit);
+                    Ok(vector)
+                }
+            }
+        }
+    }
+}
+
+/// A chunk as stored in the index, without its vector.
+pub struct StoredChunk {
+    pub text: String,
+    pub document_id: String,
+    pub chunk_index: u32,
+    pub lines: Option<LineRange>,
+    pub bytes: Option<ByteRange>,
+    pub location: Location,
+}
+
+/// Read every chunk of a base, ordered by document and chunk index.
+pub async fn all_chunks(base_id: &str) -> Result<Vec<StoredChunk>, String> {
+    let Some(table) = open_existing_table(base_id).await? else {
+        return Ok(Vec::new());
+    };
+    read_chunks(&table, None).await
+}
+
+/// Like `all_chunks`, with each chunk's vector.
+pub async fn all_chunks_with_vectors(base_id: &str) -> Result<Vec<(StoredChunk, Vec<f32>)>, String> {
+    let Some(table) = open_existing_table(base_id).await? else {
+        return Ok(Vec::new());
+    };
+    read_chunk_rows(&table, None, true).await
+}
+
+/// Read the chunks of one document, in order; only those in `range` if given.
+pub async fn document_chunks(
+    base_id: &str,
+    document_id: &str,
+    range: Option<RangeInclusive<u32>>,
+) -> Result<Vec<StoredChunk>, String> {
+    // The id goes into a filter expression, same as in remove_document_chunks
+    uuid::Uuid::parse_str(document_id)
+        .map_err(|_| format!("Invalid document_id: '{document_id}' is not a valid UUID"))?;
+
+    let Some(table) = open_existing_table(base_id).await? else {
+        return Ok(Vec::new());
+    };
+    let mut filter = format!("document_id = '{document_id}'");
+    if let Some(range) = range {
+        filter.push_str(&format!(
+            " AND chunk_index >= {} AND chunk_index <= {}",
+            range.start(),
+            range.end()
+        ));
+    }
+    read_chunks(&table, Some(filter)).await
+}
+
+async fn read_chunks(
+    table: &lancedb::Table,
+    filter: Option<String>,
+) -> Result<Vec<StoredChunk>, String> {
+    let rows = read_chunk_rows(table, filter, false).await?;
+    Ok(rows.into_iter().map(|(chunk, _)| chunk).collect())
+}
+
+/// Read chunks in document and chunk order; the vectors are empty unless
+/// `with_vectors` is set.
+async fn read_chunk_rows(
+    table: &lancedb::Table,
+    filter: Option<String>,
+    with_vectors: bool,
+) -> Result<Vec<(StoredChunk, Vec<f32>)>, String> {
+    let mut columns = [&["text", "document_id", "chunk_index"], PROVENANCE_COLUMNS].concat();
+    if with_vectors {
+        columns.push("vector");
+    }
+    let mut query = table.query().select(Select::columns(&columns));
+    if let Some(filter) = filter {
+        query = query.only_if(filter);
+    }
+    let results = query
         .execute()
         .await
-        .map_err(|e| format!("Failed to execute search: {e}"))?;
+        .map_err(|e| format!("Failed to read chunks: {e}"))?;
 
-    use futures_util::TryStreamExt;
     let batches: Vec<RecordBatch> = results
         .try_collect()
         .await
-        .map_err(|e| format!("Failed to collect search results: {e}"))?;
+        .map_err(|e| format!("Failed to collect chunks: {e}"))?;
 
     let mut out = Vec::new();
     for batch in &batches {
@@ -243,45 +775,174 @@ pub async fn search(
             .column_by_name("chunk_index")
             .ok_or("Missing 'chunk_index' column")?
             .as_primitive::<arrow_array::types::UInt32Type>();
-        let distances = batch
-            .column_by_name("_distance")
-            .ok_or("Missing '_distance' column")?
-            .as_primitive::<arrow_array::types::Float32Type>();
+        let provenance = ProvenanceColumns::of(batch)?;
+        let vectors = if with_vectors {
+            Some(
+                batch
+                    .column_by_name("vector")
+                    .ok_or("Missing 'vector' column")?
+                    .as_fixed_size_list(),
+            )
+        } else {
+            None
+        };
 
         for i in 0..batch.num_rows() {
-            out.push(RawSearchResult {
-                chunk_text: texts.value(i).to_string(),
+            let chunk = StoredChunk {
+                text: texts.value(i).to_string(),
                 document_id: doc_ids.value(i).to_string(),
                 chunk_index: chunk_indices.value(i),
-                score: 1.0 - distances.value(i),
+                lines: provenance.lines(i),
+                bytes: provenance.bytes(i),
+                location: provenance.location(i),
+            };
+            let vector = vectors.map_or_else(Vec::new, |v| {
+                v.value(i)
+                    .as_primitive::<arrow_array::types::Float32Type>()
+                    .values()
+                    .to_vec()
             });
+            out.push((chunk, vector));
         }
     }
-
+    out.sort_by(|(a, _), (b, _)| {
+        a.document_id
+            .cmp(&b.document_id)
+            .then(a.chunk_index.cmp(&b.chunk_index))
+    });
     Ok(out)
 }
 
-/// Get index status for a knowledge base.
-pub async fn get_status(base_id: &str) -> Result<IndexStatus, String> {
-    let db = open_db(base_id).await?;
-    let names = db
-        .table_names()
+/// A chunk's identity, text hash and vector, for duplicate detection.
+pub struct ChunkFingerprint {
+    pub document_id: String,
+    pub chunk_index: u32,
+    pub text: String,
+    pub hash: String,
+    pub vector: Vec<f32>,
+}
+
+/// Read the hash and vector of every chunk of a base. Chunks indexed before
+/// hashes were stored are hashed on the fly.
+pub async fn chunk_fingerprints(base_id: &str) -> Result<Vec<ChunkFingerprint>, String> {
+    let Some(table) = open_existing_table(base_id).await? else {
+        return Ok(Vec::new());
+    };
+    let batches: Vec<RecordBatch> = table
+        .query()
+        .select(Select::columns(&[
+            "text",
+            "document_id",
+            "chunk_index",
+            "chunk_hash",
+            "vector",
+        ]))
         .execute()
         .await
-        .map_err(|e| format!("Failed to list tables: {e}"))?;
+        .map_err(|e| format!("Failed to read chunks: {e}"))?
+        .try_collect()
+        .await
+        .map_err(|e| format!("Failed to collect chunks: {e}"))?;
 
-    if !names.contains(&TABLE_NAME.to_string()) {
+    let mut out = Vec::new();
+    for batch in &batches {
+        let texts = batch
+            .column_by_name("text")
+            .ok_or("Missing 'text' column")?
+            .as_string::<i32>();
+        let doc_ids = batch
+            .column_by_name("document_id")
+            .ok_or("Missing 'document_id' column")?
+            .as_string::<i32>();
+        let chunk_indices = batch
+            .column_by_name("chunk_index")
+            .ok_or("Missing 'chunk_index' column")?
+            .as_primitive::<arrow_array::types::UInt32Type>();
+        let hashes = batch
+            .column_by_name("chunk_hash")
+            .ok_or("Missing 'chunk_hash' column")?
+            .as_string::<i32>();
+        let vectors = batch
+            .column_by_name("vector")
+            .ok_or("Missing 'vector' column")?
+            .as_fixed_size_list();
+
+        for i in 0..batch.num_rows() {
+            let text = texts.value(i);
+            let hash = if hashes.is_null(i) {
+                store::content_hash(text.as_bytes())
+            } else {
+                hashes.value(i).to_string()
+            };
+            let vector = vectors.value(i);
+            out.push(ChunkFingerprint {
+                document_id: doc_ids.value(i).to_string(),
+                chunk_index: chunk_indices.value(i),
+                text: text.to_string(),
+                hash,
+                vector: vector
+                    .as_primitive::<arrow_array::types::Float32Type>()
+                    .values()
+                    .to_vec(),
+            });
+        }
+    }
+    out.sort_by(|a, b| {
+        a.document_id
+            .cmp(&b.document_id)
+            .then(a.chunk_index.cmp(&b.chunk_index))
+    });
+    Ok(out)
+}
+
+/// Rebuild the parsed text covered by consecutive chunks of one document.
+/// Chunks with byte ranges are laid out by offset, so overlapping text is not
+/// repeated (the whitespace between chunks is approximated). Chunks indexed
+/// without ranges are joined with blank lines.
+pub fn stitch_chunks(chunks: &[StoredChunk]) -> String {
+    let mut text = String::new();
+    // Parsed-text offset that `text` ends at, while ranges line up
+    let mut end: Option<u64> = None;
+    for chunk in chunks {
+        let bytes = chunk
+            .bytes
+            .filter(|b| b.end - b.start == chunk.text.len() as u64);
+        match (bytes, end) {
+            (Some(b), Some(e)) if b.start >= e => {
+                let gap = (b.start - e) as usize;
+                text.push_str(&if gap == 1 { " ".to_string() } else { "\n".repeat(gap) });
+                text.push_str(&chunk.text);
+            }
+            (Some(b), Some(e)) => {
+                // Overlaps the previous chunk; append only what's new
+                if let Some(rest) = chunk.text.get((e - b.start) as usize..) {
+                    text.push_str(rest);
+                }
+            }
+            _ => {
+                if !text.is_empty() {
+                    text.push_str("\n\n");
+                }
+                text.push_str(&chunk.text);
+            }
+        }
+        end = match (bytes, end) {
+            (Some(b), Some(e)) => Some(b.end.max(e)),
+            (Some(b), None) if text.len() == chunk.text.len() => Some(b.end),
+            _ => None,
+        };
+    }
+    text
+}
+
+/// Get index status for a knowledge base.
+pub async fn get_status(base_id: &str) -> Result<IndexStatus, String> {
+    let Some(table) = open_existing_table(base_id).await? else {
         return Ok(IndexStatus {
             total_chunks: 0,
             has_index: false,
         });
-    }
-
-    let table = db
-        .open_table(TABLE_NAME)
-        .execute()
-        .await
-        .map_err(|e| format!("Failed to open table: {e}"))?;
+    };
 
     let count = table
         .count_rows(None)
@@ -293,3 +954,106 @@ pub async fn get_status(base_id: &str) -> Result<IndexStatus, String> {
         has_index: count > 0,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(doc: &str, chunk: u32) -> RawSearchResult {
+        RawSearchResult {
+            chunk_text: format!("{doc}#{chunk}"),
+            document_id: doc.to_string(),
+            chunk_index: chunk,
+            lines: None,
+            bytes: None,
+            location: Location::default(),
+            score: 0.0,
+            similarity: None,
+        }
+    }
+
+    fn keys(results: &[RawSearchResult]) -> Vec<(&str, u32)> {
+        results
+            .iter()
+            .map(|r| (r.document_id.as_str(), r.chunk_index))
+            .collect()
+    }
+
+    #[test]
+    fn fusion_favors_chunks_in_both_rankings() {
+        let vector = vec![hit("a", 0), hit("b", 0), hit("c", 0)];
+        let keyword = vec![hit("c", 0), hit("d", 1), hit("b", 0)];
+        let fused = fuse_rankings(vec![vector, keyword], 10);
+        // c is ranked 3rd and 1st, b 2nd and 3rd, a and d only once
+        assert_eq!(keys(&fused), [("c", 0), ("b", 0), ("a", 0), ("d", 1)]);
+        assert!(fused.iter().all(|r| r.score > 0.0 && r.score <= 1.0));
+
+        // Top of every list scores 1.0
+        let top = fuse_rankings(vec![vec![hit("a", 0)], vec![hit("a", 0)]], 10);
+        assert!((top[0].score - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn fusion_keeps_vector_similarity() {
+        let similar = |doc: &str, similarity: f32| RawSearchResult {
+            similarity: Some(similarity),
+            ..hit(doc, 0)
+        };
+        let vector = vec![similar("a", 0.8), similar("b", 0.6)];
+        let keyword = vec![hit("b", 0), hit("c", 0)];
+        let fused = fuse_rankings(vec![keyword, vector], 10);
+        let similarities: Vec<Option<f32>> = fused.iter().map(|r| r.similarity).collect();
+        assert_eq!(keys(&fused), [("b", 0), ("a", 0), ("c", 0)]);
+        assert_eq!(similarities, [Some(0.6), Some(0.8), None]);
+    }
+
+    #[test]
+    fn fusion_truncates_and_handles_empty() {
+        let fused = fuse_rankings(vec![vec![hit("a", 0), hit("a", 1), hit("a", 2)]], 2);
+        assert_eq!(keys(&fused), [("a", 0), ("a", 1)]);
+        assert!(fuse_rankings(Vec::new(), 5).is_empty());
+        assert!(fuse_rankings(vec![Vec::new(), Vec::new()], 5).is_empty());
+    }
+
+    fn stored(index: u32, text: &str, start: Option<u64>) -> StoredChunk {
+        StoredChunk {
+            text: text.to_string(),
+            document_id: "doc".to_string(),
+            chunk_index: index,
+            lines: None,
+            bytes: start.map(|start| ByteRange {
+                start,
+                end: start + text.len() as u64,
+            }),
+            location: Location::default(),
+        }
+    }
+
+    #[test]
+    fn stitching_drops_overlaps_and_keeps_gaps() {
+        // "One two three.\n\nFour five." split with overlap
+        let chunks = [
+            stored(0, "One two three.", Some(0)),
+            stored(1, "three.", Some(8)),
+            stored(2, "Four five.", Some(16)),
+        ];
+        assert_eq!(stitch_chunks(&chunks), "One two three.\n\nFour five.");
+
+        let chunks = [stored(0, "a b", Some(0)), stored(1, "b c", Some(2))];
+        assert_eq!(stitch_chunks(&chunks), "a b c");
+        assert_eq!(stitch_chunks(&[]), "");
+    }
+
+    #[test]
+    fn stitching_without_ranges_joins_chunks() {
+        let chunks = [stored(0, "First", None), stored(1, "Second", Some(7))];
+        assert_eq!(stitch_chunks(&chunks), "First\n\nSecond");
+    }
+
+    #[test]
+    fn search_mode_parses() {
+        assert_eq!(SearchMode::parse("keyword").unwrap(), SearchMode::Keyword);
+        assert_eq!(SearchMode::default(), SearchMode::Hybrid);
+        assert!(SearchMode::parse("bm25").is_err());
+    }
+}
@@ -6,7 +6,7 @@ use std::sync::{Arc, Mutex};
 use tokio::sync::RwLock;
 
 use crate::mcp_transport::{self, McpServerInfo, McpState, McpToolHandler};
-use super::{commands, embedder, index, rag_settings, store, validate_uuid};
+use super::{commands, filter, index, migrate, rag_settings, store, validate_uuid};
 
 // ── State ──
 
@@ -63,12 +63,21 @@ pub fn stop_server_sync() {
 
 // ── Tool execution ──
 
+/// Chunks on each side of the requested one in get_chunk_context.
+const DEFAULT_CONTEXT_WINDOW: u64 = 2;
+const MAX_CONTEXT_WINDOW: u64 = 10;
+/// Characters per read_document page.
+const DEFAULT_READ_LENGTH: u64 = 8000;
+const MAX_READ_LENGTH: u64 = 50_000;
+
 async fn execute_tool(name: &str, args: &Value) -> Result<String, String> {
     match name {
         "search_knowledge_base" => tool_search(args).await,
         "list_knowledge_bases" => tool_list_bases().await,
         "get_document_info" => tool_get_documents(args).await,
         "reindex_document" => tool_reindex_document(args).await,
+        "get_chunk_context" => tool_get_chunk_context(args).await,
+        "read_document" => tool_read_document(args).await,
         _ => Err(format!("Unknown tool: {name}")),
     }
 }
@@ -77,18 +86,16 @@ async fn tool_search(args: &Value) -> Result<String, String> {
     let query = args["query"]
         .as_str()
         .ok_or("Missing 'query' parameter")?;
-    let default_limit = rag_settings::load().search_results_limit as u64;
+    let settings = rag_settings::load();
+    let default_limit = settings.search_results_limit as u64;
     let limit = (args["limit"].as_u64().unwrap_or(default_limit) as usize).clamp(1, 100);
+    let candidates = commands::candidate_count(&settings, limit);
 
-    let q = query.to_string();
-    let embeddings = tokio::task::spawn_blocking(move || embedder::embed_texts(&[q]))
-        .await
-        .map_err(|e| format!("Task join error: {e}"))??;
-
-    let query_vec = embeddings
-        .into_iter()
-        .next()
-        .ok_or("Failed to embed query")?;
+    let mode = match args["mode"].as_str() {
+        Some(m) => index::SearchMode::parse(m)?,
+        None => index::SearchMode::default(),
+    };
+    let filter = filter::Filter::parse(args["filter"].as_str().unwrap_or_default())?;
 
     let base_ids: Vec<String> = if let Some(id) = args["base_id"].as_str() {
         validate_uuid(id, "base_id")?;
@@ -102,15 +109,14 @@ async fn tool_search(args: &Value) -> Result<String, String> {
 
     let mut all_results = Vec::new();
     for base_id in &base_ids {
-        let raw = index::search(base_id, &query_vec, limit).await?;
-        if !raw.is_empty() {
-            let enriched = commands::enrich_results(base_id, raw).await?;
-            all_results.extend(enriched);
-        }
+        let results =
+            commands::search_base(base_id, query, mode, candidates, filter.as_ref()).await?;
+        all_results.extend(results);
     }
 
     all_results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
-    all_results.truncate(limit);
+    all_results.truncate(candidates);
+    let all_results = commands::rerank_results(&settings, query, all_results, limit).await;
 
     if all_results.is_empty() {
         return Ok("No results found.".into());
@@ -118,10 +124,28 @@ async fn tool_search(args: &Value) -> Result<String, String> {
 
     let mut output = String::new();
     for r in &all_results {
+        // Code chunks cite path:line so the agent can open the file there
+        let mut source = match (&r.citation, r.lines) {
+            (Some(citation), Some(lines)) => {
+                format!("{citation} (lines {}-{})", lines.start, lines.end)
+            }
+            (Some(citation), None) => format!("{}, {citation}", r.document_name),
+            _ => r.document_name.clone(),
+        };
+        if let Some(link) = &r.link {
+            source.push_str(&format!(" <{link}>"));
+        }
+        // Fused and reranked scores only order results, similarity is what reads as a percentage
+        let relevance = match r.similarity {
+            Some(similarity) => format!("similarity: {:.0}%", similarity * 100.0),
+            None => "keyword match".into(),
+        };
         output.push_str(&format!(
-            "--- From: {} (score: {:.0}%) ---\n{}\n\n",
-            r.document_name,
-            r.score * 100.0,
+            "--- From: {} ({relevance}, base_id: {}, document_id: {}, chunk_index: {}) ---\n{}\n\n",
+            source,
+            r.base_id,
+            r.document_id,
+            r.chunk_index,
             r.chunk_text
         ));
     }
@@ -141,8 +165,15 @@ async fn tool_list_bases() -> Result<String, String> {
     let mut output = String::new();
     for b in &bases {
         output.push_str(&format!(
-            "- {} (id: {}, {} documents)\n",
-            b.name, b.id, b.document_count
+            "- {} (id: {}, {}, {} documents, model: {})\n",
+            b.name,
+            b.id,
+            match b.kind {
+                store::BaseKind::Documents => "documents",
+                store::BaseKind::Codebase => "codebase",
+            },
+            b.document_count,
+            b.embedding_model
         ));
     }
 
@@ -166,8 +197,15 @@ async fn tool_get_documents(args: &Value) -> Result<String, String> {
 
     let mut output = String::new();
     for d in &docs {
+        let mut labels: Vec<String> = d.labels.tags.iter().map(|t| format!("tag:{t}")).collect();
+        labels.extend(d.labels.metadata.iter().map(|(k, v)| format!("{k}={v}")));
+        let labels = if labels.is_empty() {
+            String::new()
+        } else {
+            format!(", {}", labels.join(" "))
+        };
         output.push_str(&format!(
-            "- {} (id: {}, {} chunks, {} bytes)\n",
+            "- {} (id: {}, {} chunks, {} bytes{labels})\n",
             d.filename, d.id, d.chunk_count, d.size_bytes
         ));
     }
@@ -192,16 +230,7 @@ async fn tool_reindex_document(args: &Value) -> Result<String, String> {
         ));
     }
 
-    let bid = base_id.to_string();
-    let did = document_id.to_string();
-    let doc = tokio::task::spawn_blocking(move || {
-        let docs = store::list_documents(&bid)?;
-        docs.into_iter()
-            .find(|d| d.id == did)
-            .ok_or_else(|| format!("Document '{did}' not found"))
-    })
-    .await
-    .map_err(|e| format!("Task join error: {e}"))??;
+    let doc = find_document(base_id, document_id).await?;
 
     if doc.path.starts_with("http://") || doc.path.starts_with("https://") {
         return Err(format!(
@@ -210,6 +239,7 @@ async fn tool_reindex_document(args: &Value) -> Result<String, String> {
         ));
     }
 
+    let _access = migrate::write_access(base_id)?;
     let new_chunk_count = commands::reindex_single_document(
         base_id,
         &doc,
@@ -224,19 +254,127 @@ async fn tool_reindex_document(args: &Value) -> Result<String, String> {
     ))
 }
 
+async fn tool_get_chunk_context(args: &Value) -> Result<String, String> {
+    let base_id = args["base_id"]
+        .as_str()
+        .ok_or("Missing 'base_id' parameter")?;
+    validate_uuid(base_id, "base_id")?;
+    let document_id = args["document_id"]
+        .as_str()
+        .ok_or("Missing 'document_id' parameter")?;
+    validate_uuid(document_id, "document_id")?;
+    let chunk_index = args["chunk_index"]
+        .as_u64()
+        .ok_or("Missing 'chunk_index' parameter")?
+        .min(u32::MAX as u64) as u32;
+    let window = args["window"]
+        .as_u64()
+        .unwrap_or(DEFAULT_CONTEXT_WINDOW)
+        .min(MAX_CONTEXT_WINDOW) as u32;
+
+    let doc = find_document(base_id, document_id).await?;
+    let range = chunk_index.saturating_sub(window)..=chunk_index.saturating_add(window);
+    let chunks = index::document_chunks(base_id, document_id, Some(range)).await?;
+    let (Some(first), Some(last)) = (chunks.first(), chunks.last()) else {
+        return Err(format!(
+            "Chunk {chunk_index} not found in '{}' ({} chunks)",
+            doc.filename, doc.chunk_count
+        ));
+    };
+
+    // Same citation forms as search results
+    let source = match (first.lines, last.lines) {
+        (Some(a), Some(b)) => format!(" ({}:{}-{})", doc.path, a.start, b.end),
+        _ => first
+            .location
+            .label()
+            .map(|l| format!(", {l}"))
+            .unwrap_or_default(),
+    };
+    Ok(format!(
+        "--- {}{source}, chunks {}-{} of {} ---\n{}",
+        doc.filename,
+        first.chunk_index,
+        last.chunk_index,
+        doc.chunk_count,
+        index::stitch_chunks(&chunks)
+    ))
+}
+
+async fn tool_read_document(args: &Value) -> Result<String, String> {
+    let base_id = args["base_id"]
+        .as_str()
+        .ok_or("Missing 'base_id' parameter")?;
+    validate_uuid(base_id, "base_id")?;
+    let document_id = args["document_id"]
+        .as_str()
+        .ok_or("Missing 'document_id' parameter")?;
+    validate_uuid(document_id, "document_id")?;
+    let offset = args["offset"].as_u64().unwrap_or(0) as usize;
+    let length = args["length"]
+        .as_u64()
+        .unwrap_or(DEFAULT_READ_LENGTH)
+        .clamp(1, MAX_READ_LENGTH) as usize;
+
+    let doc = find_document(base_id, document_id).await?;
+    let chunks = index::document_chunks(base_id, document_id, None).await?;
+    if chunks.is_empty() {
+        return Err(format!("Document '{}' has no indexed text", doc.filename));
+    }
+
+    let text = index::stitch_chunks(&chunks);
+    let total = text.chars().count();
+    if offset >= total {
+        return Err(format!(
+            "Offset {offset} is past the end of '{}' ({total} characters)",
+            doc.filename
+        ));
+    }
+    let page: String = text.chars().skip(offset).take(length).collect();
+    let end = offset + page.chars().count();
+
+    let mut output = format!(
+        "--- {}: characters {offset}-{end} of {total} ---\n{page}",
+        doc.filename
+    );
+    if end < total {
+        output.push_str(&format!(
+            "\n\n[{} more characters, continue with offset={end}]",
+            total - end
+        ));
+    }
+    Ok(output)
+}
+
+/// Metadata of a document in a base.
+async fn find_document(base_id: &str, document_id: &str) -> Result<store::DocumentMeta, String> {
+    let bid = base_id.to_string();
+    let did = document_id.to_string();
+    tokio::task::spawn_blocking(move || {
+        let docs = store::list_documents(&bid)?;
+        docs.into_iter()
+            .find(|d| d.id == did)
+            .ok_or_else(|| format!("Document '{did}' not found"))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
 // ── Tool definitions ──
 
 fn tool_definitions() -> Value {
     json!([
         {
             "name": "search_knowledge_base",
-            "description": "Search knowledge bases for relevant information. Returns text chunks from indexed documents ranked by semantic similarity. Use this to find answers in uploaded documents, web pages, or YouTube transcripts.",
+            "description": "Search knowledge bases for relevant information. Returns text chunks from indexed documents ranked by semantic similarity, exact keyword matches, or both (hybrid, the default). Use this to find answers in uploaded documents, web pages, or YouTube transcripts. Results from codebase bases cite the source as path:line; other results cite the page, chapter, heading or video timestamp where known.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
                     "query": { "type": "string", "description": "Search query — formulate in the same language as the documents for best results" },
                     "base_id": { "type": "string", "description": "Optional: search only this knowledge base. If omitted, searches all bases." },
-                    "limit": { "type": "number", "description": "Maximum number of results (default: 10)" }
+                    "limit": { "type": "number", "description": "Maximum number of results (default: 10)" },
+                    "mode": { "type": "string", "enum": ["hybrid", "vector", "keyword"], "description": "Optional: 'keyword' for exact identifiers, error codes and names; 'vector' for meaning only; 'hybrid' (default) combines both" },
+                    "filter": { "type": "string", "description": "Optional: only documents matching all terms. 'tag:NAME' requires a tag, 'key=value' a metadata field (e.g. lang=en, author=\"Jane Doe\"); 'tag:a,b' matches either, a leading '-' excludes. get_document_info shows each document's tags and metadata." }
                 },
                 "required": ["query"]
             }
@@ -248,7 +386,7 @@ fn tool_definitions() -> Value {
         },
         {
             "name": "get_document_info",
-            "description": "Get the list of documents in a knowledge base (name, chunks, size).",
+            "description": "Get the list of documents in a knowledge base (name, chunks, size, tags and metadata).",
             "inputSchema": {
                 "type": "object",
                 "properties": {
@@ -270,6 +408,34 @@ fn tool_definitions() -> Value {
                 },
                 "required": ["base_id", "document_id"]
             }
+        },
+        {
+            "name": "get_chunk_context",
+            "description": "Get the text around a search result: the chunk with its neighbours, stitched together. Use the base_id, document_id and chunk_index printed with each search result.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "base_id": { "type": "string", "description": "Knowledge base ID" },
+                    "document_id": { "type": "string", "description": "Document ID" },
+                    "chunk_index": { "type": "number", "description": "Index of the chunk within the document" },
+                    "window": { "type": "number", "description": "Chunks to include on each side (default: 2, max: 10)" }
+                },
+                "required": ["base_id", "document_id", "chunk_index"]
+            }
+        },
+        {
+            "name": "read_document",
+            "description": "Read the full indexed text of a document, page by page. Returns up to 'length' characters from 'offset' and tells where the next page starts.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "base_id": { "type": "string", "description": "Knowledge base ID" },
+                    "document_id": { "type": "string", "description": "Document ID" },
+                    "offset": { "type": "number", "description": "Character offset to start at (default: 0)" },
+                    "length": { "type": "number", "description": "Characters to return (default: 8000, max: 50000)" }
+                },
+                "required": ["base_id", "document_id"]
+            }
         }
     ])
 }
@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use serde::Serialize;
+use tokio::sync::{OwnedRwLockReadGuard, OwnedRwLockWriteGuard, RwLock};
+
+use super::chunker::Chunk;
+use super::config as rag_config;
+use super::{embedder, index, rag_settings, store};
+
+/// One lock per base. Anything that changes a base holds it shared for its
+/// whole run; a migration holds it exclusively, since writes made meanwhile
+/// would land in the old index.
+static BASE_LOCKS: OnceLock<Mutex<HashMap<String, Arc<RwLock<()>>>>> = OnceLock::new();
+
+fn base_lock(base_id: &str) -> Result<Arc<RwLock<()>>, String> {
+    let mut locks = BASE_LOCKS
+        .get_or_init(Default::default)
+        .lock()
+        .map_err(|e| format!("Base lock registry poisoned: {e}"))?;
+    Ok(locks.entry(base_id.to_string()).or_default().clone())
+}
+
+/// Held while a base is being changed; blocks migrations until dropped.
+pub type WriteAccess = OwnedRwLockReadGuard<()>;
+
+/// Get write access to a base. Refused while it is being re-embedded.
+pub fn write_access(base_id: &str) -> Result<WriteAccess, String> {
+    base_lock(base_id)?.try_read_owned().map_err(|_| {
+        "This knowledge base is being re-embedded with a new model; try again when it finishes"
+            .to_string()
+    })
+}
+
+/// Whether a base is being re-embedded right now.
+pub fn is_migrating(base_id: &str) -> bool {
+    base_lock(base_id).is_ok_and(|lock| lock.try_read().is_err())
+}
+
+/// Take a base for re-embedding. Refused while anything else changes it.
+fn begin_migration(base_id: &str) -> Result<OwnedRwLockWriteGuard<()>, String> {
+    base_lock(base_id)?.try_write_owned().map_err(|_| {
+        "This knowledge base is being changed or re-embedded; try again when that finishes"
+            .to_string()
+    })
+}
+
+/// Re-embedding completion summary.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrateSummary {
+    pub documents: usize,
+    pub chunks: usize,
+    pub model: String,
+}
+
+/// Put the index built in `next` in place of `live` and record the new model.
+/// On failure the old index is restored.
+fn swap_index(base_id: &str, live: &Path, next: &Path, model: &str) -> Result<(), String> {
+    let old = live.with_extension("old");
+    if old.exists() {
+        std::fs::remove_dir_all(&old)
+            .map_err(|e| format!("Failed to remove stale index backup: {e}"))?;
+    }
+    if live.exists() {
+        std::fs::rename(live, &old).map_err(|e| format!("Failed to move old index: {e}"))?;
+    }
+    let restore = || {
+        if old.exists() {
+            if let Err(e) = std::fs::rename(&old, live) {
+                eprintln!("[rag] Failed to restore old index for base {base_id}: {e}");
+            }
+        }
+    };
+    if let Err(e) = std::fs::rename(next, live) {
+        restore();
+        return Err(format!("Failed to install new index: {e}"));
+    }
+    if let Err(e) = store::set_embedding_model(base_id, model) {
+        if let Err(e) = std::fs::rename(live, next) {
+            eprintln!("[rag] Failed to move new index aside for base {base_id}: {e}");
+        }
+        restore();
+        return Err(e);
+    }
+    if old.exists() {
+        if let Err(e) = std::fs::remove_dir_all(&old) {
+            eprintln!("[rag] Failed to remove old index for base {base_id}: {e}");
+        }
+    }
+    Ok(())
+}
+
+/// Re-embed every chunk of a base with `model` into a fresh index, then swap
+/// it in. The chunk texts come from the current index, so web and YouTube
+/// sources are kept. Searches use the old index and model until the swap.
+/// `on_progress(processed, total, filename)` is called before each document.
+pub async fn migrate_base(
+    base_id: &str,
+    model: &str,
+    on_progress: impl Fn(usize, usize, &str),
+) -> Result<MigrateSummary, String> {
+    rag_settings::validate_model(model)?;
+    let _migration = begin_migration(base_id)?;
+
+    let bid = base_id.to_string();
+    let meta = tokio::task::spawn_blocking(move || store::get_base(&bid))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))??;
+    if meta.embedding_model == model {
+        return Err(format!("Knowledge base already uses {model}"));
+    }
+
+    let chunks = index::all_chunks(base_id).await?;
+    let chunk_count = chunks.len();
+    let mut by_document: HashMap<String, Vec<Chunk>> = HashMap::new();
+    for chunk in chunks {
+        by_document.entry(chunk.document_id).or_default().push(Chunk {
+            text: chunk.text,
+            lines: chunk.lines,
+            bytes: chunk.bytes,
+            location: chunk.location,
+        });
+    }
+    let live = rag_config::base_lance_dir(base_id);
+    let next = live.with_extension("next");
+    if next.exists() {
+        let dir = next.clone();
+        tokio::task::spawn_blocking(move || std::fs::remove_dir_all(&dir))
+            .await
+            .map_err(|e| format!("Task join error: {e}"))?
+            .map_err(|e| format!("Failed to clear unfinished migration: {e}"))?;
+    }
+
+    eprintln!(
+        "[rag] Re-embedding base {base_id}: {} -> {model} ({} chunks)",
+        meta.embedding_model, chunk_count
+    );
+
+    let total = meta.documents.len();
+    let mut migrated_chunks = 0;
+    for (i, doc) in meta.documents.iter().enumerate() {
+        on_progress(i, total, &doc.filename);
+
+        let Some(doc_chunks) = by_document.remove(&doc.id) else {
+            continue;
+        };
+
+        let m = model.to_string();
+        let texts: Vec<String> = doc_chunks.iter().map(|c| c.text.clone()).collect();
+        let embeddings = tokio::task::spawn_blocking(move || embedder::embed_texts(&m, &texts))
+            .await
+            .map_err(|e| format!("Task join error: {e}"))??;
+
+        index::add_chunks_at(&next, &doc.id, &doc.labels, &doc_chunks, &embeddings)
+            .await
+            .map_err(|e| format!("Failed to re-embed {}: {e}", doc.filename))?;
+        migrated_chunks += doc_chunks.len();
+    }
+    on_progress(total, total, "");
+
+    // An empty base still gets an (empty) index dir, so the next upload
+    // creates its table with the new vector size
+    let (bid, m) = (base_id.to_string(), model.to_string());
+    tokio::task::spawn_blocking(move || {
+        std::fs::create_dir_all(&next).map_err(|e| format!("Failed to create index dir: {e}"))?;
+        swap_index(&bid, &live, &next, &m)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??;
+
+    Ok(MigrateSummary {
+        documents: total,
+        chunks: migrated_chunks,
+        model: model.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writers_and_migration_exclude_each_other() {
+        let id = uuid::Uuid::new_v4().to_string();
+        let first = write_access(&id).unwrap();
+        let second = write_access(&id).unwrap();
+        assert!(begin_migration(&id).is_err());
+
+        drop(first);
+        drop(second);
+        let migration = begin_migration(&id).unwrap();
+        assert!(write_access(&id).is_err());
+        assert!(is_migrating(&id));
+        assert!(begin_migration(&id).is_err());
+        assert!(write_access(&uuid::Uuid::new_v4().to_string()).is_ok());
+
+        drop(migration);
+        assert!(!is_migrating(&id));
+        assert!(write_access(&id).is_ok());
+    }
+}
@@ -1,13 +1,21 @@
+pub mod archive;
 pub mod chunker;
+pub mod code;
 pub mod commands;
 pub mod config;
+pub mod dedup;
 pub mod embedder;
+pub mod filter;
+pub mod gitignore;
 pub mod mcp_server;
+pub mod migrate;
 pub mod index;
 pub mod ocr;
 pub mod parser;
 pub mod rag_settings;
+pub mod reranker;
 pub mod store;
+pub mod sync;
 pub mod web;
 pub mod youtube;
 
@@ -277,8 +277,9 @@ fn download_if_missing(
 /// Called from parser.rs as a third fallback when pdftotext and pdf-extract
 /// both return empty text. Runs in spawn_blocking context.
 ///
-/// Pages are processed one at a time to avoid OOM on large PDFs.
-pub fn ocr_pdf(path: &Path) -> Result<String, String> {
+/// Pages are processed one at a time to avoid OOM on large PDFs. Returns the
+/// text of each page that produced any, with its 1-based page number.
+pub fn ocr_pdf(path: &Path) -> Result<Vec<(usize, String)>, String> {
     let page_count = get_page_count(path)?;
     if page_count == 0 {
         return Err("PDF has 0 pages".into());
@@ -291,7 +292,7 @@ pub fn ocr_pdf(path: &Path) -> Result<String, String> {
         );
     }
 
-    let mut all_text = String::new();
+    let mut pages = Vec::new();
     const BATCH_SIZE: usize = 10;
 
     // Process pages in batches to reduce process spawns while keeping memory bounded
@@ -319,10 +320,7 @@ pub fn ocr_pdf(path: &Path) -> Result<String, String> {
                     let trimmed = text.trim();
                     eprintln!("[rag/ocr] Page {page_num}: {} chars extracted", trimmed.len());
                     if !trimmed.is_empty() {
-                        if !all_text.is_empty() {
-                            all_text.push_str("\n\n");
-                        }
-                        all_text.push_str(trimmed);
+                        pages.push((page_num, trimmed.to_string()));
                     }
                 }
                 Err(e) => {
@@ -335,11 +333,11 @@ pub fn ocr_pdf(path: &Path) -> Result<String, String> {
         first = last + 1;
     }
 
-    if all_text.trim().is_empty() {
+    if pages.is_empty() {
         return Err("OCR produced no text".into());
     }
 
-    Ok(all_text)
+    Ok(pages)
 }
 
 // --- PDF page handling ---
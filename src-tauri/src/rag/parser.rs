@@ -1,17 +1,129 @@
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
+use serde::{Deserialize, Serialize};
+
 use crate::files::validate_path_safe;
 
+use super::code::Language;
+
+/// Where a stretch of parsed text comes from in its source. Which fields
+/// are set depends on the format.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Location {
+    /// 1-based PDF page.
+    pub page: Option<u32>,
+    /// EPUB chapter title.
+    pub chapter: Option<String>,
+    /// Markdown heading path, outermost first: "Setup › Linux".
+    pub heading: Option<String>,
+    /// Seconds into a video.
+    pub timestamp: Option<u32>,
+}
+
+impl Location {
+    /// Human-readable citation, e.g. "p. 42, § Setup › Linux" or "12:34".
+    pub fn label(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if let Some(page) = self.page {
+            parts.push(format!("p. {page}"));
+        }
+        if let Some(chapter) = &self.chapter {
+            parts.push(chapter.clone());
+        }
+        if let Some(heading) = &self.heading {
+            parts.push(format!("§ {heading}"));
+        }
+        if let Some(secs) = self.timestamp {
+            let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+            parts.push(if h > 0 {
+                format!("{h}:{m:02}:{s:02}")
+            } else {
+                format!("{m}:{s:02}")
+            });
+        }
+        (!parts.is_empty()).then(|| parts.join(", "))
+    }
+
+    /// `url` opened at this location, for video sources with a timestamp.
+    pub fn link(&self, url: &str) -> Option<String> {
+        let secs = self.timestamp?;
+        if !url.starts_with("https://") && !url.starts_with("http://") {
+            return None;
+        }
+        let sep = if url.contains('?') { '&' } else { '?' };
+        Some(format!("{url}{sep}t={secs}s"))
+    }
+}
+
+/// Start of a stretch of text sharing one location.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Section {
+    /// Byte offset into `ParsedDocument::text`.
+    pub start: usize,
+    pub location: Location,
+}
+
 /// Parsed document content ready for chunking.
 pub struct ParsedDocument {
     pub text: String,
     pub is_markdown: bool,
+    /// Set for source code, which is kept verbatim so line numbers hold.
+    pub language: Option<Language>,
+    /// Locations of the text in order; empty when the format has none.
+    pub sections: Vec<Section>,
+}
+
+impl ParsedDocument {
+    /// Plain text without locations.
+    pub fn plain(text: String) -> Self {
+        Self {
+            text,
+            is_markdown: false,
+            language: None,
+            sections: Vec::new(),
+        }
+    }
+
+    /// Join located pieces of text with `sep`, dropping empty ones.
+    pub fn from_pieces(pieces: impl IntoIterator<Item = (Location, String)>, sep: &str) -> Self {
+        let mut doc = Self::plain(String::new());
+        for (location, piece) in pieces {
+            doc.push_piece(sep, location, piece.trim());
+        }
+        doc
+    }
+
+    fn push_piece(&mut self, sep: &str, location: Location, piece: &str) {
+        if piece.is_empty() {
+            return;
+        }
+        if !self.text.is_empty() {
+            self.text.push_str(sep);
+        }
+        if self.sections.last().is_none_or(|s| s.location != location) {
+            self.sections.push(Section {
+                start: self.text.len(),
+                location,
+            });
+        }
+        self.text.push_str(piece);
+    }
+
+    /// Location of the text at byte `offset`.
+    pub fn location_at(&self, offset: usize) -> Location {
+        let i = self.sections.partition_point(|s| s.start <= offset);
+        i.checked_sub(1)
+            .map(|i| self.sections[i].location.clone())
+            .unwrap_or_default()
+    }
 }
 
-/// Parse a file into text content. Supports plain text, Markdown, PDF, and EPUB.
-/// Applies sanitize_text() to all output.
+/// Parse a file into text content. Supports plain text, Markdown, PDF, EPUB
+/// and source code. Sanitizes all output except code.
 pub fn parse_file(path: &Path) -> Result<ParsedDocument, String> {
     validate_path_safe(path)?;
 
@@ -21,18 +133,58 @@ pub fn parse_file(path: &Path) -> Result<ParsedDocument, String> {
         .map(|e| e.to_lowercase())
         .unwrap_or_default();
 
-    let mut doc = match ext.as_str() {
+    if let Some(language) = Language::from_extension(&ext) {
+        let doc = parse_text(path, &ext)?;
+        return Ok(ParsedDocument {
+            language: Some(language),
+            ..doc
+        });
+    }
+
+    let doc = match ext.as_str() {
         "pdf" => parse_pdf(path)?,
         "epub" => parse_epub(path)?,
         _ => parse_text(path, &ext)?,
     };
 
-    doc.text = sanitize_text(&doc.text);
-    Ok(doc)
+    Ok(sanitize_document(doc))
+}
+
+/// Sanitize a document section by section, so that section offsets point
+/// into the cleaned text. Pieces that began a line are rejoined as
+/// paragraphs, the others (transcript cues) with a space.
+pub fn sanitize_document(doc: ParsedDocument) -> ParsedDocument {
+    if doc.sections.is_empty() {
+        return ParsedDocument {
+            text: sanitize_text(&doc.text),
+            ..doc
+        };
+    }
+
+    let mut out = ParsedDocument::plain(String::new());
+    out.is_markdown = doc.is_markdown;
+    let mut starts: Vec<(usize, Location)> = doc
+        .sections
+        .iter()
+        .map(|s| (s.start, s.location.clone()))
+        .collect();
+    if starts[0].0 > 0 {
+        starts.insert(0, (0, Location::default()));
+    }
+    for (i, (start, location)) in starts.iter().enumerate() {
+        let end = starts.get(i + 1).map_or(doc.text.len(), |(s, _)| *s);
+        let sep = if doc.text[..*start].ends_with('\n') {
+            "\n\n"
+        } else {
+            " "
+        };
+        out.push_piece(sep, location.clone(), &sanitize_text(&doc.text[*start..end]));
+    }
+    out
 }
 
-/// Also exposed for web/youtube content that bypasses parse_file.
-pub fn sanitize_text(text: &str) -> String {
+/// Drop decorative lines and control characters, and collapse blank lines.
+fn sanitize_text(text: &str) -> String {
     let mut output = String::with_capacity(text.len());
 
     for line in text.lines() {
@@ -131,22 +283,14 @@ fn parse_text(path: &Path, ext: &str) -> Result<ParsedDocument, String> {
     let text = String::from_utf8(raw)
         .map_err(|_| format!("File is not valid UTF-8: {}", path.display()))?;
 
-    let is_markdown = matches!(ext, "md" | "markdown" | "mdx");
-
-    if is_markdown {
-        Ok(ParsedDocument {
-            text: extract_markdown_text(&text),
-            is_markdown: true,
-        })
+    if matches!(ext, "md" | "markdown" | "mdx") {
+        Ok(extract_markdown(&text))
     } else {
-        Ok(ParsedDocument {
-            text,
-            is_markdown: false,
-        })
+        Ok(ParsedDocument::plain(text))
     }
 }
 
-/// Extract text from a PDF file.
+/// Extract text from a PDF file, one section per page.
 /// Tries pdftotext first, then pdf-extract, then OCR for scanned documents.
 fn parse_pdf(path: &Path) -> Result<ParsedDocument, String> {
     // Try pdftotext CLI first (better quality for complex PDFs)
@@ -169,6 +313,20 @@ fn parse_pdf(path: &Path) -> Result<ParsedDocument, String> {
     parse_pdf_ocr(path)
 }
 
+/// Join per-page texts (page 1 first) into a document with page locations.
+fn pdf_from_pages(pages: impl IntoIterator<Item = (usize, String)>) -> ParsedDocument {
+    ParsedDocument::from_pieces(
+        pages.into_iter().map(|(page, text)| {
+            let location = Location {
+                page: Some(page as u32),
+                ..Location::default()
+            };
+            (location, text)
+        }),
+        "\n\n",
+    )
+}
+
 /// Extract text using pdftotext CLI from poppler-utils.
 /// NOTE: parse_file is always called from spawn_blocking (see commands.rs),
 /// so Command::new here does not block the async runtime.
@@ -187,15 +345,17 @@ fn parse_pdf_pdftotext(path: &Path) -> Result<ParsedDocument, String> {
     let text = String::from_utf8(output.stdout)
         .map_err(|_| "pdftotext output is not valid UTF-8".to_string())?;
 
-    let trimmed = text.trim().to_string();
-    if trimmed.is_empty() {
+    // pdftotext ends every page with a form feed
+    let doc = pdf_from_pages(
+        text.split('\x0c')
+            .enumerate()
+            .map(|(i, page)| (i + 1, page.to_string())),
+    );
+    if doc.text.is_empty() {
         return Err("pdftotext returned empty text".into());
     }
 
-    Ok(ParsedDocument {
-        text: trimmed,
-        is_markdown: false,
-    })
+    Ok(doc)
 }
 
 /// Fallback PDF extraction using pdf-extract crate.
@@ -203,59 +363,77 @@ fn parse_pdf_extract(path: &Path) -> Result<ParsedDocument, String> {
     let bytes = fs::read(path)
         .map_err(|e| format!("Failed to read PDF {}: {e}", path.display()))?;
 
-    let text = pdf_extract::extract_text_from_mem(&bytes)
+    let pages = pdf_extract::extract_text_from_mem_by_pages(&bytes)
         .map_err(|e| format!("Failed to extract text from PDF {}: {e}", path.display()))?;
 
-    let trimmed = text.trim().to_string();
-    if trimmed.is_empty() {
+    let doc = pdf_from_pages(pages.into_iter().enumerate().map(|(i, page)| (i + 1, page)));
+    if doc.text.is_empty() {
         return Err(format!("PDF contains no extractable text: {}", path.display()));
     }
 
-    Ok(ParsedDocument {
-        text: trimmed,
-        is_markdown: false,
-    })
+    Ok(doc)
 }
 
 /// Fallback OCR extraction for scanned PDFs using PP-OCR ONNX models.
 fn parse_pdf_ocr(path: &Path) -> Result<ParsedDocument, String> {
     eprintln!("[rag] Attempting OCR for scanned PDF: {}", path.display());
-    let text = super::ocr::ocr_pdf(path)?;
-    Ok(ParsedDocument {
-        text,
-        is_markdown: false,
-    })
+    let pages = super::ocr::ocr_pdf(path)?;
+    Ok(pdf_from_pages(pages))
 }
 
-/// Extract text from an EPUB file.
+/// Extract text from an EPUB file, one section per chapter.
 fn parse_epub(path: &Path) -> Result<ParsedDocument, String> {
     let mut doc = epub::doc::EpubDoc::new(path)
         .map_err(|e| format!("Failed to open EPUB {}: {e}", path.display()))?;
 
-    let mut all_text = String::new();
+    let titles = toc_titles(&doc.toc);
     let spine_ids: Vec<String> = doc.spine.iter().map(|item| item.idref.clone()).collect();
 
+    // Spine files without a table of contents entry continue the chapter before them
+    let mut chapter: Option<String> = None;
+    let mut pieces = Vec::new();
     for id in &spine_ids {
+        if let Some(title) = doc
+            .resources
+            .get(id)
+            .and_then(|r| titles.get(&r.path))
+        {
+            chapter = Some(title.clone());
+        }
         if let Some((content, _mime)) = doc.get_resource_str(id) {
-            let chapter_text = html_to_text(&content);
-            let trimmed = chapter_text.trim();
-            if !trimmed.is_empty() {
-                if !all_text.is_empty() {
-                    all_text.push_str("\n\n");
-                }
-                all_text.push_str(trimmed);
-            }
+            let location = Location {
+                chapter: chapter.clone(),
+                ..Location::default()
+            };
+            pieces.push((location, html_to_text(&content)));
         }
     }
 
-    if all_text.is_empty() {
+    let parsed = ParsedDocument::from_pieces(pieces, "\n\n");
+    if parsed.text.is_empty() {
         return Err(format!("EPUB contains no extractable text: {}", path.display()));
     }
 
-    Ok(ParsedDocument {
-        text: all_text,
-        is_markdown: false,
-    })
+    Ok(parsed)
+}
+
+/// Chapter titles by content file, from the first table of contents entry
+/// pointing into each file.
+fn toc_titles(toc: &[epub::doc::NavPoint]) -> HashMap<PathBuf, String> {
+    let mut titles = HashMap::new();
+    let mut stack: Vec<&epub::doc::NavPoint> = toc.iter().rev().collect();
+    while let Some(point) = stack.pop() {
+        let content = point.content.to_string_lossy();
+        let file = content.split('#').next().unwrap_or_default();
+        let label = point.label.trim();
+        if !label.is_empty() {
+            titles
+                .entry(PathBuf::from(file))
+                .or_insert_with(|| label.to_string());
+        }
+        stack.extend(point.children.iter().rev());
+    }
+    titles
 }
 
 /// Convert HTML to plain text using html2text crate.
@@ -265,12 +443,16 @@ fn html_to_text(html: &str) -> String {
         .unwrap_or_default()
 }
 
-/// Extract readable text from Markdown by stripping formatting via pulldown-cmark.
-pub(crate) fn extract_markdown_text(md: &str) -> String {
+/// Extract readable text from Markdown by stripping formatting via
+/// pulldown-cmark. Each heading starts a section carrying its heading path.
+pub(crate) fn extract_markdown(md: &str) -> ParsedDocument {
     use pulldown_cmark::{Event, Parser, Tag, TagEnd};
 
     let parser = Parser::new(md);
     let mut output = String::with_capacity(md.len());
+    let mut sections = Vec::new();
+    let mut headings: Vec<(u32, String)> = Vec::new();
+    let mut heading_start = None;
     let mut in_code_block = false;
 
     for event in parser {
@@ -286,8 +468,27 @@ pub(crate) fn extract_markdown_text(md: &str) -> String {
                 if !output.is_empty() && !output.ends_with('\n') {
                     output.push('\n');
                 }
+                heading_start = Some(output.len());
             }
-            Event::End(TagEnd::Heading(_)) => {
+            Event::End(TagEnd::Heading(level)) => {
+                if let Some(start) = heading_start.take() {
+                    let level = level as u32;
+                    let title = output[start..].trim().to_string();
+                    headings.retain(|(l, _)| *l < level);
+                    headings.push((level, title));
+                    let path: Vec<&str> = headings
+                        .iter()
+                        .map(|(_, t)| t.as_str())
+                        .filter(|t| !t.is_empty())
+                        .collect();
+                    sections.push(Section {
+                        start,
+                        location: Location {
+                            heading: (!path.is_empty()).then(|| path.join(" › ")),
+                            ..Location::default()
+                        },
+                    });
+                }
                 output.push('\n');
             }
             Event::End(TagEnd::Paragraph) => {
@@ -306,7 +507,15 @@ pub(crate) fn extract_markdown_text(md: &str) -> String {
         }
     }
 
-    output.trim().to_string()
+    let text = output.trim_end().to_string();
+    // A trailing empty heading leaves nothing to locate
+    sections.retain(|s| s.start < text.len());
+    ParsedDocument {
+        text,
+        is_markdown: true,
+        language: None,
+        sections,
+    }
 }
 
 #[cfg(test)]
@@ -417,7 +626,7 @@ mod tests {
     #[test]
     fn markdown_extracts_plain_text() {
         let md = "# Title\n\nSome **bold** and *italic* text.";
-        let result = extract_markdown_text(md);
+        let result = extract_markdown(md).text;
         assert!(result.contains("Title"));
         assert!(result.contains("Some bold and italic text."));
     }
@@ -425,7 +634,7 @@ mod tests {
     #[test]
     fn markdown_skips_code_blocks() {
         let md = "Text before\n\n```rust\nlet x = 1;\n```\n\nText after";
-        let result = extract_markdown_text(md);
+        let result = extract_markdown(md).text;
         assert!(result.contains("Text before"));
         assert!(result.contains("Text after"));
         assert!(!result.contains("let x = 1"));
@@ -434,13 +643,89 @@ mod tests {
     #[test]
     fn markdown_keeps_inline_code() {
         let md = "Use `println!` for output.";
-        let result = extract_markdown_text(md);
+        let result = extract_markdown(md).text;
         assert!(result.contains("println!"));
     }
 
     #[test]
     fn markdown_empty() {
-        assert_eq!(extract_markdown_text(""), "");
+        assert_eq!(extract_markdown("").text, "");
+    }
+
+    #[test]
+    fn markdown_sections_carry_heading_path() {
+        let doc = extract_markdown("# Guide\n\nIntro.\n\n## Setup\n\nSteps.\n\n### Linux\n\nApt.\n\n## Usage\n\nRun it.");
+        let at = |needle: &str| doc.location_at(doc.text.find(needle).unwrap()).heading;
+        assert_eq!(at("Intro."), Some("Guide".into()));
+        assert_eq!(at("Steps."), Some("Guide › Setup".into()));
+        assert_eq!(at("Apt."), Some("Guide › Setup › Linux".into()));
+        assert_eq!(at("Run it."), Some("Guide › Usage".into()));
+    }
+
+    // --- sanitize_document ---
+
+    fn page(n: u32) -> Location {
+        Location {
+            page: Some(n),
+            ..Location::default()
+        }
+    }
+
+    #[test]
+    fn sanitize_document_keeps_sections_in_step() {
+        let doc = ParsedDocument::from_pieces(
+            [
+                (page(1), "First ●●●\n\n\n\n\npage".to_string()),
+                (page(2), "═══════".to_string()),
+                (page(3), "Third page".to_string()),
+            ],
+            "\n\n",
+        );
+        let doc = sanitize_document(doc);
+        assert_eq!(doc.text, "First \n\n\npage\n\nThird page");
+        assert_eq!(doc.location_at(0), page(1));
+        assert_eq!(doc.location_at(doc.text.find("Third").unwrap()), page(3));
+        assert_eq!(doc.sections.len(), 2);
+    }
+
+    #[test]
+    fn sanitize_document_joins_inline_sections_with_spaces() {
+        let cue = |secs| Location {
+            timestamp: Some(secs),
+            ..Location::default()
+        };
+        let doc = ParsedDocument::from_pieces(
+            [(cue(0), "Hello".to_string()), (cue(2), "world".to_string())],
+            " ",
+        );
+        let doc = sanitize_document(doc);
+        assert_eq!(doc.text, "Hello world");
+        assert_eq!(doc.location_at(6), cue(2));
+    }
+
+    // --- Location ---
+
+    #[test]
+    fn location_labels_and_links() {
+        let loc = Location {
+            page: Some(42),
+            heading: Some("3.1 Setup".into()),
+            ..Location::default()
+        };
+        assert_eq!(loc.label().as_deref(), Some("p. 42, § 3.1 Setup"));
+        assert_eq!(Location::default().label(), None);
+
+        let cue = Location {
+            timestamp: Some(3725),
+            ..Location::default()
+        };
+        assert_eq!(cue.label().as_deref(), Some("1:02:05"));
+        assert_eq!(
+            cue.link("https://youtube.com/watch?v=abc").as_deref(),
+            Some("https://youtube.com/watch?v=abc&t=3725s")
+        );
+        assert_eq!(cue.link("youtube-abc"), None);
+        assert_eq!(loc.link("https://example.com"), None);
     }
 
     // --- html_to_text ---
@@ -13,10 +13,19 @@ pub const AVAILABLE_MODELS: &[(&str, &str, usize)] = &[
     ("bge-m3", "Multilingual M3, 100+ languages, 8192 ctx, 560MB", 1024),
 ];
 
+/// Available reranker (cross-encoder) models (fastembed).
+pub const AVAILABLE_RERANKERS: &[(&str, &str)] = &[
+    ("jina-reranker-v1-turbo-en", "Fast, English, 150MB"),
+    ("bge-reranker-base", "English/Chinese, 1.1GB"),
+    ("jina-reranker-v2-base-multilingual", "Multilingual, 1.1GB"),
+    ("bge-reranker-v2-m3", "Multilingual M3, higher quality, 2.2GB"),
+];
+
 /// RAG module settings stored in rag/settings.json.
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct RagSettings {
+    /// Model for new knowledge bases; each base keeps the model it was built with.
     #[serde(default = "default_model")]
     pub embedding_model: String,
     #[serde(default = "default_chunk_size")]
@@ -27,6 +36,14 @@ pub struct RagSettings {
     pub search_results_limit: usize,
     #[serde(default = "default_true")]
     pub knowledge_mcp_enabled: bool,
+    /// Rescore search candidates with a cross-encoder before returning them.
+    #[serde(default)]
+    pub reranker_enabled: bool,
+    #[serde(default = "default_reranker_model")]
+    pub reranker_model: String,
+    /// How many top candidates the reranker rescores.
+    #[serde(default = "default_rerank_candidates")]
+    pub rerank_candidates: usize,
 }
 
 impl Default for RagSettings {
@@ -37,6 +54,9 @@ impl Default for RagSettings {
             chunk_overlap: default_chunk_overlap(),
             search_results_limit: default_search_limit(),
             knowledge_mcp_enabled: true,
+            reranker_enabled: false,
+            reranker_model: default_reranker_model(),
+            rerank_candidates: default_rerank_candidates(),
         }
     }
 }
@@ -53,6 +73,12 @@ fn default_chunk_overlap() -> usize {
 fn default_search_limit() -> usize {
     10
 }
+fn default_reranker_model() -> String {
+    "jina-reranker-v1-turbo-en".to_string()
+}
+fn default_rerank_candidates() -> usize {
+    30
+}
 fn default_true() -> bool {
     true
 }
@@ -69,6 +95,7 @@ pub fn load() -> RagSettings {
 
 /// Save RAG settings (blocking I/O).
 pub fn save(settings: &RagSettings) -> Result<(), String> {
+    validate_model(&settings.embedding_model)?;
     if !(64..=4096).contains(&settings.chunk_size) {
         return Err(format!(
             "chunk_size must be between 64 and 4096, got {}",
@@ -88,6 +115,19 @@ pub fn save(settings: &RagSettings) -> Result<(), String> {
         ));
     }
 
+    if !(1..=200).contains(&settings.rerank_candidates) {
+        return Err(format!(
+            "rerank_candidates must be between 1 and 200, got {}",
+            settings.rerank_candidates
+        ));
+    }
+    if !AVAILABLE_RERANKERS
+        .iter()
+        .any(|(n, _)| *n == settings.reranker_model)
+    {
+        return Err(format!("Unknown reranker model: {}", settings.reranker_model));
+    }
+
     std::fs::create_dir_all(rag_config::rag_dir())
         .map_err(|e| format!("Failed to create rag dir: {e}"))?;
     write_json(&settings_path(), settings)
@@ -104,6 +144,15 @@ pub fn resolve_model(name: &str) -> fastembed::EmbeddingModel {
     }
 }
 
+/// Check that a model name is one of `AVAILABLE_MODELS`.
+pub fn validate_model(name: &str) -> Result<(), String> {
+    if AVAILABLE_MODELS.iter().any(|(n, _, _)| *n == name) {
+        Ok(())
+    } else {
+        Err(format!("Unknown embedding model: {name}"))
+    }
+}
+
 /// Get embedding dimension for a model name.
 pub fn model_dimension(name: &str) -> usize {
     AVAILABLE_MODELS
@@ -112,3 +161,15 @@ pub fn model_dimension(name: &str) -> usize {
         .map(|(_, _, dim)| *dim)
         .unwrap_or(384)
 }
+
+/// Map reranker name string to fastembed RerankerModel enum.
+pub fn resolve_reranker(name: &str) -> fastembed::RerankerModel {
+    match name {
+        "bge-reranker-base" => fastembed::RerankerModel::BGERerankerBase,
+        "bge-reranker-v2-m3" => fastembed::RerankerModel::BGERerankerV2M3,
+        "jina-reranker-v2-base-multilingual" => {
+            fastembed::RerankerModel::JINARerankerV2BaseMultiligual
+        }
+        _ => fastembed::RerankerModel::JINARerankerV1TurboEn,
+    }
+}
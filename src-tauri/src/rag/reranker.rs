@@ -0,0 +1,141 @@
+use std::sync::Mutex;
+
+use fastembed::{RerankInitOptions, TextRerank};
+
+use super::config as rag_config;
+use super::index::SearchResult;
+use super::rag_settings;
+
+/// Loaded cross-encoder (initialized on first use).
+/// NOTE: rerank must always be called from tokio::task::spawn_blocking.
+/// Unlike the embedder, changing the reranker model needs no restart: it is
+/// reloaded when the requested model differs from the loaded one.
+static RERANKER: Mutex<Option<LoadedReranker>> = Mutex::new(None);
+
+struct LoadedReranker {
+    name: String,
+    model: TextRerank,
+}
+
+/// Map a cross-encoder logit to a 0..1 relevance score.
+fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+/// Score each document against the query with the named model.
+/// Returns one 0..1 score per document, in input order.
+pub fn rerank(model_name: &str, query: &str, documents: &[String]) -> Result<Vec<f32>, String> {
+    if documents.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut loaded = RERANKER
+        .lock()
+        .map_err(|e| format!("Reranker lock poisoned: {e}"))?;
+
+    if loaded.as_ref().is_none_or(|r| r.name != model_name) {
+        let cache_dir = rag_config::models_dir();
+        std::fs::create_dir_all(&cache_dir)
+            .map_err(|e| format!("Failed to create models dir: {e}"))?;
+
+        eprintln!("[rag] Initializing reranker model: {model_name}");
+        let model = TextRerank::try_new(
+            RerankInitOptions::new(rag_settings::resolve_reranker(model_name))
+                .with_cache_dir(cache_dir),
+        )
+        .map_err(|e| format!("Failed to initialize reranker model: {e}"))?;
+
+        *loaded = Some(LoadedReranker {
+            name: model_name.to_string(),
+            model,
+        });
+    }
+
+    let reranker = loaded.as_mut().expect("just initialized");
+    let docs: Vec<&str> = documents.iter().map(String::as_str).collect();
+    let ranked = reranker
+        .model
+        .rerank(query, docs, false, None)
+        .map_err(|e| format!("Reranking failed: {e}"))?;
+
+    let mut scores = vec![0.0; documents.len()];
+    for r in ranked {
+        if let Some(score) = scores.get_mut(r.index) {
+            *score = sigmoid(r.score);
+        }
+    }
+    Ok(scores)
+}
+
+/// Replace the scores of the leading results with reranker scores (one per
+/// result, in order), reorder those by the new score and keep the rest after
+/// them. Truncates to `limit`.
+pub fn apply_scores(
+    mut results: Vec<SearchResult>,
+    scores: &[f32],
+    limit: usize,
+) -> Vec<SearchResult> {
+    let rest = results.split_off(scores.len().min(results.len()));
+    for (result, score) in results.iter_mut().zip(scores) {
+        result.score = *score;
+    }
+    results.sort_by(|a, b| b.score.total_cmp(&a.score));
+    results.extend(rest);
+    results.truncate(limit);
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(name: &str, score: f32) -> SearchResult {
+        SearchResult {
+            chunk_text: name.to_string(),
+            base_id: name.to_string(),
+            document_id: name.to_string(),
+            document_name: name.to_string(),
+            chunk_index: 0,
+            lines: None,
+            bytes: None,
+            location: Default::default(),
+            citation: None,
+            link: None,
+            score,
+            similarity: None,
+        }
+    }
+
+    fn names(results: &[SearchResult]) -> Vec<&str> {
+        results.iter().map(|r| r.chunk_text.as_str()).collect()
+    }
+
+    #[test]
+    fn reranked_head_is_reordered_and_tail_kept() {
+        let results = vec![
+            result("a", 0.9),
+            result("b", 0.8),
+            result("c", 0.7),
+            result("d", 0.6),
+        ];
+        let out = apply_scores(results, &[0.2, 0.95, 0.5], 10);
+        assert_eq!(names(&out), ["b", "c", "a", "d"]);
+        assert_eq!(out[0].score, 0.95);
+        assert_eq!(out[3].score, 0.6);
+    }
+
+    #[test]
+    fn truncates_to_limit() {
+        let results = vec![result("a", 0.9), result("b", 0.8)];
+        assert_eq!(names(&apply_scores(results.clone(), &[0.1, 0.3], 1)), ["b"]);
+        // More scores than results is tolerated
+        assert_eq!(apply_scores(results, &[0.1, 0.3, 0.5], 5).len(), 2);
+    }
+
+    #[test]
+    fn sigmoid_maps_logits_to_unit_range() {
+        assert!((sigmoid(0.0) - 0.5).abs() < 1e-6);
+        assert!(sigmoid(8.0) > 0.99);
+        assert!(sigmoid(-8.0) < 0.01);
+    }
+}
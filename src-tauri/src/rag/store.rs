@@ -1,9 +1,12 @@
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 use std::fs;
 
 use crate::file_ops::{read_json, write_json};
 
 use super::config as rag_config;
+use super::{dedup, rag_settings};
 
 /// Metadata about a single document added to a knowledge base.
 #[derive(Serialize, Deserialize, Clone)]
@@ -15,6 +18,67 @@ pub struct DocumentMeta {
     pub size_bytes: u64,
     pub chunk_count: usize,
     pub added_at: u64,
+    /// SHA-256 of the file (or fetched text) the chunks were built from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+    /// Folder source that manages this document, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_id: Option<String>,
+    #[serde(flatten)]
+    pub labels: DocumentLabels,
+}
+
+/// User-defined tags and fields of a document, copied onto its chunks so
+/// search can filter by them (see `filter`).
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentLabels {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Custom fields such as source type, language, date or author.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+}
+
+/// A folder whose files are kept in sync with the base.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderSource {
+    pub id: String,
+    pub path: String,
+    /// Globs relative to `path`; empty means all supported document types.
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    #[serde(default)]
+    pub last_sync: Option<SourceSyncResult>,
+}
+
+/// Outcome of the last sync of a folder source.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceSyncResult {
+    pub finished_at: u64,
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+    pub failed: usize,
+    /// First few errors, for the status report.
+    #[serde(default)]
+    pub errors: Vec<String>,
+}
+
+/// What a knowledge base holds.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BaseKind {
+    /// Documents, web pages and videos.
+    #[default]
+    Documents,
+    /// A project's source tree: folder sources respect `.gitignore` and
+    /// default to source files, and chunks follow the code structure.
+    Codebase,
 }
 
 /// Metadata about a knowledge base.
@@ -25,7 +89,15 @@ pub struct BaseMeta {
     pub name: String,
     pub description: String,
     pub created_at: u64,
+    #[serde(default)]
+    pub kind: BaseKind,
+    /// Model the chunks are embedded with. Missing in bases created before
+    /// per-base models; `get_base` pins those to the configured model.
+    #[serde(default)]
+    pub embedding_model: String,
     pub documents: Vec<DocumentMeta>,
+    #[serde(default)]
+    pub sources: Vec<FolderSource>,
 }
 
 /// Lightweight info returned by list_bases (no documents list).
@@ -36,6 +108,8 @@ pub struct BaseInfo {
     pub name: String,
     pub description: String,
     pub created_at: u64,
+    pub kind: BaseKind,
+    pub embedding_model: String,
     pub document_count: usize,
 }
 
@@ -46,18 +120,43 @@ impl BaseMeta {
             name: self.name.clone(),
             description: self.description.clone(),
             created_at: self.created_at,
+            kind: self.kind,
+            embedding_model: self.embedding_model.clone(),
             document_count: self.documents.len(),
         }
     }
+
+    /// A document this one would duplicate: one with the same content hash,
+    /// or fetched from the same page or video (see `dedup::canonical_source`).
+    pub fn find_duplicate(&self, content_hash: Option<&str>, path: &str) -> Option<&DocumentMeta> {
+        let canonical = dedup::canonical_source(path);
+        self.documents.iter().find(|d| {
+            (content_hash.is_some() && d.content_hash.as_deref() == content_hash)
+                || (canonical.is_some() && dedup::canonical_source(&d.path) == canonical)
+        })
+    }
+}
+
+/// Error for a document that is already in the base.
+pub fn duplicate_error(existing: &DocumentMeta) -> String {
+    format!("Document already exists: {} (id: {})", existing.filename, existing.id)
 }
 
-fn now_millis() -> u64 {
+pub fn now_millis() -> u64 {
     std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap_or_default()
         .as_millis() as u64
 }
 
+/// Hex SHA-256 of document content, for change detection.
+pub fn content_hash(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
 /// Validate base_id: no path separators, no traversal, not empty.
 fn validate_base_id(id: &str) -> Result<(), String> {
     if id.is_empty() {
@@ -104,7 +203,8 @@ pub fn list_bases() -> Result<Vec<BaseInfo>, String> {
             continue;
         }
         let meta_path = rag_config::base_meta_path(&dir_name);
-        if let Ok(meta) = read_json::<BaseMeta>(&meta_path) {
+        if let Ok(mut meta) = read_json::<BaseMeta>(&meta_path) {
+            pin_legacy_model(&mut meta);
             bases.push(meta.to_info());
         }
     }
@@ -113,8 +213,26 @@ pub fn list_bases() -> Result<Vec<BaseInfo>, String> {
     Ok(bases)
 }
 
-/// Create a new knowledge base.
-pub fn create_base(name: &str, description: &str) -> Result<BaseMeta, String> {
+/// Bases from before per-base models were embedded with the model configured
+/// at the time, which changing required a reindex; record it in their metadata.
+fn pin_legacy_model(meta: &mut BaseMeta) {
+    if !meta.embedding_model.is_empty() {
+        return;
+    }
+    meta.embedding_model = rag_settings::load().embedding_model;
+    if let Err(e) = write_json(&rag_config::base_meta_path(&meta.id), meta) {
+        eprintln!("[rag] Failed to record embedding model for base {}: {e}", meta.id);
+    }
+}
+
+/// Create a new knowledge base whose chunks are embedded with `embedding_model`.
+pub fn create_base(
+    name: &str,
+    description: &str,
+    embedding_model: &str,
+    kind: BaseKind,
+) -> Result<BaseMeta, String> {
+    rag_settings::validate_model(embedding_model)?;
     let id = uuid::Uuid::new_v4().to_string();
     let base_dir = rag_config::base_dir(&id);
 
@@ -128,18 +246,43 @@ pub fn create_base(name: &str, description: &str) -> Result<BaseMeta, String> {
         name: name.to_string(),
         description: description.to_string(),
         created_at: now_millis(),
+        kind,
+        embedding_model: embedding_model.to_string(),
         documents: Vec::new(),
+        sources: Vec::new(),
     };
 
     write_json(&rag_config::base_meta_path(&id), &meta)?;
     Ok(meta)
 }
 
+/// Record a base restored from an archive; its index is already in place.
+pub fn add_imported_base(meta: BaseMeta) -> Result<BaseMeta, String> {
+    validate_base_id(&meta.id)?;
+    rag_settings::validate_model(&meta.embedding_model)?;
+    let meta_path = rag_config::base_meta_path(&meta.id);
+    if meta_path.exists() {
+        return Err(format!("Knowledge base '{}' already exists", meta.id));
+    }
+    write_json(&meta_path, &meta)?;
+    Ok(meta)
+}
+
 /// Get metadata for a single knowledge base.
 pub fn get_base(base_id: &str) -> Result<BaseMeta, String> {
     validate_base_id(base_id)?;
     let meta_path = rag_config::base_meta_path(base_id);
-    read_json(&meta_path).map_err(|_| format!("Knowledge base '{base_id}' not found"))
+    let mut meta: BaseMeta =
+        read_json(&meta_path).map_err(|_| format!("Knowledge base '{base_id}' not found"))?;
+    pin_legacy_model(&mut meta);
+    Ok(meta)
+}
+
+/// Record that a base's chunks are now embedded with `model`.
+pub fn set_embedding_model(base_id: &str, model: &str) -> Result<(), String> {
+    let mut meta = get_base(base_id)?;
+    meta.embedding_model = model.to_string();
+    write_json(&rag_config::base_meta_path(base_id), &meta)
 }
 
 /// Delete a knowledge base and all its data.
@@ -157,13 +300,21 @@ pub fn add_document_meta(
     path: &str,
     size_bytes: u64,
     chunk_count: usize,
+    content_hash: Option<String>,
+    source_id: Option<String>,
 ) -> Result<String, String> {
     validate_base_id(base_id)?;
     let mut meta = get_base(base_id)?;
 
     // Check for duplicate: same filename + size
     if let Some(existing) = meta.documents.iter().find(|d| d.filename == filename && d.size_bytes == size_bytes) {
-        return Err(format!("Document already exists: {} (id: {})", existing.filename, existing.id));
+        return Err(duplicate_error(existing));
+    }
+    // Same content or source. Folder sources track files by path, so identical
+    // files in a synced folder are kept apart.
+    let hash = content_hash.as_deref().filter(|_| source_id.is_none());
+    if let Some(existing) = meta.find_duplicate(hash, path) {
+        return Err(duplicate_error(existing));
     }
 
     let doc_id = uuid::Uuid::new_v4().to_string();
@@ -175,6 +326,9 @@ pub fn add_document_meta(
         size_bytes,
         chunk_count,
         added_at: now_millis(),
+        content_hash,
+        source_id,
+        labels: DocumentLabels::default(),
     });
 
     write_json(&rag_config::base_meta_path(base_id), &meta)?;
@@ -193,6 +347,77 @@ pub fn remove_document_meta(base_id: &str, document_id: &str) -> Result<(), Stri
     write_json(&rag_config::base_meta_path(base_id), &meta)
 }
 
+/// Change a document record in place.
+pub fn update_document_meta(
+    base_id: &str,
+    document_id: &str,
+    f: impl FnOnce(&mut DocumentMeta),
+) -> Result<(), String> {
+    let mut meta = get_base(base_id)?;
+    let doc = meta
+        .documents
+        .iter_mut()
+        .find(|d| d.id == document_id)
+        .ok_or_else(|| format!("Document '{document_id}' not found in base '{base_id}'"))?;
+    f(doc);
+    write_json(&rag_config::base_meta_path(base_id), &meta)
+}
+
+/// Register a folder source on a base.
+pub fn add_source(
+    base_id: &str,
+    path: &str,
+    include: Vec<String>,
+    exclude: Vec<String>,
+) -> Result<FolderSource, String> {
+    let mut meta = get_base(base_id)?;
+    if meta.sources.iter().any(|s| s.path == path) {
+        return Err(format!("Folder is already a source of this base: {path}"));
+    }
+    let source = FolderSource {
+        id: uuid::Uuid::new_v4().to_string(),
+        path: path.to_string(),
+        include,
+        exclude,
+        last_sync: None,
+    };
+    meta.sources.push(source.clone());
+    write_json(&rag_config::base_meta_path(base_id), &meta)?;
+    Ok(source)
+}
+
+/// Unregister a folder source. Its documents stay in the base as regular documents.
+pub fn remove_source(base_id: &str, source_id: &str) -> Result<FolderSource, String> {
+    let mut meta = get_base(base_id)?;
+    let pos = meta
+        .sources
+        .iter()
+        .position(|s| s.id == source_id)
+        .ok_or_else(|| format!("Source '{source_id}' not found in base '{base_id}'"))?;
+    let source = meta.sources.remove(pos);
+    for doc in &mut meta.documents {
+        if doc.source_id.as_deref() == Some(source_id) {
+            doc.source_id = None;
+        }
+    }
+    write_json(&rag_config::base_meta_path(base_id), &meta)?;
+    Ok(source)
+}
+
+/// Record the outcome of a source sync. A removed source is ignored.
+pub fn set_source_result(
+    base_id: &str,
+    source_id: &str,
+    result: SourceSyncResult,
+) -> Result<(), String> {
+    let mut meta = get_base(base_id)?;
+    if let Some(source) = meta.sources.iter_mut().find(|s| s.id == source_id) {
+        source.last_sync = Some(result);
+        write_json(&rag_config::base_meta_path(base_id), &meta)?;
+    }
+    Ok(())
+}
+
 /// List documents in a knowledge base.
 pub fn list_documents(base_id: &str) -> Result<Vec<DocumentMeta>, String> {
     let meta = get_base(base_id)?;
@@ -0,0 +1,859 @@
+//! Folder sources: keep a knowledge base in step with folders on disk.
+//!
+//! A sync scans each source folder into (path, SHA-256) pairs and compares
+//! them with the documents the source added before: new files are added,
+//! files whose hash changed are reindexed, and documents whose file is gone
+//! are removed. Source folders are watched with `notify` and synced again
+//! shortly after a matching file changes.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use glob::{MatchOptions, Pattern};
+use notify_debouncer_mini::{new_debouncer, DebouncedEvent, DebouncedEventKind, Debouncer};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::mpsc;
+
+use super::gitignore::Gitignore;
+use super::{code, commands, index, migrate, store};
+
+/// Quiet period after file changes before a source is synced.
+const SYNC_DEBOUNCE: Duration = Duration::from_secs(3);
+/// How long to wait before retrying a sync held back by a migration.
+const MIGRATION_RETRY: Duration = Duration::from_secs(30);
+/// A source with more matching files than this is not synced.
+const MAX_SOURCE_FILES: usize = 5000;
+/// Errors kept in a source's last sync result.
+const MAX_REPORTED_ERRORS: usize = 10;
+/// Document types a source picks up when it has no include patterns.
+const DEFAULT_EXTENSIONS: &[&str] = &[
+    "txt", "md", "markdown", "mdx", "pdf", "epub", "json", "csv", "html",
+];
+/// Picked up in codebase bases besides source files.
+const CODEBASE_DOC_EXTENSIONS: &[&str] = &["md", "mdx", "txt"];
+/// Larger files in a codebase are generated or minified; they're skipped.
+const MAX_CODE_FILE_BYTES: u64 = 1024 * 1024;
+
+const GLOB_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Sync status of a base, for the UI.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaseSyncStatus {
+    pub syncing: bool,
+    pub sources: Vec<store::FolderSource>,
+}
+
+/// Event payload: a sync of this base finished.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncFinished {
+    base_id: String,
+}
+
+/// Check include/exclude patterns of a source.
+pub fn validate_patterns(patterns: &[String]) -> Result<(), String> {
+    for p in patterns {
+        if p.starts_with('/') || p.split('/').any(|part| part == "..") {
+            return Err(format!("Pattern must stay inside the folder: '{p}'"));
+        }
+        Pattern::new(p).map_err(|e| format!("Invalid pattern '{p}': {e}"))?;
+    }
+    Ok(())
+}
+
+/// Which files under a source folder belong to it. Paths are relative to the folder.
+struct SourceFilter {
+    kind: store::BaseKind,
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+}
+
+impl SourceFilter {
+    fn new(source: &store::FolderSource, kind: store::BaseKind) -> Result<Self, String> {
+        let compile = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|p| Pattern::new(p).map_err(|e| format!("Invalid pattern '{p}': {e}")))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(Self {
+            kind,
+            include: compile(&source.include)?,
+            exclude: compile(&source.exclude)?,
+        })
+    }
+
+    /// Hidden and excluded directories are skipped entirely.
+    fn enters(&self, rel_dir: &Path) -> bool {
+        let hidden = rel_dir
+            .file_name()
+            .is_some_and(|n| n.to_string_lossy().starts_with('.'));
+        !hidden
+            && !self
+                .exclude
+                .iter()
+                .any(|p| p.matches_path_with(rel_dir, GLOB_OPTIONS))
+    }
+
+    fn matches(&self, rel: &Path) -> bool {
+        let in_skipped_dir = rel
+            .ancestors()
+            .skip(1)
+            .filter(|a| !a.as_os_str().is_empty())
+            .any(|a| !self.enters(a));
+        let hidden = rel
+            .file_name()
+            .is_some_and(|n| n.to_string_lossy().starts_with('.'));
+        if in_skipped_dir
+            || hidden
+            || self
+                .exclude
+                .iter()
+                .any(|p| p.matches_path_with(rel, GLOB_OPTIONS))
+        {
+            return false;
+        }
+        if self.include.is_empty() {
+            let Some(ext) = rel.extension().and_then(|e| e.to_str()) else {
+                return false;
+            };
+            let ext = ext.to_lowercase();
+            match self.kind {
+                store::BaseKind::Documents => DEFAULT_EXTENSIONS.contains(&ext.as_str()),
+                store::BaseKind::Codebase => {
+                    code::SOURCE_EXTENSIONS.contains(&ext.as_str())
+                        || CODEBASE_DOC_EXTENSIONS.contains(&ext.as_str())
+                }
+            }
+        } else {
+            self.include
+                .iter()
+                .any(|p| p.matches_path_with(rel, GLOB_OPTIONS))
+        }
+    }
+}
+
+/// A file found in a source; `hash` is None if it couldn't be read.
+struct ScannedFile {
+    path: String,
+    hash: Option<String>,
+}
+
+/// Walk a source folder and hash the files that belong to it. Codebases
+/// are walked the way git sees them, honouring `.gitignore` files.
+fn scan(root: &Path, filter: &SourceFilter) -> Result<Vec<ScannedFile>, String> {
+    crate::files::validate_path_safe(root)?;
+    if !root.is_dir() {
+        return Err(format!("Folder not found: {}", root.display()));
+    }
+
+    let mut ignore = (filter.kind == store::BaseKind::Codebase).then(Gitignore::default);
+    let mut paths = Vec::new();
+    walk(root, Path::new(""), filter, &mut ignore, &mut paths)?;
+    paths.sort();
+
+    Ok(paths
+        .into_iter()
+        .map(|path| ScannedFile {
+            hash: std::fs::read(&path)
+                .map(|bytes| store::content_hash(&bytes))
+                .ok(),
+            path: path.to_string_lossy().into_owned(),
+        })
+        .collect())
+}
+
+fn walk(
+    root: &Path,
+    dir: &Path,
+    filter: &SourceFilter,
+    ignore: &mut Option<Gitignore>,
+    paths: &mut Vec<PathBuf>,
+) -> Result<(), String> {
+    let entries = match std::fs::read_dir(root.join(dir)) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("[rag] Failed to read {}: {e}", root.join(dir).display());
+            return Ok(());
+        }
+    };
+    let rules = ignore.as_mut().map(|gi| gi.push_dir(root, dir));
+
+    for entry in entries.flatten() {
+        let rel = dir.join(entry.file_name());
+        // Symlinks are not followed
+        let Ok(ft) = entry.file_type() else {
+            continue;
+        };
+        let ignored = |is_dir| {
+            ignore
+                .as_ref()
+                .is_some_and(|gi| gi.is_ignored(&rel, is_dir))
+        };
+        if ft.is_dir() {
+            if filter.enters(&rel) && !ignored(true) {
+                walk(root, &rel, filter, ignore, paths)?;
+            }
+        } else if ft.is_file() && filter.matches(&rel) && !ignored(false) {
+            if ignore.is_some()
+                && entry
+                    .metadata()
+                    .is_ok_and(|m| m.len() > MAX_CODE_FILE_BYTES)
+            {
+                continue;
+            }
+            paths.push(entry.path());
+            if paths.len() > MAX_SOURCE_FILES {
+                return Err(format!(
+                    "More than {MAX_SOURCE_FILES} matching files; narrow the include patterns"
+                ));
+            }
+        }
+    }
+
+    if let (Some(gi), Some(len)) = (ignore.as_mut(), rules) {
+        gi.truncate(len);
+    }
+    Ok(())
+}
+
+/// What a sync has to do for one source.
+#[derive(Debug, Default, PartialEq)]
+struct SyncPlan {
+    /// New files to add.
+    add: Vec<String>,
+    /// Documents added by hand from a file in the folder; the source takes them over.
+    adopt: Vec<String>,
+    /// Documents whose file changed.
+    update: Vec<String>,
+    /// Documents whose file is gone.
+    remove: Vec<String>,
+}
+
+fn plan_sync(source_id: &str, docs: &[store::DocumentMeta], files: &[ScannedFile]) -> SyncPlan {
+    let owned: HashMap<&str, &store::DocumentMeta> = docs
+        .iter()
+        .filter(|d| d.source_id.as_deref() == Some(source_id))
+        .map(|d| (d.path.as_str(), d))
+        .collect();
+    let manual: HashMap<&str, &store::DocumentMeta> = docs
+        .iter()
+        .filter(|d| d.source_id.is_none())
+        .map(|d| (d.path.as_str(), d))
+        .collect();
+
+    let mut plan = SyncPlan::default();
+    for file in files {
+        let doc = match (
+            owned.get(file.path.as_str()),
+            manual.get(file.path.as_str()),
+        ) {
+            (Some(doc), _) => doc,
+            (None, Some(doc)) => {
+                plan.adopt.push(doc.id.clone());
+                doc
+            }
+            (None, None) => {
+                // An unreadable file will fail to parse too; try it on the next sync
+                if file.hash.is_some() {
+                    plan.add.push(file.path.clone());
+                }
+                continue;
+            }
+        };
+        if file.hash.is_some() && doc.content_hash != file.hash {
+            plan.update.push(doc.id.clone());
+        }
+    }
+
+    let present: HashSet<&str> = files.iter().map(|f| f.path.as_str()).collect();
+    plan.remove = owned
+        .values()
+        .filter(|d| !present.contains(d.path.as_str()))
+        .map(|d| d.id.clone())
+        .collect();
+    plan.remove.sort();
+    plan
+}
+
+// ---------------------------------------------------------------------------
+// Running a sync
+// ---------------------------------------------------------------------------
+
+/// Bases with a sync in progress.
+static SYNCING: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+
+fn syncing() -> &'static Mutex<HashSet<String>> {
+    SYNCING.get_or_init(Default::default)
+}
+
+/// Whether a base is being synced right now.
+pub fn is_syncing(base_id: &str) -> bool {
+    syncing()
+        .lock()
+        .map(|set| set.contains(base_id))
+        .unwrap_or(false)
+}
+
+/// Marks a base as syncing while alive.
+struct SyncGuard(String);
+
+impl SyncGuard {
+    fn acquire(base_id: &str) -> Result<Self, String> {
+        let mut set = syncing()
+            .lock()
+            .map_err(|e| format!("Sync lock poisoned: {e}"))?;
+        if !set.insert(base_id.to_string()) {
+            return Err("This knowledge base is already syncing".into());
+        }
+        Ok(Self(base_id.to_string()))
+    }
+}
+
+impl Drop for SyncGuard {
+    fn drop(&mut self) {
+        match syncing().lock() {
+            Ok(mut set) => {
+                set.remove(&self.0);
+            }
+            Err(e) => eprintln!("[rag] Sync lock poisoned: {e}"),
+        }
+    }
+}
+
+fn record_error(result: &mut store::SourceSyncResult, error: String) {
+    eprintln!("[rag] Sync: {error}");
+    result.failed += 1;
+    if result.errors.len() < MAX_REPORTED_ERRORS {
+        result.errors.push(error);
+    }
+}
+
+async fn sync_source(
+    base_id: &str,
+    kind: store::BaseKind,
+    source: &store::FolderSource,
+    docs: &[store::DocumentMeta],
+) -> store::SourceSyncResult {
+    let mut result = store::SourceSyncResult::default();
+
+    let root = PathBuf::from(&source.path);
+    let files = match SourceFilter::new(source, kind) {
+        Ok(filter) => tokio::task::spawn_blocking(move || scan(&root, &filter))
+            .await
+            .map_err(|e| format!("Task join error: {e}"))
+            .and_then(|r| r),
+        Err(e) => Err(e),
+    };
+    let files = match files {
+        Ok(files) => files,
+        Err(e) => {
+            // Nothing is removed when the folder can't be read (e.g. an unmounted drive)
+            result.errors.push(e);
+            result.finished_at = store::now_millis();
+            return result;
+        }
+    };
+
+    let plan = plan_sync(&source.id, docs, &files);
+    let doc_by_id: HashMap<&str, &store::DocumentMeta> =
+        docs.iter().map(|d| (d.id.as_str(), d)).collect();
+
+    for doc_id in &plan.adopt {
+        let sid = source.id.clone();
+        let (bid, did) = (base_id.to_string(), doc_id.clone());
+        let adopted = tokio::task::spawn_blocking(move || {
+            store::update_document_meta(&bid, &did, |d| d.source_id = Some(sid))
+        })
+        .await
+        .map_err(|e| format!("Task join error: {e}"))
+        .and_then(|r| r);
+        if let Err(e) = adopted {
+            record_error(&mut result, e);
+        }
+    }
+
+    for path in &plan.add {
+        match commands::add_single_document(base_id, path, Some(&source.id)).await {
+            Ok(_) => result.added += 1,
+            Err(e) => record_error(&mut result, format!("{path}: {e}")),
+        }
+    }
+
+    for doc in plan
+        .update
+        .iter()
+        .filter_map(|id| doc_by_id.get(id.as_str()))
+    {
+        match commands::reindex_single_document(base_id, doc, None, None).await {
+            Ok(_) => result.updated += 1,
+            Err(e) => record_error(&mut result, format!("{}: {e}", doc.path)),
+        }
+    }
+
+    for doc in plan
+        .remove
+        .iter()
+        .filter_map(|id| doc_by_id.get(id.as_str()))
+    {
+        let removed = async {
+            index::remove_document_chunks(base_id, &doc.id).await?;
+            let (bid, did) = (base_id.to_string(), doc.id.clone());
+            tokio::task::spawn_blocking(move || store::remove_document_meta(&bid, &did))
+                .await
+                .map_err(|e| format!("Task join error: {e}"))?
+        };
+        match removed.await {
+            Ok(()) => result.removed += 1,
+            Err(e) => record_error(&mut result, format!("{}: {e}", doc.path)),
+        }
+    }
+
+    result.finished_at = store::now_millis();
+    result
+}
+
+/// Sync every folder source of a base and record each result.
+pub async fn sync_base(base_id: &str) -> Result<(), String> {
+    let _access = migrate::write_access(base_id)?;
+    let _guard = SyncGuard::acquire(base_id)?;
+
+    let bid = base_id.to_string();
+    let meta = tokio::task::spawn_blocking(move || store::get_base(&bid))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))??;
+
+    for source in &meta.sources {
+        // Each source sees the documents as left by the previous one
+        let bid = base_id.to_string();
+        let docs = tokio::task::spawn_blocking(move || store::list_documents(&bid))
+            .await
+            .map_err(|e| format!("Task join error: {e}"))??;
+
+        let result = sync_source(base_id, meta.kind, source, &docs).await;
+        if result.added + result.updated + result.removed + result.failed > 0 {
+            eprintln!(
+                "[rag] Synced {}: {} added, {} updated, {} removed, {} failed",
+                source.path, result.added, result.updated, result.removed, result.failed
+            );
+        }
+        let (bid, sid) = (base_id.to_string(), source.id.clone());
+        tokio::task::spawn_blocking(move || store::set_source_result(&bid, &sid, result))
+            .await
+            .map_err(|e| format!("Task join error: {e}"))??;
+    }
+    Ok(())
+}
+
+/// Current sync status of a base.
+pub fn status(base_id: &str) -> Result<BaseSyncStatus, String> {
+    let meta = store::get_base(base_id)?;
+    Ok(BaseSyncStatus {
+        syncing: is_syncing(base_id),
+        sources: meta.sources,
+    })
+}
+
+/// Tell the frontend a base's documents may have changed.
+pub fn emit_finished(app: &AppHandle, base_id: &str) {
+    let payload = SyncFinished {
+        base_id: base_id.to_string(),
+    };
+    if let Err(e) = app.emit("rag-sync-finished", payload) {
+        eprintln!("[rag] Failed to emit sync event: {e}");
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Background sync: watchers, reconciled with the sources of all bases
+// ---------------------------------------------------------------------------
+
+struct BaseWatch {
+    /// Sources the watcher was built for (path, include, exclude).
+    key: Vec<(String, Vec<String>, Vec<String>)>,
+    _debouncer: Debouncer<notify::RecommendedWatcher>,
+}
+
+struct Watchers {
+    tx: mpsc::UnboundedSender<String>,
+    by_base: HashMap<String, BaseWatch>,
+}
+
+static WATCHERS: OnceLock<Mutex<Watchers>> = OnceLock::new();
+
+/// A watched source folder.
+struct WatchedRoot {
+    root: PathBuf,
+    filter: SourceFilter,
+    /// Top-level `.gitignore` of a codebase, so build output doesn't trigger syncs.
+    ignore: Option<Gitignore>,
+}
+
+impl WatchedRoot {
+    fn is_relevant(&self, path: &Path) -> bool {
+        path.strip_prefix(&self.root).is_ok_and(|rel| {
+            self.filter.matches(rel)
+                && !self
+                    .ignore
+                    .as_ref()
+                    .is_some_and(|gi| gi.is_file_ignored(rel))
+        })
+    }
+}
+
+fn watch_base(
+    meta: &store::BaseMeta,
+    tx: mpsc::UnboundedSender<String>,
+) -> Result<BaseWatch, String> {
+    let mut roots = Vec::new();
+    for source in &meta.sources {
+        let root = PathBuf::from(&source.path);
+        crate::files::validate_path_safe(&root)?;
+        // Events carry canonical paths on some platforms
+        let root = root.canonicalize().unwrap_or(root);
+        let ignore = (meta.kind == store::BaseKind::Codebase).then(|| {
+            let mut gi = Gitignore::default();
+            gi.push_dir(&root, Path::new(""));
+            gi
+        });
+        roots.push(WatchedRoot {
+            filter: SourceFilter::new(source, meta.kind)?,
+            root,
+            ignore,
+        });
+    }
+
+    let watched: Vec<PathBuf> = roots.iter().map(|r| r.root.clone()).collect();
+    let id = meta.id.clone();
+    let mut debouncer = new_debouncer(
+        SYNC_DEBOUNCE,
+        move |result: Result<Vec<DebouncedEvent>, notify::Error>| {
+            let events = match result {
+                Ok(evts) => evts,
+                Err(e) => {
+                    eprintln!("[rag] Source watcher error: {e}");
+                    return;
+                }
+            };
+            let relevant = events.iter().any(|evt| {
+                evt.kind == DebouncedEventKind::Any
+                    && roots.iter().any(|r| r.is_relevant(&evt.path))
+            });
+            if relevant && tx.send(id.clone()).is_err() {
+                eprintln!("[rag] Sync channel closed");
+            }
+        },
+    )
+    .map_err(|e| format!("Failed to create watcher: {e}"))?;
+
+    for root in &watched {
+        if !root.is_dir() {
+            eprintln!(
+                "[rag] Source folder not found, not watching: {}",
+                root.display()
+            );
+            continue;
+        }
+        debouncer
+            .watcher()
+            .watch(root, notify::RecursiveMode::Recursive)
+            .map_err(|e| format!("Failed to watch {}: {e}", root.display()))?;
+    }
+
+    Ok(BaseWatch {
+        key: meta
+            .sources
+            .iter()
+            .map(|s| (s.path.clone(), s.include.clone(), s.exclude.clone()))
+            .collect(),
+        _debouncer: debouncer,
+    })
+}
+
+/// Watch the sources of every base; call after sources are added or removed.
+/// Blocking I/O. A no-op until `start` has run.
+pub fn refresh_watchers() {
+    let Some(watchers) = WATCHERS.get() else {
+        return;
+    };
+    let bases = match store::list_bases() {
+        Ok(bases) => bases,
+        Err(e) => {
+            eprintln!("[rag] Failed to list bases for sync: {e}");
+            return;
+        }
+    };
+    let metas: Vec<store::BaseMeta> = bases
+        .iter()
+        .filter_map(|b| store::get_base(&b.id).ok())
+        .filter(|m| !m.sources.is_empty())
+        .collect();
+
+    let Ok(mut state) = watchers.lock() else {
+        eprintln!("[rag] Sync watcher lock poisoned");
+        return;
+    };
+    let wanted: HashSet<&str> = metas.iter().map(|m| m.id.as_str()).collect();
+    state.by_base.retain(|id, _| wanted.contains(id.as_str()));
+
+    for meta in &metas {
+        let key: Vec<_> = meta
+            .sources
+            .iter()
+            .map(|s| (s.path.clone(), s.include.clone(), s.exclude.clone()))
+            .collect();
+        if state.by_base.get(&meta.id).is_some_and(|w| w.key == key) {
+            continue;
+        }
+        match watch_base(meta, state.tx.clone()) {
+            Ok(watch) => {
+                state.by_base.insert(meta.id.clone(), watch);
+            }
+            Err(e) => {
+                state.by_base.remove(&meta.id);
+                eprintln!("[rag] Failed to watch sources of base {}: {e}", meta.id);
+            }
+        }
+    }
+}
+
+/// Ask the background loop to sync a base.
+pub fn queue_sync(base_id: &str) {
+    let Some(watchers) = WATCHERS.get() else {
+        return;
+    };
+    match watchers.lock() {
+        Ok(state) => {
+            if state.tx.send(base_id.to_string()).is_err() {
+                eprintln!("[rag] Sync channel closed");
+            }
+        }
+        Err(e) => eprintln!("[rag] Sync watcher lock poisoned: {e}"),
+    }
+}
+
+/// Start watching folder sources and sync every base that has any, to pick
+/// up changes made while the app was closed.
+pub async fn start(app: AppHandle) {
+    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+    let watchers = Watchers {
+        tx: tx.clone(),
+        by_base: HashMap::new(),
+    };
+    if WATCHERS.set(Mutex::new(watchers)).is_err() {
+        eprintln!("[rag] Folder sync already started");
+        return;
+    }
+
+    let with_sources = tokio::task::spawn_blocking(|| {
+        refresh_watchers();
+        store::list_bases()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|b| store::get_base(&b.id).is_ok_and(|m| !m.sources.is_empty()))
+            .map(|b| b.id)
+            .collect::<Vec<_>>()
+    })
+    .await
+    .unwrap_or_default();
+    for id in with_sources {
+        let _ = tx.send(id);
+    }
+
+    while let Some(first) = rx.recv().await {
+        // Coalesce a burst of events into one sync per base
+        let mut pending = vec![first];
+        while let Ok(id) = rx.try_recv() {
+            if !pending.contains(&id) {
+                pending.push(id);
+            }
+        }
+        for base_id in pending {
+            match sync_base(&base_id).await {
+                Ok(()) => emit_finished(&app, &base_id),
+                // The changes still need syncing once the new index is in
+                Err(_) if migrate::is_migrating(&base_id) => {
+                    let tx = tx.clone();
+                    tokio::spawn(async move {
+                        tokio::time::sleep(MIGRATION_RETRY).await;
+                        let _ = tx.send(base_id);
+                    });
+                }
+                Err(e) => eprintln!("[rag] Sync of base {base_id} skipped: {e}"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(include: &[&str], exclude: &[&str]) -> store::FolderSource {
+        store::FolderSource {
+            id: "src".into(),
+            path: "/docs".into(),
+            include: include.iter().map(|s| s.to_string()).collect(),
+            exclude: exclude.iter().map(|s| s.to_string()).collect(),
+            last_sync: None,
+        }
+    }
+
+    fn doc(id: &str, path: &str, hash: &str, source_id: Option<&str>) -> store::DocumentMeta {
+        store::DocumentMeta {
+            id: id.into(),
+            filename: path.rsplit('/').next().unwrap_or(path).into(),
+            path: path.into(),
+            size_bytes: 1,
+            chunk_count: 1,
+            added_at: 0,
+            content_hash: Some(hash.into()),
+            labels: Default::default(),
+            source_id: source_id.map(String::from),
+        }
+    }
+
+    fn file(path: &str, hash: Option<&str>) -> ScannedFile {
+        ScannedFile {
+            path: path.into(),
+            hash: hash.map(String::from),
+        }
+    }
+
+    #[test]
+    fn filter_defaults_to_document_types() {
+        let filter = SourceFilter::new(
+            &source(&[], &["drafts", "**/*.tmp.md"]),
+            store::BaseKind::Documents,
+        )
+        .unwrap();
+        assert!(filter.matches(Path::new("guide.md")));
+        assert!(filter.matches(Path::new("a/b/manual.PDF")));
+        assert!(!filter.matches(Path::new("main.rs")));
+        assert!(!filter.matches(Path::new(".hidden.md")));
+        assert!(!filter.matches(Path::new(".git/notes.md")));
+        assert!(!filter.matches(Path::new("drafts/wip.md")));
+        assert!(!filter.matches(Path::new("a/x.tmp.md")));
+        assert!(!filter.enters(Path::new("drafts")));
+    }
+
+    #[test]
+    fn filter_uses_include_patterns() {
+        let filter = SourceFilter::new(
+            &source(&["**/*.rs", "*.toml"], &[]),
+            store::BaseKind::Documents,
+        )
+        .unwrap();
+        assert!(filter.matches(Path::new("src/lib.rs")));
+        assert!(filter.matches(Path::new("Cargo.toml")));
+        assert!(!filter.matches(Path::new("sub/Cargo.toml")));
+        assert!(!filter.matches(Path::new("README.md")));
+        assert!(validate_patterns(&["../up".into()]).is_err());
+        assert!(validate_patterns(&["/abs/*.md".into()]).is_err());
+        assert!(validate_patterns(&["[".into()]).is_err());
+    }
+
+    #[test]
+    fn plan_adds_updates_and_removes() {
+        let docs = vec![
+            doc("same", "/docs/same.md", "h1", Some("src")),
+            doc("changed", "/docs/changed.md", "h2", Some("src")),
+            doc("gone", "/docs/gone.md", "h3", Some("src")),
+            doc("manual", "/docs/manual.md", "h4", None),
+            doc("other", "/elsewhere/other.md", "h5", Some("other-src")),
+        ];
+        let files = vec![
+            file("/docs/same.md", Some("h1")),
+            file("/docs/changed.md", Some("h2-new")),
+            file("/docs/manual.md", Some("h4")),
+            file("/docs/new.md", Some("h6")),
+            file("/docs/unreadable.md", None),
+        ];
+        let plan = plan_sync("src", &docs, &files);
+        assert_eq!(
+            plan,
+            SyncPlan {
+                add: vec!["/docs/new.md".into()],
+                adopt: vec!["manual".into()],
+                update: vec!["changed".into()],
+                remove: vec!["gone".into()],
+            }
+        );
+    }
+
+    #[test]
+    fn unreadable_files_are_kept() {
+        let docs = vec![doc("a", "/docs/a.md", "h1", Some("src"))];
+        let plan = plan_sync("src", &docs, &[file("/docs/a.md", None)]);
+        assert_eq!(plan, SyncPlan::default());
+    }
+
+    #[test]
+    fn scan_walks_and_hashes() {
+        let root = std::env::temp_dir().join(format!("rag-sync-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::write(root.join("a.md"), "alpha").unwrap();
+        std::fs::write(root.join("sub/b.txt"), "beta").unwrap();
+        std::fs::write(root.join("sub/c.bin"), "gamma").unwrap();
+        std::fs::write(root.join(".git/d.md"), "delta").unwrap();
+
+        let filter = SourceFilter::new(&source(&[], &[]), store::BaseKind::Documents).unwrap();
+        let files = scan(&root, &filter).unwrap();
+        let names: Vec<_> = files
+            .iter()
+            .map(|f| {
+                Path::new(&f.path)
+                    .strip_prefix(&root)
+                    .unwrap()
+                    .to_path_buf()
+            })
+            .collect();
+        assert_eq!(names, [PathBuf::from("a.md"), PathBuf::from("sub/b.txt")]);
+        assert_eq!(
+            files[0].hash.as_deref(),
+            Some(store::content_hash(b"alpha").as_str())
+        );
+
+        assert!(scan(&root.join("missing"), &filter).is_err());
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn codebase_scan_honours_gitignore() {
+        let root = std::env::temp_dir().join(format!("rag-sync-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("target/debug")).unwrap();
+        std::fs::write(root.join(".gitignore"), "target/\n*.gen.rs\n").unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(root.join("src/api.gen.rs"), "fn gen() {}").unwrap();
+        std::fs::write(root.join("src/notes.pdf"), "%PDF").unwrap();
+        std::fs::write(root.join("README.md"), "# Project").unwrap();
+        std::fs::write(root.join("target/debug/out.rs"), "fn out() {}").unwrap();
+
+        let filter = SourceFilter::new(&source(&[], &[]), store::BaseKind::Codebase).unwrap();
+        let files = scan(&root, &filter).unwrap();
+        let names: Vec<_> = files
+            .iter()
+            .map(|f| {
+                Path::new(&f.path)
+                    .strip_prefix(&root)
+                    .unwrap()
+                    .to_path_buf()
+            })
+            .collect();
+        assert_eq!(
+            names,
+            [PathBuf::from("README.md"), PathBuf::from("src/main.rs")]
+        );
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
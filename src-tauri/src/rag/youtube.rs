@@ -1,5 +1,7 @@
 use std::process::Command;
 
+use super::parser::{Location, ParsedDocument};
+
 /// A single video entry from a playlist.
 pub struct PlaylistEntry {
     pub url: String,
@@ -21,6 +23,25 @@ pub fn is_playlist_url(url: &str) -> bool {
     false
 }
 
+/// The video ID in a YouTube watch, youtu.be, shorts, embed or live URL.
+pub fn video_id(url: &str) -> Option<String> {
+    let parsed = reqwest::Url::parse(url).ok()?;
+    let host = parsed.host_str()?;
+    let host = host.strip_prefix("www.").or_else(|| host.strip_prefix("m.")).unwrap_or(host);
+    let mut segments = parsed.path_segments()?.filter(|s| !s.is_empty());
+    let id = match host {
+        "youtu.be" => segments.next()?.to_string(),
+        "youtube.com" | "music.youtube.com" => match segments.next()? {
+            "watch" => parsed.query_pairs().find(|(k, _)| k == "v")?.1.into_owned(),
+            "shorts" | "embed" | "live" | "v" => segments.next()?.to_string(),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let valid = !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    valid.then_some(id)
+}
+
 /// Fetch the list of video URLs and titles from a YouTube playlist using yt-dlp.
 /// NOTE: always called from spawn_blocking.
 pub fn fetch_playlist_urls(url: &str) -> Result<Vec<PlaylistEntry>, String> {
@@ -73,9 +94,9 @@ pub fn fetch_playlist_urls(url: &str) -> Result<Vec<PlaylistEntry>, String> {
     Ok(entries)
 }
 
-/// Fetch YouTube video transcript using yt-dlp.
+/// Fetch YouTube video transcript using yt-dlp, located by cue start time.
 /// NOTE: always called from spawn_blocking (see commands.rs).
-pub fn fetch_youtube_transcript(url: &str) -> Result<String, String> {
+pub fn fetch_youtube_transcript(url: &str) -> Result<ParsedDocument, String> {
     let temp_dir = std::env::temp_dir().join(format!("aitherflow-yt-{}", uuid::Uuid::new_v4()));
     std::fs::create_dir_all(&temp_dir)
         .map_err(|e| format!("Failed to create temp dir: {e}"))?;
@@ -128,12 +149,12 @@ pub fn fetch_youtube_transcript(url: &str) -> Result<String, String> {
         eprintln!("[rag] Failed to cleanup temp dir: {e}");
     }
 
-    let text = parse_vtt(&vtt_content);
-    if text.is_empty() {
+    let transcript = parse_vtt(&vtt_content);
+    if transcript.text.is_empty() {
         return Err("No transcript content found in subtitles".into());
     }
 
-    Ok(text)
+    Ok(transcript)
 }
 
 /// Find and read the first .vtt file in the temp directory.
@@ -158,11 +179,12 @@ fn find_and_read_vtt(dir: &std::path::Path) -> Result<String, String> {
     Err("No subtitle file found — video may not have captions".into())
 }
 
-/// Parse VTT subtitle file into clean text.
-/// Removes timestamps, duplicate lines, and VTT headers.
-fn parse_vtt(vtt: &str) -> String {
+/// Parse VTT subtitle file into clean text, with a section per cue holding
+/// its start time. Removes timestamps, duplicate lines, and VTT headers.
+fn parse_vtt(vtt: &str) -> ParsedDocument {
     let mut lines = Vec::new();
     let mut prev_line = String::new();
+    let mut cue_start = None;
 
     for line in vtt.lines() {
         let trimmed = line.trim();
@@ -177,8 +199,9 @@ fn parse_vtt(vtt: &str) -> String {
             continue;
         }
 
-        // Skip timestamp lines (00:00:00.000 --> 00:00:05.000)
-        if trimmed.contains("-->") {
+        // Timestamp lines (00:00:00.000 --> 00:00:05.000) start a cue
+        if let Some((start, _)) = trimmed.split_once("-->") {
+            cue_start = parse_vtt_time(start.trim());
             continue;
         }
 
@@ -201,10 +224,22 @@ fn parse_vtt(vtt: &str) -> String {
         }
 
         prev_line = clean.to_string();
-        lines.push(clean.to_string());
+        let location = Location {
+            timestamp: cue_start,
+            ..Location::default()
+        };
+        lines.push((location, clean.to_string()));
     }
 
-    lines.join(" ")
+    ParsedDocument::from_pieces(lines, " ")
+}
+
+/// Whole seconds of a VTT timestamp: `hh:mm:ss.ttt` or `mm:ss.ttt`.
+fn parse_vtt_time(s: &str) -> Option<u32> {
+    let whole = s.split('.').next()?;
+    whole.split(':').try_fold(0u32, |acc, part| {
+        Some(acc.checked_mul(60)? + part.parse::<u32>().ok()?)
+    })
 }
 
 /// Remove VTT formatting tags: <c>, </c>, <00:00:01.234>, etc.
@@ -228,49 +263,83 @@ fn strip_vtt_tags(s: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn video_id_from_url_forms() {
+        let id = Some("dQw4w9WgXcQ".to_string());
+        assert_eq!(video_id("https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=42s"), id);
+        assert_eq!(video_id("https://m.youtube.com/watch?feature=share&v=dQw4w9WgXcQ"), id);
+        assert_eq!(video_id("https://youtu.be/dQw4w9WgXcQ?si=abc"), id);
+        assert_eq!(video_id("https://youtube.com/shorts/dQw4w9WgXcQ"), id);
+        assert_eq!(video_id("https://www.youtube.com/embed/dQw4w9WgXcQ"), id);
+        assert_eq!(video_id("https://www.youtube.com/playlist?list=PL123"), None);
+        assert_eq!(video_id("https://example.com/watch?v=dQw4w9WgXcQ"), None);
+        assert_eq!(video_id("not a url"), None);
+    }
+
     // --- parse_vtt ---
 
     #[test]
     fn vtt_basic_transcript() {
         let vtt = "WEBVTT\nKind: captions\nLanguage: en\n\n00:00:00.000 --> 00:00:02.000\nHello world\n\n00:00:02.000 --> 00:00:04.000\nGoodbye world";
-        let result = parse_vtt(vtt);
+        let result = parse_vtt(vtt).text;
         assert_eq!(result, "Hello world Goodbye world");
     }
 
     #[test]
     fn vtt_deduplicates_consecutive_lines() {
         let vtt = "WEBVTT\n\n00:00:00.000 --> 00:00:01.000\nHello\n\n00:00:01.000 --> 00:00:02.000\nHello\n\n00:00:02.000 --> 00:00:03.000\nWorld";
-        let result = parse_vtt(vtt);
+        let result = parse_vtt(vtt).text;
         assert_eq!(result, "Hello World");
     }
 
     #[test]
     fn vtt_strips_tags() {
         let vtt = "WEBVTT\n\n00:00:00.000 --> 00:00:02.000\n<c>Hello</c> <00:00:01.234>world";
-        let result = parse_vtt(vtt);
+        let result = parse_vtt(vtt).text;
         assert_eq!(result, "Hello world");
     }
 
     #[test]
     fn vtt_skips_numeric_cues() {
         let vtt = "WEBVTT\n\n1\n00:00:00.000 --> 00:00:02.000\nHello\n\n2\n00:00:02.000 --> 00:00:04.000\nWorld";
-        let result = parse_vtt(vtt);
+        let result = parse_vtt(vtt).text;
         assert_eq!(result, "Hello World");
     }
 
     #[test]
     fn vtt_empty() {
-        assert_eq!(parse_vtt(""), "");
-        assert_eq!(parse_vtt("WEBVTT\n\n"), "");
+        assert_eq!(parse_vtt("").text, "");
+        assert_eq!(parse_vtt("WEBVTT\n\n").text, "");
     }
 
     #[test]
     fn vtt_skips_note() {
         let vtt = "WEBVTT\n\nNOTE This is a comment\n\n00:00:00.000 --> 00:00:02.000\nHello";
-        let result = parse_vtt(vtt);
+        let result = parse_vtt(vtt).text;
         assert_eq!(result, "Hello");
     }
 
+    #[test]
+    fn vtt_sections_carry_cue_start() {
+        let vtt = "WEBVTT\n\n00:00:01.500 --> 00:00:03.000\nHello\n\n01:02:03.000 --> 01:02:05.000\nHello\nthere";
+        let doc = parse_vtt(vtt);
+        assert_eq!(doc.text, "Hello there");
+        let starts: Vec<(usize, Option<u32>)> = doc
+            .sections
+            .iter()
+            .map(|s| (s.start, s.location.timestamp))
+            .collect();
+        assert_eq!(starts, [(0, Some(1)), (6, Some(3723))]);
+    }
+
+    #[test]
+    fn vtt_time_formats() {
+        assert_eq!(parse_vtt_time("00:00:00.000"), Some(0));
+        assert_eq!(parse_vtt_time("01:30.250"), Some(90));
+        assert_eq!(parse_vtt_time("1:00:00.000"), Some(3600));
+        assert_eq!(parse_vtt_time("abc"), None);
+    }
+
     // --- strip_vtt_tags ---
 
     #[test]
@@ -0,0 +1,225 @@
+//! Follow-up tasks: when a run ends, start other tasks depending on its
+//! status and final message. The follow-up gets the upstream result as its
+//! trigger (`{{trigger}}` / `{{trigger_payload}}` in the prompt).
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::triggers::{self, Rejected, TriggerContext};
+use super::{ScheduledTask, TaskRunStatus};
+
+/// Trigger kind of a run started as a follow-up.
+pub(crate) const FOLLOW_UP_KIND: &str = "follow_up";
+
+/// Which outcomes of the upstream run start a follow-up.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FollowUpWhen {
+    #[default]
+    Success,
+    /// Error or timeout.
+    Failure,
+    Always,
+}
+
+/// Condition on a run's final message. Empty fields always match; text
+/// matching is case-sensitive.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct OutputCondition {
+    #[serde(default)]
+    pub contains: Option<String>,
+    #[serde(default)]
+    pub not_contains: Option<String>,
+}
+
+impl OutputCondition {
+    pub fn matches(&self, output: &str) -> bool {
+        let set = |s: &Option<String>| s.as_deref().filter(|s| !s.is_empty()).map(String::from);
+        set(&self.contains).is_none_or(|c| output.contains(&c))
+            && set(&self.not_contains).is_none_or(|c| !output.contains(&c))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FollowUp {
+    pub task_id: String,
+    #[serde(default)]
+    pub when: FollowUpWhen,
+    #[serde(default)]
+    pub condition: OutputCondition,
+}
+
+impl FollowUp {
+    fn applies(&self, status: &TaskRunStatus, output: &str) -> bool {
+        let status_ok = match self.when {
+            FollowUpWhen::Success => *status == TaskRunStatus::Success,
+            FollowUpWhen::Failure => {
+                matches!(status, TaskRunStatus::Error | TaskRunStatus::Timeout)
+            }
+            FollowUpWhen::Always => true,
+        };
+        status_ok && self.condition.matches(output)
+    }
+}
+
+/// Check a task's follow-ups against the full task list (with `task` already in it):
+/// targets must exist and the chain must not loop back.
+pub(crate) fn validate_follow_ups(
+    task: &ScheduledTask,
+    tasks: &[ScheduledTask],
+) -> Result<(), String> {
+    for f in &task.follow_ups {
+        if f.task_id == task.id {
+            return Err("A task can't follow itself".into());
+        }
+        if !tasks.iter().any(|t| t.id == f.task_id) {
+            return Err(format!("Follow-up task not found: {}", f.task_id));
+        }
+    }
+    // Depth-first walk from the task; reaching it again means a cycle
+    let edges: HashMap<&str, Vec<&str>> = tasks
+        .iter()
+        .map(|t| {
+            let list = if t.id == task.id {
+                &task.follow_ups
+            } else {
+                &t.follow_ups
+            };
+            (
+                t.id.as_str(),
+                list.iter().map(|f| f.task_id.as_str()).collect(),
+            )
+        })
+        .collect();
+    let mut stack: Vec<&str> = edges.get(task.id.as_str()).cloned().unwrap_or_default();
+    let mut seen = std::collections::HashSet::new();
+    while let Some(id) = stack.pop() {
+        if id == task.id {
+            return Err("Follow-ups form a loop back to this task".into());
+        }
+        if seen.insert(id) {
+            stack.extend(edges.get(id).into_iter().flatten());
+        }
+    }
+    Ok(())
+}
+
+/// Context handed to a follow-up run.
+fn follow_up_context(task: &ScheduledTask, status: &TaskRunStatus, output: &str) -> TriggerContext {
+    let status = serde_json::to_value(status)
+        .ok()
+        .and_then(|v| v.as_str().map(String::from))
+        .unwrap_or_default();
+    TriggerContext {
+        kind: FOLLOW_UP_KIND,
+        summary: format!("Task '{}' finished: {status}", task.name),
+        changed_files: Vec::new(),
+        payload: Some(output.to_string()).filter(|o| !o.is_empty()),
+    }
+}
+
+/// Start the follow-ups of a finished run. Busy or disabled targets are skipped.
+pub(crate) async fn start_follow_ups(task: &ScheduledTask, status: &TaskRunStatus, output: &str) {
+    for f in task.follow_ups.iter().filter(|f| f.applies(status, output)) {
+        let context = follow_up_context(task, status, output);
+        match triggers::fire(&f.task_id, context, |_| {}).await {
+            Ok(()) => {}
+            Err(Rejected::Busy) => {
+                eprintln!(
+                    "[scheduler] Follow-up {} of '{}' is busy, skipped",
+                    f.task_id, task.name
+                );
+            }
+            Err(Rejected::Disabled) => {
+                eprintln!(
+                    "[scheduler] Follow-up {} of '{}' is disabled, skipped",
+                    f.task_id, task.name
+                );
+            }
+            Err(Rejected::NotFound) => {
+                eprintln!(
+                    "[scheduler] Follow-up {} of '{}' no longer exists",
+                    f.task_id, task.name
+                );
+            }
+            Err(Rejected::Failed(e)) => {
+                eprintln!(
+                    "[scheduler] Follow-up {} of '{}' failed to start: {e}",
+                    f.task_id, task.name
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::{test_task, TaskSchedule};
+
+    fn task(id: &str, follow: &[&str]) -> ScheduledTask {
+        let mut task = test_task(id, TaskSchedule::Manual);
+        task.follow_ups = follow
+            .iter()
+            .map(|id| FollowUp {
+                task_id: id.to_string(),
+                when: FollowUpWhen::Always,
+                condition: OutputCondition::default(),
+            })
+            .collect();
+        task
+    }
+
+    #[test]
+    fn condition_checks_contains_and_not_contains() {
+        let cond = OutputCondition {
+            contains: Some("FAIL".into()),
+            not_contains: Some("flaky".into()),
+        };
+        assert!(cond.matches("2 tests FAIL"));
+        assert!(!cond.matches("2 tests fail"));
+        assert!(!cond.matches("FAIL (flaky)"));
+        assert!(OutputCondition::default().matches(""));
+        let empty = OutputCondition {
+            contains: Some(String::new()),
+            not_contains: None,
+        };
+        assert!(empty.matches("anything"));
+    }
+
+    #[test]
+    fn follow_up_applies_by_status() {
+        let mut f = task("a", &["b"]).follow_ups.remove(0);
+        f.when = FollowUpWhen::Failure;
+        assert!(f.applies(&TaskRunStatus::Error, ""));
+        assert!(f.applies(&TaskRunStatus::Timeout, ""));
+        assert!(!f.applies(&TaskRunStatus::Success, ""));
+        f.when = FollowUpWhen::Success;
+        f.condition.contains = Some("FAIL".into());
+        assert!(f.applies(&TaskRunStatus::Success, "1 FAIL"));
+        assert!(!f.applies(&TaskRunStatus::Success, "all green"));
+    }
+
+    #[test]
+    fn validate_rejects_missing_targets_and_loops() {
+        let tasks = vec![
+            task("test", &["triage"]),
+            task("triage", &["report"]),
+            task("report", &[]),
+        ];
+        assert!(validate_follow_ups(&tasks[0], &tasks).is_ok());
+
+        // report → test closes the loop test → triage → report → test
+        let edited = task("report", &["test"]);
+        assert!(validate_follow_ups(&edited, &tasks).is_err());
+        assert!(validate_follow_ups(&task("report", &["report"]), &tasks).is_err());
+        assert!(validate_follow_ups(&task("report", &["nope"]), &tasks).is_err());
+    }
+
+    #[test]
+    fn context_carries_upstream_output() {
+        let ctx = follow_up_context(&task("test", &[]), &TaskRunStatus::Error, "3 FAIL");
+        assert_eq!(ctx.summary, "Task 'test' finished: error");
+        assert_eq!(ctx.payload.as_deref(), Some("3 FAIL"));
+    }
+}
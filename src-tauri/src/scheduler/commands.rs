@@ -1,4 +1,9 @@
-use super::{load_tasks, save_tasks, ScheduledTask, TaskRunStatus};
+use super::chain::validate_follow_ups;
+use super::notify::{normalize_rules, validate_rules};
+use super::schedule::{FireSchedule, TaskZone};
+use super::triggers::{normalize_trigger, validate_trigger};
+use super::store::{self, Claim};
+use super::{load_tasks, normalize_task_options, validate_task_options, ScheduledTask};
 
 #[tauri::command]
 pub async fn scheduler_list_tasks() -> Result<Vec<ScheduledTask>, String> {
@@ -10,19 +15,51 @@ pub async fn scheduler_list_tasks() -> Result<Vec<ScheduledTask>, String> {
 #[tauri::command]
 pub async fn scheduler_save_task(mut task: ScheduledTask) -> Result<(), String> {
     tokio::task::spawn_blocking(move || {
-        let mut tasks = load_tasks();
+        if task.schedule.trigger_kind().is_some() {
+            normalize_trigger(&mut task.schedule);
+            validate_trigger(&task.schedule)?;
+        } else {
+            FireSchedule::parse(&task.schedule)?;
+        }
+        TaskZone::parse(task.timezone.as_deref())?;
+        task.timezone = task
+            .timezone
+            .map(|tz| tz.trim().to_string())
+            .filter(|tz| !tz.is_empty());
+        normalize_task_options(&mut task);
+        validate_task_options(&task)?;
+        crate::prompt_template::validate(&task.prompt).map_err(|e| format!("Prompt: {e}"))?;
+        normalize_rules(&mut task);
+        validate_rules(&task.notifications)?;
+
+        task.follow_ups.retain(|f| !f.task_id.is_empty());
+        if !task.follow_ups.is_empty() {
+            let mut all = load_tasks();
+            all.retain(|t| t.id != task.id);
+            all.push(task.clone());
+            validate_follow_ups(&task, &all)?;
+        }
 
         if task.id.is_empty() {
             task.id = uuid::Uuid::new_v4().to_string();
             task.created_at = chrono::Local::now().to_rfc3339();
-            tasks.push(task);
-        } else if let Some(existing) = tasks.iter_mut().find(|t| t.id == task.id) {
-            *existing = task;
-        } else {
-            tasks.push(task);
+            return store::insert_task(&task);
+        }
+        // Run state is owned by the scheduler: keep it from the stored copy,
+        // the editor's snapshot may be stale
+        let updated = store::update_task(&task.id.clone(), |stored| {
+            task.created_at = std::mem::take(&mut stored.created_at);
+            task.last_run = stored.last_run.take();
+            task.last_status = stored.last_status.take();
+            task.last_scheduled = stored.last_scheduled.take();
+            task.last_session_id = stored.last_session_id.take();
+            task.last_seen_commit = stored.last_seen_commit.take();
+            *stored = task.clone();
+        })?;
+        match updated {
+            Some(()) => Ok(()),
+            None => store::insert_task(&task),
         }
-
-        save_tasks(&tasks)
     })
     .await
     .map_err(|e| format!("Task failed: {e}"))?
@@ -31,13 +68,16 @@ pub async fn scheduler_save_task(mut task: ScheduledTask) -> Result<(), String>
 #[tauri::command]
 pub async fn scheduler_delete_task(id: String) -> Result<(), String> {
     tokio::task::spawn_blocking(move || {
-        let mut tasks = load_tasks();
-        let len_before = tasks.len();
-        tasks.retain(|t| t.id != id);
-        if tasks.len() == len_before {
+        if !store::delete_task(&id)? {
             return Err(format!("Task not found: {id}"));
         }
-        save_tasks(&tasks)
+        for t in load_tasks() {
+            if t.follow_ups.iter().any(|f| f.task_id == id) {
+                store::update_task(&t.id, |t| t.follow_ups.retain(|f| f.task_id != id))?;
+            }
+        }
+        super::history::remove_runs(&id);
+        Ok(())
     })
     .await
     .map_err(|e| format!("Task failed: {e}"))?
@@ -46,33 +86,33 @@ pub async fn scheduler_delete_task(id: String) -> Result<(), String> {
 #[tauri::command]
 pub async fn scheduler_toggle_task(id: String, enabled: bool) -> Result<(), String> {
     tokio::task::spawn_blocking(move || {
-        let mut tasks = load_tasks();
-        let Some(task) = tasks.iter_mut().find(|t| t.id == id) else {
-            return Err(format!("Task not found: {id}"));
-        };
-        task.enabled = enabled;
-        save_tasks(&tasks)
+        store::update_task(&id, |t| t.enabled = enabled)?
+            .ok_or_else(|| format!("Task not found: {id}"))
     })
     .await
     .map_err(|e| format!("Task failed: {e}"))?
 }
 
 #[tauri::command]
-pub async fn scheduler_run_now(id: String, app: tauri::AppHandle) -> Result<(), String> {
-    let task = tokio::task::spawn_blocking(move || {
-        let tasks = load_tasks();
-        tasks
-            .into_iter()
-            .find(|t| t.id == id)
-            .ok_or_else(|| format!("Task not found: {id}"))
-    })
-    .await
-    .map_err(|e| format!("Task failed: {e}"))??;
+pub async fn scheduler_run_now(id: String) -> Result<(), String> {
+    let task_id = id.clone();
+    let claim = tokio::task::spawn_blocking(move || store::claim_run(&task_id))
+        .await
+        .map_err(|e| format!("Task failed: {e}"))??;
+    let previous = match claim {
+        Claim::Claimed { previous } => previous,
+        Claim::Busy => return Err("Task is already running".into()),
+        Claim::NotFound => return Err(format!("Task not found: {id}")),
+    };
 
-    if task.last_status == Some(TaskRunStatus::Running) {
-        return Err("Task is already running".into());
+    if let Err(e) = super::runner::run_task_now(&id) {
+        // Undo the claim so the task can be started again
+        match tokio::task::spawn_blocking(move || store::set_status(&id, previous)).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => eprintln!("[scheduler] Failed to reset task status: {e}"),
+            Err(e) => eprintln!("[scheduler] Status reset task panicked: {e}"),
+        }
+        return Err(e);
     }
-
-    super::runner::run_task_now(&app, &task).await;
     Ok(())
 }
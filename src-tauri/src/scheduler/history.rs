@@ -0,0 +1,293 @@
+//! Run history: one JSONL log per task with the outcome of every execution,
+//! pruned by the retention settings in `scheduler_settings.json`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+use super::settings::{load_settings, SchedulerSettings};
+use super::TaskRunStatus;
+use crate::conductor::types::CliEvent;
+use crate::config;
+use crate::file_ops::atomic_write;
+
+/// Default number of runs returned by `scheduler_list_runs`.
+const DEFAULT_LIST_LIMIT: usize = 50;
+
+/// One execution of a scheduled task.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TaskRun {
+    pub id: String,
+    pub task_id: String,
+    pub task_name: String,
+    pub started_at: String,
+    pub finished_at: String,
+    pub duration_ms: u64,
+    pub status: TaskRunStatus,
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+    /// Text of the last `MessageComplete` event — the agent's final answer.
+    #[serde(default)]
+    pub final_message: Option<String>,
+    /// Session cost from the last `UsageInfo` event.
+    #[serde(default)]
+    pub cost_usd: Option<f64>,
+    #[serde(default)]
+    pub error: Option<String>,
+    /// CLI session id, for resuming or inspecting the conversation.
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// 0 for the first try, then the retry number.
+    #[serde(default)]
+    pub attempt: u32,
+    /// What fired the run, for event-triggered tasks.
+    #[serde(default)]
+    pub trigger: Option<String>,
+}
+
+/// Outcome details collected from the session's event stream.
+#[derive(Default, Debug)]
+pub(crate) struct RunCapture {
+    pub session_id: Option<String>,
+    pub final_message: Option<String>,
+    pub cost_usd: Option<f64>,
+    pub exit_code: Option<i32>,
+    pub last_error: Option<String>,
+}
+
+impl RunCapture {
+    pub fn observe(&mut self, event: &CliEvent) {
+        match event {
+            CliEvent::SessionId { session_id, .. } => self.session_id = Some(session_id.clone()),
+            CliEvent::MessageComplete { text, .. } if !text.trim().is_empty() => {
+                self.final_message = Some(text.clone());
+            }
+            CliEvent::UsageInfo { cost_usd, .. } => self.cost_usd = Some(*cost_usd),
+            CliEvent::ProcessExited { exit_code, .. } => self.exit_code = *exit_code,
+            CliEvent::Error { message, .. } => self.last_error = Some(message.clone()),
+            _ => {}
+        }
+    }
+}
+
+/// Directory with run logs: ~/.config/aither-flow/scheduler/runs/
+fn runs_dir() -> PathBuf {
+    config::config_dir().join("scheduler").join("runs")
+}
+
+fn runs_path(task_id: &str) -> Result<PathBuf, String> {
+    if task_id.is_empty() || task_id.contains(['/', '\\', '\0']) || task_id.starts_with('.') {
+        return Err(format!("Invalid task id: '{task_id}'"));
+    }
+    Ok(runs_dir().join(format!("{task_id}.jsonl")))
+}
+
+fn parse_runs(data: &str) -> Vec<TaskRun> {
+    data.lines()
+        .filter(|l| !l.trim().is_empty())
+        .filter_map(|l| match serde_json::from_str(l) {
+            Ok(r) => Some(r),
+            Err(e) => {
+                eprintln!("[scheduler] Skipping malformed run record: {e}");
+                None
+            }
+        })
+        .collect()
+}
+
+fn read_runs(task_id: &str) -> Result<Vec<TaskRun>, String> {
+    let path = runs_path(task_id)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| format!("Failed to read run history: {e}"))?;
+    Ok(parse_runs(&data))
+}
+
+/// Most recent run of a task.
+pub(crate) fn last_run(task_id: &str) -> Option<TaskRun> {
+    read_runs(task_id).ok()?.pop()
+}
+
+/// Drop runs beyond the retention limits. `runs` is oldest first.
+fn apply_retention(runs: &mut Vec<TaskRun>, settings: &SchedulerSettings, now: DateTime<Utc>) {
+    if settings.history_max_days > 0 {
+        let cutoff = now - chrono::Duration::days(settings.history_max_days as i64);
+        runs.retain(|r| {
+            DateTime::parse_from_rfc3339(&r.started_at)
+                .map(|ts| ts.with_timezone(&Utc) >= cutoff)
+                .unwrap_or(true)
+        });
+    }
+    if settings.history_max_runs > 0 && runs.len() > settings.history_max_runs {
+        let excess = runs.len() - settings.history_max_runs;
+        runs.drain(..excess);
+    }
+}
+
+/// Rewrite a task's log with retention applied (only if something was dropped).
+fn prune_task(task_id: &str, settings: &SchedulerSettings) -> Result<(), String> {
+    let mut runs = read_runs(task_id)?;
+    let before = runs.len();
+    apply_retention(&mut runs, settings, Utc::now());
+    if runs.len() == before {
+        return Ok(());
+    }
+    let mut data = String::new();
+    for run in &runs {
+        let line =
+            serde_json::to_string(run).map_err(|e| format!("Failed to serialize run: {e}"))?;
+        data.push_str(&line);
+        data.push('\n');
+    }
+    atomic_write(&runs_path(task_id)?, data.as_bytes())
+}
+
+/// Append a finished run to its task's log, then apply retention.
+pub(crate) fn record_run(run: &TaskRun) -> Result<(), String> {
+    let path = runs_path(&run.task_id)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create runs dir: {e}"))?;
+    }
+    let line = serde_json::to_string(run).map_err(|e| format!("Failed to serialize run: {e}"))?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open run history: {e}"))?;
+    writeln!(file, "{line}").map_err(|e| format!("Failed to write run history: {e}"))?;
+    prune_task(&run.task_id, &load_settings())
+}
+
+/// Delete a task's run log (task removed).
+pub(crate) fn remove_runs(task_id: &str) {
+    let Ok(path) = runs_path(task_id) else {
+        return;
+    };
+    if let Err(e) = fs::remove_file(&path) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            eprintln!("[scheduler] Failed to remove run history: {e}");
+        }
+    }
+}
+
+/// Runs of a task, newest first.
+#[tauri::command]
+pub async fn scheduler_list_runs(
+    task_id: String,
+    limit: Option<usize>,
+) -> Result<Vec<TaskRun>, String> {
+    tokio::task::spawn_blocking(move || {
+        let mut runs = read_runs(&task_id)?;
+        runs.reverse();
+        runs.truncate(limit.unwrap_or(DEFAULT_LIST_LIMIT));
+        Ok(runs)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn run(started_at: &str) -> TaskRun {
+        TaskRun {
+            id: started_at.into(),
+            task_id: "t".into(),
+            task_name: "T".into(),
+            started_at: started_at.into(),
+            finished_at: started_at.into(),
+            duration_ms: 0,
+            status: TaskRunStatus::Success,
+            exit_code: Some(0),
+            final_message: None,
+            cost_usd: None,
+            error: None,
+            session_id: None,
+            attempt: 0,
+            trigger: None,
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-04-30T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn retention_drops_old_runs() {
+        let mut runs = vec![run("2026-03-01T00:00:00Z"), run("2026-04-29T00:00:00Z")];
+        let settings = SchedulerSettings {
+            history_max_runs: 0,
+            history_max_days: 30,
+            ..Default::default()
+        };
+        apply_retention(&mut runs, &settings, now());
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].started_at, "2026-04-29T00:00:00Z");
+    }
+
+    #[test]
+    fn retention_keeps_newest_runs_up_to_limit() {
+        let mut runs: Vec<TaskRun> = (1..=5)
+            .map(|d| run(&format!("2026-04-2{d}T00:00:00Z")))
+            .collect();
+        let settings = SchedulerSettings {
+            history_max_runs: 2,
+            history_max_days: 0,
+            ..Default::default()
+        };
+        apply_retention(&mut runs, &settings, now());
+        let kept: Vec<&str> = runs.iter().map(|r| r.started_at.as_str()).collect();
+        assert_eq!(kept, vec!["2026-04-24T00:00:00Z", "2026-04-25T00:00:00Z"]);
+    }
+
+    #[test]
+    fn capture_keeps_last_message_cost_and_exit_code() {
+        let agent: Arc<str> = Arc::from("a");
+        let mut capture = RunCapture::default();
+        for event in [
+            CliEvent::MessageComplete {
+                agent_id: agent.clone(),
+                text: "first".into(),
+            },
+            CliEvent::MessageComplete {
+                agent_id: agent.clone(),
+                text: "final answer".into(),
+            },
+            CliEvent::MessageComplete {
+                agent_id: agent.clone(),
+                text: "  ".into(),
+            },
+            CliEvent::ProcessExited {
+                agent_id: agent.clone(),
+                exit_code: Some(1),
+            },
+        ] {
+            capture.observe(&event);
+        }
+        assert_eq!(capture.final_message.as_deref(), Some("final answer"));
+        assert_eq!(capture.exit_code, Some(1));
+        assert_eq!(capture.cost_usd, None);
+    }
+
+    #[test]
+    fn parse_runs_skips_malformed_lines() {
+        let good = serde_json::to_string(&run("2026-04-01T00:00:00Z")).unwrap();
+        let runs = parse_runs(&format!("{good}\n{{broken\n\n"));
+        assert_eq!(runs.len(), 1);
+    }
+
+    #[test]
+    fn runs_path_rejects_traversal() {
+        assert!(runs_path("../etc").is_err());
+        assert!(runs_path("a/b").is_err());
+        assert!(runs_path("").is_err());
+        assert!(runs_path("abc-123").is_ok());
+    }
+}
@@ -1,8 +1,19 @@
+pub mod chain;
 pub mod commands;
+pub mod history;
+pub mod notify;
+pub mod preview;
+pub mod queue;
 pub mod runner;
+pub mod schedule;
+pub mod settings;
+pub mod store;
+pub mod triggers;
 
 use serde::{Deserialize, Serialize};
 
+pub use store::load_tasks;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ScheduledTask {
     pub id: String,
@@ -11,10 +22,76 @@ pub struct ScheduledTask {
     pub project_path: String,
     pub schedule: TaskSchedule,
     pub enabled: bool,
+    /// Legacy success message to Telegram; moved into `notifications` on save.
+    #[serde(default)]
     pub notify_telegram: bool,
     pub created_at: String,
     pub last_run: Option<String>,
     pub last_status: Option<TaskRunStatus>,
+    /// IANA timezone the schedule is evaluated in (e.g. "Europe/Berlin"). None = local time.
+    #[serde(default)]
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub catch_up: CatchUpPolicy,
+    /// Fire time the scheduler handled last (ran or skipped). Manual runs don't touch it.
+    #[serde(default)]
+    pub last_scheduled: Option<String>,
+    /// Model alias or id (e.g. "haiku"). None = CLI default.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Reasoning effort: "low" | "medium" | "high". None = CLI default.
+    #[serde(default)]
+    pub effort: Option<String>,
+    /// CLI permission mode (e.g. "plan", "bypassPermissions"). None = default.
+    #[serde(default)]
+    pub permission_mode: Option<String>,
+    /// Agent role for the session: system prompt, tools and teamwork identity.
+    #[serde(default)]
+    pub role: Option<String>,
+    /// Tools allowed without prompting. Replaces the role's list when non-empty.
+    #[serde(default)]
+    pub allowed_tools: Vec<String>,
+    /// Continue the previous run's CLI session instead of starting a new one.
+    #[serde(default)]
+    pub resume_previous: bool,
+    /// Kill the run after this many minutes. None = no limit.
+    #[serde(default)]
+    pub timeout_minutes: Option<u32>,
+    /// CLI session id of the last run (used by `resume_previous`).
+    #[serde(default)]
+    pub last_session_id: Option<String>,
+    /// Extra attempts after a failed run.
+    #[serde(default)]
+    pub max_retries: u32,
+    /// Wait before the first retry; doubles with each further attempt.
+    #[serde(default = "default_retry_delay_secs")]
+    pub retry_delay_secs: u32,
+    /// Commit a `git_commit` trigger saw last on its branch.
+    #[serde(default)]
+    pub last_seen_commit: Option<String>,
+    /// Tasks started when a run ends, depending on its outcome.
+    #[serde(default)]
+    pub follow_ups: Vec<chain::FollowUp>,
+    /// Where to report finished runs, per outcome.
+    #[serde(default)]
+    pub notifications: Vec<notify::NotifyRule>,
+}
+
+fn default_retry_delay_secs() -> u32 {
+    60
+}
+
+/// What to do with fire times missed while the app was closed or the machine asleep.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CatchUpPolicy {
+    /// Drop missed runs, wait for the next fire time.
+    #[default]
+    Skip,
+    /// Run once for all missed fire times.
+    Once,
+    /// Run every missed fire time, one after another.
+    All,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -28,6 +105,31 @@ pub enum TaskSchedule {
     Weekly { day: u8, hour: u8, minute: u8 },
     #[serde(rename = "cron")]
     Cron { expression: String },
+    /// Files matching any of the glob patterns (relative to the project) changed.
+    #[serde(rename = "file_change")]
+    FileChange { patterns: Vec<String> },
+    /// New commits appeared on a branch of the project repo.
+    #[serde(rename = "git_commit")]
+    GitCommit { branch: String },
+    /// POST to the local webhook endpoint of the task.
+    #[serde(rename = "webhook")]
+    Webhook,
+    /// Started only by Run Now or as a follow-up of another task.
+    #[serde(rename = "manual")]
+    Manual,
+}
+
+impl TaskSchedule {
+    /// Trigger kind of an event-driven schedule; None for time-based ones.
+    pub fn trigger_kind(&self) -> Option<&'static str> {
+        match self {
+            Self::FileChange { .. } => Some("file_change"),
+            Self::GitCommit { .. } => Some("git_commit"),
+            Self::Webhook => Some("webhook"),
+            Self::Manual => Some("manual"),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -36,28 +138,95 @@ pub enum TaskRunStatus {
     Success,
     Error,
     Running,
+    /// Killed after exceeding `timeout_minutes`.
+    Timeout,
+    /// Waiting for a free run slot or for a retry.
+    Queued,
 }
 
-/// Load scheduled tasks from config file. Returns empty vec if file doesn't exist.
-pub fn load_tasks() -> Vec<ScheduledTask> {
-    let path = crate::config::config_dir().join("scheduled_tasks.json");
-    if !path.exists() {
-        return Vec::new();
+impl TaskRunStatus {
+    /// A run is in flight or waiting to start.
+    pub fn is_busy(&self) -> bool {
+        matches!(self, Self::Running | Self::Queued)
     }
-    match crate::file_ops::read_json::<Vec<ScheduledTask>>(&path) {
-        Ok(tasks) => tasks,
-        Err(e) => {
-            eprintln!("[scheduler] Failed to load tasks: {e}");
-            Vec::new()
+}
+
+/// Effort levels accepted by the CLI `--effort` flag.
+const EFFORT_LEVELS: &[&str] = &["low", "medium", "high"];
+/// Modes accepted by the CLI `--permission-mode` flag.
+const PERMISSION_MODES: &[&str] = &["default", "acceptEdits", "plan", "bypassPermissions"];
+
+/// Check session options of a task before saving. Role lookup reads roles from disk.
+pub(crate) fn validate_task_options(task: &ScheduledTask) -> Result<(), String> {
+    if let Some(effort) = task.effort.as_deref() {
+        if !EFFORT_LEVELS.contains(&effort) {
+            return Err(format!("Unknown effort level: '{effort}'"));
         }
     }
+    if let Some(mode) = task.permission_mode.as_deref() {
+        if !PERMISSION_MODES.contains(&mode) {
+            return Err(format!("Unknown permission mode: '{mode}'"));
+        }
+    }
+    if task.timeout_minutes == Some(0) {
+        return Err("Timeout must be at least 1 minute".into());
+    }
+    if let Some(role) = task.role.as_deref() {
+        crate::teamwork::roles::find_role(role)?;
+    }
+    Ok(())
 }
 
-/// Save scheduled tasks to config file (atomic write).
-pub fn save_tasks(tasks: &[ScheduledTask]) -> Result<(), String> {
-    let path = crate::config::config_dir().join("scheduled_tasks.json");
-    let data = serde_json::to_string_pretty(tasks)
-        .map_err(|e| format!("Failed to serialize tasks: {e}"))?;
-    crate::file_ops::atomic_write(&path, data.as_bytes())
-        .map_err(|e| format!("Failed to write tasks: {e}"))
+/// Blank optional strings → None, trimmed tool names without empties.
+pub(crate) fn normalize_task_options(task: &mut ScheduledTask) {
+    for field in [
+        &mut task.model,
+        &mut task.effort,
+        &mut task.permission_mode,
+        &mut task.role,
+    ] {
+        *field = field
+            .take()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+    }
+    task.allowed_tools = task
+        .allowed_tools
+        .iter()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect();
+}
+
+/// Enabled task that never ran, for tests; tweak the fields a test cares about.
+#[cfg(test)]
+pub(crate) fn test_task(id: &str, schedule: TaskSchedule) -> ScheduledTask {
+    ScheduledTask {
+        id: id.into(),
+        name: id.into(),
+        prompt: "p".into(),
+        project_path: "/tmp".into(),
+        schedule,
+        enabled: true,
+        notify_telegram: false,
+        created_at: "2026-01-01T00:00:00+00:00".into(),
+        last_run: None,
+        last_status: None,
+        timezone: None,
+        catch_up: CatchUpPolicy::Skip,
+        last_scheduled: None,
+        model: None,
+        effort: None,
+        permission_mode: None,
+        role: None,
+        allowed_tools: Vec::new(),
+        resume_previous: false,
+        timeout_minutes: None,
+        last_session_id: None,
+        max_retries: 0,
+        retry_delay_secs: default_retry_delay_secs(),
+        last_seen_commit: None,
+        follow_ups: Vec::new(),
+        notifications: Vec::new(),
+    }
 }
@@ -0,0 +1,412 @@
+//! Notifications about finished runs. Each task has rules that pick the
+//! outcomes to report and where the message goes: Telegram, a desktop
+//! notification or a local webhook. Messages are templates over the run
+//! result (see `VARIABLES`).
+
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::time::Duration;
+
+use super::history::TaskRun;
+use super::{ScheduledTask, TaskRunStatus};
+use crate::prompt_template;
+use crate::telegram::api::{split_message, MAX_MESSAGE_LEN};
+
+/// Variables a notification template may use, with a short description for the UI.
+pub const VARIABLES: &[(&str, &str)] = &[
+    ("summary", "One line: outcome, duration and cost"),
+    ("task", "Scheduled task name"),
+    ("status", "success, error or timeout"),
+    ("duration", "Run time, e.g. 2m 5s"),
+    ("cost", "Session cost, e.g. $0.042"),
+    ("output", "Final message of the agent"),
+    ("error", "Error of a failed run"),
+    ("trigger", "What fired the run (event-triggered tasks)"),
+];
+
+/// Message used when a rule has no template.
+const DEFAULT_TEMPLATE: &str =
+    "{{summary}}{{#if error}}\n\n{{error}}{{/if}}{{#if output}}\n\n{{output}}{{/if}}";
+
+/// Longest final message put into a notification.
+const MAX_OUTPUT_CHARS: usize = 12_000;
+
+/// Longest body of a desktop notification.
+const MAX_DESKTOP_CHARS: usize = 300;
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Which run outcomes a rule reports.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyWhen {
+    #[default]
+    Success,
+    /// Error or timeout.
+    Failure,
+    Timeout,
+    Always,
+}
+
+impl NotifyWhen {
+    fn matches(self, status: &TaskRunStatus) -> bool {
+        match self {
+            Self::Success => *status == TaskRunStatus::Success,
+            Self::Failure => matches!(status, TaskRunStatus::Error | TaskRunStatus::Timeout),
+            Self::Timeout => *status == TaskRunStatus::Timeout,
+            Self::Always => true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum NotifySink {
+    /// Chat of the Telegram bot (long messages are split).
+    Telegram,
+    /// System notification shown by the app window.
+    Desktop,
+    /// JSON POST with the message and the run record.
+    Webhook { url: String },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NotifyRule {
+    #[serde(default)]
+    pub when: NotifyWhen,
+    pub sink: NotifySink,
+    /// Message template. Empty = summary, error and final message.
+    #[serde(default)]
+    pub template: String,
+}
+
+/// Payload of the `scheduler:notification` event.
+#[derive(Clone, Debug, Serialize)]
+pub struct DesktopNotification {
+    pub title: String,
+    pub body: String,
+}
+
+/// Payload POSTed to a webhook sink.
+#[derive(Serialize)]
+struct WebhookPayload<'a> {
+    task_id: &'a str,
+    task_name: &'a str,
+    status: &'a TaskRunStatus,
+    message: &'a str,
+    run: &'a TaskRun,
+}
+
+/// Check rule templates and webhook URLs.
+pub(crate) fn validate_rules(rules: &[NotifyRule]) -> Result<(), String> {
+    for rule in rules {
+        prompt_template::validate_with(&rule.template, VARIABLES)
+            .map_err(|e| format!("Notification template: {e}"))?;
+        if let NotifySink::Webhook { url } = &rule.sink {
+            let parsed = reqwest::Url::parse(url)
+                .map_err(|e| format!("Invalid webhook URL '{url}': {e}"))?;
+            match parsed.scheme() {
+                "https" => {}
+                // The message carries the agent's output, so only send it
+                // unencrypted to this machine
+                "http" if is_loopback(&parsed) => {}
+                "http" => {
+                    return Err(format!(
+                        "Webhook URL must use https unless it points at localhost: '{url}'"
+                    ))
+                }
+                _ => return Err(format!("Webhook URL must be http(s): '{url}'")),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn is_loopback(url: &reqwest::Url) -> bool {
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    // IPv6 hosts come bracketed
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Trim templates and URLs; turn the legacy `notify_telegram` flag into a rule.
+pub(crate) fn normalize_rules(task: &mut ScheduledTask) {
+    for rule in &mut task.notifications {
+        if rule.template.trim().is_empty() {
+            rule.template.clear();
+        }
+        if let NotifySink::Webhook { url } = &mut rule.sink {
+            *url = url.trim().to_string();
+        }
+    }
+    if task.notify_telegram {
+        task.notify_telegram = false;
+        task.notifications.push(legacy_rule());
+    }
+}
+
+/// What `notify_telegram: true` meant before rules existed.
+fn legacy_rule() -> NotifyRule {
+    NotifyRule {
+        when: NotifyWhen::Success,
+        sink: NotifySink::Telegram,
+        template: String::new(),
+    }
+}
+
+/// Rules of a task, including the legacy flag of tasks not saved since.
+fn task_rules(task: &ScheduledTask) -> Cow<'_, [NotifyRule]> {
+    if task.notify_telegram {
+        let mut rules = task.notifications.clone();
+        rules.push(legacy_rule());
+        Cow::Owned(rules)
+    } else {
+        Cow::Borrowed(&task.notifications)
+    }
+}
+
+/// "45s", "2m 5s", "1h 3m".
+fn format_duration(ms: u64) -> String {
+    let secs = ms / 1000;
+    match secs {
+        0..=59 => format!("{secs}s"),
+        60..=3599 => format!("{}m {}s", secs / 60, secs % 60),
+        _ => format!("{}h {}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+fn status_name(status: &TaskRunStatus) -> String {
+    serde_json::to_value(status)
+        .ok()
+        .and_then(|v| v.as_str().map(String::from))
+        .unwrap_or_default()
+}
+
+fn summary(run: &TaskRun) -> String {
+    let name = &run.task_name;
+    let duration = format_duration(run.duration_ms);
+    let mut line = match run.status {
+        TaskRunStatus::Success => format!("\u{2705} Task '{name}' succeeded in {duration}"),
+        TaskRunStatus::Error => format!("\u{274c} Task '{name}' failed after {duration}"),
+        TaskRunStatus::Timeout => format!("\u{23f1} Task '{name}' timed out after {duration}"),
+        ref other => format!("Task '{name}' finished: {}", status_name(other)),
+    };
+    if let Some(cost) = run.cost_usd {
+        line.push_str(&format!(" (${cost:.3})"));
+    }
+    line
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}\n[truncated]", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// Render a rule's message for a finished run.
+fn render_message(rule: &NotifyRule, run: &TaskRun) -> String {
+    let template = if rule.template.is_empty() {
+        DEFAULT_TEMPLATE
+    } else {
+        &rule.template
+    };
+    let get = |name: &str| {
+        let value = match name {
+            "summary" => summary(run),
+            "task" => run.task_name.clone(),
+            "status" => status_name(&run.status),
+            "duration" => format_duration(run.duration_ms),
+            "cost" => run.cost_usd.map(|c| format!("${c:.3}")).unwrap_or_default(),
+            "output" => truncate(run.final_message.as_deref().unwrap_or(""), MAX_OUTPUT_CHARS),
+            "error" => run.error.clone().unwrap_or_default(),
+            "trigger" => run.trigger.clone().unwrap_or_default(),
+            _ => return None,
+        };
+        Some(value)
+    };
+    prompt_template::render_with(template, VARIABLES, &get).unwrap_or_else(|e| {
+        eprintln!(
+            "[scheduler] Task '{}' notification template: {e}",
+            run.task_name
+        );
+        summary(run)
+    })
+}
+
+/// First line as the title, the rest (shortened) as the body.
+fn desktop_notification(message: &str) -> DesktopNotification {
+    let message = message.trim();
+    let (title, body) = message.split_once('\n').unwrap_or((message, ""));
+    DesktopNotification {
+        title: title.trim().to_string(),
+        body: truncate(body.trim(), MAX_DESKTOP_CHARS),
+    }
+}
+
+async fn send_telegram(message: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        for chunk in split_message(&message, MAX_MESSAGE_LEN) {
+            crate::telegram::commands::send_to_telegram(chunk)?;
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Task failed: {e}"))?
+}
+
+async fn send_webhook(
+    url: &str,
+    task: &ScheduledTask,
+    run: &TaskRun,
+    message: &str,
+) -> Result<(), String> {
+    let payload = WebhookPayload {
+        task_id: &task.id,
+        task_name: &task.name,
+        status: &run.status,
+        message,
+        run,
+    };
+    let resp = reqwest::Client::new()
+        .post(url)
+        .timeout(WEBHOOK_TIMEOUT)
+        .json(&payload)
+        .send()
+        .await
+        .map_err(|e| format!("Webhook request failed: {e}"))?;
+    if !resp.status().is_success() {
+        return Err(format!("Webhook returned {}", resp.status()));
+    }
+    Ok(())
+}
+
+/// Send the notifications of a finished run. Failures are logged, not returned.
+pub(crate) async fn send_notifications(
+    app: &tauri::AppHandle,
+    task: &ScheduledTask,
+    run: &TaskRun,
+) {
+    for rule in task_rules(task)
+        .iter()
+        .filter(|r| r.when.matches(&run.status))
+    {
+        let message = render_message(rule, run);
+        let result = match &rule.sink {
+            NotifySink::Telegram => send_telegram(message).await,
+            NotifySink::Desktop => tauri::Emitter::emit(
+                app,
+                "scheduler:notification",
+                desktop_notification(&message),
+            )
+            .map_err(|e| format!("Failed to emit notification: {e}")),
+            NotifySink::Webhook { url } => send_webhook(url, task, run, &message).await,
+        };
+        if let Err(e) = result {
+            eprintln!("[scheduler] Notification for '{}' failed: {e}", task.name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(status: TaskRunStatus) -> TaskRun {
+        TaskRun {
+            id: "r1".into(),
+            task_id: "t1".into(),
+            task_name: "Nightly tests".into(),
+            started_at: "2026-05-04T03:00:00+00:00".into(),
+            finished_at: "2026-05-04T03:02:05+00:00".into(),
+            duration_ms: 125_000,
+            status,
+            exit_code: None,
+            final_message: Some("2 tests FAIL".into()),
+            cost_usd: Some(0.0421),
+            error: None,
+            session_id: None,
+            attempt: 0,
+            trigger: None,
+        }
+    }
+
+    fn rule(when: NotifyWhen, template: &str) -> NotifyRule {
+        NotifyRule {
+            when,
+            sink: NotifySink::Telegram,
+            template: template.into(),
+        }
+    }
+
+    #[test]
+    fn when_matches_outcomes() {
+        assert!(NotifyWhen::Success.matches(&TaskRunStatus::Success));
+        assert!(!NotifyWhen::Success.matches(&TaskRunStatus::Error));
+        assert!(NotifyWhen::Failure.matches(&TaskRunStatus::Error));
+        assert!(NotifyWhen::Failure.matches(&TaskRunStatus::Timeout));
+        assert!(NotifyWhen::Timeout.matches(&TaskRunStatus::Timeout));
+        assert!(!NotifyWhen::Timeout.matches(&TaskRunStatus::Error));
+        assert!(NotifyWhen::Always.matches(&TaskRunStatus::Success));
+    }
+
+    #[test]
+    fn default_message_has_summary_and_output() {
+        let msg = render_message(&rule(NotifyWhen::Always, ""), &run(TaskRunStatus::Success));
+        assert_eq!(
+            msg,
+            "\u{2705} Task 'Nightly tests' succeeded in 2m 5s ($0.042)\n\n2 tests FAIL"
+        );
+
+        let mut failed = run(TaskRunStatus::Error);
+        failed.error = Some("exit code 1".into());
+        failed.final_message = None;
+        failed.cost_usd = None;
+        let msg = render_message(&rule(NotifyWhen::Always, ""), &failed);
+        assert_eq!(
+            msg,
+            "\u{274c} Task 'Nightly tests' failed after 2m 5s\n\nexit code 1"
+        );
+    }
+
+    #[test]
+    fn custom_template_and_validation() {
+        let r = rule(
+            NotifyWhen::Always,
+            "{{task}}: {{status}} in {{duration}}, {{cost}}",
+        );
+        assert_eq!(
+            render_message(&r, &run(TaskRunStatus::Timeout)),
+            "Nightly tests: timeout in 2m 5s, $0.042"
+        );
+        assert!(validate_rules(&[rule(NotifyWhen::Always, "{{branch}}")]).is_err());
+        let hook = |url: &str| NotifyRule {
+            when: NotifyWhen::Always,
+            sink: NotifySink::Webhook { url: url.into() },
+            template: String::new(),
+        };
+        assert!(validate_rules(&[hook("http://127.0.0.1:8123/hook")]).is_ok());
+        assert!(validate_rules(&[hook("http://localhost/hook")]).is_ok());
+        assert!(validate_rules(&[hook("http://[::1]:8123/hook")]).is_ok());
+        assert!(validate_rules(&[hook("https://hooks.example.com/x")]).is_ok());
+        assert!(validate_rules(&[hook("http://hooks.example.com/x")]).is_err());
+        assert!(validate_rules(&[hook("http://192.168.1.5/x")]).is_err());
+        assert!(validate_rules(&[hook("ftp://localhost/x")]).is_err());
+        assert!(validate_rules(&[hook("not a url")]).is_err());
+    }
+
+    #[test]
+    fn durations_and_desktop_split() {
+        assert_eq!(format_duration(999), "0s");
+        assert_eq!(format_duration(3_600_000 + 180_000), "1h 3m");
+        let n = desktop_notification("Title line\n\nBody text");
+        assert_eq!(n.title, "Title line");
+        assert_eq!(n.body, "Body text");
+        assert_eq!(desktop_notification("Only title").body, "");
+    }
+}
@@ -0,0 +1,263 @@
+//! Upcoming fire times: a calendar preview across tasks, and schedule
+//! validation for the task editor.
+
+use chrono::{DateTime, Duration, Local, Utc};
+use serde::Serialize;
+
+use super::schedule::{next_fire_time, FireSchedule, TaskZone};
+use super::triggers::{normalize_trigger, validate_trigger};
+use super::{load_tasks, ScheduledTask, TaskSchedule};
+
+/// Most fire times listed per task in a preview (an every-minute task over a week is 10k).
+const MAX_RUNS_PER_TASK: usize = 500;
+/// Occurrences returned by `scheduler_validate_schedule` by default, and at most.
+const DEFAULT_OCCURRENCES: usize = 5;
+const MAX_OCCURRENCES: usize = 100;
+
+/// One upcoming run in a preview.
+#[derive(Clone, Debug, Serialize)]
+pub struct UpcomingRun {
+    pub task_id: String,
+    pub task_name: String,
+    pub fire_time: String,
+}
+
+fn parse_time(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Fire times of a time-based task in `[from, to]` (`from` no earlier than
+/// `now`), at most `limit`. Interval tasks count from their last fire; one
+/// that never ran or is overdue fires at `now`, on the scheduler's next tick.
+fn task_fire_times(
+    task: &ScheduledTask,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    now: DateTime<Utc>,
+    limit: usize,
+) -> Result<Vec<DateTime<Utc>>, String> {
+    let schedule = FireSchedule::parse(&task.schedule)?;
+    let zone = TaskZone::parse(task.timezone.as_deref())?;
+    let from = from.max(now);
+    let mut next = match schedule {
+        FireSchedule::Interval { minutes } => {
+            let step = Duration::minutes(minutes as i64);
+            let anchor = task
+                .last_scheduled
+                .as_deref()
+                .or(task.last_run.as_deref())
+                .and_then(parse_time);
+            let first = anchor.map_or(now, |a| (a + step).max(now));
+            if first < from {
+                // Jump to the first step at or after `from`
+                let (gap, step) = ((from - first).num_seconds(), step.num_seconds());
+                Some(first + Duration::seconds((gap + step - 1) / step * step))
+            } else {
+                Some(first)
+            }
+        }
+        // Fire times land on whole minutes, so this includes one at `from`
+        _ => next_fire_time(&schedule, zone, from - Duration::seconds(1)),
+    };
+    let mut times = Vec::new();
+    while let Some(time) = next.filter(|t| *t <= to) {
+        if times.len() == limit {
+            break;
+        }
+        times.push(time);
+        next = next_fire_time(&schedule, zone, time);
+    }
+    Ok(times)
+}
+
+/// Runs of enabled time-based tasks in `[from, to]`, soonest first.
+fn upcoming_runs(
+    tasks: &[ScheduledTask],
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Vec<(DateTime<Utc>, &ScheduledTask)> {
+    let mut runs = Vec::new();
+    for task in tasks
+        .iter()
+        .filter(|t| t.enabled && t.schedule.trigger_kind().is_none())
+    {
+        match task_fire_times(task, from, to, now, MAX_RUNS_PER_TASK) {
+            Ok(times) => runs.extend(times.into_iter().map(|t| (t, task))),
+            Err(e) => eprintln!("[scheduler] Task '{}': {e}", task.name),
+        }
+    }
+    runs.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.name.cmp(&b.1.name)));
+    runs
+}
+
+/// Next `count` fire times of a schedule from `now`. Event-triggered schedules
+/// are checked but have no fire times.
+fn next_occurrences(
+    schedule: &TaskSchedule,
+    timezone: Option<&str>,
+    count: usize,
+    now: DateTime<Utc>,
+) -> Result<Vec<DateTime<Utc>>, String> {
+    let zone = TaskZone::parse(timezone)?;
+    if schedule.trigger_kind().is_some() {
+        let mut schedule = schedule.clone();
+        normalize_trigger(&mut schedule);
+        validate_trigger(&schedule)?;
+        return Ok(Vec::new());
+    }
+    let parsed = FireSchedule::parse(schedule)?;
+    let mut times = Vec::with_capacity(count);
+    let mut cursor = now;
+    while times.len() < count {
+        let Some(next) = next_fire_time(&parsed, zone, cursor) else {
+            break;
+        };
+        times.push(next);
+        cursor = next;
+    }
+    Ok(times)
+}
+
+/// Upcoming runs of all enabled tasks between `from` and `to` (RFC 3339).
+#[tauri::command]
+pub async fn scheduler_preview(from: String, to: String) -> Result<Vec<UpcomingRun>, String> {
+    let bound = |s: &str| parse_time(s).ok_or_else(|| format!("Invalid time: '{s}'"));
+    let (from, to) = (bound(&from)?, bound(&to)?);
+    if to < from {
+        return Err("Preview range ends before it starts".into());
+    }
+    tokio::task::spawn_blocking(move || {
+        let tasks = load_tasks();
+        upcoming_runs(&tasks, from, to, Utc::now())
+            .into_iter()
+            .map(|(time, task)| UpcomingRun {
+                task_id: task.id.clone(),
+                task_name: task.name.clone(),
+                fire_time: time.with_timezone(&Local).to_rfc3339(),
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| format!("Task failed: {e}"))
+}
+
+/// Check a schedule and return its next `count` fire times (RFC 3339), or why it is invalid.
+#[tauri::command]
+pub fn scheduler_validate_schedule(
+    schedule: TaskSchedule,
+    timezone: Option<String>,
+    count: Option<usize>,
+) -> Result<Vec<String>, String> {
+    let count = count.unwrap_or(DEFAULT_OCCURRENCES).min(MAX_OCCURRENCES);
+    let times = next_occurrences(&schedule, timezone.as_deref(), count, Utc::now())?;
+    Ok(times
+        .into_iter()
+        .map(|t| t.with_timezone(&Local).to_rfc3339())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::test_task;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        parse_time(s).unwrap()
+    }
+
+    fn task(id: &str, schedule: TaskSchedule) -> ScheduledTask {
+        let mut task = test_task(id, schedule);
+        task.timezone = Some("UTC".into());
+        task
+    }
+
+    #[test]
+    fn daily_times_within_range() {
+        let t = task("d", TaskSchedule::Daily { hour: 9, minute: 0 });
+        let now = utc("2026-05-04T10:00:00Z");
+        let times =
+            task_fire_times(&t, now, utc("2026-05-07T09:00:00Z"), now, MAX_RUNS_PER_TASK).unwrap();
+        assert_eq!(
+            times,
+            [
+                utc("2026-05-05T09:00:00Z"),
+                utc("2026-05-06T09:00:00Z"),
+                utc("2026-05-07T09:00:00Z")
+            ]
+        );
+        // A range in the past starts at now
+        let past = task_fire_times(&t, utc("2026-05-01T00:00:00Z"), now, now, 10).unwrap();
+        assert!(past.is_empty());
+    }
+
+    #[test]
+    fn interval_counts_from_last_fire() {
+        let mut t = task("i", TaskSchedule::Interval { minutes: 30 });
+        let now = utc("2026-05-04T10:00:00Z");
+        let to = utc("2026-05-04T11:00:00Z");
+        // Never ran: fires on the next tick
+        assert_eq!(
+            task_fire_times(&t, now, to, now, 10).unwrap(),
+            [now, utc("2026-05-04T10:30:00Z"), to]
+        );
+        t.last_scheduled = Some("2026-05-04T09:50:00+00:00".into());
+        assert_eq!(
+            task_fire_times(&t, utc("2026-05-04T10:30:00Z"), to, now, 10).unwrap(),
+            [utc("2026-05-04T10:50:00Z")]
+        );
+        assert_eq!(task_fire_times(&t, now, to, now, 1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn preview_merges_enabled_time_based_tasks() {
+        let mut off = task("off", TaskSchedule::Daily { hour: 8, minute: 0 });
+        off.enabled = false;
+        let tasks = vec![
+            task("b", TaskSchedule::Daily { hour: 9, minute: 0 }),
+            task(
+                "a",
+                TaskSchedule::Daily {
+                    hour: 7,
+                    minute: 30,
+                },
+            ),
+            task("hook", TaskSchedule::Webhook),
+            off,
+        ];
+        let now = utc("2026-05-04T00:00:00Z");
+        let runs = upcoming_runs(&tasks, now, utc("2026-05-04T23:59:00Z"), now);
+        let ids: Vec<_> = runs.iter().map(|(_, t)| t.id.as_str()).collect();
+        assert_eq!(ids, ["a", "b"]);
+    }
+
+    #[test]
+    fn validation_reports_precise_errors() {
+        let now = utc("2026-05-04T10:00:00Z");
+        let cron = |e: &str| TaskSchedule::Cron {
+            expression: e.into(),
+        };
+        assert_eq!(
+            next_occurrences(&cron("0 */6 * * *"), Some("UTC"), 3, now).unwrap(),
+            [
+                utc("2026-05-04T12:00:00Z"),
+                utc("2026-05-04T18:00:00Z"),
+                utc("2026-05-05T00:00:00Z")
+            ]
+        );
+        let err = next_occurrences(&cron("0 6 * *"), None, 3, now).unwrap_err();
+        assert!(err.contains("got 4"), "{err}");
+        assert!(next_occurrences(&cron("0 25 * * *"), None, 3, now).is_err());
+        assert!(next_occurrences(&cron("* * * * *"), Some("Mars/Base"), 3, now).is_err());
+
+        let files = |p: &str| TaskSchedule::FileChange {
+            patterns: vec![p.into()],
+        };
+        assert!(next_occurrences(&files("src/**"), None, 3, now)
+            .unwrap()
+            .is_empty());
+        assert!(next_occurrences(&files("/etc/*"), None, 3, now).is_err());
+    }
+}
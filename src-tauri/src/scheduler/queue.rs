@@ -0,0 +1,117 @@
+//! Concurrency limit for scheduled runs: a slot counter with a FIFO waiting
+//! queue, and the retry backoff schedule.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use super::triggers::TriggerContext;
+
+/// Longest wait between retries.
+const MAX_RETRY_DELAY_SECS: u64 = 3600;
+
+/// A run waiting for (or holding) a slot. `attempt` is 0 for the first try.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct QueuedRun {
+    pub task_id: String,
+    pub attempt: u32,
+    /// Event that started the run, for event-triggered tasks.
+    pub trigger: Option<TriggerContext>,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum Admission {
+    /// A slot was taken — start the run now.
+    Start,
+    /// All slots busy — the run waits in the queue.
+    Queued,
+    /// This task is already waiting; the request is dropped.
+    AlreadyQueued,
+}
+
+#[derive(Default, Debug)]
+pub(crate) struct RunQueue {
+    active: usize,
+    waiting: VecDeque<QueuedRun>,
+}
+
+impl RunQueue {
+    /// Take a slot if one is free under `cap` (0 = unlimited), otherwise queue the run.
+    pub fn admit(&mut self, run: QueuedRun, cap: usize) -> Admission {
+        if self.waiting.iter().any(|r| r.task_id == run.task_id) {
+            return Admission::AlreadyQueued;
+        }
+        if self.waiting.is_empty() && (cap == 0 || self.active < cap) {
+            self.active += 1;
+            return Admission::Start;
+        }
+        self.waiting.push_back(run);
+        Admission::Queued
+    }
+
+    /// Free a finished run's slot and hand free slots to waiting runs, oldest first.
+    pub fn release(&mut self, cap: usize) -> Vec<QueuedRun> {
+        self.active = self.active.saturating_sub(1);
+        let mut ready = Vec::new();
+        while cap == 0 || self.active < cap {
+            let Some(run) = self.waiting.pop_front() else {
+                break;
+            };
+            self.active += 1;
+            ready.push(run);
+        }
+        ready
+    }
+}
+
+/// Delay before retry number `attempt` (1-based): `base`, doubled per attempt, capped at an hour.
+pub(crate) fn retry_delay(base_secs: u32, attempt: u32) -> Duration {
+    let factor = 1u64 << attempt.saturating_sub(1).min(20);
+    Duration::from_secs((base_secs as u64 * factor).min(MAX_RETRY_DELAY_SECS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(id: &str) -> QueuedRun {
+        QueuedRun {
+            task_id: id.into(),
+            attempt: 0,
+            trigger: None,
+        }
+    }
+
+    #[test]
+    fn queue_respects_cap_and_order() {
+        let mut q = RunQueue::default();
+        assert_eq!(q.admit(run("a"), 2), Admission::Start);
+        assert_eq!(q.admit(run("b"), 2), Admission::Start);
+        assert_eq!(q.admit(run("c"), 2), Admission::Queued);
+        assert_eq!(q.admit(run("d"), 2), Admission::Queued);
+        assert_eq!(q.admit(run("c"), 2), Admission::AlreadyQueued);
+
+        assert_eq!(q.release(2), vec![run("c")]);
+        assert_eq!(q.release(2), vec![run("d")]);
+        assert!(q.release(2).is_empty());
+        assert!(q.release(2).is_empty());
+        assert_eq!(q.admit(run("e"), 2), Admission::Start);
+    }
+
+    #[test]
+    fn raised_cap_drains_several_waiting_runs() {
+        let mut q = RunQueue::default();
+        q.admit(run("a"), 1);
+        q.admit(run("b"), 1);
+        q.admit(run("c"), 1);
+        assert_eq!(q.release(0), vec![run("b"), run("c")]);
+    }
+
+    #[test]
+    fn retry_delay_doubles_and_caps() {
+        assert_eq!(retry_delay(60, 1), Duration::from_secs(60));
+        assert_eq!(retry_delay(60, 2), Duration::from_secs(120));
+        assert_eq!(retry_delay(60, 3), Duration::from_secs(240));
+        assert_eq!(retry_delay(60, 10), Duration::from_secs(MAX_RETRY_DELAY_SECS));
+        assert_eq!(retry_delay(60, u32::MAX), Duration::from_secs(MAX_RETRY_DELAY_SECS));
+    }
+}
@@ -1,18 +1,49 @@
-use std::str::FromStr;
+use std::sync::OnceLock;
 use std::time::Duration;
 
-use chrono::{Datelike, Local, Timelike};
-use cron::Schedule;
+use chrono::{DateTime, Local, Utc};
 use tauri::Manager;
+use tokio::sync::mpsc;
 
-use super::{load_tasks, save_tasks, ScheduledTask, TaskRunStatus, TaskSchedule};
+use super::chain;
+use super::history::{self, RunCapture, TaskRun};
+use super::notify;
+use super::queue::{retry_delay, Admission, QueuedRun, RunQueue};
+use super::schedule::{missed_fire_times, FireSchedule, TaskZone};
+use super::settings::load_settings;
+use super::triggers::{self, TriggerContext, Triggers};
+use super::store::{self, Claim};
+use super::{load_tasks, CatchUpPolicy, ScheduledTask, TaskRunStatus};
 use crate::conductor::process::{CliSessionConfig, EventSink};
 use crate::conductor::session::SessionManager;
+use crate::prompt_template::{self, PromptVars};
+use crate::teamwork::roles::find_role;
 
 /// Main scheduler loop. Runs every 30 seconds, checks all tasks.
 pub async fn start_scheduler(app_handle: tauri::AppHandle) {
     eprintln!("[scheduler] Started");
 
+    if let Err(e) = tokio::task::spawn_blocking(recover_stale_runs).await {
+        eprintln!("[scheduler] Stale run recovery panicked: {e}");
+    }
+
+    let (tx, rx) = mpsc::unbounded_channel();
+    if DISPATCH.set(tx).is_err() {
+        eprintln!("[scheduler] Already started");
+        return;
+    }
+    tokio::spawn(dispatch_runs(app_handle.clone(), rx));
+
+    let (trigger_tx, trigger_rx) = mpsc::unbounded_channel();
+    tokio::spawn(triggers::handle_events(trigger_rx));
+    let mut trigger_set = Triggers::new(trigger_tx);
+    let settings = tokio::task::spawn_blocking(load_settings)
+        .await
+        .unwrap_or_default();
+    if let Err(e) = triggers::apply_webhook_settings(&settings).await {
+        eprintln!("[scheduler] {e}");
+    }
+
     loop {
         tokio::time::sleep(Duration::from_secs(30)).await;
 
@@ -24,127 +55,451 @@ pub async fn start_scheduler(app_handle: tauri::AppHandle) {
             }
         };
 
-        let now = Local::now();
+        trigger_set.tick(&tasks).await;
+
+        let now = Utc::now();
 
         for task in &tasks {
             if !task.enabled {
                 continue;
             }
-            if !should_run(task, &now) {
-                continue;
+            match check_due(task, now) {
+                Some(Due::Run(fire_time)) => match claim_scheduled(&task.id, fire_time).await {
+                    Some(Claim::Claimed { previous }) => {
+                        eprintln!("[scheduler] Running task '{}' ({})", task.name, task.id);
+                        if let Err(e) = run_task_now(&task.id) {
+                            eprintln!("[scheduler] Failed to submit task '{}': {e}", task.name);
+                            reset_status(&task.id, previous).await;
+                        }
+                    }
+                    Some(Claim::Busy) => {
+                        eprintln!("[scheduler] Task '{}' is already running, skipping", task.name);
+                    }
+                    Some(Claim::NotFound) | None => {}
+                },
+                Some(Due::Skip(fire_time)) => {
+                    eprintln!(
+                        "[scheduler] Skipping missed run of '{}' due at {}",
+                        task.name,
+                        fire_time.with_timezone(&Local).to_rfc3339()
+                    );
+                    mark_scheduled(&task.id, fire_time).await;
+                }
+                None => {}
             }
-
-            eprintln!("[scheduler] Running task '{}' ({})", task.name, task.id);
-            run_task_now(&app_handle, task).await;
         }
     }
 }
 
-/// Check if a task should run based on its schedule and last_run.
-pub(crate) fn should_run(task: &ScheduledTask, now: &chrono::DateTime<Local>) -> bool {
-    let last_run = task
-        .last_run
+/// Missed fire times replayed at most under `CatchUpPolicy::All`.
+const MAX_CATCH_UP_RUNS: usize = 24;
+/// A fire time seen within this window counts as on time (the loop ticks every 30s).
+const ON_TIME_GRACE_SECS: i64 = 120;
+
+/// Scheduler decision for a task.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Due {
+    /// Run now for this fire time.
+    Run(DateTime<Utc>),
+    /// This fire time was missed and the catch-up policy drops it.
+    Skip(DateTime<Utc>),
+}
+
+fn parse_time(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Decide whether a task is due, based on its schedule, timezone and catch-up policy.
+pub(crate) fn check_due(task: &ScheduledTask, now: DateTime<Utc>) -> Option<Due> {
+    // Don't re-run if already running or waiting for a slot
+    if task.last_status.as_ref().is_some_and(TaskRunStatus::is_busy) {
+        return None;
+    }
+    // Event-triggered tasks are started by `triggers`
+    if task.schedule.trigger_kind().is_some() {
+        return None;
+    }
+
+    let (schedule, zone) = match FireSchedule::parse(&task.schedule)
+        .and_then(|s| Ok((s, TaskZone::parse(task.timezone.as_deref())?)))
+    {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("[scheduler] Task '{}': {e}", task.name);
+            return None;
+        }
+    };
+
+    let anchor = task
+        .last_scheduled
         .as_deref()
-        .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
-        .map(|dt| dt.with_timezone(&Local));
+        .or(task.last_run.as_deref())
+        .and_then(parse_time);
+    let anchor = match anchor {
+        Some(anchor) => anchor,
+        // Never ran: interval tasks start right away, calendar ones count from creation
+        None if matches!(schedule, FireSchedule::Interval { .. }) => return Some(Due::Run(now)),
+        None => parse_time(&task.created_at)
+            .unwrap_or(now - chrono::Duration::seconds(ON_TIME_GRACE_SECS)),
+    };
 
-    // Don't re-run if already running
-    if matches!(task.last_status, Some(TaskRunStatus::Running)) {
-        return false;
+    let limit = match task.catch_up {
+        CatchUpPolicy::All => MAX_CATCH_UP_RUNS,
+        CatchUpPolicy::Skip | CatchUpPolicy::Once => 1,
+    };
+    let missed = missed_fire_times(&schedule, zone, anchor, now, limit);
+    let latest = *missed.last()?;
+    let on_time = (now - latest).num_seconds() <= ON_TIME_GRACE_SECS;
+    match task.catch_up {
+        CatchUpPolicy::All => Some(Due::Run(missed[0])),
+        CatchUpPolicy::Once => Some(Due::Run(latest)),
+        CatchUpPolicy::Skip if on_time => Some(Due::Run(latest)),
+        CatchUpPolicy::Skip => Some(Due::Skip(latest)),
     }
+}
 
-    match &task.schedule {
-        TaskSchedule::Interval { minutes } => {
-            let Some(last) = last_run else {
-                return true; // never ran
-            };
-            let elapsed = (*now - last).num_minutes();
-            elapsed >= *minutes as i64
+/// Remember the fire time the scheduler has handled for a task.
+async fn mark_scheduled(task_id: &str, fire_time: DateTime<Utc>) {
+    let task_id = task_id.to_string();
+    let result = tokio::task::spawn_blocking(move || {
+        store::update_task(&task_id, |t| t.last_scheduled = Some(fire_time.to_rfc3339()))
+            .map(|_| ())
+    })
+    .await;
+    match result {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => eprintln!("[scheduler] Failed to update task schedule: {e}"),
+        Err(e) => eprintln!("[scheduler] Schedule update task panicked: {e}"),
+    }
+}
+
+/// Record `fire_time` as handled and claim the run in the same write, so a
+/// Run Now or trigger landing on the same tick can't start a second session.
+/// None if the task couldn't be updated.
+async fn claim_scheduled(task_id: &str, fire_time: DateTime<Utc>) -> Option<Claim> {
+    let task_id = task_id.to_string();
+    let result = tokio::task::spawn_blocking(move || {
+        store::update_task(&task_id, |t| {
+            t.last_scheduled = Some(fire_time.to_rfc3339());
+            store::claim_loaded(t)
+        })
+    })
+    .await;
+    match result {
+        Ok(Ok(claim)) => Some(claim.unwrap_or(Claim::NotFound)),
+        Ok(Err(e)) => {
+            eprintln!("[scheduler] Failed to update task schedule: {e}");
+            None
         }
-        TaskSchedule::Daily { hour, minute } => {
-            if now.hour() as u8 != *hour || now.minute() as u8 != *minute {
-                return false;
-            }
-            // Check we haven't already run this minute
-            match last_run {
-                Some(last) => last.date_naive() < now.date_naive() || last.hour() as u8 != *hour || last.minute() as u8 != *minute,
-                None => true,
-            }
+        Err(e) => {
+            eprintln!("[scheduler] Schedule update task panicked: {e}");
+            None
         }
-        TaskSchedule::Weekly {
-            day,
-            hour,
-            minute,
-        } => {
-            // day: 0=Mon..6=Sun; chrono: Mon=0..Sun=6 via weekday().num_days_from_monday()
-            let current_day = now.weekday().num_days_from_monday() as u8;
-            if current_day != *day || now.hour() as u8 != *hour || now.minute() as u8 != *minute {
-                return false;
-            }
-            match last_run {
-                Some(last) => {
-                    // Haven't run this week at this time
-                    let days_since = (*now - last).num_days();
-                    days_since >= 1
-                }
-                None => true,
+    }
+}
+
+/// Undo a claim whose run couldn't be submitted.
+async fn reset_status(task_id: &str, previous: Option<TaskRunStatus>) {
+    let task_id = task_id.to_string();
+    match tokio::task::spawn_blocking(move || store::set_status(&task_id, previous)).await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => eprintln!("[scheduler] Failed to reset task status: {e}"),
+        Err(e) => eprintln!("[scheduler] Status reset task panicked: {e}"),
+    }
+}
+
+/// Run a CLI session, killing it via `SessionManager::kill` once `timeout_minutes` elapse.
+/// Returns the session result and whether the timeout cut it off.
+async fn run_with_timeout(
+    sink: EventSink,
+    sessions: SessionManager,
+    config: CliSessionConfig,
+    timeout_minutes: Option<u32>,
+) -> (Result<(), String>, bool) {
+    let agent_id = config.agent_id.clone();
+    let session = crate::conductor::process::run_cli_session(sink, sessions.clone(), config);
+    let Some(minutes) = timeout_minutes else {
+        return (session.await, false);
+    };
+    tokio::pin!(session);
+    tokio::select! {
+        result = &mut session => (result, false),
+        _ = tokio::time::sleep(Duration::from_secs(minutes as u64 * 60)) => {
+            eprintln!("[scheduler] Task {agent_id} timed out after {minutes} min, killing");
+            sessions.kill(&agent_id).await;
+            // Let the session wind down so its exit is captured
+            if let Err(e) = session.await {
+                eprintln!("[scheduler] Task {agent_id} ended with: {e}");
             }
+            (Err(format!("Timed out after {minutes} minutes")), true)
         }
-        TaskSchedule::Cron { expression } => {
-            // cron crate uses 7-field format: sec min hour dom month dow year
-            // User provides 5-field: min hour dom month dow
-            // Prepend "0 " (second=0) and append " *" (year=any)
-            let full_expr = format!("0 {expression} *");
-            let schedule = match Schedule::from_str(&full_expr) {
-                Ok(s) => s,
-                Err(e) => {
-                    eprintln!("[scheduler] Invalid cron expression '{}': {e}", expression);
-                    return false;
-                }
-            };
+    }
+}
 
-            // cron crate parses everything as UTC. To make "0 6 * * *" fire at
-            // 6:00 local, we feed the crate our local time disguised as UTC.
-            let offset_secs = now.offset().local_minus_utc() as i64;
-            let now_utc = now.with_timezone(&chrono::Utc);
-            let fake_now = now_utc - chrono::Duration::seconds(offset_secs);
-            let window_start = fake_now - chrono::Duration::seconds(30);
-            if let Some(next) = schedule.after(&window_start).next() {
-                if next <= fake_now {
-                    // Check we haven't already run for this occurrence
-                    match last_run {
-                        Some(last) => (*now - last).num_seconds() > 30,
-                        None => true,
+enum QueueMsg {
+    Submit(QueuedRun),
+    Finished,
+}
+
+/// Sender to the run dispatcher, set once by `start_scheduler`.
+static DISPATCH: OnceLock<mpsc::UnboundedSender<QueueMsg>> = OnceLock::new();
+
+fn send_to_dispatcher(msg: QueueMsg) -> Result<(), String> {
+    DISPATCH
+        .get()
+        .ok_or("Scheduler is not running")?
+        .send(msg)
+        .map_err(|_| "Scheduler dispatcher stopped".to_string())
+}
+
+/// Request a run of a task (public for `scheduler_run_now` command).
+/// Starts right away if a run slot is free, otherwise waits in the queue.
+pub fn run_task_now(task_id: &str) -> Result<(), String> {
+    send_to_dispatcher(QueueMsg::Submit(QueuedRun {
+        task_id: task_id.to_string(),
+        attempt: 0,
+        trigger: None,
+    }))
+}
+
+/// Request a run started by an event trigger; `trigger` is appended to the prompt.
+pub(crate) fn run_triggered(task_id: &str, trigger: TriggerContext) -> Result<(), String> {
+    send_to_dispatcher(QueueMsg::Submit(QueuedRun {
+        task_id: task_id.to_string(),
+        attempt: 0,
+        trigger: Some(trigger),
+    }))
+}
+
+/// Owns the run queue: admits submitted runs under the concurrency cap and
+/// starts waiting ones as slots free up.
+async fn dispatch_runs(app: tauri::AppHandle, mut rx: mpsc::UnboundedReceiver<QueueMsg>) {
+    let mut queue = RunQueue::default();
+    while let Some(msg) = rx.recv().await {
+        // Re-read every time so a changed cap applies without restart
+        let cap = tokio::task::spawn_blocking(load_settings)
+            .await
+            .map(|s| s.max_concurrent_runs)
+            .unwrap_or_else(|e| {
+                eprintln!("[scheduler] Failed to load settings: {e}");
+                0
+            });
+        let ready = match msg {
+            QueueMsg::Submit(run) => match queue.admit(run.clone(), cap) {
+                Admission::Start => vec![run],
+                Admission::Queued => {
+                    eprintln!("[scheduler] All {cap} run slots busy, task {} queued", run.task_id);
+                    set_status(&run.task_id, TaskRunStatus::Queued).await;
+                    if let Err(e) = tauri::Emitter::emit(&app, "scheduler:task-queued", &run.task_id) {
+                        eprintln!("[scheduler] Failed to emit task-queued: {e}");
                     }
-                } else {
-                    false
+                    Vec::new()
                 }
-            } else {
-                false
+                Admission::AlreadyQueued => Vec::new(),
+            },
+            QueueMsg::Finished => queue.release(cap),
+        };
+        for run in ready {
+            tokio::spawn(start_queued(app.clone(), run));
+        }
+    }
+}
+
+/// Start a run that was given a slot. The task is re-read: it may have changed while waiting.
+async fn start_queued(app: tauri::AppHandle, run: QueuedRun) {
+    let task_id = run.task_id.clone();
+    let task = tokio::task::spawn_blocking(move || store::load_task(&task_id))
+        .await
+        .map_err(|e| format!("Task failed: {e}"))
+        .and_then(|r| r)
+        .unwrap_or_else(|e| {
+            eprintln!("[scheduler] Failed to load task: {e}");
+            None
+        });
+    match task {
+        Some(task) => start_run(&app, &task, run.attempt, run.trigger).await,
+        None => {
+            eprintln!("[scheduler] Task {} no longer exists, skipping run", run.task_id);
+            release_slot();
+        }
+    }
+}
+
+fn release_slot() {
+    if let Err(e) = send_to_dispatcher(QueueMsg::Finished) {
+        eprintln!("[scheduler] Failed to release run slot: {e}");
+    }
+}
+
+async fn set_status(task_id: &str, status: TaskRunStatus) {
+    let task_id = task_id.to_string();
+    let result =
+        tokio::task::spawn_blocking(move || store::set_status(&task_id, Some(status))).await;
+    match result {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => eprintln!("[scheduler] Failed to update task status: {e}"),
+        Err(e) => eprintln!("[scheduler] Status update task panicked: {e}"),
+    }
+}
+
+/// Runs still marked running or queued at startup were cut off by the previous exit.
+/// Running ones are closed as errors (and logged to history); queued ones are dropped.
+fn recover_stale(tasks: &mut [ScheduledTask], now: DateTime<Local>) -> Vec<TaskRun> {
+    let mut interrupted = Vec::new();
+    for task in tasks.iter_mut() {
+        match task.last_status {
+            Some(TaskRunStatus::Running) => {
+                let started = task
+                    .last_run
+                    .as_deref()
+                    .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                    .map(|dt| dt.with_timezone(&Local))
+                    .unwrap_or(now);
+                interrupted.push(TaskRun {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    task_id: task.id.clone(),
+                    task_name: task.name.clone(),
+                    started_at: started.to_rfc3339(),
+                    finished_at: now.to_rfc3339(),
+                    duration_ms: (now - started).num_milliseconds().max(0) as u64,
+                    status: TaskRunStatus::Error,
+                    exit_code: None,
+                    final_message: None,
+                    cost_usd: None,
+                    error: Some("Interrupted: the app exited during the run".into()),
+                    session_id: None,
+                    attempt: 0,
+                    trigger: None,
+                });
+                task.last_status = Some(TaskRunStatus::Error);
             }
+            Some(TaskRunStatus::Queued) => task.last_status = None,
+            _ => {}
         }
     }
+    interrupted
 }
 
-/// Launch a scheduled task as an agent session (public for `scheduler_run_now` command).
-pub async fn run_task_now(app_handle: &tauri::AppHandle, task: &ScheduledTask) {
+fn recover_stale_runs() {
+    let mut tasks: Vec<ScheduledTask> = load_tasks()
+        .into_iter()
+        .filter(|t| t.last_status.as_ref().is_some_and(TaskRunStatus::is_busy))
+        .collect();
+    if tasks.is_empty() {
+        return;
+    }
+    let interrupted = recover_stale(&mut tasks, Local::now());
+    eprintln!(
+        "[scheduler] Recovered {} interrupted run(s) from the previous session",
+        interrupted.len()
+    );
+    for run in &interrupted {
+        if let Err(e) = history::record_run(run) {
+            eprintln!("[scheduler] Failed to record interrupted run: {e}");
+        }
+    }
+    for task in tasks {
+        if let Err(e) = store::set_status(&task.id, task.last_status) {
+            eprintln!("[scheduler] Failed to save recovered task '{}': {e}", task.name);
+        }
+    }
+}
+
+/// Template variables for a run of `task` in `project_path`: previous run,
+/// files changed since it, and the trigger (whose files take precedence).
+pub(crate) fn task_prompt_vars(
+    task: &ScheduledTask,
+    project_path: &str,
+    trigger: Option<&TriggerContext>,
+) -> PromptVars {
+    let mut vars = PromptVars::for_project(project_path);
+    vars.task = task.name.clone();
+    let previous = history::last_run(&task.id);
+    if let Some(run) = &previous {
+        vars.last_status = serde_json::to_value(&run.status)
+            .ok()
+            .and_then(|v| v.as_str().map(String::from))
+            .unwrap_or_default();
+        if let Some(output) = &run.final_message {
+            vars.set_last_output(output);
+        }
+    }
+    match trigger {
+        Some(trigger) => {
+            vars.changed_files = trigger.changed_files.clone();
+            vars.trigger = trigger.summary.clone();
+            vars.trigger_payload = trigger.payload.clone().unwrap_or_default();
+        }
+        None => {
+            let since = previous.as_ref().map(|r| r.started_at.as_str());
+            vars.changed_files = prompt_template::changed_files_since(project_path, since);
+        }
+    }
+    vars
+}
+
+/// Final prompt of a run: the task template rendered, plus the trigger details
+/// unless the template already places them.
+fn render_task_prompt(task: &ScheduledTask, trigger: Option<&TriggerContext>) -> String {
+    let vars = task_prompt_vars(task, &task.project_path, trigger);
+    render_with_vars(task, &vars, trigger)
+}
+
+fn render_with_vars(
+    task: &ScheduledTask,
+    vars: &PromptVars,
+    trigger: Option<&TriggerContext>,
+) -> String {
+    let prompt = prompt_template::render(&task.prompt, vars).unwrap_or_else(|e| {
+        eprintln!("[scheduler] Task '{}' prompt template: {e}", task.name);
+        task.prompt.clone()
+    });
+    match trigger {
+        Some(trigger) if !prompt_template::uses_any(&task.prompt, &["trigger", "trigger_payload"]) => {
+            trigger.apply_to(&prompt)
+        }
+        _ => prompt,
+    }
+}
+
+/// Launch a scheduled task as an agent session. `attempt` is 0 for the first try.
+/// Holds a run slot until the session ends.
+async fn start_run(
+    app_handle: &tauri::AppHandle,
+    task: &ScheduledTask,
+    attempt: u32,
+    trigger: Option<TriggerContext>,
+) {
     let agent_id = format!("scheduler_{}", task.id);
     let now_iso = Local::now().to_rfc3339();
 
+    // Rendered before last_run changes: it reads the previous run
+    let prompt = {
+        let (t, trigger) = (task.clone(), trigger.clone());
+        tokio::task::spawn_blocking(move || render_task_prompt(&t, trigger.as_ref()))
+            .await
+            .unwrap_or_else(|e| {
+                eprintln!("[scheduler] Prompt rendering failed: {e}");
+                task.prompt.clone()
+            })
+    };
+
     // Update last_run and status
     if let Err(e) = tokio::task::spawn_blocking({
         let task_id = task.id.clone();
         let now_iso = now_iso.clone();
         move || {
-            let mut tasks = load_tasks();
-            if let Some(t) = tasks.iter_mut().find(|t| t.id == task_id) {
+            store::update_task(&task_id, |t| {
                 t.last_run = Some(now_iso);
                 t.last_status = Some(TaskRunStatus::Running);
-            }
-            save_tasks(&tasks)
+            })
         }
     })
     .await
+    .map_err(|e| format!("Task failed: {e}"))
+    .and_then(|r| r)
     {
         eprintln!("[scheduler] Failed to update task status: {e}");
     }
@@ -177,34 +532,73 @@ pub async fn run_task_now(app_handle: &tauri::AppHandle, task: &ScheduledTask) {
 
     let sessions: SessionManager = app_handle.state::<SessionManager>().inner().clone();
     let app_clone = app_handle.clone();
-    let prompt = task.prompt.clone();
     let teamwork_project_path = Some(project_path.clone());
+    let resume_session_id = task
+        .resume_previous
+        .then(|| task.last_session_id.clone())
+        .flatten();
 
     let task_id_for_spawn = task.id.clone();
-    let task_name_for_spawn = task.name.clone();
-    let task_notify_tg = task.notify_telegram;
+    let task_name_for_history = task.name.clone();
+    let task_options = task.clone();
+    let app_for_retry = app_handle.clone();
     tokio::spawn(async move {
-        let result = crate::conductor::process::run_cli_session(
-            EventSink::new(app_clone.clone()),
-            sessions,
-            CliSessionConfig {
-                agent_id: agent_id.clone(),
-                prompt,
-                project_path: Some(project_path),
-                model: None,
-                effort: None,
-                resume_session_id: None,
-                permission_mode: None,
-                chrome: false,
-                image_attachments: Vec::new(),
-                teamwork_project_path,
-                additional_dirs,
-                role_system_prompt: None,
-                role_allowed_tools: None,
-                role_name: None,
-            },
-        )
-        .await;
+        // Copy of the session's events to capture the run outcome for history
+        let (tap_tx, mut tap_rx) = tokio::sync::mpsc::unbounded_channel();
+        let collector = tokio::spawn(async move {
+            let mut capture = RunCapture::default();
+            while let Some(event) = tap_rx.recv().await {
+                capture.observe(&event);
+            }
+            capture
+        });
+        let started = Local::now();
+
+        let role = match task_options.role.clone() {
+            Some(name) => tokio::task::spawn_blocking(move || find_role(&name))
+                .await
+                .map_err(|e| format!("Task failed: {e}"))
+                .and_then(|r| r)
+                .map(Some),
+            None => Ok(None),
+        };
+
+        let (result, timed_out) = match role {
+            Err(e) => {
+                drop(tap_tx); // no session: close the capture channel
+                (Err(e), false)
+            }
+            Ok(role) => {
+                let allowed_tools = if task_options.allowed_tools.is_empty() {
+                    role.as_ref().map(|r| r.allowed_tools.clone())
+                } else {
+                    Some(task_options.allowed_tools.clone())
+                };
+                let config = CliSessionConfig {
+                    agent_id: agent_id.clone(),
+                    prompt,
+                    project_path: Some(project_path),
+                    model: task_options.model.clone(),
+                    effort: task_options.effort.clone(),
+                    resume_session_id,
+                    permission_mode: task_options.permission_mode.clone(),
+                    chrome: false,
+                    image_attachments: Vec::new(),
+                    teamwork_project_path,
+                    additional_dirs,
+                    role_system_prompt: role.as_ref().map(|r| r.system_prompt.clone()),
+                    role_allowed_tools: allowed_tools,
+                    role_name: role.map(|r| r.name),
+                };
+                run_with_timeout(
+                    EventSink::with_tap(app_clone.clone(), tap_tx),
+                    sessions,
+                    config,
+                    task_options.timeout_minutes,
+                )
+                .await
+            }
+        };
 
         let final_status = match &result {
             Ok(()) => TaskRunStatus::Success,
@@ -220,44 +614,106 @@ pub async fn run_task_now(app_handle: &tauri::AppHandle, task: &ScheduledTask) {
                 ) {
                     eprintln!("[scheduler] Failed to emit error: {e2}");
                 }
-                TaskRunStatus::Error
+                if timed_out {
+                    TaskRunStatus::Timeout
+                } else {
+                    TaskRunStatus::Error
+                }
             }
         };
 
-        // Send Telegram notification if enabled
-        if task_notify_tg && final_status == TaskRunStatus::Success {
-            let tg_name = task_name_for_spawn;
-            match tokio::task::spawn_blocking(move || {
-                crate::telegram::commands::send_to_telegram(
-                    format!("\u{2705} Scheduled task '{tg_name}' completed successfully"),
-                )
-            })
-            .await
-            {
-                Ok(Ok(())) => {}
-                Ok(Err(e)) => eprintln!("[scheduler] Telegram send failed: {e}"),
-                Err(e) => eprintln!("[scheduler] Telegram task panicked: {e}"),
-            }
+        // Record the run in history
+        let capture = collector.await.unwrap_or_else(|e| {
+            eprintln!("[scheduler] Run capture task failed: {e}");
+            RunCapture::default()
+        });
+        let finished = Local::now();
+        let run = TaskRun {
+            id: uuid::Uuid::new_v4().to_string(),
+            task_id: task_id_for_spawn.clone(),
+            task_name: task_name_for_history,
+            started_at: started.to_rfc3339(),
+            finished_at: finished.to_rfc3339(),
+            duration_ms: (finished - started).num_milliseconds().max(0) as u64,
+            status: final_status.clone(),
+            exit_code: capture.exit_code,
+            final_message: capture.final_message.clone(),
+            cost_usd: capture.cost_usd,
+            error: result.err().or(capture.last_error),
+            session_id: capture.session_id.clone(),
+            attempt,
+            trigger: trigger.as_ref().map(|t| t.summary.clone()),
+        };
+        let record = run.clone();
+        match tokio::task::spawn_blocking(move || history::record_run(&record)).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => eprintln!("[scheduler] Failed to record run: {e}"),
+            Err(e) => eprintln!("[scheduler] Run record task panicked: {e}"),
         }
 
-        // Update last_status after completion
-        let tid = task_id_for_spawn;
+        // Failed runs are retried with backoff while attempts remain
+        let retry_attempt = (final_status == TaskRunStatus::Error
+            && attempt < task_options.max_retries)
+            .then_some(attempt + 1);
+        let stored_status = if retry_attempt.is_some() {
+            TaskRunStatus::Queued
+        } else {
+            final_status.clone()
+        };
+
+        // Update last_status (and session for resume) after completion
+        let tid = task_id_for_spawn.clone();
+        let session_id = capture.session_id;
         if let Err(e) = tokio::task::spawn_blocking(move || {
-            let mut tasks = load_tasks();
-            if let Some(t) = tasks.iter_mut().find(|t| t.id == tid) {
-                t.last_status = Some(final_status);
-            }
-            save_tasks(&tasks)
+            store::update_task(&tid, |t| {
+                t.last_status = Some(stored_status);
+                if session_id.is_some() {
+                    t.last_session_id = session_id;
+                }
+            })
         })
         .await
+        .map_err(|e| format!("Task failed: {e}"))
+        .and_then(|r| r)
         {
             eprintln!("[scheduler] Failed to update final status: {e}");
         }
+        release_slot();
+
+        // Notifications and follow-ups wait until the run is final (no retry pending)
+        if retry_attempt.is_none() {
+            notify::send_notifications(&app_for_retry, &task_options, &run).await;
+            let output = capture.final_message.unwrap_or_default();
+            chain::start_follow_ups(&task_options, &final_status, &output).await;
+        }
+
+        if let Some(next) = retry_attempt {
+            let delay = retry_delay(task_options.retry_delay_secs, next);
+            eprintln!(
+                "[scheduler] Task '{}' failed, retry {next}/{} in {}s",
+                task_options.name,
+                task_options.max_retries,
+                delay.as_secs()
+            );
+            if let Err(e) = tauri::Emitter::emit(&app_for_retry, "scheduler:task-queued", &task_options.id) {
+                eprintln!("[scheduler] Failed to emit task-queued: {e}");
+            }
+            tokio::time::sleep(delay).await;
+            let retry = QueuedRun {
+                task_id: task_options.id.clone(),
+                attempt: next,
+                trigger,
+            };
+            if let Err(e) = send_to_dispatcher(QueueMsg::Submit(retry)) {
+                eprintln!("[scheduler] Failed to submit retry: {e}");
+            }
+        }
     });
 }
 
 #[cfg(test)]
 mod tests {
+    use super::super::{normalize_task_options, test_task, validate_task_options, TaskSchedule};
     use super::*;
     use chrono::TimeZone;
 
@@ -266,18 +722,14 @@ mod tests {
         last_run: Option<&str>,
         last_status: Option<TaskRunStatus>,
     ) -> ScheduledTask {
-        ScheduledTask {
-            id: "test".into(),
-            name: "Test".into(),
-            prompt: "do stuff".into(),
-            project_path: "/tmp".into(),
-            schedule,
-            enabled: true,
-            notify_telegram: false,
-            created_at: "2026-01-01T00:00:00+00:00".into(),
-            last_run: last_run.map(String::from),
-            last_status,
-        }
+        let mut task = test_task("test", schedule);
+        task.last_run = last_run.map(String::from);
+        task.last_status = last_status;
+        task
+    }
+
+    fn should_run(task: &ScheduledTask, now: &chrono::DateTime<Local>) -> bool {
+        matches!(check_due(task, now.with_timezone(&Utc)), Some(Due::Run(_)))
     }
 
     // 2026-03-30 is a Monday
@@ -379,14 +831,27 @@ mod tests {
 
     #[test]
     fn weekly_already_ran_this_week() {
-        let last = mon(0, 1, 0).to_rfc3339();
+        let last = mon(9, 0, 0).to_rfc3339();
         let task = make_task(
             TaskSchedule::Weekly { day: 0, hour: 9, minute: 0 },
             Some(&last),
             Some(TaskRunStatus::Success),
         );
-        // last_run was today at 00:01, now is 09:00 same day → days_since = 0 < 1
-        assert!(!should_run(&task, &mon(9, 0, 0)));
+        // Ran on Monday; on Thursday at the same time the next fire is still days away
+        let thu = Local.with_ymd_and_hms(2026, 4, 2, 9, 0, 0).unwrap();
+        assert!(!should_run(&task, &thu));
+    }
+
+    #[test]
+    fn weekly_already_ran_within_grace_window() {
+        let last = mon(9, 0, 0).to_rfc3339();
+        let task = make_task(
+            TaskSchedule::Weekly { day: 0, hour: 9, minute: 0 },
+            Some(&last),
+            Some(TaskRunStatus::Success),
+        );
+        // Already ran for today's 09:00 fire time
+        assert!(!should_run(&task, &mon(9, 0, 20)));
     }
 
     // ── Cron ──
@@ -424,6 +889,172 @@ mod tests {
         assert!(!should_run(&task, &now));
     }
 
+    // ── Catch-up ──
+
+    fn missed_daily(policy: CatchUpPolicy) -> ScheduledTask {
+        // Last handled Friday 06:00; machine was off over the weekend
+        let fri = Local.with_ymd_and_hms(2026, 3, 27, 6, 0, 0).unwrap().to_rfc3339();
+        let mut task = make_task(
+            TaskSchedule::Daily { hour: 6, minute: 0 },
+            Some(&fri),
+            Some(TaskRunStatus::Success),
+        );
+        task.catch_up = policy;
+        task
+    }
+
+    fn utc(dt: chrono::DateTime<Local>) -> DateTime<Utc> {
+        dt.with_timezone(&Utc)
+    }
+
+    #[test]
+    fn catch_up_skip_drops_late_fire_time() {
+        let task = missed_daily(CatchUpPolicy::Skip);
+        assert_eq!(
+            check_due(&task, utc(mon(8, 0, 0))),
+            Some(Due::Skip(utc(mon(6, 0, 0))))
+        );
+    }
+
+    #[test]
+    fn catch_up_once_runs_latest_fire_time() {
+        let task = missed_daily(CatchUpPolicy::Once);
+        assert_eq!(
+            check_due(&task, utc(mon(8, 0, 0))),
+            Some(Due::Run(utc(mon(6, 0, 0))))
+        );
+    }
+
+    #[test]
+    fn catch_up_all_replays_oldest_first() {
+        let mut task = missed_daily(CatchUpPolicy::All);
+        let sat = Local.with_ymd_and_hms(2026, 3, 28, 6, 0, 0).unwrap();
+        assert_eq!(check_due(&task, utc(mon(8, 0, 0))), Some(Due::Run(utc(sat))));
+        task.last_scheduled = Some(sat.to_rfc3339());
+        task.last_run = Some(mon(8, 0, 0).to_rfc3339());
+        let sun = Local.with_ymd_and_hms(2026, 3, 29, 6, 0, 0).unwrap();
+        assert_eq!(check_due(&task, utc(mon(8, 1, 0))), Some(Due::Run(utc(sun))));
+    }
+
+    #[test]
+    fn manual_run_does_not_shift_schedule() {
+        let mut task = missed_daily(CatchUpPolicy::Skip);
+        task.last_scheduled = Some(Local.with_ymd_and_hms(2026, 3, 29, 6, 0, 0).unwrap().to_rfc3339());
+        task.last_run = Some(mon(5, 30, 0).to_rfc3339());
+        assert!(should_run(&task, &mon(6, 0, 10)));
+    }
+
+    #[test]
+    fn daily_in_task_timezone() {
+        let mut task = make_task(TaskSchedule::Daily { hour: 6, minute: 0 }, None, None);
+        task.timezone = Some("Asia/Tokyo".into());
+        // 06:00 JST = 21:00 UTC the previous day
+        let fire = DateTime::parse_from_rfc3339("2026-03-29T21:00:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(check_due(&task, fire), Some(Due::Run(fire)));
+        assert_eq!(check_due(&task, fire + chrono::Duration::hours(1)).map(|d| matches!(d, Due::Skip(_))), Some(true));
+    }
+
+    #[test]
+    fn invalid_timezone_never_runs() {
+        let mut task = make_task(TaskSchedule::Interval { minutes: 1 }, None, None);
+        task.timezone = Some("Nowhere/Special".into());
+        assert!(!should_run(&task, &mon(9, 0, 0)));
+    }
+
+    // ── Session options ──
+
+    #[test]
+    fn task_options_are_normalized_and_validated() {
+        let mut task = make_task(TaskSchedule::Interval { minutes: 30 }, None, None);
+        task.model = Some("  ".into());
+        task.effort = Some(" low ".into());
+        task.permission_mode = Some("plan".into());
+        task.allowed_tools = vec![" Read ".into(), "".into()];
+        normalize_task_options(&mut task);
+        assert_eq!(task.model, None);
+        assert_eq!(task.effort.as_deref(), Some("low"));
+        assert_eq!(task.allowed_tools, vec!["Read"]);
+        assert!(validate_task_options(&task).is_ok());
+
+        task.effort = Some("extreme".into());
+        assert!(validate_task_options(&task).is_err());
+        task.effort = None;
+        task.permission_mode = Some("yolo".into());
+        assert!(validate_task_options(&task).is_err());
+        task.permission_mode = None;
+        task.timeout_minutes = Some(0);
+        assert!(validate_task_options(&task).is_err());
+    }
+
+    // ── Stale runs ──
+
+    #[test]
+    fn stale_runs_are_recovered_at_startup() {
+        let started = mon(8, 0, 0).to_rfc3339();
+        let mut tasks = vec![
+            make_task(TaskSchedule::Interval { minutes: 30 }, Some(&started), Some(TaskRunStatus::Running)),
+            make_task(TaskSchedule::Interval { minutes: 30 }, None, Some(TaskRunStatus::Queued)),
+            make_task(TaskSchedule::Interval { minutes: 30 }, None, Some(TaskRunStatus::Success)),
+        ];
+        let runs = recover_stale(&mut tasks, mon(9, 0, 0));
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].status, TaskRunStatus::Error);
+        assert_eq!(runs[0].duration_ms, 3_600_000);
+        assert_eq!(tasks[0].last_status, Some(TaskRunStatus::Error));
+        assert_eq!(tasks[1].last_status, None);
+        assert_eq!(tasks[2].last_status, Some(TaskRunStatus::Success));
+    }
+
+    #[test]
+    fn skip_if_queued() {
+        let task = make_task(TaskSchedule::Interval { minutes: 1 }, None, Some(TaskRunStatus::Queued));
+        assert!(!should_run(&task, &mon(9, 0, 0)));
+    }
+
+    #[test]
+    fn event_tasks_never_fire_on_time() {
+        for schedule in [
+            TaskSchedule::Webhook,
+            TaskSchedule::GitCommit {
+                branch: "main".into(),
+            },
+            TaskSchedule::FileChange {
+                patterns: vec!["src/**".into()],
+            },
+        ] {
+            let task = make_task(schedule, None, None);
+            assert_eq!(check_due(&task, mon(9, 0, 0).with_timezone(&Utc)), None);
+        }
+    }
+
+    #[test]
+    fn trigger_details_appended_unless_template_uses_them() {
+        let trigger = TriggerContext {
+            kind: "webhook",
+            summary: "Webhook request".into(),
+            changed_files: Vec::new(),
+            payload: Some("{\"ref\":\"main\"}".into()),
+        };
+        let mut task = make_task(TaskSchedule::Webhook, None, None);
+        task.name = "Test".into();
+        let vars = PromptVars {
+            task: task.name.clone(),
+            trigger: trigger.summary.clone(),
+            trigger_payload: trigger.payload.clone().unwrap_or_default(),
+            ..Default::default()
+        };
+        task.prompt = "Deploy {{task}}".into();
+        assert_eq!(
+            render_with_vars(&task, &vars, Some(&trigger)),
+            "Deploy Test\n\n---\nTrigger: Webhook request\n\nDetails:\n{\"ref\":\"main\"}\n"
+        );
+        task.prompt = "Deploy {{task}} for {{trigger_payload}}".into();
+        assert_eq!(
+            render_with_vars(&task, &vars, Some(&trigger)),
+            "Deploy Test for {\"ref\":\"main\"}"
+        );
+    }
+
     // ── Running status ──
 
     #[test]
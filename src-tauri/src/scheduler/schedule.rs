@@ -0,0 +1,382 @@
+//! Fire-time computation for task schedules.
+//!
+//! Calendar schedules (daily, weekly, cron) are evaluated on the wall clock of
+//! the task's timezone and then mapped to real instants:
+//! - a time skipped by a DST jump (02:30 on spring-forward day) fires shifted
+//!   forward by the gap, i.e. right after the clocks change;
+//! - a time that occurs twice (01:30 on fall-back day) fires once, at the
+//!   first occurrence.
+
+use std::str::FromStr;
+
+use chrono::{
+    DateTime, Datelike, Duration, Local, LocalResult, NaiveDate, NaiveDateTime, NaiveTime,
+    TimeZone, Utc,
+};
+use cron::Schedule;
+
+use super::TaskSchedule;
+
+/// Upper bound on wall-clock candidates inspected per lookup.
+const MAX_CANDIDATES: usize = 2000;
+/// Longest DST transition we have to look across (Lord Howe's is 30 min, most are 1 h).
+const MAX_DST_SHIFT_HOURS: i64 = 3;
+
+/// Timezone a task is scheduled in.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum TaskZone {
+    Local,
+    Named(chrono_tz::Tz),
+}
+
+impl TaskZone {
+    /// Parse an IANA timezone name. Empty / missing = system local time.
+    pub fn parse(name: Option<&str>) -> Result<Self, String> {
+        match name.map(str::trim).filter(|n| !n.is_empty()) {
+            None => Ok(Self::Local),
+            Some(name) => name
+                .parse::<chrono_tz::Tz>()
+                .map(Self::Named)
+                .map_err(|_| format!("Unknown timezone: '{name}'")),
+        }
+    }
+}
+
+/// A task schedule with its cron expression parsed once.
+pub(crate) enum FireSchedule {
+    Interval { minutes: u32 },
+    Daily { time: NaiveTime },
+    Weekly { day: u8, time: NaiveTime },
+    Cron(Box<Schedule>),
+}
+
+impl FireSchedule {
+    pub fn parse(schedule: &TaskSchedule) -> Result<Self, String> {
+        let time = |hour: u8, minute: u8| {
+            NaiveTime::from_hms_opt(hour as u32, minute as u32, 0)
+                .ok_or_else(|| format!("Invalid time {hour:02}:{minute:02}"))
+        };
+        match schedule {
+            TaskSchedule::Interval { minutes } => {
+                if *minutes == 0 {
+                    return Err("Interval must be at least 1 minute".into());
+                }
+                Ok(Self::Interval { minutes: *minutes })
+            }
+            TaskSchedule::Daily { hour, minute } => Ok(Self::Daily {
+                time: time(*hour, *minute)?,
+            }),
+            TaskSchedule::Weekly { day, hour, minute } => {
+                if *day > 6 {
+                    return Err(format!("Invalid day of week: {day}"));
+                }
+                Ok(Self::Weekly {
+                    day: *day,
+                    time: time(*hour, *minute)?,
+                })
+            }
+            TaskSchedule::Cron { expression } => {
+                // cron crate uses 7-field format: sec min hour dom month dow year
+                // User provides 5-field: min hour dom month dow
+                let fields = expression.split_whitespace().count();
+                if fields != 5 {
+                    return Err(format!(
+                        "Cron expression needs 5 fields (minute hour day-of-month month day-of-week), got {fields}"
+                    ));
+                }
+                let full_expr = format!("0 {} *", expression.trim());
+                Schedule::from_str(&full_expr)
+                    .map(|s| Self::Cron(Box::new(s)))
+                    .map_err(|e| format!("Invalid cron expression '{expression}': {e}"))
+            }
+            TaskSchedule::FileChange { .. }
+            | TaskSchedule::GitCommit { .. }
+            | TaskSchedule::Webhook
+            | TaskSchedule::Manual => {
+                Err("Event-triggered schedules have no fire times".into())
+            }
+        }
+    }
+
+    /// First wall-clock time strictly after `from`.
+    fn next_naive(&self, from: NaiveDateTime) -> Option<NaiveDateTime> {
+        match self {
+            Self::Interval { .. } => None,
+            Self::Daily { time } => {
+                let today = from.date().and_time(*time);
+                if today > from {
+                    Some(today)
+                } else {
+                    Some(from.date().succ_opt()?.and_time(*time))
+                }
+            }
+            Self::Weekly { day, time } => {
+                let mut date: NaiveDate = from.date();
+                for _ in 0..8 {
+                    let candidate = date.and_time(*time);
+                    if date.weekday().num_days_from_monday() as u8 == *day && candidate > from {
+                        return Some(candidate);
+                    }
+                    date = date.succ_opt()?;
+                }
+                None
+            }
+            // The cron crate evaluates in a timezone; UTC has no DST, so it walks
+            // the wall clock exactly and DST is handled by `resolve_local`.
+            Self::Cron(schedule) => schedule
+                .after(&Utc.from_utc_datetime(&from))
+                .next()
+                .map(|dt| dt.naive_utc()),
+        }
+    }
+}
+
+/// Map a wall-clock time in `tz` to an instant (see module docs for DST rules).
+fn resolve_local<Z: TimeZone>(tz: &Z, naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+    match tz.from_local_datetime(&naive) {
+        LocalResult::Single(dt) => Some(dt.with_timezone(&Utc)),
+        LocalResult::Ambiguous(first, _) => Some(first.with_timezone(&Utc)),
+        LocalResult::None => {
+            // Skipped by a forward jump: interpret with the offset in effect before it
+            let before = tz
+                .from_local_datetime(&(naive - Duration::hours(MAX_DST_SHIFT_HOURS)))
+                .earliest()?;
+            let offset = before.naive_local() - before.naive_utc();
+            Some(Utc.from_utc_datetime(&(naive - offset)))
+        }
+    }
+}
+
+fn next_calendar_fire<Z: TimeZone>(
+    schedule: &FireSchedule,
+    tz: &Z,
+    after: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let shift = Duration::hours(MAX_DST_SHIFT_HOURS);
+    // Start a little early: around a DST change, wall-clock order and real order can differ
+    let mut from = after.with_timezone(tz).naive_local() - shift;
+    let mut best: Option<DateTime<Utc>> = None;
+    let mut first_hit: Option<NaiveDateTime> = None;
+    for _ in 0..MAX_CANDIDATES {
+        let Some(naive) = schedule.next_naive(from) else {
+            break;
+        };
+        if first_hit.is_some_and(|hit| naive > hit + shift) {
+            break;
+        }
+        if let Some(instant) = resolve_local(tz, naive).filter(|i| *i > after) {
+            first_hit.get_or_insert(naive);
+            if best.is_none_or(|b| instant < b) {
+                best = Some(instant);
+            }
+        }
+        from = naive;
+    }
+    best
+}
+
+/// First time `schedule` fires strictly after `after`.
+///
+/// Interval schedules have no calendar anchor: the next fire is `after + minutes`.
+pub(crate) fn next_fire_time(
+    schedule: &FireSchedule,
+    zone: TaskZone,
+    after: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    match schedule {
+        FireSchedule::Interval { minutes } => Some(after + Duration::minutes(*minutes as i64)),
+        _ => match zone {
+            TaskZone::Local => next_calendar_fire(schedule, &Local, after),
+            TaskZone::Named(tz) => next_calendar_fire(schedule, &tz, after),
+        },
+    }
+}
+
+/// Fire times in `(anchor, now]`, oldest first — only the most recent `limit` of them.
+pub(crate) fn missed_fire_times(
+    schedule: &FireSchedule,
+    zone: TaskZone,
+    anchor: DateTime<Utc>,
+    now: DateTime<Utc>,
+    limit: usize,
+) -> Vec<DateTime<Utc>> {
+    if limit == 0 || now <= anchor {
+        return Vec::new();
+    }
+    if let FireSchedule::Interval { minutes } = schedule {
+        let step = Duration::minutes(*minutes as i64);
+        let count = (now - anchor).num_minutes() / *minutes as i64;
+        let first = (count - limit as i64 + 1).max(1);
+        return (first..=count).map(|k| anchor + step * k as i32).collect();
+    }
+
+    // Search backwards in growing windows so a long-missed, frequent schedule
+    // doesn't walk every occurrence since the anchor.
+    let mut span = Duration::hours(1);
+    loop {
+        let start = (now - span).max(anchor);
+        let mut found = Vec::new();
+        let mut cursor = start;
+        while let Some(next) = next_fire_time(schedule, zone, cursor) {
+            if next > now {
+                break;
+            }
+            found.push(next);
+            cursor = next;
+        }
+        if found.len() >= limit || start == anchor {
+            let excess = found.len().saturating_sub(limit);
+            found.drain(..excess);
+            return found;
+        }
+        span = span * 8;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn zone(name: &str) -> TaskZone {
+        TaskZone::parse(Some(name)).unwrap()
+    }
+
+    fn daily(hour: u8, minute: u8) -> FireSchedule {
+        FireSchedule::parse(&TaskSchedule::Daily { hour, minute }).unwrap()
+    }
+
+    fn cron(expr: &str) -> FireSchedule {
+        FireSchedule::parse(&TaskSchedule::Cron {
+            expression: expr.into(),
+        })
+        .unwrap()
+    }
+
+    // America/New_York 2026: DST starts Mar 8 02:00 → 03:00, ends Nov 1 02:00 → 01:00.
+
+    #[test]
+    fn daily_keeps_wall_time_across_spring_forward() {
+        let ny = zone("America/New_York");
+        let s = daily(9, 0);
+        let first = next_fire_time(&s, ny, utc("2026-03-07T12:00:00Z")).unwrap();
+        assert_eq!(first, utc("2026-03-07T14:00:00Z")); // 09:00 EST
+        let second = next_fire_time(&s, ny, first).unwrap();
+        assert_eq!(second, utc("2026-03-08T13:00:00Z")); // 09:00 EDT
+    }
+
+    #[test]
+    fn time_skipped_by_spring_forward_fires_after_the_jump() {
+        let ny = zone("America/New_York");
+        let s = daily(2, 30);
+        let fire = next_fire_time(&s, ny, utc("2026-03-07T12:00:00Z")).unwrap();
+        assert_eq!(fire, utc("2026-03-08T07:30:00Z")); // 03:30 EDT
+        let next = next_fire_time(&s, ny, fire).unwrap();
+        assert_eq!(next, utc("2026-03-09T06:30:00Z")); // 02:30 EDT
+    }
+
+    #[test]
+    fn repeated_time_on_fall_back_fires_once() {
+        let ny = zone("America/New_York");
+        let s = daily(1, 30);
+        let fire = next_fire_time(&s, ny, utc("2026-10-31T12:00:00Z")).unwrap();
+        assert_eq!(fire, utc("2026-11-01T05:30:00Z")); // first 01:30 (EDT)
+        let next = next_fire_time(&s, ny, fire).unwrap();
+        assert_eq!(next, utc("2026-11-02T06:30:00Z")); // next day, 01:30 EST
+    }
+
+    #[test]
+    fn cron_in_explicit_timezone() {
+        let berlin = zone("Europe/Berlin");
+        let s = cron("0 6 * * *");
+        // Winter: CET = UTC+1
+        let winter = next_fire_time(&s, berlin, utc("2026-01-10T00:00:00Z")).unwrap();
+        assert_eq!(winter, utc("2026-01-10T05:00:00Z"));
+        // Summer: CEST = UTC+2 (Europe switches Mar 29 2026)
+        let summer = next_fire_time(&s, berlin, utc("2026-03-29T00:00:00Z")).unwrap();
+        assert_eq!(summer, utc("2026-03-29T04:00:00Z"));
+    }
+
+    #[test]
+    fn cron_sub_hourly_through_spring_forward_stays_ordered() {
+        let ny = zone("America/New_York");
+        let s = cron("*/30 * * * *");
+        let mut t = utc("2026-03-08T06:00:00Z"); // 01:00 EST
+        let mut fires = Vec::new();
+        for _ in 0..4 {
+            t = next_fire_time(&s, ny, t).unwrap();
+            fires.push(t);
+        }
+        assert_eq!(
+            fires,
+            vec![
+                utc("2026-03-08T06:30:00Z"), // 01:30 EST
+                utc("2026-03-08T07:00:00Z"), // 02:00 (skipped) → 03:00 EDT
+                utc("2026-03-08T07:30:00Z"), // 02:30 (skipped) / 03:30 EDT
+                utc("2026-03-08T08:00:00Z"), // 04:00 EDT
+            ]
+        );
+    }
+
+    #[test]
+    fn weekly_finds_next_matching_day() {
+        let berlin = zone("Europe/Berlin");
+        // day 4 = Friday; 2026-04-01 is a Wednesday
+        let s = FireSchedule::parse(&TaskSchedule::Weekly {
+            day: 4,
+            hour: 18,
+            minute: 0,
+        })
+        .unwrap();
+        let fire = next_fire_time(&s, berlin, utc("2026-04-01T10:00:00Z")).unwrap();
+        assert_eq!(fire, utc("2026-04-03T16:00:00Z"));
+    }
+
+    #[test]
+    fn missed_fire_times_returns_most_recent() {
+        let ny = zone("America/New_York");
+        let s = daily(6, 0);
+        let anchor = utc("2026-04-01T00:00:00Z");
+        let now = utc("2026-04-04T12:00:00Z");
+        let all = missed_fire_times(&s, ny, anchor, now, 10);
+        assert_eq!(all.len(), 4); // Apr 1..4 at 06:00 EDT
+        assert_eq!(all[0], utc("2026-04-01T10:00:00Z"));
+        let latest = missed_fire_times(&s, ny, anchor, now, 1);
+        assert_eq!(latest, vec![utc("2026-04-04T10:00:00Z")]);
+
+        let every_minute = cron("* * * * *");
+        let recent = missed_fire_times(&every_minute, ny, anchor, now, 3);
+        assert_eq!(recent.last(), Some(&now));
+        assert_eq!(recent.len(), 3);
+    }
+
+    #[test]
+    fn missed_interval_runs_stay_on_grid() {
+        let s = FireSchedule::parse(&TaskSchedule::Interval { minutes: 30 }).unwrap();
+        let anchor = utc("2026-04-01T08:00:00Z");
+        let missed = missed_fire_times(&s, TaskZone::Local, anchor, utc("2026-04-01T09:40:00Z"), 2);
+        assert_eq!(
+            missed,
+            vec![utc("2026-04-01T09:00:00Z"), utc("2026-04-01T09:30:00Z")]
+        );
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        assert!(TaskZone::parse(Some("Mars/Olympus")).is_err());
+        assert!(matches!(TaskZone::parse(Some("  ")), Ok(TaskZone::Local)));
+        assert!(FireSchedule::parse(&TaskSchedule::Daily {
+            hour: 24,
+            minute: 0
+        })
+        .is_err());
+        assert!(FireSchedule::parse(&TaskSchedule::Interval { minutes: 0 }).is_err());
+        assert!(FireSchedule::parse(&TaskSchedule::Cron {
+            expression: "nope".into()
+        })
+        .is_err());
+    }
+}
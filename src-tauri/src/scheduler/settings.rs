@@ -0,0 +1,95 @@
+//! Global scheduler settings: run history retention, concurrency and the webhook endpoint.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::config;
+use crate::file_ops::{read_json, write_json};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SchedulerSettings {
+    /// Runs kept per task (0 = unlimited).
+    #[serde(default = "default_history_max_runs")]
+    pub history_max_runs: usize,
+    /// Runs older than this many days are dropped (0 = keep forever).
+    #[serde(default = "default_history_max_days")]
+    pub history_max_days: u32,
+    /// Scheduled agents running at once; further runs wait in a queue (0 = unlimited).
+    #[serde(default = "default_max_concurrent_runs")]
+    pub max_concurrent_runs: usize,
+    /// Port of the local webhook server for webhook-triggered tasks (0 = off).
+    #[serde(default)]
+    pub webhook_port: u16,
+    /// Bearer token webhook requests must send. Generated when the webhook is enabled.
+    #[serde(default)]
+    pub webhook_token: String,
+}
+
+impl Default for SchedulerSettings {
+    fn default() -> Self {
+        Self {
+            history_max_runs: default_history_max_runs(),
+            history_max_days: default_history_max_days(),
+            max_concurrent_runs: default_max_concurrent_runs(),
+            webhook_port: 0,
+            webhook_token: String::new(),
+        }
+    }
+}
+
+fn default_history_max_runs() -> usize {
+    100
+}
+
+fn default_history_max_days() -> u32 {
+    30
+}
+
+fn default_max_concurrent_runs() -> usize {
+    2
+}
+
+/// Path to settings: ~/.config/aither-flow/scheduler_settings.json
+fn settings_path() -> PathBuf {
+    config::config_dir().join("scheduler_settings.json")
+}
+
+pub(crate) fn load_settings() -> SchedulerSettings {
+    let path = settings_path();
+    if !path.exists() {
+        return SchedulerSettings::default();
+    }
+    read_json(&path).unwrap_or_else(|e| {
+        eprintln!("[scheduler] Failed to read settings: {e}");
+        SchedulerSettings::default()
+    })
+}
+
+#[tauri::command]
+pub async fn scheduler_load_settings() -> Result<SchedulerSettings, String> {
+    tokio::task::spawn_blocking(load_settings)
+        .await
+        .map_err(|e| format!("Task failed: {e}"))
+}
+
+/// Save settings and restart the webhook server if its port or token changed.
+/// Returns them as stored (with a generated webhook token if one was needed).
+#[tauri::command]
+pub async fn scheduler_save_settings(
+    mut settings: SchedulerSettings,
+) -> Result<SchedulerSettings, String> {
+    let settings = tokio::task::spawn_blocking(move || {
+        if settings.webhook_port != 0 && settings.webhook_token.trim().is_empty() {
+            settings.webhook_token = uuid::Uuid::new_v4().to_string();
+        }
+        write_json(&settings_path(), &settings)?;
+        Ok::<_, String>(settings)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {e}"))??;
+    // The settings are saved either way; a port that can't be bound is only logged
+    if let Err(e) = super::triggers::apply_webhook_settings(&settings).await {
+        eprintln!("[scheduler] {e}");
+    }
+    Ok(settings)
+}
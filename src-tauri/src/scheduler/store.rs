@@ -0,0 +1,323 @@
+//! Task storage: one JSON file per task in ~/.config/aither-flow/scheduler/tasks/.
+//!
+//! Every change is a read-modify-write of a single task file under its lock
+//! (`file_ops::lock_file`), so a run updating its status and an edit from the
+//! UI can't overwrite each other. The old single `scheduled_tasks.json` is
+//! split into task files on first load.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{ScheduledTask, TaskRunStatus};
+use crate::config;
+use crate::file_ops::{atomic_write, lock_file, read_json};
+
+/// Directory with task files: ~/.config/aither-flow/scheduler/tasks/
+fn tasks_dir() -> PathBuf {
+    config::config_dir().join("scheduler").join("tasks")
+}
+
+/// Pre-split store, migrated by `migrate_legacy`.
+fn legacy_path() -> PathBuf {
+    config::config_dir().join("scheduled_tasks.json")
+}
+
+fn task_path(dir: &Path, task_id: &str) -> Result<PathBuf, String> {
+    if task_id.is_empty() || task_id.contains(['/', '\\', '\0']) || task_id.starts_with('.') {
+        return Err(format!("Invalid task id: '{task_id}'"));
+    }
+    Ok(dir.join(format!("{task_id}.json")))
+}
+
+fn write_task(path: &Path, task: &ScheduledTask) -> Result<(), String> {
+    let data =
+        serde_json::to_string_pretty(task).map_err(|e| format!("Failed to serialize task: {e}"))?;
+    atomic_write(path, data.as_bytes()).map_err(|e| format!("Failed to write task: {e}"))
+}
+
+/// Split `legacy` into task files in `dir`, then rename it to `*.migrated`.
+/// Task files that already exist win over the legacy copy.
+fn migrate_from(legacy: &Path, dir: &Path) -> Result<usize, String> {
+    let _lock = lock_file(legacy)?;
+    // Another thread may have migrated while we waited for the lock
+    if !legacy.exists() {
+        return Ok(0);
+    }
+    let tasks: Vec<ScheduledTask> = read_json(legacy)?;
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create tasks dir: {e}"))?;
+    let mut migrated = 0;
+    for task in &tasks {
+        let path = match task_path(dir, &task.id) {
+            Ok(path) => path,
+            Err(e) => {
+                eprintln!(
+                    "[scheduler] Skipping task '{}' in migration: {e}",
+                    task.name
+                );
+                continue;
+            }
+        };
+        if !path.exists() {
+            write_task(&path, task)?;
+            migrated += 1;
+        }
+    }
+    fs::rename(legacy, legacy.with_extension("json.migrated"))
+        .map_err(|e| format!("Failed to retire {}: {e}", legacy.display()))?;
+    Ok(migrated)
+}
+
+fn migrate_legacy() {
+    let legacy = legacy_path();
+    if !legacy.exists() {
+        return;
+    }
+    match migrate_from(&legacy, &tasks_dir()) {
+        Ok(0) => {}
+        Ok(n) => eprintln!("[scheduler] Moved {n} task(s) to per-task files"),
+        Err(e) => eprintln!("[scheduler] Task store migration failed: {e}"),
+    }
+}
+
+fn load_from(dir: &Path) -> Vec<ScheduledTask> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
+        Err(e) => {
+            eprintln!("[scheduler] Failed to read tasks dir: {e}");
+            return Vec::new();
+        }
+    };
+    let mut tasks: Vec<ScheduledTask> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|p| match read_json(&p) {
+            Ok(task) => Some(task),
+            Err(e) => {
+                eprintln!("[scheduler] Skipping task file {}: {e}", p.display());
+                None
+            }
+        })
+        .collect();
+    tasks.sort_by(|a, b| {
+        a.created_at
+            .cmp(&b.created_at)
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    tasks
+}
+
+fn load_one(dir: &Path, task_id: &str) -> Result<Option<ScheduledTask>, String> {
+    let path = task_path(dir, task_id)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    read_json(&path).map(Some)
+}
+
+fn update_in<T>(
+    dir: &Path,
+    task_id: &str,
+    f: impl FnOnce(&mut ScheduledTask) -> T,
+) -> Result<Option<T>, String> {
+    let path = task_path(dir, task_id)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let _lock = lock_file(&path)?;
+    // Deleted while we waited for the lock
+    if !path.exists() {
+        return Ok(None);
+    }
+    let mut task: ScheduledTask = read_json(&path)?;
+    let before = serde_json::to_value(&task).ok();
+    let result = f(&mut task);
+    if serde_json::to_value(&task).ok() != before {
+        write_task(&path, &task)?;
+    }
+    Ok(Some(result))
+}
+
+fn insert_in(dir: &Path, task: &ScheduledTask) -> Result<(), String> {
+    let path = task_path(dir, &task.id)?;
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create tasks dir: {e}"))?;
+    let _lock = lock_file(&path)?;
+    if path.exists() {
+        return Err(format!("Task already exists: {}", task.id));
+    }
+    write_task(&path, task)
+}
+
+fn delete_in(dir: &Path, task_id: &str) -> Result<bool, String> {
+    let path = task_path(dir, task_id)?;
+    if !path.exists() {
+        return Ok(false);
+    }
+    let lock = lock_file(&path)?;
+    let existed = match fs::remove_file(&path) {
+        Ok(()) => true,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => false,
+        Err(e) => return Err(format!("Failed to delete task: {e}")),
+    };
+    drop(lock);
+    if let Err(e) = fs::remove_file(path.with_extension("json.lock")) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            eprintln!("[scheduler] Failed to remove task lock: {e}");
+        }
+    }
+    Ok(existed)
+}
+
+/// All tasks, oldest first. Unreadable task files are skipped.
+pub fn load_tasks() -> Vec<ScheduledTask> {
+    migrate_legacy();
+    load_from(&tasks_dir())
+}
+
+/// One task, or None if it doesn't exist.
+pub fn load_task(task_id: &str) -> Result<Option<ScheduledTask>, String> {
+    migrate_legacy();
+    load_one(&tasks_dir(), task_id)
+}
+
+/// Change a task under its lock and return what `f` returned; None if the
+/// task doesn't exist. The file is rewritten only if `f` changed the task,
+/// so `f` can also check-and-set (e.g. claim a run only if none is in flight).
+pub(crate) fn update_task<T>(
+    task_id: &str,
+    f: impl FnOnce(&mut ScheduledTask) -> T,
+) -> Result<Option<T>, String> {
+    migrate_legacy();
+    update_in(&tasks_dir(), task_id, f)
+}
+
+/// Store a new task. Fails if a task with its id exists.
+pub(crate) fn insert_task(task: &ScheduledTask) -> Result<(), String> {
+    migrate_legacy();
+    insert_in(&tasks_dir(), task)
+}
+
+/// Remove a task; false if it didn't exist.
+pub(crate) fn delete_task(task_id: &str) -> Result<bool, String> {
+    migrate_legacy();
+    delete_in(&tasks_dir(), task_id)
+}
+
+/// Set the status of a task. A missing task is not an error: it was deleted
+/// while the run was in flight.
+pub(crate) fn set_status(task_id: &str, status: Option<TaskRunStatus>) -> Result<(), String> {
+    update_task(task_id, |t| t.last_status = status).map(|_| ())
+}
+
+/// Outcome of `claim_run`.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Claim {
+    /// Now queued; `previous` is the status to restore if the run can't start.
+    Claimed {
+        previous: Option<TaskRunStatus>,
+    },
+    Busy,
+    NotFound,
+}
+
+/// Mark a task as queued unless a run is already in flight or queued.
+/// Checked and set under the task's lock, so two claims can't both win.
+pub(crate) fn claim_run(task_id: &str) -> Result<Claim, String> {
+    let claim = update_task(task_id, claim_loaded)?;
+    Ok(claim.unwrap_or(Claim::NotFound))
+}
+
+/// `claim_run` for a task already loaded under its lock.
+pub(crate) fn claim_loaded(task: &mut ScheduledTask) -> Claim {
+    if task.last_status.as_ref().is_some_and(TaskRunStatus::is_busy) {
+        return Claim::Busy;
+    }
+    let previous = task.last_status.replace(TaskRunStatus::Queued);
+    Claim::Claimed { previous }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::{test_task, TaskSchedule};
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("sched-store-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn task(id: &str, created_at: &str) -> ScheduledTask {
+        let mut task = test_task(id, TaskSchedule::Manual);
+        task.created_at = created_at.into();
+        task
+    }
+
+    #[test]
+    fn migrates_legacy_file_once() {
+        let root = temp_dir();
+        let legacy = root.join("scheduled_tasks.json");
+        let dir = root.join("tasks");
+        let tasks = vec![
+            task("b", "2026-01-02T00:00:00+00:00"),
+            task("a", "2026-01-01T00:00:00+00:00"),
+            task("../evil", "2026-01-03T00:00:00+00:00"),
+        ];
+        fs::write(&legacy, serde_json::to_string(&tasks).unwrap()).unwrap();
+
+        assert_eq!(migrate_from(&legacy, &dir).unwrap(), 2);
+        assert!(!legacy.exists());
+        assert!(root.join("scheduled_tasks.json.migrated").exists());
+        let ids: Vec<_> = load_from(&dir).into_iter().map(|t| t.id).collect();
+        assert_eq!(ids, ["a", "b"]);
+        assert_eq!(migrate_from(&legacy, &dir).unwrap(), 0);
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn update_only_touches_one_task() {
+        let dir = temp_dir();
+        insert_in(&dir, &task("a", "1")).unwrap();
+        insert_in(&dir, &task("b", "2")).unwrap();
+        assert!(insert_in(&dir, &task("a", "3")).is_err());
+
+        let edited = update_in(&dir, "a", |t| t.name = "renamed".into()).unwrap();
+        assert_eq!(edited, Some(()));
+        let status = update_in(&dir, "b", |t| t.last_status = Some(TaskRunStatus::Running));
+        assert_eq!(status.unwrap(), Some(()));
+        assert_eq!(update_in(&dir, "missing", |_| ()).unwrap(), None);
+
+        let tasks = load_from(&dir);
+        assert_eq!(tasks[0].name, "renamed");
+        assert_eq!(tasks[0].last_status, None);
+        assert_eq!(tasks[1].last_status, Some(TaskRunStatus::Running));
+
+        assert!(delete_in(&dir, "a").unwrap());
+        assert!(!delete_in(&dir, "a").unwrap());
+        assert!(load_one(&dir, "a").unwrap().is_none());
+        assert!(load_one(&dir, "../b").is_err());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn concurrent_updates_are_not_lost() {
+        let dir = temp_dir();
+        insert_in(&dir, &task("a", "1")).unwrap();
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let dir = dir.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..10 {
+                        update_in(&dir, "a", |t| t.max_retries += 1).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for h in handles {
+            h.join().unwrap();
+        }
+        assert_eq!(load_one(&dir, "a").unwrap().unwrap().max_retries, 80);
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,715 @@
+//! Event triggers for scheduled tasks: file changes in the project, new
+//! commits on a git branch, and an authenticated local webhook.
+//!
+//! A trigger queues a run like the scheduler loop does, with a description of
+//! the event appended to the task prompt.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path as FsPath, PathBuf};
+use std::process::Command;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use axum::extract::Path;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use glob::{MatchOptions, Pattern};
+use notify_debouncer_mini::{DebouncedEvent, DebouncedEventKind};
+use tokio::sync::mpsc;
+
+use super::chain::FOLLOW_UP_KIND;
+use super::settings::SchedulerSettings;
+use super::{runner, store, ScheduledTask, TaskRunStatus, TaskSchedule};
+use crate::file_watcher::{self, Watcher};
+
+/// Quiet period before a burst of file changes fires a task.
+const FILE_DEBOUNCE: Duration = Duration::from_secs(2);
+/// Files listed in the prompt; the rest are summarized as a count.
+const MAX_LISTED_FILES: usize = 100;
+/// Commits listed in the prompt for a `git_commit` trigger.
+const MAX_LISTED_COMMITS: usize = 50;
+/// Webhook request bodies passed to the prompt are cut to this size.
+const MAX_PAYLOAD_BYTES: usize = 64 * 1024;
+
+const GLOB_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// What fired a triggered run.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct TriggerContext {
+    /// Trigger kind, as in `TaskSchedule::trigger_kind`.
+    pub kind: &'static str,
+    /// One-line description, e.g. "Branch main moved to 1a2b3c4".
+    pub summary: String,
+    /// Project-relative files that changed (file trigger) or that the new commits touch (git).
+    pub changed_files: Vec<String>,
+    /// Raw details: commit list for git, request body for webhooks.
+    pub payload: Option<String>,
+}
+
+impl TriggerContext {
+    /// The task prompt with the trigger details appended.
+    pub fn apply_to(&self, prompt: &str) -> String {
+        let mut out = format!("{prompt}\n\n---\nTrigger: {}\n", self.summary);
+        if !self.changed_files.is_empty() {
+            out.push_str("\nChanged files:\n");
+            for file in self.changed_files.iter().take(MAX_LISTED_FILES) {
+                out.push_str(&format!("- {file}\n"));
+            }
+            if self.changed_files.len() > MAX_LISTED_FILES {
+                out.push_str(&format!(
+                    "- ... and {} more\n",
+                    self.changed_files.len() - MAX_LISTED_FILES
+                ));
+            }
+        }
+        if let Some(payload) = self.payload.as_deref().filter(|p| !p.trim().is_empty()) {
+            out.push_str(&format!("\nDetails:\n{}\n", payload.trim_end()));
+        }
+        out
+    }
+}
+
+/// A trigger fired for a task (sent from watcher threads to the async side).
+pub(crate) struct TriggerEvent {
+    pub task_id: String,
+    pub context: TriggerContext,
+}
+
+/// Why a trigger did not start a run.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Rejected {
+    /// No such task, or it no longer uses this trigger.
+    NotFound,
+    Disabled,
+    /// A run is in flight or queued; the event is dropped.
+    Busy,
+    Failed(String),
+}
+
+/// Check session-independent trigger settings before saving a task.
+pub(crate) fn validate_trigger(schedule: &TaskSchedule) -> Result<(), String> {
+    match schedule {
+        TaskSchedule::FileChange { patterns } => {
+            if patterns.iter().all(|p| p.trim().is_empty()) {
+                return Err("Add at least one file pattern".into());
+            }
+            for p in patterns {
+                let p = p.trim();
+                if p.starts_with('/') || p.split('/').any(|part| part == "..") {
+                    return Err(format!("Pattern must stay inside the project: '{p}'"));
+                }
+                Pattern::new(p).map_err(|e| format!("Invalid file pattern '{p}': {e}"))?;
+            }
+            Ok(())
+        }
+        TaskSchedule::GitCommit { branch } => {
+            let branch = branch.trim();
+            if branch.is_empty() {
+                return Err("Branch name cannot be empty".into());
+            }
+            if branch.starts_with('-') || branch.contains(char::is_whitespace) {
+                return Err(format!("Invalid branch name: '{branch}'"));
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Blank patterns dropped, names trimmed.
+pub(crate) fn normalize_trigger(schedule: &mut TaskSchedule) {
+    match schedule {
+        TaskSchedule::FileChange { patterns } => {
+            *patterns = patterns
+                .iter()
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty())
+                .collect();
+        }
+        TaskSchedule::GitCommit { branch } => *branch = branch.trim().to_string(),
+        _ => {}
+    }
+}
+
+/// Mark a task as queued for a trigger of `kind`, so further events are dropped
+/// until its run ends. Follow-ups may start a task of any schedule.
+fn claim<'a>(
+    tasks: &'a mut [ScheduledTask],
+    task_id: &str,
+    kind: &str,
+) -> Result<&'a mut ScheduledTask, Rejected> {
+    let task = tasks
+        .iter_mut()
+        .find(|t| {
+            t.id == task_id && (kind == FOLLOW_UP_KIND || t.schedule.trigger_kind() == Some(kind))
+        })
+        .ok_or(Rejected::NotFound)?;
+    if !task.enabled {
+        return Err(Rejected::Disabled);
+    }
+    if task
+        .last_status
+        .as_ref()
+        .is_some_and(TaskRunStatus::is_busy)
+    {
+        return Err(Rejected::Busy);
+    }
+    task.last_status = Some(TaskRunStatus::Queued);
+    Ok(task)
+}
+
+/// Queue a run of `task_id` for a trigger event. `update` is applied to the
+/// task in the same write that claims it.
+pub(crate) async fn fire(
+    task_id: &str,
+    context: TriggerContext,
+    update: impl FnOnce(&mut ScheduledTask) + Send + 'static,
+) -> Result<(), Rejected> {
+    let id = task_id.to_string();
+    let kind = context.kind;
+    let claimed = tokio::task::spawn_blocking(move || {
+        // Checked and set under the task's lock
+        store::update_task(&id, |task| {
+            let task = claim(std::slice::from_mut(task), &id, kind)?;
+            update(task);
+            Ok(())
+        })
+        .map_err(Rejected::Failed)?
+        .unwrap_or(Err(Rejected::NotFound))
+    })
+    .await
+    .map_err(|e| Rejected::Failed(format!("Task failed: {e}")))?;
+    claimed?;
+
+    eprintln!(
+        "[scheduler] Trigger for task {task_id}: {}",
+        context.summary
+    );
+    if let Err(e) = runner::run_triggered(task_id, context) {
+        // Undo the claim so the next event can try again
+        let id = task_id.to_string();
+        match tokio::task::spawn_blocking(move || store::set_status(&id, None)).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => eprintln!("[scheduler] Failed to reset task status: {e}"),
+            Err(e) => eprintln!("[scheduler] Status reset task panicked: {e}"),
+        }
+        return Err(Rejected::Failed(e));
+    }
+    Ok(())
+}
+
+/// Fire tasks for events coming from file watchers.
+pub(crate) async fn handle_events(mut rx: mpsc::UnboundedReceiver<TriggerEvent>) {
+    while let Some(event) = rx.recv().await {
+        // Events for busy or disabled tasks are dropped
+        if let Err(Rejected::Failed(e)) = fire(&event.task_id, event.context, |_| {}).await {
+            eprintln!("[scheduler] Trigger for task {} failed: {e}", event.task_id);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// File changes
+// ---------------------------------------------------------------------------
+
+/// Project-relative paths of `events` that match any of `patterns`.
+/// Anything inside `.git/` is ignored.
+fn matching_files(root: &FsPath, patterns: &[Pattern], events: &[DebouncedEvent]) -> Vec<String> {
+    let mut files: Vec<String> = events
+        .iter()
+        .filter(|e| e.kind == DebouncedEventKind::Any)
+        .filter_map(|e| e.path.strip_prefix(root).ok())
+        .filter(|rel| !rel.starts_with(".git"))
+        .filter(|rel| {
+            patterns
+                .iter()
+                .any(|p| p.matches_path_with(rel, GLOB_OPTIONS))
+        })
+        .map(|rel| rel.to_string_lossy().into_owned())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    files.sort();
+    files
+}
+
+struct FileWatch {
+    /// Project path and patterns the watcher was built for.
+    key: (String, Vec<String>),
+    _watcher: Watcher,
+}
+
+fn start_file_watch(
+    task_id: String,
+    project_path: String,
+    patterns: Vec<String>,
+    tx: mpsc::UnboundedSender<TriggerEvent>,
+) -> Result<FileWatch, String> {
+    let root = PathBuf::from(&project_path);
+    crate::files::validate_path_safe(&root)?;
+    if !root.is_dir() {
+        return Err(format!("Not a directory: {project_path}"));
+    }
+    // Events carry canonical paths on some platforms
+    let root = root.canonicalize().unwrap_or(root);
+    let compiled = patterns
+        .iter()
+        .map(|p| Pattern::new(p).map_err(|e| format!("Invalid file pattern '{p}': {e}")))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let watch_root = root.clone();
+    let mut watcher = file_watcher::new_watcher(FILE_DEBOUNCE, "scheduler", move |events| {
+        let files = matching_files(&watch_root, &compiled, events);
+        if files.is_empty() {
+            return;
+        }
+        let summary = match files.as_slice() {
+            [one] => format!("File changed: {one}"),
+            many => format!("{} files changed", many.len()),
+        };
+        let event = TriggerEvent {
+            task_id: task_id.clone(),
+            context: TriggerContext {
+                kind: "file_change",
+                summary,
+                changed_files: files,
+                payload: None,
+            },
+        };
+        if tx.send(event).is_err() {
+            eprintln!("[scheduler] Trigger channel closed");
+        }
+    })?;
+    file_watcher::watch_recursive(&mut watcher, &root)?;
+
+    Ok(FileWatch {
+        key: (project_path, patterns),
+        _watcher: watcher,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Git commits
+// ---------------------------------------------------------------------------
+
+fn git(dir: &FsPath, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git {} failed: {}", args[0], stderr.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Commit `branch` points to in the repo at `project_path`.
+fn resolve_commit(project_path: &str, branch: &str) -> Result<String, String> {
+    let dir = FsPath::new(project_path);
+    crate::files::validate_path_safe(dir)?;
+    git(
+        dir,
+        &["rev-parse", "--verify", &format!("{branch}^{{commit}}")],
+    )
+    .map_err(|_| format!("Branch '{branch}' not found in {project_path}"))
+}
+
+/// Describe the commits between `old` and `new`. A rewritten branch (force
+/// push) still fires; the log then shows the new tip only.
+fn commit_context(project_path: &str, branch: &str, old: &str, new: &str) -> TriggerContext {
+    let dir = FsPath::new(project_path);
+    let range = format!("{old}..{new}");
+    let limit = format!("-{MAX_LISTED_COMMITS}");
+    let log = git(dir, &["log", "--oneline", "--no-decorate", &limit, &range])
+        .or_else(|_| git(dir, &["log", "--oneline", "--no-decorate", "-1", new]))
+        .unwrap_or_default();
+    let changed_files = git(dir, &["diff", "--name-only", old, new])
+        .map(|out| out.lines().map(String::from).collect())
+        .unwrap_or_default();
+    let short = |sha: &str| sha.chars().take(7).collect::<String>();
+    TriggerContext {
+        kind: "git_commit",
+        summary: format!(
+            "Branch {branch} moved from {} to {}",
+            short(old),
+            short(new)
+        ),
+        changed_files,
+        payload: Some(log).filter(|l| !l.is_empty()),
+    }
+}
+
+/// What a git poll found for a task.
+#[derive(Debug, PartialEq)]
+enum GitPoll {
+    /// First look at the branch: remember the commit, don't fire.
+    Baseline(String),
+    Unchanged,
+    Moved {
+        old: String,
+        new: String,
+    },
+}
+
+fn compare_commit(last_seen: Option<&str>, current: String) -> GitPoll {
+    match last_seen {
+        None => GitPoll::Baseline(current),
+        Some(old) if old == current => GitPoll::Unchanged,
+        Some(old) => GitPoll::Moved {
+            old: old.to_string(),
+            new: current,
+        },
+    }
+}
+
+async fn poll_git_task(task: &ScheduledTask, branch: &str) -> Result<(), String> {
+    let (path, br) = (task.project_path.clone(), branch.to_string());
+    let current = tokio::task::spawn_blocking(move || resolve_commit(&path, &br))
+        .await
+        .map_err(|e| format!("Task failed: {e}"))??;
+
+    match compare_commit(task.last_seen_commit.as_deref(), current) {
+        GitPoll::Unchanged => Ok(()),
+        GitPoll::Baseline(sha) => {
+            let id = task.id.clone();
+            tokio::task::spawn_blocking(move || {
+                store::update_task(&id, |t| t.last_seen_commit = Some(sha)).map(|_| ())
+            })
+            .await
+            .map_err(|e| format!("Task failed: {e}"))?
+        }
+        GitPoll::Moved { old, new } => {
+            let (path, br, new_sha) = (task.project_path.clone(), branch.to_string(), new.clone());
+            let context =
+                tokio::task::spawn_blocking(move || commit_context(&path, &br, &old, &new_sha))
+                    .await
+                    .map_err(|e| format!("Task failed: {e}"))?;
+            match fire(&task.id, context, move |t| t.last_seen_commit = Some(new)).await {
+                // Busy: keep the old commit so the change fires after the current run
+                Ok(())
+                | Err(Rejected::Busy)
+                | Err(Rejected::Disabled)
+                | Err(Rejected::NotFound) => Ok(()),
+                Err(Rejected::Failed(e)) => Err(e),
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Trigger set, reconciled with the task list on every scheduler tick
+// ---------------------------------------------------------------------------
+
+pub(crate) struct Triggers {
+    tx: mpsc::UnboundedSender<TriggerEvent>,
+    files: HashMap<String, FileWatch>,
+    /// Tasks whose last setup or poll failed — logged once until they recover.
+    failing: HashSet<String>,
+}
+
+impl Triggers {
+    pub fn new(tx: mpsc::UnboundedSender<TriggerEvent>) -> Self {
+        Self {
+            tx,
+            files: HashMap::new(),
+            failing: HashSet::new(),
+        }
+    }
+
+    fn report(&mut self, task: &ScheduledTask, result: Result<(), String>) {
+        match result {
+            Ok(()) => {
+                self.failing.remove(&task.id);
+            }
+            Err(e) => {
+                if self.failing.insert(task.id.clone()) {
+                    eprintln!("[scheduler] Trigger of task '{}': {e}", task.name);
+                }
+            }
+        }
+    }
+
+    /// Start file watchers for new or edited tasks, stop those of removed or
+    /// disabled ones, and poll git branches.
+    pub async fn tick(&mut self, tasks: &[ScheduledTask]) {
+        let mut wanted = HashSet::new();
+        for task in tasks.iter().filter(|t| t.enabled) {
+            match &task.schedule {
+                TaskSchedule::FileChange { patterns } => {
+                    wanted.insert(task.id.clone());
+                    let key = (task.project_path.clone(), patterns.clone());
+                    if self.files.get(&task.id).is_some_and(|w| w.key == key) {
+                        continue;
+                    }
+                    self.files.remove(&task.id);
+                    let (id, tx) = (task.id.clone(), self.tx.clone());
+                    let (path, pats) = key;
+                    let result =
+                        tokio::task::spawn_blocking(move || start_file_watch(id, path, pats, tx))
+                            .await
+                            .map_err(|e| format!("Task failed: {e}"))
+                            .and_then(|r| r);
+                    let result = result.map(|watch| {
+                        self.files.insert(task.id.clone(), watch);
+                    });
+                    self.report(task, result);
+                }
+                TaskSchedule::GitCommit { branch } => {
+                    if task
+                        .last_status
+                        .as_ref()
+                        .is_some_and(TaskRunStatus::is_busy)
+                    {
+                        continue;
+                    }
+                    let result = poll_git_task(task, branch).await;
+                    self.report(task, result);
+                }
+                _ => {}
+            }
+        }
+        self.files.retain(|id, _| wanted.contains(id));
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Webhook
+// ---------------------------------------------------------------------------
+
+/// Cut a request body to `MAX_PAYLOAD_BYTES` on a char boundary.
+fn truncate_payload(mut body: String) -> String {
+    if body.len() > MAX_PAYLOAD_BYTES {
+        let mut end = MAX_PAYLOAD_BYTES;
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        body.truncate(end);
+        body.push_str("\n[truncated]");
+    }
+    body
+}
+
+async fn handle_hook(
+    Path(task_id): Path<String>,
+    headers: HeaderMap,
+    token: String,
+    body: String,
+) -> Response {
+    if let Some(resp) = crate::mcp_transport::check_auth(&headers, &token) {
+        return resp;
+    }
+    let context = TriggerContext {
+        kind: "webhook",
+        summary: "Webhook request".into(),
+        changed_files: Vec::new(),
+        payload: Some(truncate_payload(body)),
+    };
+    match fire(&task_id, context, |_| {}).await {
+        Ok(()) => (StatusCode::ACCEPTED, "Run queued").into_response(),
+        Err(Rejected::NotFound) => {
+            (StatusCode::NOT_FOUND, "No webhook task with this id").into_response()
+        }
+        Err(Rejected::Disabled) => (StatusCode::CONFLICT, "Task is disabled").into_response(),
+        Err(Rejected::Busy) => (StatusCode::CONFLICT, "Task is already running").into_response(),
+        Err(Rejected::Failed(e)) => {
+            eprintln!("[scheduler] Webhook for task {task_id} failed: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, "Failed to queue run").into_response()
+        }
+    }
+}
+
+/// The running webhook server and the settings it was started with.
+struct WebhookServer {
+    port: u16,
+    token: String,
+    task: tokio::task::JoinHandle<()>,
+}
+
+static WEBHOOK_SERVER: LazyLock<tokio::sync::Mutex<Option<WebhookServer>>> =
+    LazyLock::new(|| tokio::sync::Mutex::new(None));
+
+/// Start, restart or stop the webhook server to match `settings`.
+pub(crate) async fn apply_webhook_settings(settings: &SchedulerSettings) -> Result<(), String> {
+    let mut server = WEBHOOK_SERVER.lock().await;
+    if let Some(running) = server.as_ref() {
+        if running.port == settings.webhook_port && running.token == settings.webhook_token {
+            return Ok(());
+        }
+    }
+    if let Some(old) = server.take() {
+        old.task.abort();
+        // Wait until the listener is dropped so the port can be bound again
+        let _ = old.task.await;
+        eprintln!("[scheduler] Webhook server on port {} stopped", old.port);
+    }
+    if settings.webhook_port != 0 {
+        *server = Some(start_webhook_server(settings).await?);
+    }
+    Ok(())
+}
+
+/// Serve `POST /hooks/{task_id}` on 127.0.0.1 at the configured port.
+/// Requests must carry `Authorization: Bearer <webhook_token>`.
+async fn start_webhook_server(settings: &SchedulerSettings) -> Result<WebhookServer, String> {
+    let port = settings.webhook_port;
+    if settings.webhook_token.is_empty() {
+        return Err("Webhook token is not set".into());
+    }
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", port))
+        .await
+        .map_err(|e| format!("Failed to bind webhook server on port {port}: {e}"))?;
+
+    let token = settings.webhook_token.clone();
+    let app = Router::new().route(
+        "/hooks/:task_id",
+        post(
+            move |path: Path<String>, headers: HeaderMap, body: String| {
+                handle_hook(path, headers, token.clone(), body)
+            },
+        ),
+    );
+
+    let task = tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, app).await {
+            eprintln!("[scheduler] Webhook server error: {e}");
+        }
+    });
+    eprintln!("[scheduler] Webhook server listening on 127.0.0.1:{port}");
+    Ok(WebhookServer {
+        port,
+        token: settings.webhook_token.clone(),
+        task,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::test_task;
+
+    fn task(id: &str, schedule: TaskSchedule) -> ScheduledTask {
+        let mut task = test_task(id, schedule);
+        task.prompt = "Review".into();
+        task
+    }
+
+    fn event(path: &str) -> DebouncedEvent {
+        DebouncedEvent {
+            path: PathBuf::from(path),
+            kind: DebouncedEventKind::Any,
+        }
+    }
+
+    #[test]
+    fn file_patterns_match_relative_paths() {
+        let patterns = vec![
+            Pattern::new("src/**/*.rs").unwrap(),
+            Pattern::new("*.md").unwrap(),
+        ];
+        let events = [
+            event("/p/src/a.rs"),
+            event("/p/src/deep/b.rs"),
+            event("/p/src/a.rs"),
+            event("/p/README.md"),
+            event("/p/docs/guide.md"),
+            event("/p/.git/index"),
+            event("/elsewhere/src/c.rs"),
+        ];
+        let files = matching_files(FsPath::new("/p"), &patterns, &events);
+        assert_eq!(files, vec!["README.md", "src/a.rs", "src/deep/b.rs"]);
+    }
+
+    #[test]
+    fn validate_rejects_bad_triggers() {
+        let files = |p: &str| {
+            task(
+                "t",
+                TaskSchedule::FileChange {
+                    patterns: vec![p.into()],
+                },
+            )
+        };
+        assert!(validate_trigger(&files("src/**/*.ts").schedule).is_ok());
+        assert!(validate_trigger(&files("  ").schedule).is_err());
+        assert!(validate_trigger(&files("/etc/*").schedule).is_err());
+        assert!(validate_trigger(&files("../other/*").schedule).is_err());
+        assert!(validate_trigger(&files("[").schedule).is_err());
+
+        let branch = |b: &str| task("t", TaskSchedule::GitCommit { branch: b.into() });
+        assert!(validate_trigger(&branch("main").schedule).is_ok());
+        assert!(validate_trigger(&branch("origin/main").schedule).is_ok());
+        assert!(validate_trigger(&branch("").schedule).is_err());
+        assert!(validate_trigger(&branch("--upload-pack=x").schedule).is_err());
+    }
+
+    #[test]
+    fn claim_checks_kind_enabled_and_busy() {
+        let mut tasks = vec![
+            task("hook", TaskSchedule::Webhook),
+            task("daily", TaskSchedule::Daily { hour: 9, minute: 0 }),
+        ];
+        assert_eq!(
+            claim(&mut tasks, "daily", "webhook").err(),
+            Some(Rejected::NotFound)
+        );
+        assert_eq!(
+            claim(&mut tasks, "hook", "git_commit").err(),
+            Some(Rejected::NotFound)
+        );
+
+        assert!(claim(&mut tasks, "hook", "webhook").is_ok());
+        assert_eq!(tasks[0].last_status, Some(TaskRunStatus::Queued));
+        // A second event while queued is dropped
+        assert_eq!(
+            claim(&mut tasks, "hook", "webhook").err(),
+            Some(Rejected::Busy)
+        );
+
+        tasks[0].last_status = None;
+        tasks[0].enabled = false;
+        assert_eq!(
+            claim(&mut tasks, "hook", "webhook").err(),
+            Some(Rejected::Disabled)
+        );
+    }
+
+    #[test]
+    fn git_poll_sets_baseline_then_detects_moves() {
+        assert_eq!(
+            compare_commit(None, "a".into()),
+            GitPoll::Baseline("a".into())
+        );
+        assert_eq!(compare_commit(Some("a"), "a".into()), GitPoll::Unchanged);
+        assert_eq!(
+            compare_commit(Some("a"), "b".into()),
+            GitPoll::Moved {
+                old: "a".into(),
+                new: "b".into()
+            }
+        );
+    }
+
+    #[test]
+    fn context_is_appended_to_prompt() {
+        let ctx = TriggerContext {
+            kind: "git_commit",
+            summary: "Branch main moved from 1111111 to 2222222".into(),
+            changed_files: vec!["src/lib.rs".into()],
+            payload: Some("2222222 Fix parser\n".into()),
+        };
+        assert_eq!(
+            ctx.apply_to("Review the new commits."),
+            "Review the new commits.\n\n---\nTrigger: Branch main moved from 1111111 to 2222222\n\
+             \nChanged files:\n- src/lib.rs\n\nDetails:\n2222222 Fix parser\n"
+        );
+        assert!(truncate_payload("é".repeat(MAX_PAYLOAD_BYTES)).ends_with("[truncated]"));
+    }
+}
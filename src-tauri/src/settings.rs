@@ -82,6 +82,14 @@ pub fn get_voice_language() -> String {
         .unwrap_or_default()
 }
 
+/// Read translation_language from settings (blocking I/O). Returns empty string if not set.
+pub fn get_translation_language() -> String {
+    let path = settings_path();
+    read_json::<AppSettings>(&path)
+        .map(|s| s.translation_language)
+        .unwrap_or_default()
+}
+
 /// Load settings from disk. API keys are loaded from system keyring;
 /// if not found there, migrates from JSON to keyring.
 #[tauri::command]
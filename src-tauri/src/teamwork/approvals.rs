@@ -0,0 +1,364 @@
+//! Human-in-the-loop approval gates: an agent asks the user a question with a
+//! fixed set of options and is parked until the user picks one (in the UI or
+//! via Telegram buttons) or the request times out. Every outcome is appended
+//! to the team's decision log.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+use super::mailbox;
+use super::validate_name;
+
+/// Default time an agent waits for an answer.
+pub(crate) const DEFAULT_TIMEOUT_SECS: u64 = 600;
+/// Upper bound on the wait, so a forgotten request can't park an agent forever.
+pub(crate) const MAX_TIMEOUT_SECS: u64 = 24 * 3600;
+const MAX_OPTIONS: usize = 10;
+/// Default number of decision log entries returned to the UI.
+const DEFAULT_LOG_LIMIT: usize = 100;
+
+/// A pending question shown to the user.
+#[derive(Serialize, Clone, Debug)]
+pub struct ApprovalRequest {
+    pub id: String,
+    pub team: String,
+    pub agent_id: String,
+    pub question: String,
+    pub options: Vec<String>,
+    pub created_at: String,
+    pub expires_at: String,
+}
+
+/// One entry of the per-team decision log.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ApprovalDecision {
+    pub id: String,
+    pub agent_id: String,
+    pub question: String,
+    pub options: Vec<String>,
+    /// Chosen option, or None if the request timed out.
+    pub answer: Option<String>,
+    /// "ui", "telegram" or "timeout".
+    pub source: String,
+    pub requested_at: String,
+    pub decided_at: String,
+}
+
+struct PendingApproval {
+    request: ApprovalRequest,
+    tx: oneshot::Sender<(String, String)>,
+}
+
+/// request id → pending approval (in memory only: a parked agent dies with the app).
+static PENDING: LazyLock<Mutex<HashMap<String, PendingApproval>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn with_pending<R>(f: impl FnOnce(&mut HashMap<String, PendingApproval>) -> R) -> R {
+    let mut guard = PENDING.lock().unwrap_or_else(|e| {
+        eprintln!("[teamwork] Approvals mutex was poisoned, recovering");
+        e.into_inner()
+    });
+    f(&mut guard)
+}
+
+/// Removes the pending entry when the waiting MCP call ends for any reason —
+/// including the agent disconnecting mid-wait, which drops the handler future.
+struct PendingGuard(String);
+
+impl Drop for PendingGuard {
+    fn drop(&mut self) {
+        with_pending(|p| p.remove(&self.0));
+    }
+}
+
+/// Path to a team's decision log: ~/.config/aither-flow/teams/{team}/approvals.jsonl
+fn log_path(team: &str) -> PathBuf {
+    mailbox::team_dir(team).join("approvals.jsonl")
+}
+
+fn log_lock_key(team: &str) -> String {
+    format!("{team}/__approvals__")
+}
+
+/// Trim options, drop empties and duplicates, and check the count.
+fn normalize_options(options: &[String]) -> Result<Vec<String>, String> {
+    let mut out: Vec<String> = Vec::new();
+    for opt in options {
+        let opt = opt.trim();
+        if !opt.is_empty() && !out.iter().any(|o| o == opt) {
+            out.push(opt.to_string());
+        }
+    }
+    if out.len() < 2 {
+        return Err("At least two distinct options are required".to_string());
+    }
+    if out.len() > MAX_OPTIONS {
+        return Err(format!("At most {MAX_OPTIONS} options are allowed"));
+    }
+    Ok(out)
+}
+
+/// Match a user's answer against the offered options (exact, then case-insensitive).
+fn resolve_answer(options: &[String], answer: &str) -> Option<String> {
+    let answer = answer.trim();
+    options
+        .iter()
+        .find(|o| o.as_str() == answer)
+        .or_else(|| options.iter().find(|o| o.eq_ignore_ascii_case(answer)))
+        .cloned()
+}
+
+fn append_decision_sync(team: &str, decision: &ApprovalDecision) -> Result<(), String> {
+    let lock = mailbox::inbox_lock(&log_lock_key(team));
+    let _guard = lock
+        .lock()
+        .map_err(|e| format!("Approvals lock poisoned: {e}"))?;
+    let path = log_path(team);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create team dir: {e}"))?;
+    }
+    let line =
+        serde_json::to_string(decision).map_err(|e| format!("Failed to serialize decision: {e}"))?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open decision log: {e}"))?;
+    writeln!(file, "{line}").map_err(|e| format!("Failed to write decision log: {e}"))
+}
+
+fn parse_decisions(data: &str) -> Vec<ApprovalDecision> {
+    data.lines()
+        .filter(|l| !l.trim().is_empty())
+        .filter_map(|l| match serde_json::from_str(l) {
+            Ok(d) => Some(d),
+            Err(e) => {
+                eprintln!("[teamwork] Skipping malformed decision log line: {e}");
+                None
+            }
+        })
+        .collect()
+}
+
+/// Newest-first decision log for a team.
+fn read_log_sync(team: &str, limit: usize) -> Result<Vec<ApprovalDecision>, String> {
+    validate_name(team, "team")?;
+    let lock = mailbox::inbox_lock(&log_lock_key(team));
+    let _guard = lock
+        .lock()
+        .map_err(|e| format!("Approvals lock poisoned: {e}"))?;
+    let path = log_path(team);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read decision log: {e}"))?;
+    let mut decisions = parse_decisions(&data);
+    decisions.reverse();
+    decisions.truncate(limit);
+    Ok(decisions)
+}
+
+fn log_decision(request: &ApprovalRequest, answer: Option<String>, source: &str) {
+    let decision = ApprovalDecision {
+        id: request.id.clone(),
+        agent_id: request.agent_id.clone(),
+        question: request.question.clone(),
+        options: request.options.clone(),
+        answer,
+        source: source.to_string(),
+        requested_at: request.created_at.clone(),
+        decided_at: chrono::Utc::now().to_rfc3339(),
+    };
+    let team = request.team.clone();
+    tokio::task::spawn_blocking(move || {
+        if let Err(e) = append_decision_sync(&team, &decision) {
+            eprintln!("[teamwork] Failed to log approval decision: {e}");
+        }
+    });
+}
+
+/// Ask the user a question and wait for one of `options` to be chosen.
+/// Returns the chosen option; fails on invalid input or timeout.
+pub(crate) async fn request_approval(
+    team: &str,
+    agent_id: &str,
+    question: &str,
+    options: &[String],
+    timeout_secs: u64,
+) -> Result<String, String> {
+    let question = question.trim();
+    if question.is_empty() {
+        return Err("Question cannot be empty".to_string());
+    }
+    let options = normalize_options(options)?;
+    let timeout_secs = timeout_secs.clamp(1, MAX_TIMEOUT_SECS);
+
+    let now = chrono::Utc::now();
+    let request = ApprovalRequest {
+        id: uuid::Uuid::new_v4().to_string(),
+        team: team.to_string(),
+        agent_id: agent_id.to_string(),
+        question: question.to_string(),
+        options,
+        created_at: now.to_rfc3339(),
+        expires_at: (now + chrono::Duration::seconds(timeout_secs as i64)).to_rfc3339(),
+    };
+
+    let (tx, rx) = oneshot::channel();
+    with_pending(|p| {
+        p.insert(
+            request.id.clone(),
+            PendingApproval {
+                request: request.clone(),
+                tx,
+            },
+        )
+    });
+    let _guard = PendingGuard(request.id.clone());
+    eprintln!(
+        "[teamwork] Approval {} requested by {agent_id} in {team}",
+        request.id
+    );
+
+    // Telegram is best-effort: the UI can always answer
+    if let Err(e) = crate::telegram::commands::send_approval_to_telegram(
+        &request.id,
+        &request.question,
+        &request.options,
+    )
+    .await
+    {
+        eprintln!("[teamwork] Approval not sent to Telegram: {e}");
+    }
+
+    match tokio::time::timeout(Duration::from_secs(timeout_secs), rx).await {
+        Ok(Ok((answer, source))) => {
+            log_decision(&request, Some(answer.clone()), &source);
+            Ok(answer)
+        }
+        Ok(Err(_)) => Err("Approval request was cancelled".to_string()),
+        Err(_) => {
+            log_decision(&request, None, "timeout");
+            Err(format!(
+                "No answer within {timeout_secs}s — treat the request as not approved"
+            ))
+        }
+    }
+}
+
+/// Deliver an answer to a pending request. `source` is "ui" or "telegram".
+pub(crate) fn answer_approval(id: &str, answer: &str, source: &str) -> Result<String, String> {
+    let pending = with_pending(|p| {
+        let entry = p.get(id).ok_or("Approval request not found or already answered")?;
+        let chosen = resolve_answer(&entry.request.options, answer)
+            .ok_or_else(|| format!("'{answer}' is not one of the offered options"))?;
+        let entry = p.remove(id).ok_or("Approval request not found or already answered")?;
+        Ok::<_, String>((entry, chosen))
+    });
+    let (entry, chosen) = pending?;
+    entry
+        .tx
+        .send((chosen.clone(), source.to_string()))
+        .map_err(|_| "The requesting agent is no longer waiting".to_string())?;
+    Ok(chosen)
+}
+
+/// Answer by option index (used by Telegram buttons, whose payload must stay short).
+pub(crate) fn answer_approval_by_index(id: &str, index: usize, source: &str) -> Result<String, String> {
+    let option = with_pending(|p| {
+        p.get(id)
+            .and_then(|entry| entry.request.options.get(index).cloned())
+    })
+    .ok_or("Approval request not found or already answered")?;
+    answer_approval(id, &option, source)
+}
+
+/// Pending approval requests, oldest first. `team` = None returns all teams.
+#[tauri::command]
+pub fn team_pending_approvals(team: Option<String>) -> Result<Vec<ApprovalRequest>, String> {
+    let mut requests: Vec<ApprovalRequest> = with_pending(|p| {
+        p.values()
+            .filter(|entry| team.as_ref().is_none_or(|t| &entry.request.team == t))
+            .map(|entry| entry.request.clone())
+            .collect()
+    });
+    requests.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+    Ok(requests)
+}
+
+#[tauri::command]
+pub fn team_answer_approval(id: String, answer: String) -> Result<String, String> {
+    answer_approval(&id, &answer, "ui")
+}
+
+/// Decision log for a team, newest first.
+#[tauri::command]
+pub async fn team_approval_log(
+    team: String,
+    limit: Option<usize>,
+) -> Result<Vec<ApprovalDecision>, String> {
+    tokio::task::spawn_blocking(move || {
+        read_log_sync(&team, limit.unwrap_or(DEFAULT_LOG_LIMIT))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opts(v: &[&str]) -> Vec<String> {
+        v.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn normalize_trims_and_dedups() {
+        let out = normalize_options(&opts(&[" yes ", "no", "yes", ""])).unwrap();
+        assert_eq!(out, opts(&["yes", "no"]));
+    }
+
+    #[test]
+    fn normalize_rejects_single_option() {
+        assert!(normalize_options(&opts(&["ok", " ok "])).is_err());
+    }
+
+    #[test]
+    fn normalize_rejects_too_many_options() {
+        let many: Vec<String> = (0..=MAX_OPTIONS).map(|i| i.to_string()).collect();
+        assert!(normalize_options(&many).is_err());
+    }
+
+    #[test]
+    fn resolve_answer_prefers_exact_match() {
+        let options = opts(&["Deploy", "deploy", "Abort"]);
+        assert_eq!(resolve_answer(&options, "deploy").as_deref(), Some("deploy"));
+        assert_eq!(resolve_answer(&options, " abort ").as_deref(), Some("Abort"));
+        assert_eq!(resolve_answer(&options, "maybe"), None);
+    }
+
+    #[test]
+    fn parse_decisions_skips_malformed_lines() {
+        let ok = ApprovalDecision {
+            id: "a".into(),
+            agent_id: "agent".into(),
+            question: "Ship?".into(),
+            options: opts(&["yes", "no"]),
+            answer: Some("yes".into()),
+            source: "ui".into(),
+            requested_at: "2026-01-01T00:00:00Z".into(),
+            decided_at: "2026-01-01T00:01:00Z".into(),
+        };
+        let data = format!("{}\nnot json\n\n", serde_json::to_string(&ok).unwrap());
+        let parsed = parse_decisions(&data);
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].answer.as_deref(), Some("yes"));
+    }
+}
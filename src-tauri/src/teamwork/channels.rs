@@ -0,0 +1,185 @@
+//! Cross-project channels: opt-in named links that let agents of different
+//! project teams message each other. Only projects listed in a channel's
+//! allow-list can send or receive on it.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::config;
+use crate::file_ops::{lock_file, read_json, write_json};
+
+use super::validate_name;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TeamChannel {
+    pub name: String,
+    /// Project paths allowed to send and receive on this channel.
+    pub projects: Vec<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl TeamChannel {
+    /// Whether a project is on this channel's allow-list.
+    pub fn allows(&self, project_path: &str) -> bool {
+        self.projects.iter().any(|p| same_project(p, project_path))
+    }
+}
+
+/// Compare project paths, ignoring a trailing slash.
+pub fn same_project(a: &str, b: &str) -> bool {
+    a.trim_end_matches('/') == b.trim_end_matches('/')
+}
+
+/// Path to channels file: ~/.config/aither-flow/team_channels.json
+fn channels_path() -> PathBuf {
+    config::config_dir().join("team_channels.json")
+}
+
+/// All channels; a missing file means none. A file that can't be read is an
+/// error, so saving over it can't wipe the channels.
+fn read_channels_sync() -> Result<Vec<TeamChannel>, String> {
+    let path = channels_path();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    read_json::<Vec<TeamChannel>>(&path)
+}
+
+fn write_channels_sync(channels: &[TeamChannel]) -> Result<(), String> {
+    write_json(&channels_path(), channels)
+}
+
+/// Read-modify-write the channels file under its lock.
+fn update_channels_sync(
+    f: impl FnOnce(&mut Vec<TeamChannel>) -> Result<(), String>,
+) -> Result<(), String> {
+    let path = channels_path();
+    let _lock = lock_file(&path)?;
+    let mut channels = read_channels_sync()?;
+    f(&mut channels)?;
+    write_channels_sync(&channels)
+}
+
+/// Enabled channels whose allow-list includes the given project.
+pub(crate) fn channels_for_project(project_path: &str) -> Result<Vec<TeamChannel>, String> {
+    Ok(read_channels_sync()?
+        .into_iter()
+        .filter(|c| c.enabled && c.allows(project_path))
+        .collect())
+}
+
+/// Resolve a channel by name for a sending project.
+/// Fails if the channel doesn't exist, is disabled, or the project isn't allowed on it.
+pub(crate) fn channel_for_sender(name: &str, project_path: &str) -> Result<TeamChannel, String> {
+    find_sendable(read_channels_sync()?, name, project_path)
+}
+
+fn find_sendable(
+    channels: Vec<TeamChannel>,
+    name: &str,
+    project_path: &str,
+) -> Result<TeamChannel, String> {
+    let channel = channels
+        .into_iter()
+        .find(|c| c.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| format!("Channel '{name}' not found"))?;
+    if !channel.enabled {
+        return Err(format!("Channel '{name}' is disabled"));
+    }
+    if !channel.allows(project_path) {
+        return Err(format!("Your project is not allowed on channel '{name}'"));
+    }
+    Ok(channel)
+}
+
+#[tauri::command]
+pub async fn team_channels_list() -> Result<Vec<TeamChannel>, String> {
+    tokio::task::spawn_blocking(read_channels_sync)
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command]
+pub async fn team_channels_save(channel: TeamChannel) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let mut channel = channel;
+        channel.name = channel.name.trim().to_string();
+        validate_name(&channel.name, "channel name")?;
+        let mut projects: Vec<String> = Vec::new();
+        for p in channel.projects {
+            if !p.trim().is_empty() && !projects.iter().any(|u| same_project(u, &p)) {
+                projects.push(p);
+            }
+        }
+        channel.projects = projects;
+        if channel.projects.len() < 2 {
+            return Err("A channel must link at least two projects".to_string());
+        }
+        update_channels_sync(|channels| {
+            if let Some(existing) = channels
+                .iter_mut()
+                .find(|c| c.name.eq_ignore_ascii_case(&channel.name))
+            {
+                *existing = channel;
+            } else {
+                channels.push(channel);
+            }
+            Ok(())
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command]
+pub async fn team_channels_delete(name: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        update_channels_sync(|channels| {
+            let before = channels.len();
+            channels.retain(|c| !c.name.eq_ignore_ascii_case(&name));
+            if channels.len() == before {
+                return Err(format!("Channel '{name}' not found"));
+            }
+            Ok(())
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel(name: &str, enabled: bool) -> TeamChannel {
+        TeamChannel {
+            name: name.into(),
+            projects: vec!["/work/api/".into(), "/work/web".into()],
+            enabled,
+        }
+    }
+
+    #[test]
+    fn allow_list_ignores_trailing_slash() {
+        let ch = channel("sync", true);
+        assert!(ch.allows("/work/api"));
+        assert!(ch.allows("/work/web/"));
+        assert!(!ch.allows("/work/api2"));
+        assert!(!ch.allows("/work"));
+    }
+
+    #[test]
+    fn sender_must_be_on_an_enabled_channel() {
+        let channels = || vec![channel("sync", true), channel("old", false)];
+        assert_eq!(find_sendable(channels(), "SYNC", "/work/api").unwrap().name, "sync");
+        let err = |name: &str, project: &str| find_sendable(channels(), name, project).unwrap_err();
+        assert!(err("sync", "/work/other").contains("not allowed"));
+        assert!(err("old", "/work/api").contains("disabled"));
+        assert!(err("missing", "/work/api").contains("not found"));
+    }
+}
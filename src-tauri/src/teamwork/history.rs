@@ -0,0 +1,695 @@
+//! Team message history: retention policy, compaction of the feed and of read
+//! inbox messages into an archive, and full-text search over feed + archive.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::config;
+use crate::file_ops::{atomic_write, read_json, write_json};
+
+use super::mailbox::{self, TeamMessage};
+use super::validate_name;
+
+/// Default number of search results returned when no limit is given.
+const DEFAULT_SEARCH_LIMIT: usize = 50;
+/// Hard cap on search results.
+const MAX_SEARCH_LIMIT: usize = 500;
+
+/// Retention policy applied to every team's message history. Archiving is
+/// opt-in: the mailbox panel shows only the feed, so archived messages are
+/// reachable only through search.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct RetentionPolicy {
+    /// Feed messages, and read inbox messages, older than this many days are
+    /// moved to the archive (0 = never; read inbox messages are then dropped,
+    /// the feed keeps a copy).
+    #[serde(default)]
+    pub archive_after_days: u32,
+    /// Once feed.jsonl grows past this many bytes, the oldest messages are archived (0 = no limit).
+    #[serde(default)]
+    pub max_feed_bytes: u64,
+    /// Archived messages older than this many days are deleted (0 = keep forever).
+    #[serde(default)]
+    pub delete_after_days: u32,
+}
+
+/// Result of a compaction pass.
+#[derive(Serialize, Default, Debug, PartialEq)]
+pub struct CompactionReport {
+    /// Messages moved from the feed and inboxes into the archive.
+    pub archived: usize,
+    /// Archived messages deleted by the retention policy.
+    pub deleted: usize,
+}
+
+/// Search filters over a team's message history.
+#[derive(Deserialize, Default, Clone)]
+pub struct MessageQuery {
+    /// Whitespace-separated terms; every term must appear in the message text (case-insensitive).
+    #[serde(default)]
+    pub query: String,
+    #[serde(default)]
+    pub from: Option<String>,
+    #[serde(default)]
+    pub to: Option<String>,
+    /// RFC 3339 timestamp — only messages at or after this moment.
+    #[serde(default)]
+    pub since: Option<String>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// Path to the retention policy: ~/.config/aither-flow/mailbox_retention.json
+fn policy_path() -> PathBuf {
+    config::config_dir().join("mailbox_retention.json")
+}
+
+/// Path to a team's archive: ~/.config/aither-flow/teams/{team}/archive.jsonl
+fn archive_path(team: &str) -> PathBuf {
+    mailbox::team_dir(team).join("archive.jsonl")
+}
+
+/// Lock key for a team's archive
+fn archive_lock_key(team: &str) -> String {
+    format!("{team}/__archive__")
+}
+
+/// Load the retention policy (defaults if missing or unreadable).
+pub(crate) fn load_policy() -> RetentionPolicy {
+    let path = policy_path();
+    if !path.exists() {
+        return RetentionPolicy::default();
+    }
+    read_json(&path).unwrap_or_else(|e| {
+        eprintln!("[teamwork] Failed to read retention policy: {e}");
+        RetentionPolicy::default()
+    })
+}
+
+fn parse_timestamp(ts: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(ts)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Serialized size of a message as a feed line (including newline).
+fn line_len(msg: &TeamMessage) -> u64 {
+    serde_json::to_string(msg).map(|s| s.len() as u64 + 1).unwrap_or(0)
+}
+
+fn serialize_lines(messages: &[TeamMessage]) -> Result<String, String> {
+    let mut out = String::new();
+    for msg in messages {
+        let line = serde_json::to_string(msg)
+            .map_err(|e| format!("Failed to serialize message: {e}"))?;
+        out.push_str(&line);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Split feed messages into (keep, archive) according to the policy.
+///
+/// Messages older than `archive_after_days` are archived; if the remainder still
+/// exceeds `max_feed_bytes`, the oldest are archived until the feed is at 3/4 of the
+/// limit, so compaction doesn't run again on the very next append.
+fn partition_feed(
+    mut messages: Vec<TeamMessage>,
+    policy: &RetentionPolicy,
+    now: DateTime<Utc>,
+) -> (Vec<TeamMessage>, Vec<TeamMessage>) {
+    messages.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+
+    let age_cutoff = (policy.archive_after_days > 0)
+        .then(|| now - chrono::Duration::days(policy.archive_after_days as i64));
+
+    let (mut archive, mut keep): (Vec<_>, Vec<_>) = messages.into_iter().partition(|m| {
+        match (age_cutoff, parse_timestamp(&m.timestamp)) {
+            (Some(cutoff), Some(ts)) => ts < cutoff,
+            _ => false,
+        }
+    });
+
+    if policy.max_feed_bytes > 0 {
+        let mut total: u64 = keep.iter().map(line_len).sum();
+        if total > policy.max_feed_bytes {
+            let target = policy.max_feed_bytes / 4 * 3;
+            let mut cut = 0;
+            while cut < keep.len() && total > target {
+                total -= line_len(&keep[cut]);
+                cut += 1;
+            }
+            archive.extend(keep.drain(..cut));
+        }
+    }
+
+    (keep, archive)
+}
+
+/// Split inbox messages into (keep, archive). Unread messages always stay.
+/// Read ones are archived once older than `archive_after_days`; with archiving
+/// off they are dropped right away.
+fn partition_inbox(
+    messages: Vec<TeamMessage>,
+    policy: &RetentionPolicy,
+    now: DateTime<Utc>,
+) -> (Vec<TeamMessage>, Vec<TeamMessage>) {
+    if policy.archive_after_days == 0 {
+        return (messages.into_iter().filter(|m| !m.read).collect(), Vec::new());
+    }
+    let cutoff = now - chrono::Duration::days(policy.archive_after_days as i64);
+    messages
+        .into_iter()
+        .partition(|m| !m.read || parse_timestamp(&m.timestamp).is_none_or(|ts| ts >= cutoff))
+}
+
+/// Drop archived messages older than `delete_after_days`. Returns (kept, deleted_count).
+fn expire_archive(
+    messages: Vec<TeamMessage>,
+    policy: &RetentionPolicy,
+    now: DateTime<Utc>,
+) -> (Vec<TeamMessage>, usize) {
+    if policy.delete_after_days == 0 {
+        return (messages, 0);
+    }
+    let cutoff = now - chrono::Duration::days(policy.delete_after_days as i64);
+    let before = messages.len();
+    let kept: Vec<TeamMessage> = messages
+        .into_iter()
+        .filter(|m| parse_timestamp(&m.timestamp).is_none_or(|ts| ts >= cutoff))
+        .collect();
+    let deleted = before - kept.len();
+    (kept, deleted)
+}
+
+/// Check whether a message passes the search filters.
+fn matches_query(
+    msg: &TeamMessage,
+    terms: &[String],
+    query: &MessageQuery,
+    since: Option<DateTime<Utc>>,
+) -> bool {
+    if let Some(ref from) = query.from {
+        if msg.from != *from {
+            return false;
+        }
+    }
+    if let Some(ref to) = query.to {
+        if msg.to != *to {
+            return false;
+        }
+    }
+    if let Some(since) = since {
+        match parse_timestamp(&msg.timestamp) {
+            Some(ts) if ts >= since => {}
+            _ => return false,
+        }
+    }
+    if terms.is_empty() {
+        return true;
+    }
+    let text = msg.text.to_lowercase();
+    terms.iter().all(|t| text.contains(t.as_str()))
+}
+
+fn read_messages_file(path: &std::path::Path, label: &str) -> Result<Vec<TeamMessage>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {label}: {e}"))?;
+    Ok(mailbox::parse_messages(&data, label))
+}
+
+/// Append messages to the archive (O(n) in the appended messages only).
+fn append_to_archive(team: &str, messages: &[TeamMessage]) -> Result<(), String> {
+    if messages.is_empty() {
+        return Ok(());
+    }
+    let path = archive_path(team);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create team dir: {e}"))?;
+    }
+    let data = serialize_lines(messages)?;
+
+    let mut opts = fs::OpenOptions::new();
+    opts.create(true).append(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        opts.mode(0o600);
+    }
+    let mut file = opts
+        .open(&path)
+        .map_err(|e| format!("Failed to open archive {}: {e}", path.display()))?;
+    file.write_all(data.as_bytes())
+        .map_err(|e| format!("Failed to write to archive: {e}"))?;
+    file.sync_all()
+        .map_err(|e| format!("Failed to sync archive: {e}"))
+}
+
+/// Rewrite a message file with the given messages (removes it when empty).
+pub(super) fn rewrite_messages_file(path: &std::path::Path, messages: &[TeamMessage]) -> Result<(), String> {
+    if messages.is_empty() {
+        if path.exists() {
+            fs::remove_file(path)
+                .map_err(|e| format!("Failed to remove {}: {e}", path.display()))?;
+        }
+        return Ok(());
+    }
+    atomic_write(path, serialize_lines(messages)?.as_bytes())
+}
+
+/// Apply the retention policy to an agent's inbox messages: old read ones go
+/// to the archive. Returns the messages that stay and how many were archived.
+/// The caller holds the inbox lock and writes the inbox afterwards, so a
+/// crash in between duplicates messages rather than losing them.
+pub(super) fn archive_read_messages(
+    team: &str,
+    messages: Vec<TeamMessage>,
+    policy: &RetentionPolicy,
+) -> Result<(Vec<TeamMessage>, usize), String> {
+    let (keep, to_archive) = partition_inbox(messages, policy, Utc::now());
+    if !to_archive.is_empty() {
+        let lock = mailbox::inbox_lock(&archive_lock_key(team));
+        let _guard = lock
+            .lock()
+            .map_err(|e| format!("Archive lock poisoned: {e}"))?;
+        append_to_archive(team, &to_archive)?;
+    }
+    Ok((keep, to_archive.len()))
+}
+
+/// Compact every inbox of a team. Returns how many messages were archived.
+fn compact_inboxes(team: &str, policy: &RetentionPolicy) -> Result<usize, String> {
+    let mut archived = 0;
+    for agent_id in mailbox::inbox_agents(team)? {
+        // Lock order: inbox, then archive
+        let lock = mailbox::inbox_lock(&mailbox::inbox_lock_key(team, &agent_id));
+        let _guard = lock
+            .lock()
+            .map_err(|e| format!("Inbox lock poisoned: {e}"))?;
+        let path = mailbox::inbox_path(team, &agent_id);
+        let messages = read_messages_file(&path, "inbox")?;
+        let before = messages.len();
+        let (keep, moved) = archive_read_messages(team, messages, policy)?;
+        if keep.len() != before {
+            rewrite_messages_file(&path, &keep)?;
+        }
+        archived += moved;
+    }
+    Ok(archived)
+}
+
+/// Apply the retention policy to one team: move old read inbox messages and
+/// old/excess feed messages into the archive, then expire old archived messages.
+pub(crate) fn compact_team_sync(
+    team: &str,
+    policy: &RetentionPolicy,
+) -> Result<CompactionReport, String> {
+    validate_name(team, "team")?;
+    let now = Utc::now();
+    let mut report = CompactionReport {
+        archived: compact_inboxes(team, policy)?,
+        ..Default::default()
+    };
+
+    // Lock order: feed, then archive
+    let feed_lock = mailbox::inbox_lock(&mailbox::feed_lock_key(team));
+    let _feed_guard = feed_lock
+        .lock()
+        .map_err(|e| format!("Feed lock poisoned: {e}"))?;
+    let archive_lock = mailbox::inbox_lock(&archive_lock_key(team));
+    let _archive_guard = archive_lock
+        .lock()
+        .map_err(|e| format!("Archive lock poisoned: {e}"))?;
+
+    let feed = mailbox::feed_path(team);
+    let messages = read_messages_file(&feed, "feed")?;
+    let (keep, to_archive) = partition_feed(messages, policy, now);
+    if !to_archive.is_empty() {
+        // Archive first: a crash between the two writes duplicates, never loses
+        append_to_archive(team, &to_archive)?;
+        rewrite_messages_file(&feed, &keep)?;
+        report.archived += to_archive.len();
+    }
+
+    if policy.delete_after_days > 0 {
+        let archive = archive_path(team);
+        let archived = read_messages_file(&archive, "archive")?;
+        let (kept, deleted) = expire_archive(archived, policy, now);
+        if deleted > 0 {
+            rewrite_messages_file(&archive, &kept)?;
+            report.deleted = deleted;
+        }
+    }
+
+    Ok(report)
+}
+
+/// Compact a team's feed if the last append pushed it past the size limit.
+/// Errors are logged — a failed compaction must not fail the message send.
+pub(super) fn compact_if_oversized(team: &str, feed_len: u64) {
+    let policy = load_policy();
+    if policy.max_feed_bytes == 0 || feed_len <= policy.max_feed_bytes {
+        return;
+    }
+    match compact_team_sync(team, &policy) {
+        Ok(r) => eprintln!("[teamwork] Compacted feed of '{team}': {} archived", r.archived),
+        Err(e) => eprintln!("[teamwork] Feed compaction failed for '{team}': {e}"),
+    }
+}
+
+/// Apply the retention policy to every team on disk (called once at startup).
+pub(crate) fn compact_all_teams() {
+    let dir = config::config_dir().join("teams");
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return;
+    };
+    let policy = load_policy();
+    for entry in entries.flatten() {
+        if !entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false) {
+            continue;
+        }
+        let team = entry.file_name().to_string_lossy().into_owned();
+        match compact_team_sync(&team, &policy) {
+            Ok(r) if r.archived > 0 || r.deleted > 0 => eprintln!(
+                "[teamwork] Compacted '{team}': {} archived, {} deleted",
+                r.archived, r.deleted
+            ),
+            Ok(_) => {}
+            Err(e) => eprintln!("[teamwork] Compaction failed for '{team}': {e}"),
+        }
+    }
+}
+
+/// Remove feed and archive messages older than `cutoff`.
+pub(super) fn clear_before_sync(team: &str, cutoff: DateTime<Utc>) -> Result<(), String> {
+    let older = |m: &TeamMessage| parse_timestamp(&m.timestamp).is_some_and(|ts| ts < cutoff);
+
+    let feed_lock = mailbox::inbox_lock(&mailbox::feed_lock_key(team));
+    let _feed_guard = feed_lock
+        .lock()
+        .map_err(|e| format!("Feed lock poisoned: {e}"))?;
+    let archive_lock = mailbox::inbox_lock(&archive_lock_key(team));
+    let _archive_guard = archive_lock
+        .lock()
+        .map_err(|e| format!("Archive lock poisoned: {e}"))?;
+
+    for (path, label) in [(mailbox::feed_path(team), "feed"), (archive_path(team), "archive")] {
+        let mut messages = read_messages_file(&path, label)?;
+        let before = messages.len();
+        messages.retain(|m| !older(m));
+        if messages.len() != before {
+            rewrite_messages_file(&path, &messages)?;
+        }
+    }
+    Ok(())
+}
+
+/// Delete a team's archive file.
+pub(super) fn remove_archive_sync(team: &str) -> Result<(), String> {
+    let lock = mailbox::inbox_lock(&archive_lock_key(team));
+    let _guard = lock
+        .lock()
+        .map_err(|e| format!("Archive lock poisoned: {e}"))?;
+    let path = archive_path(team);
+    if path.exists() {
+        fs::remove_file(&path).map_err(|e| format!("Failed to remove archive: {e}"))?;
+    }
+    Ok(())
+}
+
+/// Search feed + archive. Results are newest first.
+pub(crate) fn search_sync(team: &str, query: &MessageQuery) -> Result<Vec<TeamMessage>, String> {
+    validate_name(team, "team")?;
+
+    let since = match query.since.as_deref().filter(|s| !s.is_empty()) {
+        Some(s) => Some(
+            parse_timestamp(s).ok_or_else(|| format!("Invalid 'since' timestamp: '{s}'"))?,
+        ),
+        None => None,
+    };
+    let terms: Vec<String> = query
+        .query
+        .split_whitespace()
+        .map(|t| t.to_lowercase())
+        .collect();
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+
+    // Feed first: a message archived between the two reads is then seen
+    // twice (and deduplicated below) rather than missed
+    let mut all = {
+        let lock = mailbox::inbox_lock(&mailbox::feed_lock_key(team));
+        let _guard = lock
+            .lock()
+            .map_err(|e| format!("Feed lock poisoned: {e}"))?;
+        read_messages_file(&mailbox::feed_path(team), "feed")?
+    };
+    {
+        let lock = mailbox::inbox_lock(&archive_lock_key(team));
+        let _guard = lock
+            .lock()
+            .map_err(|e| format!("Archive lock poisoned: {e}"))?;
+        all.extend(read_messages_file(&archive_path(team), "archive")?);
+    }
+
+    // Read inbox messages are archived alongside their feed copy, and a crash
+    // mid-compaction can leave a message in both files
+    let mut seen = HashSet::new();
+    let mut found: Vec<TeamMessage> = all
+        .into_iter()
+        .filter(|m| matches_query(m, &terms, query, since))
+        .filter(|m| seen.insert(m.id.clone()))
+        .collect();
+    found.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    found.truncate(limit);
+    Ok(found)
+}
+
+/// Search a team's message history (feed + archive).
+#[tauri::command]
+pub async fn team_search_messages(
+    team: String,
+    query: String,
+    from: Option<String>,
+    to: Option<String>,
+    since: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<TeamMessage>, String> {
+    tokio::task::spawn_blocking(move || {
+        search_sync(
+            &team,
+            &MessageQuery {
+                query,
+                from,
+                to,
+                since,
+                limit,
+            },
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+/// Run compaction for a team now, using the saved retention policy.
+#[tauri::command]
+pub async fn team_compact_messages(team: String) -> Result<CompactionReport, String> {
+    tokio::task::spawn_blocking(move || compact_team_sync(&team, &load_policy()))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command]
+pub async fn team_load_retention() -> Result<RetentionPolicy, String> {
+    tokio::task::spawn_blocking(load_policy)
+        .await
+        .map_err(|e| format!("Task join error: {e}"))
+}
+
+#[tauri::command]
+pub async fn team_save_retention(policy: RetentionPolicy) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        if policy.delete_after_days > 0
+            && policy.archive_after_days > 0
+            && policy.delete_after_days < policy.archive_after_days
+        {
+            return Err(format!(
+                "delete_after_days ({}) must not be less than archive_after_days ({})",
+                policy.delete_after_days, policy.archive_after_days
+            ));
+        }
+        write_json(&policy_path(), &policy)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn msg(id: &str, from: &str, to: &str, text: &str, ts: DateTime<Utc>) -> TeamMessage {
+        TeamMessage {
+            id: id.into(),
+            from: from.into(),
+            to: to.into(),
+            text: text.into(),
+            timestamp: ts.to_rfc3339(),
+            read: false,
+            broadcast_id: None,
+            channel: None,
+            from_project: None,
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 5, 20, 12, 0, 0).unwrap()
+    }
+
+    fn days_ago(d: i64) -> DateTime<Utc> {
+        now() - chrono::Duration::days(d)
+    }
+
+    // ── partition_feed ──
+
+    #[test]
+    fn partition_by_age() {
+        let policy = RetentionPolicy { archive_after_days: 7, max_feed_bytes: 0, delete_after_days: 0 };
+        let msgs = vec![
+            msg("old", "a", "b", "x", days_ago(10)),
+            msg("new", "a", "b", "y", days_ago(1)),
+        ];
+        let (keep, archive) = partition_feed(msgs, &policy, now());
+        assert_eq!(keep.iter().map(|m| m.id.as_str()).collect::<Vec<_>>(), ["new"]);
+        assert_eq!(archive.iter().map(|m| m.id.as_str()).collect::<Vec<_>>(), ["old"]);
+    }
+
+    #[test]
+    fn inbox_archives_only_old_read_messages() {
+        let read = |id, ts| TeamMessage { read: true, ..msg(id, "a", "b", "x", ts) };
+        let msgs = || {
+            vec![
+                read("old-read", days_ago(10)),
+                read("new-read", days_ago(1)),
+                msg("old-unread", "a", "b", "x", days_ago(10)),
+            ]
+        };
+        let ids = |v: &[TeamMessage]| v.iter().map(|m| m.id.clone()).collect::<Vec<_>>();
+
+        let policy = RetentionPolicy { archive_after_days: 7, ..Default::default() };
+        let (keep, archive) = partition_inbox(msgs(), &policy, now());
+        assert_eq!(ids(&keep), ["new-read", "old-unread"]);
+        assert_eq!(ids(&archive), ["old-read"]);
+
+        // Archiving off: read messages are dropped, not kept forever
+        let (keep, archive) = partition_inbox(msgs(), &RetentionPolicy::default(), now());
+        assert_eq!(ids(&keep), ["old-unread"]);
+        assert!(archive.is_empty());
+    }
+
+    #[test]
+    fn partition_by_size_archives_oldest_first() {
+        let msgs: Vec<_> = (0..10)
+            .map(|i| msg(&format!("m{i}"), "a", "b", &"x".repeat(100), days_ago(0) - chrono::Duration::minutes(10 - i)))
+            .collect();
+        let one = line_len(&msgs[0]);
+        let policy = RetentionPolicy { archive_after_days: 0, max_feed_bytes: one * 8, delete_after_days: 0 };
+        let (keep, archive) = partition_feed(msgs, &policy, now());
+        // Trimmed to 3/4 of the limit = 6 messages
+        assert_eq!(keep.len(), 6);
+        assert_eq!(archive.len(), 4);
+        assert_eq!(archive[0].id, "m0");
+        assert_eq!(keep[0].id, "m4");
+    }
+
+    #[test]
+    fn default_policy_keeps_everything() {
+        let policy = RetentionPolicy::default();
+        let msgs = vec![msg("a", "a", "b", &"x".repeat(4096), days_ago(400))];
+        let (keep, archive) = partition_feed(msgs, &policy, now());
+        assert_eq!(keep.len(), 1);
+        assert!(archive.is_empty());
+    }
+
+    #[test]
+    fn partition_keeps_unparseable_timestamps() {
+        let policy = RetentionPolicy { archive_after_days: 1, max_feed_bytes: 0, delete_after_days: 0 };
+        let mut m = msg("bad", "a", "b", "x", now());
+        m.timestamp = "not a date".into();
+        let (keep, archive) = partition_feed(vec![m], &policy, now());
+        assert_eq!(keep.len(), 1);
+        assert!(archive.is_empty());
+    }
+
+    // ── expire_archive ──
+
+    #[test]
+    fn expire_disabled() {
+        let policy = RetentionPolicy { delete_after_days: 0, ..Default::default() };
+        let (kept, deleted) = expire_archive(vec![msg("a", "a", "b", "x", days_ago(400))], &policy, now());
+        assert_eq!(kept.len(), 1);
+        assert_eq!(deleted, 0);
+    }
+
+    #[test]
+    fn expire_old_messages() {
+        let policy = RetentionPolicy { delete_after_days: 30, ..Default::default() };
+        let msgs = vec![
+            msg("old", "a", "b", "x", days_ago(31)),
+            msg("new", "a", "b", "y", days_ago(29)),
+        ];
+        let (kept, deleted) = expire_archive(msgs, &policy, now());
+        assert_eq!(deleted, 1);
+        assert_eq!(kept[0].id, "new");
+    }
+
+    // ── matches_query ──
+
+    fn terms(q: &str) -> Vec<String> {
+        q.split_whitespace().map(|t| t.to_lowercase()).collect()
+    }
+
+    #[test]
+    fn query_all_terms_case_insensitive() {
+        let m = msg("1", "lead", "coder", "Please fix the FAILING test in parser.rs", now());
+        let q = MessageQuery::default();
+        assert!(matches_query(&m, &terms("failing PARSER"), &q, None));
+        assert!(!matches_query(&m, &terms("failing lexer"), &q, None));
+    }
+
+    #[test]
+    fn query_empty_matches_everything() {
+        let m = msg("1", "lead", "coder", "anything", now());
+        assert!(matches_query(&m, &[], &MessageQuery::default(), None));
+    }
+
+    #[test]
+    fn query_from_to_filters() {
+        let m = msg("1", "lead", "coder", "hi", now());
+        let q = MessageQuery { from: Some("lead".into()), to: Some("coder".into()), ..Default::default() };
+        assert!(matches_query(&m, &[], &q, None));
+        let q = MessageQuery { from: Some("coder".into()), ..Default::default() };
+        assert!(!matches_query(&m, &[], &q, None));
+        let q = MessageQuery { to: Some("broadcast".into()), ..Default::default() };
+        assert!(!matches_query(&m, &[], &q, None));
+    }
+
+    #[test]
+    fn query_since_filter() {
+        let m = msg("1", "a", "b", "hi", days_ago(2));
+        let q = MessageQuery::default();
+        assert!(matches_query(&m, &[], &q, Some(days_ago(3))));
+        assert!(!matches_query(&m, &[], &q, Some(days_ago(1))));
+    }
+}
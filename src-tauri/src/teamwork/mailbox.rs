@@ -7,10 +7,9 @@ use std::sync::{Arc, LazyLock, Mutex};
 use tokio::sync::Notify;
 
 use crate::config;
-use crate::file_ops::atomic_write;
 use crate::named_mutex_pool::NamedMutexPool;
 
-use super::validate_name;
+use super::{history, validate_name};
 
 /// Per-inbox lock to prevent concurrent write races.
 static INBOX_LOCKS: LazyLock<NamedMutexPool> =
@@ -45,7 +44,7 @@ fn notify_inbox(team: &str, agent_id: &str) {
     }
 }
 
-fn inbox_lock(key: &str) -> Arc<Mutex<()>> {
+pub(super) fn inbox_lock(key: &str) -> Arc<Mutex<()>> {
     INBOX_LOCKS.lock(key)
 }
 
@@ -77,31 +76,92 @@ pub struct TeamMessage {
     pub read: bool,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub broadcast_id: Option<String>,
+    /// Cross-project channel the message was routed through (None = same-project message).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    /// Sender's project path for channel messages.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_project: Option<String>,
+}
+
+/// A cross-project message routed through a named channel.
+pub(crate) struct ChannelMessage<'a> {
+    pub channel: &'a str,
+    pub from_team: &'a str,
+    pub from_project: &'a str,
+    pub from: &'a str,
+    pub to_team: &'a str,
+    /// Recipient agent_id, or None to reach every agent in `target_agents`.
+    pub to: Option<&'a str>,
+    pub text: &'a str,
+}
+
+/// Directory for a team's data: ~/.config/aither-flow/teams/{team_name}/
+pub(super) fn team_dir(team: &str) -> PathBuf {
+    config::config_dir().join("teams").join(team)
 }
 
 /// Directory for team inboxes: ~/.config/aither-flow/teams/{team_name}/inboxes/
 fn inboxes_dir(team: &str) -> PathBuf {
-    config::config_dir()
-        .join("teams")
-        .join(team)
-        .join("inboxes")
+    team_dir(team).join("inboxes")
 }
 
 /// Path to an agent's inbox file
-fn inbox_path(team: &str, agent_id: &str) -> PathBuf {
+pub(super) fn inbox_path(team: &str, agent_id: &str) -> PathBuf {
     inboxes_dir(team).join(format!("{agent_id}.jsonl"))
 }
 
 /// Path to the persistent feed log (append-only, for UI display)
-fn feed_path(team: &str) -> PathBuf {
+pub(super) fn feed_path(team: &str) -> PathBuf {
     inboxes_dir(team).join("feed.jsonl")
 }
 
 /// Lock key for an inbox
-fn inbox_lock_key(team: &str, agent_id: &str) -> String {
+pub(super) fn inbox_lock_key(team: &str, agent_id: &str) -> String {
     format!("{team}/{agent_id}")
 }
 
+/// Agent ids that have an inbox file in the team.
+pub(super) fn inbox_agents(team: &str) -> Result<Vec<String>, String> {
+    let dir = inboxes_dir(team);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries = fs::read_dir(&dir).map_err(|e| format!("Failed to read inboxes dir: {e}"))?;
+    Ok(entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|ft| ft.is_file()))
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+                return None;
+            }
+            let stem = path.file_stem()?.to_str()?.to_string();
+            (stem != "feed" && validate_name(&stem, "agent_id").is_ok()).then_some(stem)
+        })
+        .collect())
+}
+
+/// Lock key for the team feed
+pub(super) fn feed_lock_key(team: &str) -> String {
+    format!("{team}/__feed__")
+}
+
+/// Parse a JSONL message file, skipping (and logging) malformed lines.
+pub(super) fn parse_messages(data: &str, label: &str) -> Vec<TeamMessage> {
+    let mut messages = Vec::new();
+    for (i, line) in data.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<TeamMessage>(line) {
+            Ok(msg) => messages.push(msg),
+            Err(e) => eprintln!("[teamwork] Bad line {} in {label}: {e}", i + 1),
+        }
+    }
+    messages
+}
+
 /// Create a new message with UUID and ISO-8601 timestamp
 fn new_message(from: &str, to: &str, text: &str, broadcast_id: Option<String>) -> TeamMessage {
     TeamMessage {
@@ -112,6 +172,8 @@ fn new_message(from: &str, to: &str, text: &str, broadcast_id: Option<String>) -
         timestamp: chrono::Utc::now().to_rfc3339(),
         read: false,
         broadcast_id,
+        channel: None,
+        from_project: None,
     }
 }
 
@@ -156,11 +218,18 @@ fn append_to_inbox(team: &str, msg: &TeamMessage) -> Result<(), String> {
 }
 
 /// Append a message to the persistent feed log (for UI display).
-/// The feed is append-only; messages are never removed except by team_clear_messages.
+/// Old messages leave the feed only through history compaction or team_clear_messages.
 fn append_to_feed(team: &str, msg: &TeamMessage) -> Result<(), String> {
+    let feed_len = write_feed_line(team, msg)?;
+    // Oversized feeds are compacted into the archive (outside the feed lock)
+    history::compact_if_oversized(team, feed_len);
+    Ok(())
+}
+
+/// Append one line to feed.jsonl under the feed lock. Returns the new file size.
+fn write_feed_line(team: &str, msg: &TeamMessage) -> Result<u64, String> {
     let path = feed_path(team);
-    let key = format!("{team}/__feed__");
-    let lock = inbox_lock(&key);
+    let lock = inbox_lock(&feed_lock_key(team));
     let _guard = lock
         .lock()
         .map_err(|e| format!("Feed lock poisoned: {e}"))?;
@@ -190,7 +259,9 @@ fn append_to_feed(team: &str, msg: &TeamMessage) -> Result<(), String> {
     file.sync_all()
         .map_err(|e| format!("Failed to sync feed: {e}"))?;
 
-    Ok(())
+    file.metadata()
+        .map(|m| m.len())
+        .map_err(|e| format!("Failed to stat feed: {e}"))
 }
 
 /// Send a message (sync, for use inside spawn_blocking).
@@ -234,6 +305,70 @@ pub(crate) fn broadcast_sync(
     Ok(())
 }
 
+/// Where one copy of a message is written.
+#[derive(Debug, PartialEq)]
+enum Destination<'a> {
+    /// The recipient's inbox in this team.
+    Inbox(&'a str),
+    /// This team's feed.
+    Feed(&'a str),
+}
+
+/// Deliver a channel message to another project's team (sync).
+/// Recipients get it in their inboxes; the message is recorded in the feeds of
+/// both the sending and the receiving team.
+pub(crate) fn send_channel_message_sync(
+    cm: &ChannelMessage,
+    target_agents: &[String],
+) -> Result<(), String> {
+    route_channel_message(cm, target_agents, &mut |dest, msg| match dest {
+        Destination::Inbox(team) => append_to_inbox(team, msg),
+        Destination::Feed(team) => append_to_feed(team, msg),
+    })
+}
+
+/// Tag the copies of a channel message and hand each to `deliver`.
+fn route_channel_message<'a>(
+    cm: &ChannelMessage<'a>,
+    target_agents: &[String],
+    deliver: &mut dyn FnMut(Destination<'a>, &TeamMessage) -> Result<(), String>,
+) -> Result<(), String> {
+    validate_name(cm.from_team, "team")?;
+    validate_name(cm.to_team, "team")?;
+    validate_name(cm.from, "from")?;
+    if cm.from_team == cm.to_team {
+        return Err("Channel messages must go to another project".to_string());
+    }
+
+    let tag = |mut msg: TeamMessage| {
+        msg.channel = Some(cm.channel.to_string());
+        msg.from_project = Some(cm.from_project.to_string());
+        msg
+    };
+
+    match cm.to {
+        Some(to) => {
+            validate_name(to, "to")?;
+            let msg = tag(new_message(cm.from, to, cm.text, None));
+            deliver(Destination::Inbox(cm.to_team), &msg)?;
+            deliver(Destination::Feed(cm.to_team), &msg)?;
+            deliver(Destination::Feed(cm.from_team), &msg)?;
+        }
+        None => {
+            let bid = uuid::Uuid::new_v4().to_string();
+            let feed_msg = tag(new_message(cm.from, "broadcast", cm.text, Some(bid.clone())));
+            deliver(Destination::Feed(cm.to_team), &feed_msg)?;
+            deliver(Destination::Feed(cm.from_team), &feed_msg)?;
+            for agent_id in target_agents {
+                validate_name(agent_id, "agent_id")?;
+                let msg = tag(new_message(cm.from, agent_id, cm.text, Some(bid.clone())));
+                deliver(Destination::Inbox(cm.to_team), &msg)?;
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Send a message from one agent to another. Returns the message ID.
 #[allow(dead_code)] // available as tauri command when needed
 #[tauri::command]
@@ -360,43 +495,21 @@ pub(crate) fn mark_read_sync(
 
     let ids_set: std::collections::HashSet<&str> =
         message_ids.iter().map(|s| s.as_str()).collect();
-
-    // Keep only messages that are NOT being marked as read now and were not
-    // already read — this prevents the file from growing unboundedly.
-    let mut lines = Vec::new();
-    for line in data.lines() {
-        if line.trim().is_empty() {
-            continue;
-        }
-        match serde_json::from_str::<TeamMessage>(line) {
-            Ok(msg) => {
-                // Drop messages being marked as read now, and already-read messages
-                if ids_set.contains(msg.id.as_str()) || msg.read {
-                    continue;
-                }
-                lines.push(line.to_string());
-            }
-            Err(e) => {
-                eprintln!("[teamwork] Skipping bad line during mark_read: {e}");
-            }
+    let mut messages = parse_messages(&data, "inbox");
+    for msg in &mut messages {
+        if ids_set.contains(msg.id.as_str()) {
+            msg.read = true;
         }
     }
 
-    if lines.is_empty() {
-        // All messages read — remove the file entirely
-        if let Err(e) = fs::remove_file(&path) {
-            eprintln!("[teamwork] Failed to remove empty inbox {}: {e}", path.display());
-        }
-        return Ok(());
-    }
-
-    let mut output = lines.join("\n");
-    output.push('\n');
-
-    atomic_write(&path, output.as_bytes())
+    // Read messages stay until the retention policy archives (or drops) them,
+    // which keeps the inbox from growing unboundedly
+    let (keep, _) = history::archive_read_messages(team, messages, &history::load_policy())?;
+    history::rewrite_messages_file(&path, &keep)
 }
 
 /// Read ALL messages from the persistent feed log, sorted by timestamp.
+/// Archived messages are not included — use `team_search_messages` for those.
 #[tauri::command]
 pub async fn team_read_all_messages(team: String) -> Result<Vec<TeamMessage>, String> {
     tokio::task::spawn_blocking(move || {
@@ -407,8 +520,7 @@ pub async fn team_read_all_messages(team: String) -> Result<Vec<TeamMessage>, St
             return Ok(Vec::new());
         }
 
-        let key = format!("{team}/__feed__");
-        let lock = inbox_lock(&key);
+        let lock = inbox_lock(&feed_lock_key(&team));
         let _guard = lock
             .lock()
             .map_err(|e| format!("Feed lock poisoned: {e}"))?;
@@ -416,19 +528,7 @@ pub async fn team_read_all_messages(team: String) -> Result<Vec<TeamMessage>, St
         let data =
             fs::read_to_string(&path).map_err(|e| format!("Failed to read feed: {e}"))?;
 
-        let mut all = Vec::new();
-        for (i, line) in data.lines().enumerate() {
-            if line.trim().is_empty() {
-                continue;
-            }
-            match serde_json::from_str::<TeamMessage>(line) {
-                Ok(msg) => all.push(msg),
-                Err(e) => {
-                    eprintln!("[teamwork] Bad line {} in feed: {e}", i + 1);
-                }
-            }
-        }
-
+        let mut all = parse_messages(&data, "feed");
         all.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
         Ok(all)
     })
@@ -436,44 +536,51 @@ pub async fn team_read_all_messages(team: String) -> Result<Vec<TeamMessage>, St
     .map_err(|e| format!("Task join error: {e}"))?
 }
 
-/// Delete all inbox JSONL files for a team (clears message history).
+/// Clear team message history.
+/// Without `before`, deletes all inbox JSONL files and the archive.
+/// With `before` (RFC 3339), only feed and archive messages older than that are removed.
 #[tauri::command]
-pub async fn team_clear_messages(team: String) -> Result<(), String> {
+pub async fn team_clear_messages(team: String, before: Option<String>) -> Result<(), String> {
     tokio::task::spawn_blocking(move || {
         validate_name(&team, "team")?;
 
-        let dir = inboxes_dir(&team);
-        if !dir.exists() {
-            return Ok(());
+        if let Some(before) = before {
+            let cutoff = chrono::DateTime::parse_from_rfc3339(&before)
+                .map_err(|e| format!("Invalid 'before' timestamp '{before}': {e}"))?
+                .with_timezone(&chrono::Utc);
+            return history::clear_before_sync(&team, cutoff);
         }
 
-        let entries =
-            fs::read_dir(&dir).map_err(|e| format!("Failed to read inboxes dir: {e}"))?;
-
-        for entry in entries.flatten() {
-            let ft = entry
-                .file_type()
-                .map_err(|e| format!("Failed to get file type: {e}"))?;
-            if ft.is_dir() || ft.is_symlink() {
-                continue;
-            }
-            let path = entry.path();
-            if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
-                continue;
-            }
-            if let Err(e) = fs::remove_file(&path) {
-                eprintln!("[teamwork] Failed to remove inbox {}: {e}", path.display());
+        let dir = inboxes_dir(&team);
+        if dir.exists() {
+            let entries =
+                fs::read_dir(&dir).map_err(|e| format!("Failed to read inboxes dir: {e}"))?;
+
+            for entry in entries.flatten() {
+                let ft = entry
+                    .file_type()
+                    .map_err(|e| format!("Failed to get file type: {e}"))?;
+                if ft.is_dir() || ft.is_symlink() {
+                    continue;
+                }
+                let path = entry.path();
+                if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+                    continue;
+                }
+                if let Err(e) = fs::remove_file(&path) {
+                    eprintln!("[teamwork] Failed to remove inbox {}: {e}", path.display());
+                }
             }
         }
 
-        Ok(())
+        history::remove_archive_sync(&team)
     })
     .await
     .map_err(|e| format!("Task join error: {e}"))?
 }
 
 /// Mark specific messages as read in an agent's inbox.
-/// Rewrites the JSONL file with updated read flags.
+/// Rewrites the JSONL file with updated read flags, applying the retention policy.
 #[allow(dead_code)] // available as tauri command when needed
 #[tauri::command]
 pub async fn team_mark_read(
@@ -485,3 +592,69 @@ pub async fn team_mark_read(
         .await
         .map_err(|e| format!("Task join error: {e}"))?
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel_message(to: Option<&'static str>) -> ChannelMessage<'static> {
+        ChannelMessage {
+            channel: "sync",
+            from_team: "api",
+            from_project: "/work/api",
+            from: "lead",
+            to_team: "web",
+            to,
+            text: "schema changed",
+        }
+    }
+
+    /// Route a message, recording (destination, recipient) of every copy.
+    fn route(cm: &ChannelMessage<'static>, agents: &[&str]) -> Vec<(Destination<'static>, String)> {
+        let agents: Vec<String> = agents.iter().map(|a| a.to_string()).collect();
+        let mut copies = Vec::new();
+        route_channel_message(cm, &agents, &mut |dest, msg| {
+            assert_eq!(msg.channel.as_deref(), Some("sync"));
+            assert_eq!(msg.from_project.as_deref(), Some("/work/api"));
+            copies.push((dest, msg.to.clone()));
+            Ok(())
+        })
+        .unwrap();
+        copies
+    }
+
+    #[test]
+    fn direct_channel_message_reaches_both_feeds() {
+        assert_eq!(
+            route(&channel_message(Some("dev")), &[]),
+            [
+                (Destination::Inbox("web"), "dev".to_string()),
+                (Destination::Feed("web"), "dev".to_string()),
+                (Destination::Feed("api"), "dev".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn channel_broadcast_is_fed_once_per_team() {
+        assert_eq!(
+            route(&channel_message(None), &["dev", "qa"]),
+            [
+                (Destination::Feed("web"), "broadcast".to_string()),
+                (Destination::Feed("api"), "broadcast".to_string()),
+                (Destination::Inbox("web"), "dev".to_string()),
+                (Destination::Inbox("web"), "qa".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn channel_message_needs_another_team() {
+        let cm = ChannelMessage {
+            to_team: "api",
+            ..channel_message(Some("dev"))
+        };
+        let result = route_channel_message(&cm, &[], &mut |_, _| Ok(()));
+        assert!(result.unwrap_err().contains("another project"));
+    }
+}
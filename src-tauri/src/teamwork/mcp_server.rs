@@ -10,7 +10,7 @@ use std::sync::{Arc, OnceLock};
 use tokio::sync::RwLock;
 
 use super::roles::AgentRole;
-use super::{mailbox, tasks};
+use super::{approvals, channels, history, mailbox, tasks};
 use crate::conductor::session::SessionManager;
 
 // ---------------------------------------------------------------------------
@@ -433,12 +433,16 @@ fn is_known_tool(name: &str) -> bool {
         "send_message"
             | "broadcast"
             | "read_inbox"
+            | "search_messages"
+            | "list_channels"
+            | "send_channel_message"
             | "list_tasks"
             | "create_task"
             | "claim_task"
             | "complete_task"
             | "list_agents"
             | "send_prompt"
+            | "request_approval"
     )
 }
 
@@ -546,6 +550,136 @@ async fn execute_tool(
             Ok(text)
         }
 
+        "search_messages" => {
+            let query = history::MessageQuery {
+                query: args["query"].as_str().unwrap_or("").to_string(),
+                from: args["from"].as_str().map(String::from),
+                to: args["to"].as_str().map(String::from),
+                since: args["since"].as_str().map(String::from),
+                limit: args["limit"].as_u64().map(|l| l as usize),
+            };
+            let team = team_name;
+            let messages =
+                tokio::task::spawn_blocking(move || history::search_sync(&team, &query))
+                    .await
+                    .map_err(|e| format!("Task panic: {e}"))??;
+
+            if messages.is_empty() {
+                return Ok("No matching messages".to_string());
+            }
+            serde_json::to_string_pretty(&messages)
+                .map_err(|e| format!("Serialize error: {e}"))
+        }
+
+        // ---- Cross-project channel tools ----
+
+        "list_channels" => {
+            let pp = project_path.clone();
+            let my_channels = tokio::task::spawn_blocking(move || channels::channels_for_project(&pp))
+                .await
+                .map_err(|e| format!("Task panic: {e}"))??;
+            if my_channels.is_empty() {
+                return Ok("Your project is not on any channel".to_string());
+            }
+            let agents = state.agents.read().await;
+            let list: Vec<Value> = my_channels
+                .iter()
+                .map(|ch| {
+                    let projects: Vec<Value> = ch
+                        .projects
+                        .iter()
+                        .map(|p| {
+                            let online: Vec<Value> = agents
+                                .iter()
+                                .filter(|(_, info)| channels::same_project(&info.project_path, p))
+                                .map(|(id, info)| json!({ "agent_id": id, "role": info.role.name }))
+                                .collect();
+                            json!({
+                                "project": p,
+                                "is_self": channels::same_project(p, &project_path),
+                                "agents": online,
+                            })
+                        })
+                        .collect();
+                    json!({ "channel": ch.name, "projects": projects })
+                })
+                .collect();
+            serde_json::to_string_pretty(&list)
+                .map_err(|e| format!("Serialize error: {e}"))
+        }
+
+        "send_channel_message" => {
+            let channel_name = args["channel"]
+                .as_str()
+                .ok_or("Missing 'channel' parameter")?
+                .to_string();
+            let text = args["text"]
+                .as_str()
+                .ok_or("Missing 'text' parameter")?
+                .to_string();
+            let to = args["to"].as_str().filter(|s| !s.is_empty()).map(String::from);
+            let target_project = args["project"].as_str().filter(|s| !s.is_empty()).map(String::from);
+
+            let pp = project_path.clone();
+            let channel = tokio::task::spawn_blocking(move || {
+                channels::channel_for_sender(&channel_name, &pp)
+            })
+            .await
+            .map_err(|e| format!("Task panic: {e}"))??;
+
+            // Resolve the receiving project: from the target agent, or explicitly
+            let (to_project, target_agents) = {
+                let agents = state.agents.read().await;
+                let to_project = match (&to, &target_project) {
+                    (Some(to), _) => agents
+                        .get(to)
+                        .map(|info| info.project_path.clone())
+                        .ok_or_else(|| format!("Agent {to} is not online"))?,
+                    (None, Some(p)) => p.clone(),
+                    (None, None) => {
+                        return Err("Either 'to' or 'project' is required".to_string());
+                    }
+                };
+                let target_agents: Vec<String> = agents
+                    .iter()
+                    .filter(|(_, info)| channels::same_project(&info.project_path, &to_project))
+                    .map(|(id, _)| id.clone())
+                    .collect();
+                (to_project, target_agents)
+            };
+            if channels::same_project(&to_project, &project_path) {
+                return Err("Target is in your own project — use send_message instead".to_string());
+            }
+            if !channel.allows(&to_project) {
+                return Err(format!(
+                    "Project {to_project} is not allowed on channel '{}'",
+                    channel.name
+                ));
+            }
+
+            let to_team = crate::projects::project_teamwork_slug(&to_project);
+            let from_team = team_name;
+            let from = agent_id;
+            let delivered = if to.is_some() { 1 } else { target_agents.len() };
+            tokio::task::spawn_blocking(move || {
+                mailbox::send_channel_message_sync(
+                    &mailbox::ChannelMessage {
+                        channel: &channel.name,
+                        from_team: &from_team,
+                        from_project: &project_path,
+                        from: &from,
+                        to_team: &to_team,
+                        to: to.as_deref(),
+                        text: &text,
+                    },
+                    &target_agents,
+                )
+            })
+            .await
+            .map_err(|e| format!("Task panic: {e}"))??;
+            Ok(format!("Channel message sent ({delivered} recipient(s))"))
+        }
+
         // ---- Task tools ----
 
         "list_tasks" => {
@@ -663,6 +797,30 @@ async fn execute_tool(
             }
         }
 
+        "request_approval" => {
+            let question = args["question"]
+                .as_str()
+                .ok_or("Missing 'question' parameter")?;
+            let options: Vec<String> = args["options"]
+                .as_array()
+                .ok_or("Missing 'options' parameter")?
+                .iter()
+                .filter_map(|o| o.as_str().map(String::from))
+                .collect();
+            let timeout_secs = args["timeout_secs"]
+                .as_u64()
+                .unwrap_or(approvals::DEFAULT_TIMEOUT_SECS);
+            let answer = approvals::request_approval(
+                &team_name,
+                &agent_id,
+                question,
+                &options,
+                timeout_secs,
+            )
+            .await?;
+            Ok(format!("The user chose: {answer}"))
+        }
+
         _ => Err(format!("Unknown tool: {tool_name}")),
     }
 }
@@ -715,6 +873,71 @@ fn communication_tool_definitions() -> Vec<Value> {
                 "required": []
             }
         }),
+        json!({
+            "name": "search_messages",
+            "description": "Search your project's message history, including archived messages. Returns matches newest first.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "Words that must all appear in the message text (case-insensitive). Empty matches any text."
+                    },
+                    "from": {
+                        "type": "string",
+                        "description": "Optional: only messages sent by this agent_id"
+                    },
+                    "to": {
+                        "type": "string",
+                        "description": "Optional: only messages addressed to this agent_id (use \"broadcast\" for broadcasts)"
+                    },
+                    "since": {
+                        "type": "string",
+                        "description": "Optional: RFC 3339 timestamp, only messages at or after this time"
+                    },
+                    "limit": {
+                        "type": "number",
+                        "description": "Maximum number of results (default: 50)"
+                    }
+                },
+                "required": []
+            }
+        }),
+        json!({
+            "name": "list_channels",
+            "description": "List the cross-project channels your project is allowed on, with the projects on each channel and their online agents.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {},
+                "required": []
+            }
+        }),
+        json!({
+            "name": "send_channel_message",
+            "description": "Send a message to an agent in another project through a cross-project channel. Give 'to' for a single agent, or 'project' to reach every agent of that project. The message is recorded in both projects' feeds.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "channel": {
+                        "type": "string",
+                        "description": "Channel name (see list_channels)"
+                    },
+                    "to": {
+                        "type": "string",
+                        "description": "Optional: agent_id of the recipient in the other project"
+                    },
+                    "project": {
+                        "type": "string",
+                        "description": "Optional: project path to message all its agents (used when 'to' is omitted)"
+                    },
+                    "text": {
+                        "type": "string",
+                        "description": "The message text"
+                    }
+                },
+                "required": ["channel", "text"]
+            }
+        }),
         json!({
             "name": "list_tasks",
             "description": "List all tasks for your project, sorted by status (pending first, then in-progress, then completed).",
@@ -797,6 +1020,29 @@ fn communication_tool_definitions() -> Vec<Value> {
                 "required": ["agent_id", "prompt"]
             }
         }),
+        json!({
+            "name": "request_approval",
+            "description": "Ask the human user a question and wait for them to pick one of the given options (in the app or via Telegram). Blocks until answered; returns the chosen option. Fails if nobody answers before the timeout — treat that as not approved.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "question": {
+                        "type": "string",
+                        "description": "The question, with enough context for the user to decide"
+                    },
+                    "options": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "2–10 short answer options, e.g. [\"Approve\", \"Reject\"]"
+                    },
+                    "timeout_secs": {
+                        "type": "integer",
+                        "description": "How long to wait for an answer (default 600, max 86400)"
+                    }
+                },
+                "required": ["question", "options"]
+            }
+        }),
     ]
 }
 
@@ -1,6 +1,10 @@
+pub mod approvals;
+pub mod channels;
+pub mod history;
 pub mod mailbox;
 pub mod mcp_server;
 pub mod presets;
+pub mod role_library;
 pub mod roles;
 pub mod tasks;
 
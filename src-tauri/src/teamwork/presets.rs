@@ -8,7 +8,8 @@ use crate::config;
 use crate::file_ops::{read_json, write_json};
 use std::path::PathBuf;
 
-use super::roles::{default_roles, AgentRole, DEFAULT_START_MESSAGE};
+use super::roles::{default_start_message, find_role, AgentRole};
+use crate::prompt_template::{self, PromptVars};
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct TeamPreset {
@@ -106,21 +107,14 @@ pub async fn presets_delete(id: String) -> Result<(), String> {
     .map_err(|e| format!("Task join error: {e}"))?
 }
 
-/// Find an AgentRole by name — custom roles first (overrides), then defaults.
-fn find_role_by_name(name: &str) -> Option<AgentRole> {
-    // Custom roles take priority (user overrides)
-    let custom_path = config::config_dir().join("custom_roles.json");
-    if custom_path.exists() {
-        let custom = read_json::<Vec<AgentRole>>(&custom_path).unwrap_or_else(|e| {
-            eprintln!("[teamwork] Failed to read custom roles: {e}");
-            Vec::new()
-        });
-        if let Some(r) = custom.into_iter().find(|r| r.name.eq_ignore_ascii_case(name)) {
-            return Some(r);
-        }
+/// Fill in the role's start message template. A broken template is sent
+/// as written rather than failing the launch.
+fn render_start_message(role: &mut AgentRole, vars: &PromptVars) {
+    let message = role.start_message.get_or_insert_with(default_start_message);
+    match prompt_template::render(message, vars) {
+        Ok(rendered) => *message = rendered,
+        Err(e) => eprintln!("[teamwork] Start message of role '{}': {e}", role.name),
     }
-    // Fall back to defaults
-    default_roles().into_iter().find(|r| r.name.eq_ignore_ascii_case(name))
 }
 
 #[tauri::command]
@@ -137,11 +131,13 @@ pub async fn launch_team(
 
     let models_vec = models.unwrap_or_default();
 
+    let pp = project_path.clone();
     let roles_to_launch: Vec<(String, AgentRole)> = tokio::task::spawn_blocking(move || {
+        let vars = PromptVars::for_project(&pp);
         let mut resolved = Vec::new();
         for role_name in &roles {
-            let role = find_role_by_name(role_name)
-                .ok_or_else(|| format!("Role '{}' not found", role_name))?;
+            let mut role = find_role(role_name)?;
+            render_start_message(&mut role, &vars);
             let agent_id = Uuid::new_v4().to_string();
             resolved.push((agent_id, role));
         }
@@ -153,7 +149,7 @@ pub async fn launch_team(
     let mut launched_ids: Vec<String> = Vec::new();
 
     for (i, (agent_id, role)) in roles_to_launch.into_iter().enumerate() {
-        let prompt = role.start_message.clone().unwrap_or_else(|| DEFAULT_START_MESSAGE.to_string());
+        let prompt = role.start_message.clone().unwrap_or_default();
         let role_name_str = role.name.clone();
         let per_role_model = models_vec.get(i).cloned().filter(|m| !m.is_empty());
         let options = StartSessionOptions {
@@ -205,11 +201,13 @@ pub async fn presets_launch(
     .map_err(|e| format!("Task join error: {e}"))??;
 
     // Resolve roles (sync file I/O for custom roles)
+    let pp = project_path.clone();
     let roles_to_launch: Vec<(String, AgentRole)> = tokio::task::spawn_blocking(move || {
+        let vars = PromptVars::for_project(&pp);
         let mut resolved = Vec::new();
         for role_name in &preset.roles {
-            let role = find_role_by_name(role_name)
-                .ok_or_else(|| format!("Role '{}' not found", role_name))?;
+            let mut role = find_role(role_name)?;
+            render_start_message(&mut role, &vars);
             let agent_id = Uuid::new_v4().to_string();
             resolved.push((agent_id, role));
         }
@@ -221,7 +219,7 @@ pub async fn presets_launch(
     let mut launched_ids: Vec<String> = Vec::new();
 
     for (agent_id, role) in roles_to_launch {
-        let prompt = role.start_message.clone().unwrap_or_else(|| DEFAULT_START_MESSAGE.to_string());
+        let prompt = role.start_message.clone().unwrap_or_default();
         let role_name_str = role.name.clone();
         let options = StartSessionOptions {
             agent_id: Some(agent_id.clone()),
@@ -0,0 +1,335 @@
+//! Role library: per-role version history with rollback, and import/export
+//! of role bundles so a curated role set can be shared across machines.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::config;
+use crate::file_ops::{read_json, write_json};
+
+use super::roles::{
+    default_roles, normalize_role, read_custom_roles_sync, upsert_role, validate_all,
+    write_custom_roles_sync, AgentRole,
+};
+
+/// Versions kept per role; older ones are dropped.
+const MAX_VERSIONS_PER_ROLE: usize = 30;
+/// `format` marker of a role bundle file.
+const BUNDLE_FORMAT: &str = "aitherflow-roles";
+const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RoleVersion {
+    pub version: u32,
+    pub saved_at: String,
+    pub role: AgentRole,
+}
+
+/// Portable set of role definitions.
+#[derive(Serialize, Deserialize)]
+pub struct RoleBundle {
+    pub format: String,
+    pub version: u32,
+    #[serde(default)]
+    pub exported_at: String,
+    pub roles: Vec<AgentRole>,
+}
+
+#[derive(Serialize, Default)]
+pub struct RoleImportReport {
+    pub imported: Vec<String>,
+    /// Roles that already exist and were left untouched (import without overwrite).
+    pub skipped: Vec<String>,
+}
+
+/// Path to role history: ~/.config/aither-flow/role_history.json
+fn history_path() -> PathBuf {
+    config::config_dir().join("role_history.json")
+}
+
+/// History key: role names are case-insensitive.
+fn history_key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+fn read_history_sync() -> HashMap<String, Vec<RoleVersion>> {
+    let path = history_path();
+    if !path.exists() {
+        return HashMap::new();
+    }
+    read_json(&path).unwrap_or_else(|e| {
+        eprintln!("[teamwork] Failed to read role history: {e}");
+        HashMap::new()
+    })
+}
+
+/// Append `role` as a new version unless it equals the latest one. Returns true if added.
+fn push_version(versions: &mut Vec<RoleVersion>, role: &AgentRole, saved_at: String) -> bool {
+    if let Some(last) = versions.last() {
+        let same = serde_json::to_value(&last.role).ok() == serde_json::to_value(role).ok();
+        if same {
+            return false;
+        }
+    }
+    let version = versions.last().map(|v| v.version + 1).unwrap_or(1);
+    versions.push(RoleVersion {
+        version,
+        saved_at,
+        role: role.clone(),
+    });
+    if versions.len() > MAX_VERSIONS_PER_ROLE {
+        let excess = versions.len() - MAX_VERSIONS_PER_ROLE;
+        versions.drain(..excess);
+    }
+    true
+}
+
+/// Record a saved role in its history. Failures are logged, never fatal to the save.
+pub(super) fn record_version(role: &AgentRole) {
+    let mut history = read_history_sync();
+    let versions = history.entry(history_key(&role.name)).or_default();
+    if push_version(versions, role, chrono::Utc::now().to_rfc3339()) {
+        if let Err(e) = write_json(&history_path(), &history) {
+            eprintln!("[teamwork] Failed to write role history: {e}");
+        }
+    }
+}
+
+/// Custom roles to export plus any custom ancestors they `extends`.
+fn collect_for_export(custom: &[AgentRole], names: Option<&[String]>) -> Result<Vec<AgentRole>, String> {
+    let mut selected: Vec<AgentRole> = match names {
+        None => custom.to_vec(),
+        Some(names) => {
+            let mut out = Vec::new();
+            for name in names {
+                // Unmodified built-in roles can be exported too
+                let role = custom
+                    .iter()
+                    .find(|r| r.name.eq_ignore_ascii_case(name))
+                    .cloned()
+                    .or_else(|| {
+                        default_roles()
+                            .into_iter()
+                            .find(|r| r.name.eq_ignore_ascii_case(name))
+                    })
+                    .ok_or_else(|| format!("Role '{name}' not found"))?;
+                out.push(role);
+            }
+            out
+        }
+    };
+
+    // Pull in custom parents so the bundle resolves on another machine
+    let mut i = 0;
+    while i < selected.len() {
+        if let Some(parent) = selected[i].extends.clone() {
+            let missing = !selected.iter().any(|r| r.name.eq_ignore_ascii_case(&parent));
+            if missing {
+                if let Some(p) = custom.iter().find(|r| r.name.eq_ignore_ascii_case(&parent)) {
+                    selected.push(p.clone());
+                }
+            }
+        }
+        i += 1;
+    }
+    Ok(selected)
+}
+
+/// Merge bundle roles into the custom set. Existing roles are replaced only with `overwrite`.
+fn merge_bundle(
+    custom: &mut Vec<AgentRole>,
+    bundle: RoleBundle,
+    overwrite: bool,
+) -> Result<(RoleImportReport, Vec<AgentRole>), String> {
+    if bundle.format != BUNDLE_FORMAT {
+        return Err("Not a role bundle file".to_string());
+    }
+    if bundle.version > BUNDLE_VERSION {
+        return Err(format!(
+            "Role bundle version {} is newer than supported ({BUNDLE_VERSION})",
+            bundle.version
+        ));
+    }
+    let mut report = RoleImportReport::default();
+    let mut changed = Vec::new();
+    for role in bundle.roles {
+        let role = normalize_role(role)?;
+        let exists = custom.iter().any(|r| r.name.eq_ignore_ascii_case(&role.name));
+        if exists && !overwrite {
+            report.skipped.push(role.name);
+            continue;
+        }
+        report.imported.push(role.name.clone());
+        changed.push(role.clone());
+        upsert_role(custom, role);
+    }
+    validate_all(custom)?;
+    Ok((report, changed))
+}
+
+/// Saved versions of a role, newest first.
+#[tauri::command]
+pub async fn roles_history(name: String) -> Result<Vec<RoleVersion>, String> {
+    tokio::task::spawn_blocking(move || {
+        let mut versions = read_history_sync()
+            .remove(&history_key(&name))
+            .unwrap_or_default();
+        versions.reverse();
+        Ok(versions)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+/// Restore a role to a saved version. The restored state is recorded as a new version.
+#[tauri::command]
+pub async fn roles_rollback(name: String, version: u32) -> Result<AgentRole, String> {
+    tokio::task::spawn_blocking(move || {
+        let role = read_history_sync()
+            .remove(&history_key(&name))
+            .and_then(|versions| versions.into_iter().find(|v| v.version == version))
+            .map(|v| v.role)
+            .ok_or_else(|| format!("Version {version} of role '{name}' not found"))?;
+        let mut custom = read_custom_roles_sync();
+        upsert_role(&mut custom, role.clone());
+        validate_all(&custom)?;
+        write_custom_roles_sync(&custom)?;
+        record_version(&role);
+        Ok(role)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+/// Export roles to `{dir}/aitherflow-roles-{date}.json`. `names` = None exports all custom roles.
+/// Returns the written file path.
+#[tauri::command]
+pub async fn roles_export(dir: String, names: Option<Vec<String>>) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        let dir = Path::new(&dir);
+        crate::files::validate_path_safe(dir)?;
+        if !dir.is_dir() {
+            return Err(format!("Not a directory: {}", dir.display()));
+        }
+        let roles = collect_for_export(&read_custom_roles_sync(), names.as_deref())?;
+        if roles.is_empty() {
+            return Err("No custom roles to export".to_string());
+        }
+        let now = chrono::Local::now();
+        let bundle = RoleBundle {
+            format: BUNDLE_FORMAT.to_string(),
+            version: BUNDLE_VERSION,
+            exported_at: now.to_rfc3339(),
+            roles,
+        };
+        let path = dir.join(format!("aitherflow-roles-{}.json", now.format("%Y%m%d-%H%M%S")));
+        write_json(&path, &bundle)?;
+        Ok(path.to_string_lossy().to_string())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+/// Import a role bundle. Nothing is written if any resulting role fails to resolve.
+#[tauri::command]
+pub async fn roles_import(path: String, overwrite: bool) -> Result<RoleImportReport, String> {
+    tokio::task::spawn_blocking(move || {
+        let path = Path::new(&path);
+        crate::files::validate_path_safe(path)?;
+        let bundle = read_json::<RoleBundle>(path)?;
+        let mut custom = read_custom_roles_sync();
+        let (report, changed) = merge_bundle(&mut custom, bundle, overwrite)?;
+        if !changed.is_empty() {
+            write_custom_roles_sync(&custom)?;
+            for role in &changed {
+                record_version(role);
+            }
+        }
+        Ok(report)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn role(name: &str, prompt: &str, extends: Option<&str>) -> AgentRole {
+        AgentRole {
+            name: name.into(),
+            system_prompt: prompt.into(),
+            allowed_tools: vec!["Read".into()],
+            can_manage: false,
+            start_message: None,
+            extends: extends.map(String::from),
+        }
+    }
+
+    fn bundle(roles: Vec<AgentRole>) -> RoleBundle {
+        RoleBundle {
+            format: BUNDLE_FORMAT.into(),
+            version: BUNDLE_VERSION,
+            exported_at: String::new(),
+            roles,
+        }
+    }
+
+    #[test]
+    fn push_version_skips_unchanged_and_caps_history() {
+        let mut versions = Vec::new();
+        assert!(push_version(&mut versions, &role("R", "v1", None), "t".into()));
+        assert!(!push_version(&mut versions, &role("R", "v1", None), "t".into()));
+        for i in 0..MAX_VERSIONS_PER_ROLE + 5 {
+            push_version(&mut versions, &role("R", &format!("p{i}"), None), "t".into());
+        }
+        assert_eq!(versions.len(), MAX_VERSIONS_PER_ROLE);
+        assert_eq!(versions.last().unwrap().version, MAX_VERSIONS_PER_ROLE as u32 + 6);
+    }
+
+    #[test]
+    fn export_pulls_in_custom_parents() {
+        let custom = vec![
+            role("Base", "b", None),
+            role("Child", "c", Some("Base")),
+            role("Other", "o", None),
+        ];
+        let names = vec!["child".to_string()];
+        let out = collect_for_export(&custom, Some(&names)).unwrap();
+        let out_names: Vec<&str> = out.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(out_names, vec!["Child", "Base"]);
+        assert!(collect_for_export(&custom, Some(&["nope".to_string()])).is_err());
+    }
+
+    #[test]
+    fn merge_respects_overwrite_flag() {
+        let mut custom = vec![role("Existing", "old", None)];
+        let (report, changed) = merge_bundle(
+            &mut custom,
+            bundle(vec![role("Existing", "new", None), role("Fresh", "f", Some("Existing"))]),
+            false,
+        )
+        .unwrap();
+        assert_eq!(report.skipped, vec!["Existing"]);
+        assert_eq!(report.imported, vec!["Fresh"]);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(custom[0].system_prompt, "old");
+
+        let (report, _) =
+            merge_bundle(&mut custom, bundle(vec![role("existing", "new", None)]), true).unwrap();
+        assert_eq!(report.imported, vec!["existing"]);
+        assert_eq!(custom.len(), 2);
+        assert_eq!(custom[0].system_prompt, "new");
+    }
+
+    #[test]
+    fn merge_rejects_foreign_or_broken_bundles() {
+        let mut custom = Vec::new();
+        let mut foreign = bundle(vec![role("A", "a", None)]);
+        foreign.format = "something-else".into();
+        assert!(merge_bundle(&mut custom, foreign, true).is_err());
+        let broken = bundle(vec![role("A", "a", Some("Nowhere"))]);
+        assert!(merge_bundle(&mut custom, broken, true).is_err());
+    }
+}
@@ -5,9 +5,7 @@ use crate::file_ops::{read_json, write_json};
 use crate::settings;
 use std::path::PathBuf;
 
-pub const DEFAULT_START_MESSAGE: &str = "привет";
-
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct AgentRole {
     pub name: String,
     pub system_prompt: String,
@@ -15,6 +13,9 @@ pub struct AgentRole {
     pub can_manage: bool,
     #[serde(default)]
     pub start_message: Option<String>,
+    /// Parent role name: its system prompt and tools are inherited and this role's are appended.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
 }
 
 impl PartialEq for AgentRole {
@@ -23,53 +24,188 @@ impl PartialEq for AgentRole {
     }
 }
 
-/// Predefined roles shipped with the app.
+/// Built-in role set for one language, bundled from `resources/roles/{lang}.json`.
+#[derive(Deserialize)]
+struct RolePack {
+    /// Prompt sent to a freshly launched agent whose role has no start message.
+    start_message: String,
+    roles: Vec<AgentRole>,
+}
+
+/// Language used when the configured one has no role pack.
+const FALLBACK_LANGUAGE: &str = "en";
+
+/// (language code, pack JSON). Codes match `AppSettings::translation_language`;
+/// role names stay in English in every pack since presets and prompts refer to them.
+const ROLE_PACKS: &[(&str, &str)] = &[
+    ("en", include_str!("../../resources/roles/en.json")),
+    ("ru", include_str!("../../resources/roles/ru.json")),
+    ("es", include_str!("../../resources/roles/es.json")),
+    ("fr", include_str!("../../resources/roles/fr.json")),
+    ("zh", include_str!("../../resources/roles/zh.json")),
+    ("ja", include_str!("../../resources/roles/ja.json")),
+];
+
+fn role_pack(lang: &str) -> RolePack {
+    let json = ROLE_PACKS
+        .iter()
+        .find(|(code, _)| *code == lang)
+        .or_else(|| ROLE_PACKS.iter().find(|(code, _)| *code == FALLBACK_LANGUAGE))
+        .map(|(_, json)| *json)
+        .unwrap_or("");
+    serde_json::from_str(json).unwrap_or_else(|e| {
+        eprintln!("[teamwork] Bundled role pack '{lang}' is invalid: {e}");
+        RolePack {
+            start_message: String::new(),
+            roles: Vec::new(),
+        }
+    })
+}
+
+/// Language of the built-in roles: the translation language, English when unset.
+fn roles_language() -> String {
+    let lang = settings::get_translation_language();
+    if lang.is_empty() {
+        FALLBACK_LANGUAGE.to_string()
+    } else {
+        lang
+    }
+}
+
+/// Predefined roles shipped with the app, in the configured language.
 pub fn default_roles() -> Vec<AgentRole> {
-    vec![
-        AgentRole {
-            name: "Agent".into(),
-            system_prompt: "Не додумывай за пользователя. Не делай лишнего. Если не уверен — спроси. Если сломал — скажи сразу.".into(),
-            allowed_tools: vec!["Edit","Write","Bash","Glob","Grep","Read"].into_iter().map(String::from).collect(),
-            can_manage: false,
-            start_message: Some(DEFAULT_START_MESSAGE.to_string()),
-        },
-        AgentRole {
-            name: "Team Lead".into(),
-            system_prompt: "Ты тимлид. Посредник между пользователем и командой агентов. Общение с агентами — только через MCP.\nПолучаешь задачу от пользователя → разбиваешь на подзадачи → раздаёшь агентам.\nКод не пишешь, файлы не трогаешь. Но читать код и искать информацию по проекту можешь сам — не нагружай этим других.\nДля поиска информации во внешних источниках (интернет, документация библиотек, конкуренты, другие проекты) — отправляй задачу ресёрчеру.\nКогда кодер отчитался — отправляешь ревьюеру. Когда ревьюер нашёл проблемы — возвращаешь кодеру.\nНе додумывай за пользователя. Если задача непонятна — уточни у пользователя, а не у агентов.".into(),
-            allowed_tools: vec!["Read","Glob","Grep"].into_iter().map(String::from).collect(),
-            can_manage: false,
-            start_message: Some("Ты тимлид. Команда только что запущена.\n\nПервым делом:\n1. Вызови list_agents — посмотри кто в команде и их роли.\n2. Жди пока все агенты напишут тебе что готовы к работе. Не пиши им первым — они сами отпишутся.\n3. Когда все отчитались — сообщи пользователю что команда собрана и готова.\n\nКак работать дальше:\n- Обсуждаешь задачу с пользователем, уточняешь если что-то непонятно.\n- Если нужно разобраться в коде — отправляешь задачу ресёрчеру.\n- Если нужно написать или исправить код — отправляешь задачу кодеру.\n- После каждого изменения кода — отправляешь на проверку ревьюеру.\n- Если ревьюер нашёл проблемы — возвращаешь кодеру на исправление.\n- Вся коммуникация с агентами — через send_message.".to_string()),
-        },
-        AgentRole {
-            name: "Coder".into(),
-            system_prompt: "Ты работаешь в команде агентов. Общение с другими агентами — только через MCP.\nТы пишешь код и вносишь изменения. Когда задача выполнена — коммитишь и сообщаешь через MCP — потому что ревью делается по коммитам.\nЕсли что-то непонятно — спроси через MCP. Не додумывай требования сам — неверные допущения дороже вопроса.\nСледуй правилам проекта из CLAUDE.md — там конвенции и запреты, нарушения придётся переделывать.".into(),
-            allowed_tools: vec!["Edit","Write","Bash","Glob","Grep","Read"].into_iter().map(String::from).collect(),
-            can_manage: false,
-            start_message: Some("Ты работаешь в команде. Твоя роль — кодер.\n\nПервым делом:\n1. Вызови list_agents — посмотри кто в команде.\n2. Найди агента с ролью \"Team Lead\" и отправь ему через send_message что ты готов к работе.\n3. Жди задачу от тимлида.\n\nЗадания получаешь от тимлида, результат отправляешь ему же через send_message.".to_string()),
-        },
-        AgentRole {
-            name: "Reviewer".into(),
-            system_prompt: "Ты работаешь в команде агентов. Общение с другими агентами — только через MCP.\nТы проверяешь закоммиченные изменения через git diff — так видны только реальные правки, а не весь проект.\nНе правишь код сам — только описываешь проблемы. Исправления делает Кодер, иначе потеряется ответственность за код.\nРезультат ревью отправляешь через MCP.\nСледуй правилам проекта из CLAUDE.md — проверяй их соблюдение, это главный критерий ревью.".into(),
-            allowed_tools: vec!["Read","Glob","Grep"].into_iter().map(String::from).collect(),
-            can_manage: false,
-            start_message: Some("Ты работаешь в команде. Твоя роль — ревьюер.\n\nПервым делом:\n1. Вызови list_agents — посмотри кто в команде.\n2. Найди агента с ролью \"Team Lead\" и отправь ему через send_message что ты готов к работе.\n3. Жди задачу от тимлида.\n\nЗадания на проверку кода получаешь от тимлида, результат ревью отправляешь ему же через send_message.".to_string()),
-        },
-        AgentRole {
-            name: "Researcher".into(),
-            system_prompt: "Ты работаешь в команде агентов. Общение с другими агентами — только через MCP.\nТы ищешь информацию и собираешь выжимку. Читаешь документацию, код, логи, веб-ресурсы.\nРезультат — краткий отчёт с фактами и ссылками на источники. Не додумывай — если не нашёл, так и скажи.\nОтправляешь результат через MCP.".into(),
-            allowed_tools: vec!["Read","Glob","Grep","Bash"].into_iter().map(String::from).collect(),
-            can_manage: false,
-            start_message: Some("Ты работаешь в команде. Твоя роль — ресёрчер.\n\nПервым делом:\n1. Вызови list_agents — посмотри кто в команде.\n2. Найди агента с ролью \"Team Lead\" и отправь ему через send_message что ты готов к работе.\n3. Жди задачу от тимлида.\n\nЗадания на исследование получаешь от тимлида, результат отправляешь ему же через send_message.".to_string()),
-        },
-    ]
+    role_pack(&roles_language()).roles
+}
+
+/// First prompt for an agent whose role has no start message, in the configured language.
+pub fn default_start_message() -> String {
+    role_pack(&roles_language()).start_message
 }
 
 /// Wrapper returned by roles_list — includes is_default flag.
+/// `role` is the effective role (inheritance applied); for a role that
+/// `extends` another, `definition` holds the stored, editable form.
 #[derive(Serialize)]
 pub struct RoleEntry {
     #[serde(flatten)]
     pub role: AgentRole,
     pub is_default: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub definition: Option<AgentRole>,
+}
+
+/// Longest allowed `extends` chain.
+const MAX_EXTENDS_DEPTH: usize = 8;
+
+/// Stored definition for a role name: custom override first, then default.
+fn lookup_role<'a>(
+    name: &str,
+    custom: &'a [AgentRole],
+    defaults: &'a [AgentRole],
+) -> Option<&'a AgentRole> {
+    custom
+        .iter()
+        .find(|r| r.name.eq_ignore_ascii_case(name))
+        .or_else(|| defaults.iter().find(|r| r.name.eq_ignore_ascii_case(name)))
+}
+
+/// Apply `extends` inheritance: ancestors' system prompts come first with each
+/// descendant's appended, tools are merged, and the nearest `start_message` wins.
+pub(crate) fn resolve_role(
+    role: &AgentRole,
+    custom: &[AgentRole],
+    defaults: &[AgentRole],
+) -> Result<AgentRole, String> {
+    let mut chain = vec![role];
+    let mut current = role;
+    while let Some(parent_name) = current.extends.as_deref().filter(|n| !n.trim().is_empty()) {
+        let parent_name = parent_name.trim();
+        if chain.iter().any(|r| r.name.eq_ignore_ascii_case(parent_name)) {
+            return Err(format!(
+                "Role '{}' has an inheritance cycle through '{parent_name}'",
+                role.name
+            ));
+        }
+        if chain.len() > MAX_EXTENDS_DEPTH {
+            return Err(format!(
+                "Role '{}' inheritance is deeper than {MAX_EXTENDS_DEPTH} levels",
+                role.name
+            ));
+        }
+        let parent = lookup_role(parent_name, custom, defaults).ok_or_else(|| {
+            format!("Role '{}' extends unknown role '{parent_name}'", current.name)
+        })?;
+        chain.push(parent);
+        current = parent;
+    }
+
+    let mut ancestors = chain.into_iter().rev();
+    let mut resolved = ancestors.next().unwrap_or(role).clone();
+    for r in ancestors {
+        let own_prompt = r.system_prompt.trim();
+        if !own_prompt.is_empty() {
+            if resolved.system_prompt.trim().is_empty() {
+                resolved.system_prompt = own_prompt.to_string();
+            } else {
+                resolved.system_prompt = format!("{}\n\n{own_prompt}", resolved.system_prompt);
+            }
+        }
+        for tool in &r.allowed_tools {
+            if !resolved.allowed_tools.contains(tool) {
+                resolved.allowed_tools.push(tool.clone());
+            }
+        }
+        if r.start_message.is_some() {
+            resolved.start_message = r.start_message.clone();
+        }
+        resolved.name = r.name.clone();
+        resolved.can_manage = r.can_manage;
+    }
+    resolved.extends = role.extends.clone();
+    Ok(resolved)
+}
+
+/// Find a role by name (custom overrides first) with inheritance applied.
+pub(crate) fn find_role(name: &str) -> Result<AgentRole, String> {
+    let custom = read_custom_roles_sync();
+    let defaults = default_roles();
+    let role = lookup_role(name, &custom, &defaults)
+        .ok_or_else(|| format!("Role '{name}' not found"))?;
+    resolve_role(role, &custom, &defaults)
+}
+
+/// Normalize a role before storing: trimmed name, empty `extends` → None.
+pub(super) fn normalize_role(mut role: AgentRole) -> Result<AgentRole, String> {
+    role.name = role.name.trim().to_string();
+    if role.name.is_empty() {
+        return Err("Role name cannot be empty".to_string());
+    }
+    role.extends = role
+        .extends
+        .map(|e| e.trim().to_string())
+        .filter(|e| !e.is_empty());
+    if let Some(message) = &role.start_message {
+        crate::prompt_template::validate(message).map_err(|e| format!("Start message: {e}"))?;
+    }
+    Ok(role)
+}
+
+/// Insert or replace a custom role (case-insensitive name match).
+pub(super) fn upsert_role(custom: &mut Vec<AgentRole>, role: AgentRole) {
+    if let Some(existing) = custom.iter_mut().find(|r| r.name.eq_ignore_ascii_case(&role.name)) {
+        *existing = role;
+    } else {
+        custom.push(role);
+    }
+}
+
+/// Check that every custom role still resolves (no cycles or dangling parents).
+pub(super) fn validate_all(custom: &[AgentRole]) -> Result<(), String> {
+    let defaults = default_roles();
+    for role in custom {
+        resolve_role(role, custom, &defaults)?;
+    }
+    Ok(())
 }
 
 /// Path to custom roles file: ~/.config/aither-flow/custom_roles.json
@@ -78,7 +214,7 @@ fn custom_roles_path() -> PathBuf {
 }
 
 /// Read custom roles from disk (sync).
-fn read_custom_roles_sync() -> Vec<AgentRole> {
+pub(super) fn read_custom_roles_sync() -> Vec<AgentRole> {
     let path = custom_roles_path();
     if !path.exists() {
         return Vec::new();
@@ -90,7 +226,7 @@ fn read_custom_roles_sync() -> Vec<AgentRole> {
 }
 
 /// Write custom roles to disk (sync).
-fn write_custom_roles_sync(roles: &[AgentRole]) -> Result<(), String> {
+pub(super) fn write_custom_roles_sync(roles: &[AgentRole]) -> Result<(), String> {
     write_json(&custom_roles_path(), roles)
 }
 
@@ -106,22 +242,33 @@ pub async fn roles_list() -> Result<Vec<RoleEntry>, String> {
         let default_names = default_role_names();
         let custom = read_custom_roles_sync();
 
+        let entry = |stored: &AgentRole, is_default: bool| {
+            let resolved = resolve_role(stored, &custom, &defaults).unwrap_or_else(|e| {
+                eprintln!("[teamwork] {e}");
+                stored.clone()
+            });
+            RoleEntry {
+                role: resolved,
+                is_default,
+                definition: stored.extends.is_some().then(|| stored.clone()),
+            }
+        };
+
         let mut entries: Vec<RoleEntry> = Vec::new();
 
         // For each default role: use custom override if present, otherwise default
-        for def in defaults {
-            let is_default = true;
-            if let Some(overridden) = custom.iter().find(|c| c.name.eq_ignore_ascii_case(&def.name)) {
-                entries.push(RoleEntry { role: overridden.clone(), is_default });
-            } else {
-                entries.push(RoleEntry { role: def, is_default });
-            }
+        for def in &defaults {
+            let stored = custom
+                .iter()
+                .find(|c| c.name.eq_ignore_ascii_case(&def.name))
+                .unwrap_or(def);
+            entries.push(entry(stored, true));
         }
 
         // Add purely custom roles (not overriding a default)
-        for cr in custom {
+        for cr in &custom {
             if !default_names.iter().any(|n| n.eq_ignore_ascii_case(&cr.name)) {
-                entries.push(RoleEntry { role: cr, is_default: false });
+                entries.push(entry(cr, false));
             }
         }
 
@@ -134,17 +281,13 @@ pub async fn roles_list() -> Result<Vec<RoleEntry>, String> {
 #[tauri::command]
 pub async fn roles_save(role: AgentRole) -> Result<(), String> {
     tokio::task::spawn_blocking(move || {
-        if role.name.trim().is_empty() {
-            return Err("Role name cannot be empty".to_string());
-        }
+        let role = normalize_role(role)?;
         let mut custom = read_custom_roles_sync();
-        // Update existing or append (case-insensitive match)
-        if let Some(existing) = custom.iter_mut().find(|r| r.name.eq_ignore_ascii_case(&role.name)) {
-            *existing = role;
-        } else {
-            custom.push(role);
-        }
-        write_custom_roles_sync(&custom)
+        upsert_role(&mut custom, role.clone());
+        validate_all(&custom)?;
+        write_custom_roles_sync(&custom)?;
+        super::role_library::record_version(&role);
+        Ok(())
     })
     .await
     .map_err(|e| format!("Task join error: {e}"))?
@@ -159,6 +302,8 @@ pub async fn roles_delete(name: String) -> Result<(), String> {
         if custom.len() == before {
             return Err(format!("Custom role '{name}' not found"));
         }
+        // Resetting a default override is fine; deleting a parent that others extend is not
+        validate_all(&custom)?;
         write_custom_roles_sync(&custom)
     })
     .await
@@ -197,3 +342,103 @@ pub async fn roles_set_default(name: String) -> Result<(), String> {
     .await
     .map_err(|e| format!("Task join error: {e}"))?
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn role(name: &str, prompt: &str, tools: &[&str], extends: Option<&str>) -> AgentRole {
+        AgentRole {
+            name: name.into(),
+            system_prompt: prompt.into(),
+            allowed_tools: tools.iter().map(|t| t.to_string()).collect(),
+            can_manage: false,
+            start_message: None,
+            extends: extends.map(String::from),
+        }
+    }
+
+    #[test]
+    fn resolve_appends_prompt_and_merges_tools() {
+        let defaults = vec![role("Base", "base rules", &["Read", "Grep"], None)];
+        let custom = vec![
+            role("Mid", "mid rules", &["Bash"], Some("Base")),
+            role("Leaf", "leaf rules", &["Read", "Edit"], Some("Mid")),
+        ];
+        let leaf = resolve_role(&custom[1], &custom, &defaults).unwrap();
+        assert_eq!(leaf.name, "Leaf");
+        assert_eq!(leaf.system_prompt, "base rules\n\nmid rules\n\nleaf rules");
+        assert_eq!(leaf.allowed_tools, vec!["Read", "Grep", "Bash", "Edit"]);
+        assert_eq!(leaf.extends.as_deref(), Some("Mid"));
+    }
+
+    #[test]
+    fn resolve_keeps_nearest_start_message() {
+        let mut base = role("Base", "p", &[], None);
+        base.start_message = Some("from base".into());
+        let child = role("Child", "", &[], Some("base"));
+        let resolved = resolve_role(&child, &[], &[base]).unwrap();
+        assert_eq!(resolved.start_message.as_deref(), Some("from base"));
+        assert_eq!(resolved.system_prompt, "p");
+    }
+
+    #[test]
+    fn resolve_custom_override_shadows_default_parent() {
+        let defaults = vec![role("Coder", "default coder", &["Read"], None)];
+        let custom = vec![
+            role("Coder", "my coder", &["Read"], None),
+            role("Rust Coder", "rust", &[], Some("Coder")),
+        ];
+        let resolved = resolve_role(&custom[1], &custom, &defaults).unwrap();
+        assert_eq!(resolved.system_prompt, "my coder\n\nrust");
+    }
+
+    #[test]
+    fn resolve_rejects_cycles_and_unknown_parents() {
+        let custom = vec![
+            role("A", "", &[], Some("B")),
+            role("B", "", &[], Some("a")),
+            role("C", "", &[], Some("Missing")),
+            role("Self", "", &[], Some("Self")),
+        ];
+        assert!(resolve_role(&custom[0], &custom, &[]).unwrap_err().contains("cycle"));
+        assert!(resolve_role(&custom[2], &custom, &[]).unwrap_err().contains("unknown role"));
+        assert!(resolve_role(&custom[3], &custom, &[]).is_err());
+    }
+
+    #[test]
+    fn role_packs_parse_with_matching_role_names() {
+        let names = |lang: &str| -> Vec<String> {
+            role_pack(lang).roles.into_iter().map(|r| r.name).collect()
+        };
+        let expected = names(FALLBACK_LANGUAGE);
+        assert_eq!(expected.len(), 5);
+        for (lang, _) in ROLE_PACKS {
+            let pack = role_pack(lang);
+            assert!(!pack.start_message.is_empty(), "{lang}: empty start message");
+            assert_eq!(names(lang), expected, "{lang}: role names differ");
+            assert!(
+                pack.roles.iter().all(|r| !r.system_prompt.is_empty() && r.start_message.is_some()),
+                "{lang}: incomplete role"
+            );
+            assert!(
+                pack.roles.iter().all(|r| normalize_role(r.clone()).is_ok()),
+                "{lang}: start message is not a valid template"
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_language_falls_back_to_english() {
+        assert_eq!(role_pack("xx").start_message, role_pack("en").start_message);
+        assert_eq!(role_pack("ru").start_message, "привет");
+    }
+
+    #[test]
+    fn resolve_without_extends_is_identity() {
+        let r = role("Solo", "prompt", &["Read"], None);
+        let resolved = resolve_role(&r, &[], &[]).unwrap();
+        assert_eq!(resolved.system_prompt, "prompt");
+        assert_eq!(resolved.allowed_tools, vec!["Read"]);
+    }
+}
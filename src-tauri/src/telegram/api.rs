@@ -3,6 +3,8 @@ use serde::Deserialize;
 use super::{TgFile, TgResponse, TgUpdate, TgUser};
 
 const TG_API: &str = "https://api.telegram.org/bot";
+/// Longest chunk sent as one message (Telegram allows 4096 chars).
+pub(crate) const MAX_MESSAGE_LEN: usize = 4000;
 
 pub(crate) fn sanitize_error(err: &str, token: &str) -> String {
     if token.is_empty() {
@@ -61,7 +63,7 @@ pub(crate) async fn tg_send_message(
     chat_id: i64,
     text: &str,
 ) -> Result<(), String> {
-    let chunks = split_message(text, 4000);
+    let chunks = split_message(text, MAX_MESSAGE_LEN);
     for chunk in chunks {
         let url = format!("{TG_API}{token}/sendMessage");
         let body = serde_json::json!({
@@ -469,3 +469,26 @@ pub fn telegram_stream_reset() {
         }
     });
 }
+
+/// Send an agent's approval request with one inline button per option.
+/// Callback data is "approval:{id}:{index}" — a UUID plus index stays under 64 bytes,
+/// and it doesn't go through the callback registry so other menus can't invalidate it.
+pub(crate) async fn send_approval_to_telegram(
+    id: &str,
+    question: &str,
+    options: &[String],
+) -> Result<(), String> {
+    let (token, chat_id, client) = get_bot_connection()?;
+    let buttons: Vec<Vec<serde_json::Value>> = options
+        .iter()
+        .enumerate()
+        .map(|(idx, option)| {
+            vec![serde_json::json!({
+                "text": option,
+                "callback_data": format!("approval:{id}:{idx}"),
+            })]
+        })
+        .collect();
+    let text = format!("Approval requested:\n\n{question}");
+    tg_send_inline_keyboard(&client, &token, chat_id, &text, buttons).await
+}
@@ -47,6 +47,26 @@ pub(super) async fn handle_callback(
         return;
     }
 
+    // Approval buttons carry their own payload (not in the callback registry)
+    if let Some(rest) = data.strip_prefix("approval:") {
+        let reply = match rest
+            .rsplit_once(':')
+            .and_then(|(id, idx)| Some((id, idx.parse::<usize>().ok()?)))
+        {
+            Some((id, idx)) => {
+                match crate::teamwork::approvals::answer_approval_by_index(id, idx, "telegram") {
+                    Ok(answer) => format!("Answered: {answer}"),
+                    Err(e) => format!("Approval failed: {e}"),
+                }
+            }
+            None => format!("Invalid approval callback: {rest}"),
+        };
+        if let Err(e) = tg_send_message(client, token, chat_id, &reply).await {
+            eprintln!("[TG] confirm approval: {e}");
+        }
+        return;
+    }
+
     // Resolve indexed callback to actual payload
     let resolved;
     let data = if data.starts_with("cb:") {
@@ -103,6 +103,14 @@ function formatSchedule(s: ScheduledTask["schedule"]): string {
     }
     case "cron":
       return s.expression;
+    case "file_change":
+      return "on file change";
+    case "git_commit":
+      return `on ${s.branch} commits`;
+    case "webhook":
+      return "on webhook";
+    case "manual":
+      return "manual";
   }
 }
 
@@ -1,20 +1,26 @@
-import { memo, useCallback, useMemo, useState } from "react";
-import { Plus, Trash2, Database, X, RefreshCw } from "lucide-react";
+import { memo, useCallback, useEffect, useMemo, useState } from "react";
+import { Plus, Trash2, Database, X, RefreshCw, Cpu, Copy, Download } from "lucide-react";
 import { useShallow } from "zustand/react/shallow";
 import { useKnowledgeStore } from "../../stores/knowledgeStore";
 import { DocumentList } from "./DocumentList";
 import { SearchPanel } from "./SearchPanel";
+import { SourcesPanel } from "./SourcesPanel";
 import { AddDocumentModal } from "./AddDocumentModal";
+import { DuplicatesModal } from "./DuplicatesModal";
 import { Modal } from "../Modal";
 import { Tooltip } from "../shared/Tooltip";
+import { invoke, saveDialog } from "../../lib/transport";
+import type { EmbeddingModelInfo } from "../../types/knowledge";
 
 export const BaseDetail = memo(function BaseDetail() {
-  const { bases, selectedBaseId, deleteBase, reindexBase, bgOperation, bgResult, clearBgResult, error, clearError } = useKnowledgeStore(
+  const { bases, selectedBaseId, deleteBase, reindexBase, migrateBase, exportBase, bgOperation, bgResult, clearBgResult, error, clearError } = useKnowledgeStore(
     useShallow((s) => ({
       bases: s.bases,
       selectedBaseId: s.selectedBaseId,
       deleteBase: s.deleteBase,
       reindexBase: s.reindexBase,
+      migrateBase: s.migrateBase,
+      exportBase: s.exportBase,
       bgOperation: s.bgOperation,
       bgResult: s.bgResult,
       clearBgResult: s.clearBgResult,
@@ -26,6 +32,10 @@ export const BaseDetail = memo(function BaseDetail() {
   const [addModalOpen, setAddModalOpen] = useState(false);
   const [deleteConfirmOpen, setDeleteConfirmOpen] = useState(false);
   const [reindexConfirmOpen, setReindexConfirmOpen] = useState(false);
+  const [modelModalOpen, setModelModalOpen] = useState(false);
+  const [duplicatesOpen, setDuplicatesOpen] = useState(false);
+  const [models, setModels] = useState<EmbeddingModelInfo[]>([]);
+  const [targetModel, setTargetModel] = useState("");
 
   const base = useMemo(
     () => bases.find((b) => b.id === selectedBaseId) ?? null,
@@ -45,9 +55,42 @@ export const BaseDetail = memo(function BaseDetail() {
     reindexBase(base.id);
   }, [base, reindexBase]);
 
+  useEffect(() => {
+    if (!modelModalOpen) return;
+    invoke<EmbeddingModelInfo[]>("rag_get_available_models")
+      .then(setModels)
+      .catch(console.error);
+  }, [modelModalOpen]);
+
+  const openModelModal = useCallback(() => {
+    setTargetModel("");
+    setModelModalOpen(true);
+  }, []);
+
+  const handleModelConfirm = useCallback(() => {
+    if (!base || !targetModel) return;
+    setModelModalOpen(false);
+    migrateBase(base.id, targetModel);
+  }, [base, targetModel, migrateBase]);
+
+  const handleExport = useCallback(async () => {
+    if (!base) return;
+    try {
+      const path = await saveDialog({
+        defaultPath: `${base.name}.zip`,
+        filters: [{ name: "Knowledge base archive", extensions: ["zip"] }],
+        title: "Export knowledge base",
+      });
+      if (path) exportBase(base.id, path);
+    } catch (e) {
+      console.error("Failed to open save dialog:", e);
+    }
+  }, [base, exportBase]);
+
   // Is any bg operation running for this base?
   const isBusy = bgOperation !== null && bgOperation.baseId === base?.id;
   const isReindexing = isBusy && bgOperation?.type === "reindex";
+  const isMigrating = isBusy && bgOperation?.type === "migrate";
   // Show result only for current base
   const visibleResult = bgResult && base && bgResult.baseId === base.id ? bgResult : null;
 
@@ -112,7 +155,8 @@ export const BaseDetail = memo(function BaseDetail() {
         </div>
         {base.description && <p className="kb-detail__desc">{base.description}</p>}
         <div className="kb-detail__stats">
-          <span>{base.documentCount} documents</span>
+          <span>{base.documentCount} {base.kind === "codebase" ? "files" : "documents"}</span>
+          <span>{base.embeddingModel}</span>
         </div>
         <div className="kb-detail__actions">
           <Tooltip text="Add documents">
@@ -131,6 +175,28 @@ export const BaseDetail = memo(function BaseDetail() {
               <span>{isReindexing ? "Reindexing…" : "Reindex All"}</span>
             </button>
           </Tooltip>
+          <Tooltip text="Re-embed with another model">
+            <button className="kb-btn kb-btn--secondary" onClick={openModelModal} disabled={isBusy}>
+              <Cpu size={14} className={isMigrating ? "kb-spin" : ""} />
+              <span>{isMigrating ? "Re-embedding…" : "Change Model"}</span>
+            </button>
+          </Tooltip>
+          <Tooltip text="Find repeated chunks">
+            <button
+              className="kb-btn kb-btn--secondary"
+              onClick={() => setDuplicatesOpen(true)}
+              disabled={isBusy || base.documentCount === 0}
+            >
+              <Copy size={14} />
+              <span>Duplicates</span>
+            </button>
+          </Tooltip>
+          <Tooltip text="Export as a single archive, with embeddings">
+            <button className="kb-btn kb-btn--secondary" onClick={handleExport} disabled={isBusy}>
+              <Download size={14} />
+              <span>Export</span>
+            </button>
+          </Tooltip>
           <Tooltip text="Delete knowledge base">
             <button className="kb-btn kb-btn--danger" onClick={() => setDeleteConfirmOpen(true)}>
               <Trash2 size={14} />
@@ -141,9 +207,11 @@ export const BaseDetail = memo(function BaseDetail() {
       </div>
 
       <SearchPanel baseId={base.id} />
+      <SourcesPanel baseId={base.id} disabled={isBusy} />
       <DocumentList baseId={base.id} />
 
       <AddDocumentModal open={addModalOpen} baseId={base.id} onClose={() => setAddModalOpen(false)} />
+      <DuplicatesModal open={duplicatesOpen} baseId={base.id} onClose={() => setDuplicatesOpen(false)} />
 
       <Modal
         open={deleteConfirmOpen}
@@ -168,6 +236,35 @@ export const BaseDetail = memo(function BaseDetail() {
       >
         <p>Reindex all documents in &ldquo;{base.name}&rdquo;? This will re-parse, re-chunk, and re-embed all local documents. Web and YouTube sources will be skipped. This may take a while.</p>
       </Modal>
+
+      <Modal
+        open={modelModalOpen}
+        title="Change Embedding Model"
+        onClose={() => setModelModalOpen(false)}
+        actions={[
+          { label: "Cancel", onClick: () => setModelModalOpen(false) },
+          { label: "Re-embed", variant: "accent", onClick: handleModelConfirm, disabled: !targetModel },
+        ]}
+      >
+        <div className="kb-form">
+          <p>
+            &ldquo;{base.name}&rdquo; is embedded with {base.embeddingModel}. All chunks, including web and YouTube
+            sources, will be re-embedded in the background; search keeps working with the current model until it
+            finishes.
+          </p>
+          <label className="kb-form__label">
+            New model
+            <select className="kb-form__input" value={targetModel} onChange={(e) => setTargetModel(e.target.value)}>
+              <option value="" disabled>Select a model…</option>
+              {models
+                .filter((m) => m.id !== base.embeddingModel)
+                .map((m) => (
+                  <option key={m.id} value={m.id}>{m.id} — {m.label}</option>
+                ))}
+            </select>
+          </label>
+        </div>
+      </Modal>
     </div>
   );
 });
@@ -1,19 +1,23 @@
 import { memo, useCallback, useEffect, useState } from "react";
-import { Plus, Database } from "lucide-react";
+import { Plus, Database, Upload, Loader2 } from "lucide-react";
 import { useShallow } from "zustand/react/shallow";
 import { useKnowledgeStore } from "../../stores/knowledgeStore";
 import { CreateBaseModal } from "./CreateBaseModal";
 import { Tooltip } from "../shared/Tooltip";
+import { openDialog } from "../../lib/transport";
 
 export const BaseList = memo(function BaseList() {
-  const { bases, selectedBaseId, loadBases, selectBase } = useKnowledgeStore(
+  const { bases, selectedBaseId, loadBases, selectBase, importBase, bgOperation } = useKnowledgeStore(
     useShallow((s) => ({
       bases: s.bases,
       selectedBaseId: s.selectedBaseId,
       loadBases: s.loadBases,
       selectBase: s.selectBase,
+      importBase: s.importBase,
+      bgOperation: s.bgOperation,
     })),
   );
+  const importing = bgOperation?.type === "import" ? bgOperation : null;
 
   const [createModalOpen, setCreateModalOpen] = useState(false);
 
@@ -28,17 +32,44 @@ export const BaseList = memo(function BaseList() {
     [selectedBaseId, selectBase],
   );
 
+  const handleImport = useCallback(async () => {
+    try {
+      const selected = await openDialog({
+        multiple: false,
+        filters: [{ name: "Knowledge base archive", extensions: ["zip"] }],
+        title: "Import knowledge base",
+      });
+      if (typeof selected === "string") importBase(selected);
+    } catch (e) {
+      console.error("Failed to open file dialog:", e);
+    }
+  }, [importBase]);
+
   return (
     <div className="kb-list">
       <div className="kb-list__header">
         <h3 className="kb-list__title">Knowledge Bases</h3>
-        <Tooltip text="Create new base">
-          <button className="kb-list__add-btn" onClick={() => setCreateModalOpen(true)}>
-            <Plus size={16} />
-          </button>
-        </Tooltip>
+        <div className="kb-list__actions">
+          <Tooltip text="Import exported base">
+            <button className="kb-list__add-btn kb-list__add-btn--secondary" onClick={handleImport} disabled={importing !== null}>
+              {importing ? <Loader2 size={16} className="kb-status__spinner" /> : <Upload size={16} />}
+            </button>
+          </Tooltip>
+          <Tooltip text="Create new base">
+            <button className="kb-list__add-btn" onClick={() => setCreateModalOpen(true)}>
+              <Plus size={16} />
+            </button>
+          </Tooltip>
+        </div>
       </div>
 
+      {importing && (
+        <div className="kb-list__importing">
+          {importing.total > 1 ? `${importing.processed}/${importing.total} · ` : ""}
+          {importing.label}
+        </div>
+      )}
+
       <div className="kb-list__items">
         {bases.length === 0 ? (
           <div className="kb-empty">
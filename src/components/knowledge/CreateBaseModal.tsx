@@ -1,6 +1,9 @@
-import { memo, useCallback, useState } from "react";
+import { memo, useCallback, useEffect, useState } from "react";
+import { FolderOpen } from "lucide-react";
 import { Modal } from "../Modal";
 import { useKnowledgeStore } from "../../stores/knowledgeStore";
+import { invoke, openDialog } from "../../lib/transport";
+import type { BaseKind, EmbeddingModelInfo } from "../../types/knowledge";
 
 interface CreateBaseModalProps {
   open: boolean;
@@ -10,19 +13,55 @@ interface CreateBaseModalProps {
 export const CreateBaseModal = memo(function CreateBaseModal({ open, onClose }: CreateBaseModalProps) {
   const [name, setName] = useState("");
   const [description, setDescription] = useState("");
+  // Empty = the default model from settings
+  const [model, setModel] = useState("");
+  const [models, setModels] = useState<EmbeddingModelInfo[]>([]);
+  const [kind, setKind] = useState<BaseKind>("documents");
+  const [root, setRoot] = useState("");
   const createBase = useKnowledgeStore((s) => s.createBase);
 
+  useEffect(() => {
+    if (!open) return;
+    invoke<EmbeddingModelInfo[]>("rag_get_available_models")
+      .then(setModels)
+      .catch(console.error);
+  }, [open]);
+
   const resetForm = useCallback(() => {
     setName("");
     setDescription("");
+    setModel("");
+    setKind("documents");
+    setRoot("");
   }, []);
 
+  const handlePickRoot = useCallback(async () => {
+    try {
+      const selected = await openDialog({ directory: true, multiple: false, title: "Select project folder" });
+      if (typeof selected === "string") {
+        setRoot(selected);
+        if (!name.trim()) setName(selected.split(/[\\/]/).filter(Boolean).pop() ?? "");
+      }
+    } catch (e) {
+      console.error("Failed to open folder dialog:", e);
+    }
+  }, [name]);
+
+  const isCodebase = kind === "codebase";
+  const canCreate = name.trim() !== "" && (!isCodebase || root !== "");
+
   const handleCreate = useCallback(async () => {
-    if (!name.trim()) return;
-    await createBase(name.trim(), description.trim());
+    if (!canCreate) return;
+    await createBase(
+      name.trim(),
+      description.trim(),
+      model || undefined,
+      kind,
+      isCodebase ? root : undefined,
+    );
     resetForm();
     onClose();
-  }, [name, description, createBase, resetForm, onClose]);
+  }, [canCreate, name, description, model, kind, isCodebase, root, createBase, resetForm, onClose]);
 
   const handleClose = useCallback(() => {
     resetForm();
@@ -31,12 +70,31 @@ export const CreateBaseModal = memo(function CreateBaseModal({ open, onClose }:
 
   const actions = [
     { label: "Cancel", onClick: handleClose },
-    { label: "Create", variant: "accent" as const, onClick: handleCreate, disabled: !name.trim() },
+    { label: "Create", variant: "accent" as const, onClick: handleCreate, disabled: !canCreate },
   ];
 
   return (
     <Modal open={open} title="Create Knowledge Base" onClose={handleClose} actions={actions}>
       <div className="kb-form">
+        <label className="kb-form__label">
+          Type
+          <select className="kb-form__input" value={kind} onChange={(e) => setKind(e.target.value as BaseKind)}>
+            <option value="documents">Documents</option>
+            <option value="codebase">Codebase</option>
+          </select>
+        </label>
+        {isCodebase && (
+          <>
+            <button className="kb-file-picker" onClick={handlePickRoot}>
+              <FolderOpen size={18} />
+              <span>{root || "Choose project folder…"}</span>
+            </button>
+            <span className="kb-settings__hint">
+              Source files are indexed along functions and classes, skipping whatever .gitignore excludes, and kept in
+              sync as they change. Search results point to file and line.
+            </span>
+          </>
+        )}
         <label className="kb-form__label">
           Name
           <input
@@ -58,6 +116,15 @@ export const CreateBaseModal = memo(function CreateBaseModal({ open, onClose }:
             rows={3}
           />
         </label>
+        <label className="kb-form__label">
+          Embedding Model
+          <select className="kb-form__input" value={model} onChange={(e) => setModel(e.target.value)}>
+            <option value="">Default from settings</option>
+            {models.map((m) => (
+              <option key={m.id} value={m.id}>{m.id} — {m.label}</option>
+            ))}
+          </select>
+        </label>
       </div>
     </Modal>
   );
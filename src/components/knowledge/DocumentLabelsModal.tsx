@@ -0,0 +1,88 @@
+import { memo, useCallback, useEffect, useState } from "react";
+import { Modal } from "../Modal";
+import { useKnowledgeStore } from "../../stores/knowledgeStore";
+import type { KnowledgeDocument } from "../../types/knowledge";
+
+interface DocumentLabelsModalProps {
+  baseId: string;
+  /** Document being edited; the modal is closed when null. */
+  document: KnowledgeDocument | null;
+  onClose: () => void;
+}
+
+/** Parse "key=value" lines, ignoring blank lines. */
+function parseMetadata(text: string): Record<string, string> {
+  const metadata: Record<string, string> = {};
+  for (const line of text.split("\n")) {
+    const eq = line.indexOf("=");
+    if (eq === -1) continue;
+    metadata[line.slice(0, eq).trim()] = line.slice(eq + 1).trim();
+  }
+  return metadata;
+}
+
+export const DocumentLabelsModal = memo(function DocumentLabelsModal({
+  baseId,
+  document,
+  onClose,
+}: DocumentLabelsModalProps) {
+  const [tags, setTags] = useState("");
+  const [metadata, setMetadata] = useState("");
+  const setDocumentLabels = useKnowledgeStore((s) => s.setDocumentLabels);
+
+  useEffect(() => {
+    if (!document) return;
+    setTags((document.tags ?? []).join(", "));
+    setMetadata(
+      Object.entries(document.metadata ?? {})
+        .map(([k, v]) => `${k}=${v}`)
+        .join("\n"),
+    );
+  }, [document]);
+
+  const handleSave = useCallback(async () => {
+    if (!document) return;
+    await setDocumentLabels(baseId, document.id, {
+      tags: tags.split(",").map((t) => t.trim()).filter(Boolean),
+      metadata: parseMetadata(metadata),
+    });
+    onClose();
+  }, [baseId, document, tags, metadata, setDocumentLabels, onClose]);
+
+  const actions = [
+    { label: "Cancel", onClick: onClose },
+    { label: "Save", variant: "accent" as const, onClick: handleSave },
+  ];
+
+  return (
+    <Modal open={document !== null} title="Tags & Metadata" onClose={onClose} actions={actions}>
+      <div className="kb-form">
+        <span className="kb-settings__hint">{document?.filename}</span>
+        <label className="kb-form__label">
+          Tags
+          <input
+            className="kb-form__input"
+            type="text"
+            value={tags}
+            onChange={(e) => setTags(e.target.value)}
+            placeholder="rust, async"
+            autoFocus
+          />
+        </label>
+        <label className="kb-form__label">
+          Metadata
+          <textarea
+            className="kb-form__textarea"
+            value={metadata}
+            onChange={(e) => setMetadata(e.target.value)}
+            placeholder={"author=Jane Doe\nlang=en\ndate=2024-05"}
+            rows={4}
+          />
+        </label>
+        <span className="kb-settings__hint">
+          One key=value per line. Search can be limited to matching documents, e.g. tag:rust lang=en.
+        </span>
+      </div>
+    </Modal>
+  );
+});
@@ -1,7 +1,9 @@
-import { memo, useCallback } from "react";
-import { FileText, Trash2 } from "lucide-react";
+import { memo, useCallback, useState } from "react";
+import { FileText, Tag, Trash2 } from "lucide-react";
 import { useKnowledgeStore } from "../../stores/knowledgeStore";
 import { Tooltip } from "../shared/Tooltip";
+import { DocumentLabelsModal } from "./DocumentLabelsModal";
+import type { KnowledgeDocument } from "../../types/knowledge";
 
 interface DocumentListProps {
   baseId: string;
@@ -10,6 +12,7 @@ interface DocumentListProps {
 export const DocumentList = memo(function DocumentList({ baseId }: DocumentListProps) {
   const documents = useKnowledgeStore((s) => s.documents);
   const removeDocument = useKnowledgeStore((s) => s.removeDocument);
+  const [editing, setEditing] = useState<KnowledgeDocument | null>(null);
 
   const handleRemove = useCallback(
     (docId: string) => {
@@ -31,8 +34,21 @@ export const DocumentList = memo(function DocumentList({ baseId }: DocumentListP
             <span className="kb-doc-item__name">{doc.filename}</span>
             <span className="kb-doc-item__meta">
               {doc.chunkCount} chunks · {new Date(doc.addedAt).toLocaleDateString()}
+              {Object.entries(doc.metadata ?? {}).map(([k, v]) => ` · ${k}: ${v}`)}
             </span>
+            {doc.tags && doc.tags.length > 0 && (
+              <span className="kb-doc-item__tags">
+                {doc.tags.map((tag) => (
+                  <span key={tag} className="kb-doc-tag">{tag}</span>
+                ))}
+              </span>
+            )}
           </div>
+          <Tooltip text="Tags & metadata">
+            <button className="kb-doc-item__remove kb-doc-item__edit" onClick={() => setEditing(doc)}>
+              <Tag size={14} />
+            </button>
+          </Tooltip>
           <Tooltip text="Remove document">
             <button className="kb-doc-item__remove" onClick={() => handleRemove(doc.id)}>
               <Trash2 size={14} />
@@ -40,6 +56,7 @@ export const DocumentList = memo(function DocumentList({ baseId }: DocumentListP
           </Tooltip>
         </div>
       ))}
+      <DocumentLabelsModal baseId={baseId} document={editing} onClose={() => setEditing(null)} />
     </div>
   );
 });
@@ -0,0 +1,62 @@
+import { memo, useEffect, useState } from "react";
+import { Loader2 } from "lucide-react";
+import { Modal } from "../Modal";
+import { invoke } from "../../lib/transport";
+import type { DuplicateChunk, DuplicatePair } from "../../types/knowledge";
+
+interface DuplicatesModalProps {
+  open: boolean;
+  baseId: string;
+  onClose: () => void;
+}
+
+function describeChunk(c: DuplicateChunk): string {
+  return `${c.documentName || c.documentId} · chunk ${c.chunkIndex + 1}`;
+}
+
+export const DuplicatesModal = memo(function DuplicatesModal({ open, baseId, onClose }: DuplicatesModalProps) {
+  const [pairs, setPairs] = useState<DuplicatePair[] | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!open) return;
+    setPairs(null);
+    setError(null);
+    invoke<DuplicatePair[]>("rag_find_duplicates", { baseId })
+      .then(setPairs)
+      .catch((e) => setError(String(e)));
+  }, [open, baseId]);
+
+  return (
+    <Modal open={open} title="Duplicate Chunks" onClose={onClose} actions={[{ label: "Close", onClick: onClose }]}>
+      {error && <div className="kb-empty">{error}</div>}
+      {!error && pairs === null && (
+        <div className="kb-empty">
+          <Loader2 size={16} className="kb-status__spinner" /> Comparing chunks…
+        </div>
+      )}
+      {pairs?.length === 0 && <div className="kb-empty">No duplicate chunks found.</div>}
+      {pairs && pairs.length > 0 && (
+        <div className="kb-search__results kb-duplicates">
+          {pairs.map((p) => (
+            <div
+              key={`${p.first.documentId}-${p.first.chunkIndex}-${p.second.documentId}-${p.second.chunkIndex}`}
+              className="kb-search__result"
+            >
+              <div className="kb-search__result-header">
+                <span className="kb-search__result-doc">
+                  {describeChunk(p.first)} ↔ {describeChunk(p.second)}
+                </span>
+                <span className="kb-search__result-score">
+                  {p.exact ? "identical" : `${(p.score * 100).toFixed(1)}%`}
+                </span>
+              </div>
+              <div className="kb-search__result-text">{p.first.text}</div>
+              {!p.exact && <div className="kb-search__result-text kb-duplicates__second">{p.second.text}</div>}
+            </div>
+          ))}
+        </div>
+      )}
+    </Modal>
+  );
+});
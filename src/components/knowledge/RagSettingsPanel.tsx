@@ -1,19 +1,19 @@
 import { memo, useCallback, useEffect, useMemo, useState } from "react";
-import { Save, AlertTriangle, RotateCcw } from "lucide-react";
+import { Save } from "lucide-react";
 import { useShallow } from "zustand/react/shallow";
 import { useKnowledgeStore } from "../../stores/knowledgeStore";
 import { invoke } from "../../lib/transport";
-import type { RagSettings } from "../../types/knowledge";
+import type { EmbeddingModelInfo, RagSettings } from "../../types/knowledge";
 
-interface ModelInfo {
+interface RerankerInfo {
   id: string;
   label: string;
-  dimension: number;
 }
 
 const CHUNK_SIZES = [256, 512, 1024, 2048];
 const ALL_OVERLAPS = [32, 64, 128, 256];
 const SEARCH_LIMITS = [5, 10, 20, 50];
+const RERANK_CANDIDATES = [20, 30, 50, 100];
 
 export const RagSettingsPanel = memo(function RagSettingsPanel() {
   const { ragSettings, loadRagSettings, saveRagSettings } = useKnowledgeStore(
@@ -25,15 +25,17 @@ export const RagSettingsPanel = memo(function RagSettingsPanel() {
   );
 
   const [draft, setDraft] = useState<RagSettings | null>(null);
-  const [models, setModels] = useState<ModelInfo[]>([]);
-  const [modelChanged, setModelChanged] = useState(false);
-  const [restartNeeded, setRestartNeeded] = useState(false);
+  const [models, setModels] = useState<EmbeddingModelInfo[]>([]);
+  const [rerankers, setRerankers] = useState<RerankerInfo[]>([]);
 
   useEffect(() => {
     loadRagSettings().catch(console.error);
-    invoke<ModelInfo[]>("rag_get_available_models")
+    invoke<EmbeddingModelInfo[]>("rag_get_available_models")
       .then(setModels)
       .catch(console.error);
+    invoke<RerankerInfo[]>("rag_get_available_rerankers")
+      .then(setRerankers)
+      .catch(console.error);
   }, [loadRagSettings]);
 
   useEffect(() => {
@@ -54,10 +56,6 @@ export const RagSettingsPanel = memo(function RagSettingsPanel() {
         if (!prev) return prev;
         const next = { ...prev, [field]: value };
 
-        if (field === "embeddingModel" && ragSettings) {
-          setModelChanged(value !== ragSettings.embeddingModel);
-        }
-
         // Auto-clamp overlap when chunk size decreases
         if (field === "chunkSize") {
           const newSize = value as number;
@@ -70,16 +68,12 @@ export const RagSettingsPanel = memo(function RagSettingsPanel() {
         return next;
       });
     },
-    [ragSettings],
+    [],
   );
 
   const handleSave = useCallback(async () => {
     if (!draft) return;
-    const changed = await saveRagSettings(draft);
-    setModelChanged(false);
-    if (changed) {
-      setRestartNeeded(true);
-    }
+    await saveRagSettings(draft);
   }, [draft, saveRagSettings]);
 
   if (!draft) return null;
@@ -88,7 +82,7 @@ export const RagSettingsPanel = memo(function RagSettingsPanel() {
     <div className="kb-settings">
       <div className="kb-settings__row">
         <label className="kb-settings__label">
-          Embedding Model
+          Default Embedding Model
           <select
             className="kb-settings__select"
             value={draft.embeddingModel}
@@ -99,12 +93,9 @@ export const RagSettingsPanel = memo(function RagSettingsPanel() {
             ))}
           </select>
         </label>
-        {modelChanged && (
-          <div className="kb-settings__warning">
-            <AlertTriangle size={14} />
-            <span>Changing model requires reindexing all bases (app restart needed)</span>
-          </div>
-        )}
+        <span className="kb-settings__hint">
+          Used for new bases. Existing bases keep their model; change it on the base page.
+        </span>
       </div>
 
       <div className="kb-settings__row">
@@ -152,6 +143,52 @@ export const RagSettingsPanel = memo(function RagSettingsPanel() {
         </label>
       </div>
 
+      <div className="kb-settings__row">
+        <label className="kb-settings__toggle-label">
+          <span>Rerank Results</span>
+          <input
+            type="checkbox"
+            className="kb-settings__checkbox"
+            checked={draft.rerankerEnabled}
+            onChange={(e) => handleChange("rerankerEnabled", e.target.checked)}
+          />
+        </label>
+      </div>
+
+      {draft.rerankerEnabled && (
+        <>
+          <div className="kb-settings__row">
+            <label className="kb-settings__label">
+              Reranker Model
+              <select
+                className="kb-settings__select"
+                value={draft.rerankerModel}
+                onChange={(e) => handleChange("rerankerModel", e.target.value)}
+              >
+                {rerankers.map((m) => (
+                  <option key={m.id} value={m.id}>{m.id} — {m.label}</option>
+                ))}
+              </select>
+            </label>
+          </div>
+
+          <div className="kb-settings__row">
+            <label className="kb-settings__label">
+              Rerank Candidates
+              <select
+                className="kb-settings__select"
+                value={draft.rerankCandidates}
+                onChange={(e) => handleChange("rerankCandidates", Number(e.target.value))}
+              >
+                {RERANK_CANDIDATES.map((c) => (
+                  <option key={c} value={c}>top {c}</option>
+                ))}
+              </select>
+            </label>
+          </div>
+        </>
+      )}
+
       <div className="kb-settings__row">
         <label className="kb-settings__toggle-label">
           <span>Knowledge MCP Server</span>
@@ -168,13 +205,6 @@ export const RagSettingsPanel = memo(function RagSettingsPanel() {
         <Save size={14} />
         <span>Save Settings</span>
       </button>
-
-      {restartNeeded && (
-        <div className="kb-settings__warning">
-          <RotateCcw size={14} />
-          <span>Model changed. Restart the app and reindex all bases.</span>
-        </div>
-      )}
     </div>
   );
 });
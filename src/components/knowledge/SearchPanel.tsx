@@ -2,6 +2,15 @@ import { memo, useCallback, useState } from "react";
 import { Search, Loader2 } from "lucide-react";
 import { useShallow } from "zustand/react/shallow";
 import { useKnowledgeStore } from "../../stores/knowledgeStore";
+import { openUrl } from "../../lib/transport";
+import type { SearchMode, SearchResult } from "../../types/knowledge";
+
+/** "main.rs:10-24" for code, "book.pdf · p. 42" where the location is known. */
+function describeSource(r: SearchResult): string {
+  if (r.lines) return `${r.documentName}:${r.lines.start}-${r.lines.end}`;
+  if (r.citation) return `${r.documentName} · ${r.citation}`;
+  return r.documentName;
+}
 
 interface SearchPanelProps {
   baseId: string;
@@ -9,6 +18,8 @@ interface SearchPanelProps {
 
 export const SearchPanel = memo(function SearchPanel({ baseId }: SearchPanelProps) {
   const [query, setQuery] = useState("");
+  const [mode, setMode] = useState<SearchMode>("hybrid");
+  const [filter, setFilter] = useState("");
   const { searchResults, searchQuery, isSearching, search } = useKnowledgeStore(
     useShallow((s) => ({
       searchResults: s.searchResults,
@@ -20,8 +31,8 @@ export const SearchPanel = memo(function SearchPanel({ baseId }: SearchPanelProp
 
   const handleSearch = useCallback(() => {
     if (!query.trim()) return;
-    search(baseId, query.trim()).catch(console.error);
-  }, [query, baseId, search]);
+    search(baseId, query.trim(), mode, filter.trim()).catch(console.error);
+  }, [query, baseId, mode, filter, search]);
 
   const handleKeyDown = useCallback(
     (e: React.KeyboardEvent) => {
@@ -41,6 +52,25 @@ export const SearchPanel = memo(function SearchPanel({ baseId }: SearchPanelProp
           onKeyDown={handleKeyDown}
           placeholder="Search knowledge base…"
         />
+        <input
+          className="kb-search__input kb-search__filter"
+          type="text"
+          value={filter}
+          onChange={(e) => setFilter(e.target.value)}
+          onKeyDown={handleKeyDown}
+          placeholder="Filter: tag:rust lang=en"
+          title="Only documents matching all terms: tag:NAME, key=value; tag:a,b matches either, -term excludes"
+        />
+        <select
+          className="kb-search__mode"
+          value={mode}
+          onChange={(e) => setMode(e.target.value as SearchMode)}
+          title="Hybrid combines meaning and exact keyword matches"
+        >
+          <option value="hybrid">Hybrid</option>
+          <option value="vector">Semantic</option>
+          <option value="keyword">Keyword</option>
+        </select>
         <button className="kb-search__btn" onClick={handleSearch} disabled={isSearching || !query.trim()}>
           {isSearching ? <Loader2 size={16} className="kb-status__spinner" /> : <Search size={16} />}
         </button>
@@ -51,8 +81,27 @@ export const SearchPanel = memo(function SearchPanel({ baseId }: SearchPanelProp
           {searchResults.map((r, i) => (
             <div key={`${r.documentId}-${i}`} className="kb-search__result">
               <div className="kb-search__result-header">
-                <span className="kb-search__result-doc">{r.documentName}</span>
-                <span className="kb-search__result-score">{(r.score * 100).toFixed(0)}%</span>
+                <span className="kb-search__result-doc" title={r.citation ?? undefined}>
+                  {describeSource(r)}
+                  {r.link && (
+                    <a
+                      href="#"
+                      className="kb-search__result-link"
+                      onClick={(e) => {
+                        e.preventDefault();
+                        openUrl(r.link!).catch(console.error);
+                      }}
+                    >
+                      Watch
+                    </a>
+                  )}
+                </span>
+                <span
+                  className="kb-search__result-score"
+                  title={r.similarity != null ? "Similarity to the query" : "Found by keyword search"}
+                >
+                  {r.similarity != null ? `${(r.similarity * 100).toFixed(0)}%` : "keyword"}
+                </span>
               </div>
               <div className="kb-search__result-text">
                 {r.chunkText.length > 300 ? `${r.chunkText.slice(0, 300)}…` : r.chunkText}
@@ -0,0 +1,246 @@
+import { memo, useCallback, useEffect, useState } from "react";
+import { FolderOpen, FolderPlus, FolderSync, RefreshCw, Trash2 } from "lucide-react";
+import { useKnowledgeStore } from "../../stores/knowledgeStore";
+import { Modal } from "../Modal";
+import { Tooltip } from "../shared/Tooltip";
+import { invoke, listen, openDialog } from "../../lib/transport";
+import type { BaseSyncStatus, FolderSource } from "../../types/knowledge";
+
+interface SourcesPanelProps {
+  baseId: string;
+  disabled: boolean;
+}
+
+/** "a, b ,c" → ["a", "b", "c"] */
+function splitPatterns(value: string): string[] {
+  return value
+    .split(",")
+    .map((p) => p.trim())
+    .filter(Boolean);
+}
+
+function describeSync(source: FolderSource): string {
+  const result = source.lastSync;
+  if (!result) return "Not synced yet";
+  const when = new Date(result.finishedAt).toLocaleString();
+  if (result.failed === 0 && result.errors.length > 0) return `${result.errors[0]} · ${when}`;
+  const parts = [`${result.added} added`, `${result.updated} updated`, `${result.removed} removed`];
+  if (result.failed > 0) parts.push(`${result.failed} failed`);
+  return `${parts.join(", ")} · ${when}`;
+}
+
+export const SourcesPanel = memo(function SourcesPanel({ baseId, disabled }: SourcesPanelProps) {
+  const loadDocuments = useKnowledgeStore((s) => s.loadDocuments);
+  const loadBases = useKnowledgeStore((s) => s.loadBases);
+
+  const [status, setStatus] = useState<BaseSyncStatus | null>(null);
+  const [syncing, setSyncing] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [addOpen, setAddOpen] = useState(false);
+  const [folder, setFolder] = useState("");
+  const [include, setInclude] = useState("");
+  const [exclude, setExclude] = useState("");
+  const [removing, setRemoving] = useState<FolderSource | null>(null);
+
+  const refresh = useCallback(() => {
+    invoke<BaseSyncStatus>("rag_sync_status", { baseId })
+      .then(setStatus)
+      .catch((e) => setError(String(e)));
+  }, [baseId]);
+
+  useEffect(() => {
+    setStatus(null);
+    setError(null);
+    refresh();
+  }, [refresh]);
+
+  // Background syncs change documents without going through the store
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+    let cancelled = false;
+    listen<{ baseId: string }>("rag-sync-finished", (event) => {
+      if (event.payload.baseId !== baseId) return;
+      refresh();
+      loadDocuments(baseId).catch(console.error);
+      loadBases().catch(console.error);
+    })
+      .then((fn) => {
+        if (cancelled) fn();
+        else unlisten = fn;
+      })
+      .catch(console.error);
+    return () => {
+      cancelled = true;
+      if (unlisten) unlisten();
+    };
+  }, [baseId, refresh, loadDocuments, loadBases]);
+
+  const handlePickFolder = useCallback(async () => {
+    try {
+      const selected = await openDialog({ directory: true, multiple: false, title: "Select folder to sync" });
+      if (typeof selected === "string") setFolder(selected);
+    } catch (e) {
+      console.error("Failed to open folder dialog:", e);
+    }
+  }, []);
+
+  const closeAdd = useCallback(() => {
+    setAddOpen(false);
+    setFolder("");
+    setInclude("");
+    setExclude("");
+  }, []);
+
+  const handleAdd = useCallback(async () => {
+    if (!folder) return;
+    try {
+      await invoke<FolderSource>("rag_add_source", {
+        baseId,
+        path: folder,
+        include: splitPatterns(include),
+        exclude: splitPatterns(exclude),
+      });
+      setError(null);
+      closeAdd();
+      refresh();
+    } catch (e) {
+      setError(String(e));
+    }
+  }, [baseId, folder, include, exclude, closeAdd, refresh]);
+
+  const handleSync = useCallback(async () => {
+    setSyncing(true);
+    try {
+      setStatus(await invoke<BaseSyncStatus>("rag_sync_base", { baseId }));
+      setError(null);
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setSyncing(false);
+    }
+  }, [baseId]);
+
+  const handleRemove = useCallback(
+    async (removeDocuments: boolean) => {
+      if (!removing) return;
+      const sourceId = removing.id;
+      setRemoving(null);
+      try {
+        await invoke("rag_remove_source", { baseId, sourceId, removeDocuments });
+        setError(null);
+        refresh();
+        if (removeDocuments) {
+          await Promise.all([loadDocuments(baseId), loadBases()]);
+        }
+      } catch (e) {
+        setError(String(e));
+      }
+    },
+    [baseId, removing, refresh, loadDocuments, loadBases],
+  );
+
+  const sources = status?.sources ?? [];
+  const busy = syncing || (status?.syncing ?? false);
+
+  return (
+    <div className="kb-sources">
+      <div className="kb-sources__header">
+        <span className="kb-sources__title">Synced Folders</span>
+        <div className="kb-sources__actions">
+          {sources.length > 0 && (
+            <Tooltip text="Sync all folders now">
+              <button className="kb-btn kb-btn--secondary" onClick={handleSync} disabled={disabled || busy}>
+                <RefreshCw size={14} className={busy ? "kb-spin" : ""} />
+                <span>{busy ? "Syncing…" : "Sync Now"}</span>
+              </button>
+            </Tooltip>
+          )}
+          <Tooltip text="Keep a folder in sync with this base">
+            <button className="kb-btn kb-btn--secondary" onClick={() => setAddOpen(true)} disabled={disabled}>
+              <FolderPlus size={14} />
+              <span>Add Folder</span>
+            </button>
+          </Tooltip>
+        </div>
+      </div>
+
+      {error && <div className="kb-sources__error">{error}</div>}
+
+      {sources.map((source) => (
+        <div key={source.id} className="kb-doc-item">
+          <FolderSync size={14} className="kb-doc-item__icon" />
+          <div className="kb-doc-item__info">
+            <span className="kb-doc-item__name">{source.path}</span>
+            <span className="kb-doc-item__meta">
+              {source.include.length > 0 ? source.include.join(", ") : "All documents"}
+              {source.exclude.length > 0 && ` · excluding ${source.exclude.join(", ")}`}
+            </span>
+            <span className={`kb-doc-item__meta${source.lastSync?.errors.length ? " kb-sources__failed" : ""}`}>
+              {describeSync(source)}
+            </span>
+          </div>
+          <Tooltip text="Stop syncing folder">
+            <button className="kb-doc-item__remove" onClick={() => setRemoving(source)} disabled={busy}>
+              <Trash2 size={14} />
+            </button>
+          </Tooltip>
+        </div>
+      ))}
+
+      <Modal
+        open={addOpen}
+        title="Add Synced Folder"
+        onClose={closeAdd}
+        actions={[
+          { label: "Cancel", onClick: closeAdd },
+          { label: "Add", variant: "accent", onClick: handleAdd, disabled: !folder },
+        ]}
+      >
+        <div className="kb-form">
+          <p>
+            New and changed files are indexed automatically, and documents are removed when their file is deleted.
+          </p>
+          <button className="kb-file-picker" onClick={handlePickFolder}>
+            <FolderOpen size={18} />
+            <span>{folder || "Choose folder…"}</span>
+          </button>
+          <label className="kb-form__label">
+            Include
+            <input
+              className="kb-form__input"
+              value={include}
+              onChange={(e) => setInclude(e.target.value)}
+              placeholder="**/*.md, docs/**/*.pdf"
+            />
+          </label>
+          <span className="kb-settings__hint">Comma-separated globs. Empty means all supported documents.</span>
+          <label className="kb-form__label">
+            Exclude
+            <input
+              className="kb-form__input"
+              value={exclude}
+              onChange={(e) => setExclude(e.target.value)}
+              placeholder="node_modules, **/drafts"
+            />
+          </label>
+        </div>
+      </Modal>
+
+      <Modal
+        open={removing !== null}
+        title="Stop Syncing Folder"
+        onClose={() => setRemoving(null)}
+        actions={[
+          { label: "Cancel", onClick: () => setRemoving(null) },
+          { label: "Keep Documents", onClick: () => handleRemove(false) },
+          { label: "Remove Documents", variant: "danger", onClick: () => handleRemove(true) },
+        ]}
+      >
+        <p>
+          Stop syncing &ldquo;{removing?.path}&rdquo;? Its documents can stay in the base as regular documents or be
+          removed with it.
+        </p>
+      </Modal>
+    </div>
+  );
+});
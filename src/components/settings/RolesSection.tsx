@@ -1,7 +1,7 @@
 import { useCallback, useEffect, useState } from "react";
-import { Plus, Trash2, X, RotateCcw, Star } from "lucide-react";
-import { invoke } from "../../lib/transport";
-import type { AgentRole, RoleEntry } from "../../types/team";
+import { Plus, Trash2, X, RotateCcw, Star, Upload, Download } from "lucide-react";
+import { invoke, openDialog } from "../../lib/transport";
+import type { AgentRole, RoleEntry, RoleImportReport, RoleVersion } from "../../types/team";
 import { Tooltip } from "../shared/Tooltip";
 import { useConductorStore } from "../../stores/conductorStore";
 
@@ -14,6 +14,7 @@ export function RolesSection() {
   const [isNew, setIsNew] = useState(false);
   const [confirmDelete, setConfirmDelete] = useState<{ name: string; isDefault: boolean } | null>(null);
   const [defaultRoleName, setDefaultRoleName] = useState("");
+  const [libraryStatus, setLibraryStatus] = useState<string | null>(null);
 
   const loadRoles = useCallback(() => {
     invoke<RoleEntry[]>("roles_list").then(setRoles).catch(console.error);
@@ -41,7 +42,7 @@ export function RolesSection() {
   }, []);
 
   const handleEdit = useCallback((role: RoleEntry) => {
-    setEditing({ ...role });
+    setEditing({ ...(role.definition ?? role) });
     setEditIsDefault(role.is_default);
     setIsNew(false);
   }, []);
@@ -67,6 +68,34 @@ export function RolesSection() {
     }
   }, [loadRoles]);
 
+  const handleExport = useCallback(async () => {
+    try {
+      const dir = await openDialog({ directory: true, multiple: false, title: "Export roles to..." });
+      if (typeof dir !== "string") return;
+      const path = await invoke<string>("roles_export", { dir });
+      setLibraryStatus(`Exported to ${path}`);
+    } catch (e) {
+      setLibraryStatus(String(e));
+    }
+  }, []);
+
+  const handleImport = useCallback(async () => {
+    try {
+      const path = await openDialog({
+        multiple: false,
+        filters: [{ name: "Role bundle", extensions: ["json"] }],
+        title: "Import roles",
+      });
+      if (typeof path !== "string") return;
+      const report = await invoke<RoleImportReport>("roles_import", { path, overwrite: false });
+      const skipped = report.skipped.length > 0 ? `, skipped existing: ${report.skipped.join(", ")}` : "";
+      setLibraryStatus(`Imported ${report.imported.length} role(s)${skipped}`);
+      loadRoles();
+    } catch (e) {
+      setLibraryStatus(String(e));
+    }
+  }, [loadRoles]);
+
   const handleSetDefault = useCallback(async (name: string) => {
     const newDefault = name === defaultRoleName ? "" : name;
     try {
@@ -84,14 +113,28 @@ export function RolesSection() {
         <p className="settings-toggle-desc">
           Manage agent roles for team collaboration.
         </p>
-        <Tooltip text="New role">
-          <button className="roles-add-btn" onClick={handleNew}>
-            <Plus size={14} />
-            <span>New Role</span>
-          </button>
-        </Tooltip>
+        <div className="roles-header__actions">
+          <Tooltip text="Import role bundle">
+            <button className="team-btn" onClick={() => handleImport().catch(console.error)}>
+              <Download size={13} />
+            </button>
+          </Tooltip>
+          <Tooltip text="Export custom roles">
+            <button className="team-btn" onClick={() => handleExport().catch(console.error)}>
+              <Upload size={13} />
+            </button>
+          </Tooltip>
+          <Tooltip text="New role">
+            <button className="roles-add-btn" onClick={handleNew}>
+              <Plus size={14} />
+              <span>New Role</span>
+            </button>
+          </Tooltip>
+        </div>
       </div>
 
+      {libraryStatus && <p className="settings-toggle-desc">{libraryStatus}</p>}
+
       <div className="roles-list">
         {roles.map((role) => (
           <div
@@ -100,7 +143,10 @@ export function RolesSection() {
             onClick={() => handleEdit(role)}
           >
             <div className="roles-card__header">
-              <span className="roles-card__name">{role.name}</span>
+              <span className="roles-card__name">
+                {role.name}
+                {role.extends && <span className="roles-card__extends"> ← {role.extends}</span>}
+              </span>
               <Tooltip text={role.name === defaultRoleName ? "Remove default" : "Set as default"}>
                 <button
                   className={`roles-card__default-btn ${role.name === defaultRoleName ? "roles-card__default-btn--active" : ""}`}
@@ -129,7 +175,12 @@ export function RolesSection() {
           role={editing}
           isNew={isNew}
           isDefault={editIsDefault}
+          roleNames={roles.map((r) => r.name)}
           onSave={handleSave}
+          onRestored={() => {
+            setEditing(null);
+            loadRoles();
+          }}
           onRequestDelete={(name, isDefault) => {
             setConfirmDelete({ name, isDefault });
           }}
@@ -167,14 +218,18 @@ function RoleEditor({
   role,
   isNew,
   isDefault,
+  roleNames,
   onSave,
+  onRestored,
   onRequestDelete,
   onCancel,
 }: {
   role: AgentRole;
   isNew: boolean;
   isDefault: boolean;
+  roleNames: string[];
   onSave: (role: AgentRole) => void;
+  onRestored: () => void;
   onRequestDelete: (name: string, isDefault: boolean) => void;
   onCancel: () => void;
 }) {
@@ -182,6 +237,24 @@ function RoleEditor({
   const [prompt, setPrompt] = useState(role.system_prompt);
   const [startMessage, setStartMessage] = useState(role.start_message ?? "");
   const [tools, setTools] = useState<string[]>(role.allowed_tools);
+  const [extendsRole, setExtendsRole] = useState(role.extends ?? "");
+  const [history, setHistory] = useState<RoleVersion[]>([]);
+
+  useEffect(() => {
+    if (isNew) return;
+    invoke<RoleVersion[]>("roles_history", { name: role.name })
+      .then(setHistory)
+      .catch(console.error);
+  }, [isNew, role.name]);
+
+  const handleRestore = useCallback(async (version: number) => {
+    try {
+      await invoke("roles_rollback", { name: role.name, version });
+      onRestored();
+    } catch (e) {
+      console.error("[RolesSection] rollback:", e);
+    }
+  }, [role.name, onRestored]);
   const toggleTool = useCallback((tool: string) => {
     if (tool === "Read") return;
     setTools((prev) =>
@@ -198,8 +271,9 @@ function RoleEditor({
       allowed_tools: finalTools,
       can_manage: role.can_manage,
       start_message: startMessage.trim() || undefined,
+      extends: extendsRole || undefined,
     });
-  }, [name, prompt, startMessage, tools, role.can_manage, onSave]);
+  }, [name, prompt, startMessage, tools, extendsRole, role.can_manage, onSave]);
 
   return (
     <div className="roles-editor-overlay" onClick={onCancel}>
@@ -227,7 +301,25 @@ function RoleEditor({
           </div>
 
           <div className="roles-field">
-            <label className="roles-field__label">System Prompt</label>
+            <label className="roles-field__label">Extends</label>
+            <select
+              className="settings-select"
+              value={extendsRole}
+              onChange={(e) => setExtendsRole(e.target.value)}
+            >
+              <option value="">None</option>
+              {roleNames
+                .filter((n) => n.toLowerCase() !== role.name.toLowerCase())
+                .map((n) => (
+                  <option key={n} value={n}>{n}</option>
+                ))}
+            </select>
+          </div>
+
+          <div className="roles-field">
+            <label className="roles-field__label">
+              {extendsRole ? `System Prompt (appended to ${extendsRole})` : "System Prompt"}
+            </label>
             <textarea
               className="roles-textarea"
               value={prompt}
@@ -246,6 +338,9 @@ function RoleEditor({
               placeholder="First message sent to the agent on team launch..."
               rows={3}
             />
+            <span className="roles-field__hint">
+              {"Supports {{date}}, {{project}}, {{branch}} and {{#if name}}…{{/if}}; write \\{{ for literal braces"}
+            </span>
           </div>
 
           <div className="roles-field">
@@ -265,6 +360,24 @@ function RoleEditor({
             </div>
           </div>
 
+          {history.length > 0 && (
+            <div className="roles-field">
+              <label className="roles-field__label">History</label>
+              <div className="roles-history">
+                {history.map((v) => (
+                  <div key={v.version} className="roles-history__row">
+                    <span>v{v.version} · {new Date(v.saved_at).toLocaleString()}</span>
+                    <button
+                      className="team-btn"
+                      onClick={() => handleRestore(v.version).catch(console.error)}
+                    >
+                      Restore
+                    </button>
+                  </div>
+                ))}
+              </div>
+            </div>
+          )}
         </div>
 
         <div className="roles-editor__actions">
@@ -1,9 +1,26 @@
 import { useState, useEffect, useCallback, useMemo } from "react";
-import { Plus, Play, Trash2, X, Save } from "lucide-react";
+import { Plus, Play, Trash2, X, Save, Eye } from "lucide-react";
 import { invoke, listen } from "../../lib/transport";
 import { Tooltip } from "../shared/Tooltip";
-import type { ScheduledTask, TaskSchedule } from "../../types/scheduler";
+import type {
+  CatchUpPolicy,
+  FollowUp,
+  NotifyRule,
+  ScheduledTask,
+  SchedulerSettings,
+  TaskRun,
+  TaskSchedule,
+  UpcomingRun,
+} from "../../types/scheduler";
 import type { ProjectBookmark } from "../../types/projects";
+import type { RoleEntry } from "../../types/team";
+
+const MODEL_OPTIONS = [
+  { id: "", label: "Default" },
+  { id: "opus", label: "Opus" },
+  { id: "sonnet", label: "Sonnet" },
+  { id: "haiku", label: "Haiku" },
+];
 
 const DAYS = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
 const DAY_SHORTS = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
@@ -288,9 +305,319 @@ function formatScheduleLabel(s: TaskSchedule): string {
       return `${DAYS[s.day] ?? "?"} at ${pad(s.hour)}:${pad(s.minute)}`;
     case "cron":
       return `Cron: ${s.expression}`;
+    case "file_change":
+      return `On change: ${s.patterns.join(", ")}`;
+    case "git_commit":
+      return `On new commits to ${s.branch}`;
+    case "webhook":
+      return "On webhook";
+    case "manual":
+      return "Manual / follow-up only";
   }
 }
 
+function isEventSchedule(s: TaskSchedule): boolean {
+  return s.type === "file_change" || s.type === "git_commit" || s.type === "webhook" || s.type === "manual";
+}
+
+function formatDuration(ms: number): string {
+  const sec = Math.round(ms / 1000);
+  if (sec < 60) return `${sec}s`;
+  return `${Math.floor(sec / 60)}m ${pad(sec % 60)}s`;
+}
+
+function RunHistory({ taskId }: { taskId: string }) {
+  const [runs, setRuns] = useState<TaskRun[]>([]);
+  const [expanded, setExpanded] = useState<string | null>(null);
+
+  useEffect(() => {
+    invoke<TaskRun[]>("scheduler_list_runs", { taskId, limit: 20 })
+      .then(setRuns)
+      .catch(console.error);
+  }, [taskId]);
+
+  if (runs.length === 0) return null;
+
+  return (
+    <div className="scheduler-runs">
+      <span className="settings-toggle-label">Recent runs</span>
+      {runs.map((run) => (
+        <div key={run.id} className="scheduler-run">
+          <div
+            className="scheduler-run-row"
+            onClick={() => setExpanded(expanded === run.id ? null : run.id)}
+          >
+            <span className={`scheduler-status-dot scheduler-status-dot--${run.status}`} />
+            <span>{new Date(run.started_at).toLocaleString()}</span>
+            <span className="scheduler-run-meta">
+              {formatDuration(run.duration_ms)}
+              {run.cost_usd != null && ` \u00b7 $${run.cost_usd.toFixed(3)}`}
+              {run.exit_code != null && run.exit_code !== 0 && ` \u00b7 exit ${run.exit_code}`}
+              {run.attempt > 0 && ` \u00b7 retry ${run.attempt}`}
+              {run.trigger && ` \u00b7 ${run.trigger}`}
+            </span>
+          </div>
+          {expanded === run.id && (run.final_message || run.error) && (
+            <pre className="scheduler-run-output">{run.error ?? run.final_message}</pre>
+          )}
+        </div>
+      ))}
+    </div>
+  );
+}
+
+const DAY_TIME_FORMAT: Intl.DateTimeFormatOptions = {
+  weekday: "short",
+  month: "short",
+  day: "numeric",
+  hour: "2-digit",
+  minute: "2-digit",
+};
+
+/** Next fire times of the schedule being edited, or why it is invalid. */
+function NextRuns({ schedule, timezone }: { schedule: TaskSchedule; timezone: string | null }) {
+  const [times, setTimes] = useState<string[]>([]);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    const timer = setTimeout(() => {
+      invoke<string[]>("scheduler_validate_schedule", { schedule, timezone, count: 3 })
+        .then((result) => {
+          setTimes(result);
+          setError(null);
+        })
+        .catch((e) => setError(String(e)));
+    }, 300);
+    return () => clearTimeout(timer);
+  }, [schedule, timezone]);
+
+  if (error) return <div className="scheduler-next-runs scheduler-next-runs--error">{error}</div>;
+  if (times.length === 0) return null;
+  return (
+    <div className="scheduler-next-runs">
+      Next: {times.map((t) => new Date(t).toLocaleString([], DAY_TIME_FORMAT)).join(", ")}
+    </div>
+  );
+}
+
+/** Fire times of all enabled tasks over the next week, grouped by day. */
+function UpcomingRuns({ tasks }: { tasks: ScheduledTask[] }) {
+  const [runs, setRuns] = useState<UpcomingRun[]>([]);
+
+  useEffect(() => {
+    const from = new Date();
+    const to = new Date(from.getTime() + 7 * 24 * 60 * 60 * 1000);
+    invoke<UpcomingRun[]>("scheduler_preview", { from: from.toISOString(), to: to.toISOString() })
+      .then(setRuns)
+      .catch(console.error);
+  }, [tasks]);
+
+  const days = useMemo(() => {
+    const grouped = new Map<string, UpcomingRun[]>();
+    for (const run of runs) {
+      const day = new Date(run.fire_time).toLocaleDateString([], {
+        weekday: "long",
+        month: "short",
+        day: "numeric",
+      });
+      grouped.set(day, [...(grouped.get(day) ?? []), run]);
+    }
+    return [...grouped.entries()];
+  }, [runs]);
+
+  if (days.length === 0) return null;
+
+  return (
+    <div className="scheduler-upcoming">
+      <span className="settings-toggle-label">Upcoming (7 days)</span>
+      {days.map(([day, dayRuns]) => (
+        <div key={day} className="scheduler-upcoming-day">
+          <span className="scheduler-upcoming-date">{day}</span>
+          {dayRuns.map((run) => (
+            <div key={`${run.task_id}-${run.fire_time}`} className="scheduler-upcoming-run">
+              <span className="scheduler-run-meta">
+                {new Date(run.fire_time).toLocaleTimeString([], { hour: "2-digit", minute: "2-digit" })}
+              </span>
+              <span>{run.task_name}</span>
+            </div>
+          ))}
+        </div>
+      ))}
+    </div>
+  );
+}
+
+function FollowUpsEditor({
+  followUps,
+  tasks,
+  onChange,
+}: {
+  followUps: FollowUp[];
+  tasks: ScheduledTask[];
+  onChange: (followUps: FollowUp[]) => void;
+}) {
+  const update = (i: number, patch: Partial<FollowUp>) =>
+    onChange(followUps.map((f, j) => (j === i ? { ...f, ...patch } : f)));
+
+  return (
+    <div className="scheduler-follow-ups">
+      <div className="settings-toggle-info">
+        <span className="settings-toggle-label">Then run</span>
+        <span className="settings-toggle-desc">
+          {"Started when this task ends; its final message is passed as {{trigger_payload}}"}
+        </span>
+      </div>
+      {followUps.map((f, i) => (
+        <div key={i} className="scheduler-follow-up">
+          <select
+            className="settings-select"
+            value={f.task_id}
+            onChange={(e) => update(i, { task_id: e.target.value })}
+          >
+            <option value="">Choose task…</option>
+            {tasks.map((t) => (
+              <option key={t.id} value={t.id}>
+                {t.name}
+              </option>
+            ))}
+          </select>
+          <select
+            className="settings-select"
+            value={f.when}
+            onChange={(e) => update(i, { when: e.target.value as FollowUp["when"] })}
+          >
+            <option value="success">on success</option>
+            <option value="failure">on failure</option>
+            <option value="always">always</option>
+          </select>
+          <input
+            type="text"
+            className="settings-input"
+            value={f.condition.contains ?? ""}
+            onChange={(e) => update(i, { condition: { ...f.condition, contains: e.target.value || null } })}
+            placeholder="if output contains…"
+            spellCheck={false}
+          />
+          <Tooltip text="Remove">
+            <button
+              className="settings-input-toggle"
+              onClick={() => onChange(followUps.filter((_, j) => j !== i))}
+            >
+              <X size={13} />
+            </button>
+          </Tooltip>
+        </div>
+      ))}
+      {tasks.length > 0 && (
+        <button
+          className="scheduler-btn scheduler-btn--secondary"
+          onClick={() =>
+            onChange([...followUps, { task_id: "", when: "success", condition: { contains: null, not_contains: null } }])
+          }
+        >
+          <Plus size={14} />
+          Add follow-up
+        </button>
+      )}
+    </div>
+  );
+}
+
+function NotificationsEditor({
+  rules,
+  onChange,
+}: {
+  rules: NotifyRule[];
+  onChange: (rules: NotifyRule[]) => void;
+}) {
+  const update = (i: number, patch: Partial<NotifyRule>) =>
+    onChange(rules.map((r, j) => (j === i ? { ...r, ...patch } : r)));
+
+  const setSink = (i: number, type: NotifyRule["sink"]["type"]) =>
+    update(i, { sink: type === "webhook" ? { type, url: "" } : { type } });
+
+  return (
+    <div className="scheduler-follow-ups">
+      <div className="settings-toggle-info">
+        <span className="settings-toggle-label">Notifications</span>
+        <span className="settings-toggle-desc">
+          {"Template variables: {{summary}} {{task}} {{status}} {{duration}} {{cost}} {{output}} {{error}} {{trigger}}"}
+          <br />
+          Webhook URLs must use https unless they point at this machine (localhost, 127.0.0.1).
+        </span>
+      </div>
+      {rules.map((r, i) => (
+        <div key={i} className="scheduler-notify-rule">
+          <div className="scheduler-follow-up">
+            <select
+              className="settings-select"
+              value={r.sink.type}
+              onChange={(e) => setSink(i, e.target.value as NotifyRule["sink"]["type"])}
+            >
+              <option value="telegram">Telegram</option>
+              <option value="desktop">Desktop</option>
+              <option value="webhook">Webhook</option>
+            </select>
+            <select
+              className="settings-select"
+              value={r.when}
+              onChange={(e) => update(i, { when: e.target.value as NotifyRule["when"] })}
+            >
+              <option value="success">on success</option>
+              <option value="failure">on failure</option>
+              <option value="timeout">on timeout</option>
+              <option value="always">always</option>
+            </select>
+            {r.sink.type === "webhook" && (
+              <input
+                type="text"
+                className="settings-input"
+                value={r.sink.url}
+                onChange={(e) => update(i, { sink: { type: "webhook", url: e.target.value } })}
+                placeholder="http://127.0.0.1:8123/hook"
+                spellCheck={false}
+              />
+            )}
+            <Tooltip text="Remove">
+              <button
+                className="settings-input-toggle"
+                onClick={() => onChange(rules.filter((_, j) => j !== i))}
+              >
+                <X size={13} />
+              </button>
+            </Tooltip>
+          </div>
+          <textarea
+            className="settings-input scheduler-notify-template"
+            value={r.template}
+            onChange={(e) => update(i, { template: e.target.value })}
+            placeholder="Message template (empty = summary, error and final message)"
+            rows={2}
+            spellCheck={false}
+          />
+        </div>
+      ))}
+      <button
+        className="scheduler-btn scheduler-btn--secondary"
+        onClick={() => onChange([...rules, { when: "failure", sink: { type: "telegram" }, template: "" }])}
+      >
+        <Plus size={14} />
+        Add notification
+      </button>
+    </div>
+  );
+}
+
+/** Show the legacy Telegram flag as a rule, the way the backend migrates it on save. */
+function withNotificationRules(task: ScheduledTask): ScheduledTask {
+  if (!task.notify_telegram) return { ...task };
+  return {
+    ...task,
+    notify_telegram: false,
+    notifications: [...task.notifications, { when: "success", sink: { type: "telegram" }, template: "" }],
+  };
+}
+
 function pad(n: number): string {
   return String(n).padStart(2, "0");
 }
@@ -307,13 +634,36 @@ function emptyTask(): ScheduledTask {
     created_at: "",
     last_run: null,
     last_status: null,
+    timezone: null,
+    catch_up: "once",
+    last_scheduled: null,
+    model: null,
+    effort: null,
+    permission_mode: null,
+    role: null,
+    allowed_tools: [],
+    resume_previous: false,
+    timeout_minutes: null,
+    last_session_id: null,
+    max_retries: 0,
+    retry_delay_secs: 60,
+    last_seen_commit: null,
+    follow_ups: [],
+    notifications: [],
   };
 }
 
+// Intl.supportedValuesOf is ES2022 — not in our TS lib target
+const TIMEZONES: string[] =
+  (Intl as { supportedValuesOf?: (key: string) => string[] }).supportedValuesOf?.("timeZone") ?? [];
+
 export function SchedulerSection() {
   const [tasks, setTasks] = useState<ScheduledTask[]>([]);
   const [projects, setProjects] = useState<ProjectBookmark[]>([]);
+  const [roles, setRoles] = useState<RoleEntry[]>([]);
+  const [settings, setSettings] = useState<SchedulerSettings | null>(null);
   const [editing, setEditing] = useState<ScheduledTask | null>(null);
+  const [preview, setPreview] = useState<string | null>(null);
   const [loaded, setLoaded] = useState(false);
 
   const loadTasks = useCallback(() => {
@@ -330,14 +680,43 @@ export function SchedulerSection() {
     invoke<{ projects: ProjectBookmark[] }>("load_projects")
       .then((cfg) => setProjects(cfg.projects))
       .catch(console.error);
+    invoke<RoleEntry[]>("roles_list")
+      .then(setRoles)
+      .catch(console.error);
   }, [loadTasks]);
 
-  // Reload tasks when a scheduled task starts (auto or manual)
+  // Reload tasks when a scheduled task starts or is queued (auto or manual)
   useEffect(() => {
-    const unlisten = listen("scheduler:task-started", () => loadTasks());
-    return () => { unlisten.then((fn) => fn()).catch(console.error); };
+    const unlistenStarted = listen("scheduler:task-started", () => loadTasks());
+    const unlistenQueued = listen("scheduler:task-queued", () => loadTasks());
+    return () => {
+      unlistenStarted.then((fn) => fn()).catch(console.error);
+      unlistenQueued.then((fn) => fn()).catch(console.error);
+    };
   }, [loadTasks]);
 
+  useEffect(() => {
+    invoke<SchedulerSettings>("scheduler_load_settings")
+      .then(setSettings)
+      .catch(console.error);
+  }, []);
+
+  const updateSettings = useCallback((patch: Partial<SchedulerSettings>) => {
+    setSettings((prev) => {
+      if (!prev) return prev;
+      const next = { ...prev, ...patch };
+      invoke<SchedulerSettings>("scheduler_save_settings", { settings: next })
+        .then((saved) => {
+          // The backend generates a token when the webhook is first enabled
+          if (saved.webhook_token !== next.webhook_token) {
+            setSettings((cur) => cur && { ...cur, webhook_token: saved.webhook_token });
+          }
+        })
+        .catch(console.error);
+      return next;
+    });
+  }, []);
+
   const handleToggle = useCallback(
     (id: string, enabled: boolean) => {
       invoke("scheduler_toggle_task", { id, enabled })
@@ -376,6 +755,21 @@ export function SchedulerSection() {
       .catch(console.error);
   }, [editing, loadTasks]);
 
+  const handlePreview = useCallback(() => {
+    if (!editing?.project_path) return;
+    invoke<string>("prompt_template_preview", {
+      template: editing.prompt,
+      projectPath: editing.project_path,
+      taskId: editing.id || null,
+    })
+      .then(setPreview)
+      .catch((e) => setPreview(`Error: ${e}`));
+  }, [editing]);
+
+  // A preview belongs to the task it was rendered for
+  const editingId = editing?.id;
+  useEffect(() => setPreview(null), [editingId]);
+
   const handleAdd = useCallback(() => {
     const task = emptyTask();
     if (projects.length > 0) {
@@ -395,7 +789,7 @@ export function SchedulerSection() {
 
       {tasks.map((task) => (
         <div key={task.id} className="scheduler-task-row">
-          <div className="scheduler-task-info" onClick={() => setEditing({ ...task })}>
+          <div className="scheduler-task-info" onClick={() => setEditing(withNotificationRules(task))}>
             <div className="scheduler-task-name" style={{ opacity: task.enabled ? 1 : 0.5 }}>
               {task.name}
               {task.last_status && (
@@ -415,7 +809,7 @@ export function SchedulerSection() {
               <button
                 className="settings-input-toggle"
                 onClick={() => handleRunNow(task.id)}
-                disabled={task.last_status === "running"}
+                disabled={task.last_status === "running" || task.last_status === "queued"}
               >
                 <Play size={13} />
               </button>
@@ -437,6 +831,8 @@ export function SchedulerSection() {
         </div>
       ))}
 
+      {!editing && <UpcomingRuns tasks={tasks} />}
+
       {/* Editor */}
       {editing && (
         <div className="scheduler-editor">
@@ -467,6 +863,17 @@ export function SchedulerSection() {
           <div className="settings-toggle-row" style={{ alignItems: "flex-start" }}>
             <div className="settings-toggle-info">
               <span className="settings-toggle-label">Prompt</span>
+              <span className="settings-toggle-desc">
+                {"Variables: {{date}}, {{branch}}, {{last_output}}, {{changed_files}}, {{trigger}}…"}
+              </span>
+              <button
+                className="scheduler-btn scheduler-btn--secondary scheduler-preview-btn"
+                onClick={handlePreview}
+                disabled={!editing.project_path}
+              >
+                <Eye size={14} />
+                Preview
+              </button>
             </div>
             <textarea
               className="settings-input scheduler-textarea"
@@ -476,6 +883,7 @@ export function SchedulerSection() {
               rows={4}
             />
           </div>
+          {preview !== null && <pre className="scheduler-run-output scheduler-preview">{preview}</pre>}
 
           {/* Project */}
           <div className="settings-toggle-row">
@@ -519,6 +927,18 @@ export function SchedulerSection() {
                   case "cron":
                     schedule = { type: "cron", expression: "0 9 * * *" };
                     break;
+                  case "file_change":
+                    schedule = { type: "file_change", patterns: ["src/**"] };
+                    break;
+                  case "git_commit":
+                    schedule = { type: "git_commit", branch: "main" };
+                    break;
+                  case "webhook":
+                    schedule = { type: "webhook" };
+                    break;
+                  case "manual":
+                    schedule = { type: "manual" };
+                    break;
                 }
                 setEditing({ ...editing, schedule });
               }}
@@ -527,6 +947,10 @@ export function SchedulerSection() {
               <option value="daily">Daily</option>
               <option value="weekly">Weekly</option>
               <option value="cron">Advanced</option>
+              <option value="file_change">On file change</option>
+              <option value="git_commit">On new commits</option>
+              <option value="webhook">On webhook</option>
+              <option value="manual">Manual / follow-up only</option>
             </select>
           </div>
 
@@ -625,22 +1049,288 @@ export function SchedulerSection() {
             />
           )}
 
-          {/* Telegram notify */}
+          {editing.schedule.type === "file_change" && (
+            <div className="settings-toggle-row">
+              <div className="settings-toggle-info">
+                <span className="settings-toggle-label">Files</span>
+                <span className="settings-toggle-desc">Glob patterns in the project, comma-separated</span>
+              </div>
+              <input
+                type="text"
+                className="settings-input"
+                value={editing.schedule.patterns.join(",")}
+                onChange={(e) =>
+                  setEditing({
+                    ...editing,
+                    schedule: { type: "file_change", patterns: e.target.value.split(",") },
+                  })
+                }
+                placeholder="src/**/*.rs, docs/*.md"
+                spellCheck={false}
+              />
+            </div>
+          )}
+
+          {editing.schedule.type === "git_commit" && (
+            <div className="settings-toggle-row">
+              <div className="settings-toggle-info">
+                <span className="settings-toggle-label">Branch</span>
+                <span className="settings-toggle-desc">Checked every 30 seconds</span>
+              </div>
+              <input
+                type="text"
+                className="settings-input"
+                value={editing.schedule.branch}
+                onChange={(e) =>
+                  setEditing({ ...editing, schedule: { type: "git_commit", branch: e.target.value } })
+                }
+                placeholder="main"
+                spellCheck={false}
+              />
+            </div>
+          )}
+
+          {editing.schedule.type === "webhook" && (
+            <div className="settings-toggle-row">
+              <div className="settings-toggle-info">
+                <span className="settings-toggle-label">Webhook URL</span>
+                <span className="settings-toggle-desc">
+                  {!settings?.webhook_port
+                    ? "Set a webhook port below to enable"
+                    : editing.id
+                      ? "POST with header Authorization: Bearer <token>"
+                      : "Save the task to get its URL"}
+                </span>
+              </div>
+              {!!settings?.webhook_port && editing.id && (
+                <input
+                  type="text"
+                  className="settings-input"
+                  readOnly
+                  value={`http://127.0.0.1:${settings.webhook_port}/hooks/${editing.id}`}
+                  onFocus={(e) => e.target.select()}
+                />
+              )}
+            </div>
+          )}
+
+          {/* Role */}
+          <div className="settings-toggle-row">
+            <div className="settings-toggle-info">
+              <span className="settings-toggle-label">Role</span>
+              <span className="settings-toggle-desc">System prompt and tools for the agent</span>
+            </div>
+            <select
+              className="settings-select"
+              value={editing.role ?? ""}
+              onChange={(e) => setEditing({ ...editing, role: e.target.value || null })}
+            >
+              <option value="">None</option>
+              {roles.map((r) => (
+                <option key={r.name} value={r.name}>
+                  {r.name}
+                </option>
+              ))}
+            </select>
+          </div>
+
+          {/* Model + effort */}
+          <div className="settings-toggle-row">
+            <div className="settings-toggle-info">
+              <span className="settings-toggle-label">Model</span>
+            </div>
+            <div className="cron-field-group">
+              <select
+                className="settings-select"
+                value={editing.model ?? ""}
+                onChange={(e) => setEditing({ ...editing, model: e.target.value || null })}
+              >
+                {MODEL_OPTIONS.map((m) => (
+                  <option key={m.id} value={m.id}>
+                    {m.label}
+                  </option>
+                ))}
+              </select>
+              <select
+                className="settings-select"
+                value={editing.effort ?? ""}
+                onChange={(e) =>
+                  setEditing({ ...editing, effort: (e.target.value || null) as ScheduledTask["effort"] })
+                }
+              >
+                <option value="">Default effort</option>
+                <option value="low">Low</option>
+                <option value="medium">Medium</option>
+                <option value="high">High</option>
+              </select>
+            </div>
+          </div>
+
+          {/* Permission mode */}
+          <div className="settings-toggle-row">
+            <div className="settings-toggle-info">
+              <span className="settings-toggle-label">Permissions</span>
+            </div>
+            <select
+              className="settings-select"
+              value={editing.permission_mode ?? ""}
+              onChange={(e) =>
+                setEditing({
+                  ...editing,
+                  permission_mode: (e.target.value || null) as ScheduledTask["permission_mode"],
+                })
+              }
+            >
+              <option value="">Default</option>
+              <option value="plan">Plan</option>
+              <option value="acceptEdits">Accept edits</option>
+              <option value="bypassPermissions">Bypass permissions</option>
+            </select>
+          </div>
+
+          {/* Allowed tools */}
+          <div className="settings-toggle-row">
+            <div className="settings-toggle-info">
+              <span className="settings-toggle-label">Allowed tools</span>
+              <span className="settings-toggle-desc">Comma-separated; overrides the role's tools</span>
+            </div>
+            <input
+              type="text"
+              className="settings-input"
+              value={editing.allowed_tools.join(",")}
+              onChange={(e) =>
+                setEditing({ ...editing, allowed_tools: e.target.value ? e.target.value.split(",") : [] })
+              }
+              placeholder="Read,Grep,Bash(git:*)"
+              spellCheck={false}
+            />
+          </div>
+
+          {/* Timeout */}
+          <div className="settings-toggle-row">
+            <div className="settings-toggle-info">
+              <span className="settings-toggle-label">Timeout (minutes)</span>
+              <span className="settings-toggle-desc">Stop the agent if it runs longer</span>
+            </div>
+            <input
+              type="number"
+              className="settings-input scheduler-num-input"
+              min={1}
+              value={editing.timeout_minutes ?? ""}
+              onChange={(e) =>
+                setEditing({
+                  ...editing,
+                  timeout_minutes: e.target.value ? Math.max(1, Number(e.target.value)) : null,
+                })
+              }
+              placeholder="—"
+            />
+          </div>
+
+          {/* Retries */}
+          <div className="settings-toggle-row">
+            <div className="settings-toggle-info">
+              <span className="settings-toggle-label">Retries on failure</span>
+              <span className="settings-toggle-desc">First retry after the delay, then doubling</span>
+            </div>
+            <div className="cron-field-group">
+              <input
+                type="number"
+                className="settings-input scheduler-num-input"
+                min={0}
+                max={10}
+                value={editing.max_retries}
+                onChange={(e) =>
+                  setEditing({ ...editing, max_retries: Math.max(0, Math.min(10, Number(e.target.value))) })
+                }
+              />
+              <input
+                type="number"
+                className="settings-input scheduler-num-input"
+                min={1}
+                value={editing.retry_delay_secs}
+                onChange={(e) =>
+                  setEditing({ ...editing, retry_delay_secs: Math.max(1, Number(e.target.value)) })
+                }
+                title="Delay in seconds"
+              />
+            </div>
+          </div>
+
+          {/* Resume */}
           <div className="settings-toggle-row">
             <div className="settings-toggle-info">
-              <span className="settings-toggle-label">Notify via Telegram</span>
-              <span className="settings-toggle-desc">Send result to Telegram when done</span>
+              <span className="settings-toggle-label">Continue previous session</span>
+              <span className="settings-toggle-desc">Resume the last run's conversation instead of starting fresh</span>
             </div>
             <label className="toggle-switch">
               <input
                 type="checkbox"
-                checked={editing.notify_telegram}
-                onChange={(e) => setEditing({ ...editing, notify_telegram: e.target.checked })}
+                checked={editing.resume_previous}
+                onChange={(e) => setEditing({ ...editing, resume_previous: e.target.checked })}
               />
               <span className="toggle-switch-track" />
             </label>
           </div>
 
+          {/* Timezone */}
+          {editing.schedule.type !== "interval" && !isEventSchedule(editing.schedule) && (
+            <div className="settings-toggle-row">
+              <div className="settings-toggle-info">
+                <span className="settings-toggle-label">Timezone</span>
+                <span className="settings-toggle-desc">Leave empty for local time</span>
+              </div>
+              <input
+                type="text"
+                className="settings-input"
+                list="scheduler-timezones"
+                value={editing.timezone ?? ""}
+                onChange={(e) => setEditing({ ...editing, timezone: e.target.value || null })}
+                placeholder="Local"
+                spellCheck={false}
+              />
+              <datalist id="scheduler-timezones">
+                {TIMEZONES.map((tz) => (
+                  <option key={tz} value={tz} />
+                ))}
+              </datalist>
+            </div>
+          )}
+
+          <NextRuns schedule={editing.schedule} timezone={editing.timezone} />
+
+          {/* Catch-up policy */}
+          {!isEventSchedule(editing.schedule) && (
+            <div className="settings-toggle-row">
+              <div className="settings-toggle-info">
+                <span className="settings-toggle-label">Missed runs</span>
+                <span className="settings-toggle-desc">When the app was closed or the computer asleep</span>
+              </div>
+              <select
+                className="settings-select"
+                value={editing.catch_up}
+                onChange={(e) => setEditing({ ...editing, catch_up: e.target.value as CatchUpPolicy })}
+              >
+                <option value="skip">Skip</option>
+                <option value="once">Run once</option>
+                <option value="all">Run all</option>
+              </select>
+            </div>
+          )}
+
+          <NotificationsEditor
+            rules={editing.notifications}
+            onChange={(notifications) => setEditing({ ...editing, notifications })}
+          />
+
+          <FollowUpsEditor
+            followUps={editing.follow_ups}
+            tasks={tasks.filter((t) => t.id !== editing.id)}
+            onChange={(follow_ups) => setEditing({ ...editing, follow_ups })}
+          />
+
+          {editing.id && <RunHistory taskId={editing.id} />}
+
           {/* Save / Cancel */}
           <div className="scheduler-editor-footer">
             <button className="scheduler-btn scheduler-btn--secondary" onClick={() => setEditing(null)}>
@@ -665,6 +1355,77 @@ export function SchedulerSection() {
           Add task
         </button>
       )}
+
+      {/* Global settings */}
+      {!editing && settings && (
+        <div className="scheduler-global">
+          <div className="settings-toggle-row">
+            <div className="settings-toggle-info">
+              <span className="settings-toggle-label">Parallel runs</span>
+              <span className="settings-toggle-desc">More tasks wait in a queue (0 = unlimited)</span>
+            </div>
+            <input
+              type="number"
+              className="settings-input scheduler-num-input"
+              min={0}
+              value={settings.max_concurrent_runs}
+              onChange={(e) => updateSettings({ max_concurrent_runs: Math.max(0, Number(e.target.value)) })}
+            />
+          </div>
+          <div className="settings-toggle-row">
+            <div className="settings-toggle-info">
+              <span className="settings-toggle-label">Run history</span>
+              <span className="settings-toggle-desc">Runs kept per task / days (0 = no limit)</span>
+            </div>
+            <div className="cron-field-group">
+              <input
+                type="number"
+                className="settings-input scheduler-num-input"
+                min={0}
+                value={settings.history_max_runs}
+                onChange={(e) => updateSettings({ history_max_runs: Math.max(0, Number(e.target.value)) })}
+              />
+              <input
+                type="number"
+                className="settings-input scheduler-num-input"
+                min={0}
+                value={settings.history_max_days}
+                onChange={(e) => updateSettings({ history_max_days: Math.max(0, Number(e.target.value)) })}
+              />
+            </div>
+          </div>
+          <div className="settings-toggle-row">
+            <div className="settings-toggle-info">
+              <span className="settings-toggle-label">Webhook port</span>
+              <span className="settings-toggle-desc">Local server for webhook tasks (0 = off)</span>
+            </div>
+            <input
+              type="number"
+              className="settings-input scheduler-num-input"
+              min={0}
+              max={65535}
+              value={settings.webhook_port}
+              onChange={(e) =>
+                updateSettings({ webhook_port: Math.min(65535, Math.max(0, Number(e.target.value))) })
+              }
+            />
+          </div>
+          {settings.webhook_token && (
+            <div className="settings-toggle-row">
+              <div className="settings-toggle-info">
+                <span className="settings-toggle-label">Webhook token</span>
+              </div>
+              <input
+                type="text"
+                className="settings-input"
+                readOnly
+                value={settings.webhook_token}
+                onFocus={(e) => e.target.select()}
+              />
+            </div>
+          )}
+        </div>
+      )}
     </div>
   );
 }
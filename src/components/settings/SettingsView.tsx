@@ -18,6 +18,7 @@ import {
   BookOpen,
   Image,
   Clock,
+  Network,
 } from "lucide-react";
 import { useLayoutStore } from "../../stores/layoutStore";
 import { ProjectsSection } from "./ProjectsSection";
@@ -36,10 +37,12 @@ import { ExternalModelsSection } from "./ExternalModelsSection";
 import { KnowledgeSection } from "./KnowledgeSection";
 import { ImageGenSection } from "./ImageGenSection";
 import { SchedulerSection } from "./SchedulerSection";
+import { TeamChannelsSection } from "./TeamChannelsSection";
 
 const NAV_ITEMS = [
   { id: "general", label: "General", icon: User },
   { id: "roles", label: "Roles", icon: Users },
+  { id: "team-channels", label: "Team Channels", icon: Network },
   { id: "hotkeys", label: "Hotkeys", icon: Keyboard },
   { id: "hooks", label: "Hooks", icon: Webhook },
   { id: "mcp", label: "MCP Servers", icon: Cable },
@@ -120,6 +123,9 @@ function SectionContent({ section }: { section: string }) {
   if (section === "roles") {
     return <RolesSection />;
   }
+  if (section === "team-channels") {
+    return <TeamChannelsSection />;
+  }
   if (section === "skills") {
     return <SkillsSection />;
   }
@@ -0,0 +1,249 @@
+import { useCallback, useEffect, useState } from "react";
+import { Plus, Trash2, X } from "lucide-react";
+import { invoke } from "../../lib/transport";
+import { useProjectStore } from "../../stores/projectStore";
+import type { TeamChannel } from "../../types/team";
+import { Tooltip } from "../shared/Tooltip";
+
+export function TeamChannelsSection() {
+  const [channels, setChannels] = useState<TeamChannel[]>([]);
+  const [editing, setEditing] = useState<TeamChannel | null>(null);
+  const [isNew, setIsNew] = useState(false);
+  const [confirmDelete, setConfirmDelete] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const projects = useProjectStore((s) => s.projects);
+
+  const loadChannels = useCallback(() => {
+    invoke<TeamChannel[]>("team_channels_list")
+      .then((list) => {
+        setChannels(list);
+        setError(null);
+      })
+      .catch((e) => setError(String(e)));
+  }, []);
+
+  useEffect(() => {
+    loadChannels();
+  }, [loadChannels]);
+
+  const projectName = useCallback(
+    (path: string) => projects.find((p) => p.path === path)?.name ?? path,
+    [projects],
+  );
+
+  const handleSave = useCallback(async (channel: TeamChannel) => {
+    try {
+      await invoke("team_channels_save", { channel });
+      setEditing(null);
+      loadChannels();
+    } catch (e) {
+      setError(String(e));
+    }
+  }, [loadChannels]);
+
+  const handleDeleteConfirmed = useCallback(async (name: string) => {
+    try {
+      await invoke("team_channels_delete", { name });
+      setEditing(null);
+      setConfirmDelete(null);
+      loadChannels();
+    } catch (e) {
+      setError(String(e));
+    }
+  }, [loadChannels]);
+
+  return (
+    <div className="roles-section">
+      <div className="roles-header">
+        <p className="settings-toggle-desc">
+          Channels let agents of different project teams message each other. Only the projects
+          listed on a channel can send or receive on it.
+        </p>
+        <div className="roles-header__actions">
+          <Tooltip text="New channel">
+            <button
+              className="roles-add-btn"
+              onClick={() => {
+                setEditing({ name: "", projects: [], enabled: true });
+                setIsNew(true);
+                setError(null);
+              }}
+            >
+              <Plus size={14} />
+              <span>New Channel</span>
+            </button>
+          </Tooltip>
+        </div>
+      </div>
+
+      {error && !editing && <p className="settings-toggle-desc">{error}</p>}
+
+      <div className="roles-list">
+        {channels.map((channel) => (
+          <div
+            key={channel.name}
+            className="roles-card roles-card--clickable"
+            onClick={() => {
+              setEditing(channel);
+              setIsNew(false);
+              setError(null);
+            }}
+          >
+            <div className="roles-card__header">
+              <span className="roles-card__name">
+                {channel.name}
+                {!channel.enabled && <span className="roles-card__extends"> (disabled)</span>}
+              </span>
+            </div>
+            <div className="roles-card__tools">
+              {channel.projects.map((p) => (
+                <span key={p} className="roles-tool-badge">{projectName(p)}</span>
+              ))}
+            </div>
+          </div>
+        ))}
+      </div>
+
+      {editing && (
+        <ChannelEditor
+          key={editing.name}
+          channel={editing}
+          isNew={isNew}
+          error={error}
+          projects={projects.map((p) => ({ path: p.path, name: p.name }))}
+          onSave={(channel) => handleSave(channel).catch(console.error)}
+          onRequestDelete={setConfirmDelete}
+          onCancel={() => setEditing(null)}
+        />
+      )}
+
+      {confirmDelete && (
+        <div className="roles-editor-overlay" onClick={() => setConfirmDelete(null)}>
+          <div className="roles-confirm" onClick={(e) => e.stopPropagation()}>
+            <p className="roles-confirm__text">
+              {`Delete channel '${confirmDelete}'? Agents will no longer reach the other projects through it.`}
+            </p>
+            <div className="roles-confirm__actions">
+              <button className="team-btn" onClick={() => setConfirmDelete(null)}>
+                Cancel
+              </button>
+              <button
+                className="team-btn team-btn--danger"
+                onClick={() => handleDeleteConfirmed(confirmDelete).catch(console.error)}
+              >
+                Delete
+              </button>
+            </div>
+          </div>
+        </div>
+      )}
+    </div>
+  );
+}
+
+function ChannelEditor({
+  channel,
+  isNew,
+  error,
+  projects,
+  onSave,
+  onRequestDelete,
+  onCancel,
+}: {
+  channel: TeamChannel;
+  isNew: boolean;
+  error: string | null;
+  projects: { path: string; name: string }[];
+  onSave: (channel: TeamChannel) => void;
+  onRequestDelete: (name: string) => void;
+  onCancel: () => void;
+}) {
+  const [name, setName] = useState(channel.name);
+  const [members, setMembers] = useState<string[]>(channel.projects);
+  const [enabled, setEnabled] = useState(channel.enabled);
+
+  // Projects on the channel that are no longer bookmarked stay selectable
+  const choices = [
+    ...projects,
+    ...channel.projects
+      .filter((path) => !projects.some((p) => p.path === path))
+      .map((path) => ({ path, name: path })),
+  ];
+
+  const toggleProject = useCallback((path: string) => {
+    setMembers((prev) => (prev.includes(path) ? prev.filter((p) => p !== path) : [...prev, path]));
+  }, []);
+
+  const handleSave = useCallback(() => {
+    if (!name.trim()) return;
+    onSave({ name: name.trim(), projects: members, enabled });
+  }, [name, members, enabled, onSave]);
+
+  return (
+    <div className="roles-editor-overlay" onClick={onCancel}>
+      <div className="roles-editor" onClick={(e) => e.stopPropagation()}>
+        <div className="roles-editor__header">
+          <h3 className="roles-editor__title">{isNew ? "New Channel" : `Edit: ${channel.name}`}</h3>
+          <Tooltip text="Cancel">
+            <button className="settings-close" onClick={onCancel}>
+              <X size={16} />
+            </button>
+          </Tooltip>
+        </div>
+
+        <div className="roles-editor__body">
+          <div className="roles-field">
+            <label className="roles-field__label">Name</label>
+            <input
+              className="settings-input roles-input--full"
+              value={name}
+              onChange={(e) => setName(e.target.value)}
+              placeholder="Channel name"
+              disabled={!isNew}
+              autoFocus={isNew}
+            />
+          </div>
+
+          <div className="roles-field">
+            <label className="roles-field__label">Projects</label>
+            <div className="roles-tools-grid">
+              {choices.map((p) => (
+                <label key={p.path} className="roles-tool-check">
+                  <input
+                    type="checkbox"
+                    checked={members.includes(p.path)}
+                    onChange={() => toggleProject(p.path)}
+                  />
+                  <span>{p.name}</span>
+                </label>
+              ))}
+            </div>
+            <span className="roles-field__hint">A channel links at least two projects.</span>
+          </div>
+
+          <div className="roles-field">
+            <label className="roles-tool-check">
+              <input type="checkbox" checked={enabled} onChange={(e) => setEnabled(e.target.checked)} />
+              <span>Enabled</span>
+            </label>
+          </div>
+          {error && <span className="roles-field__hint">{error}</span>}
+        </div>
+
+        <div className="roles-editor__actions">
+          <button className="team-btn team-btn--primary" onClick={handleSave}>
+            Save
+          </button>
+          <button className="team-btn" onClick={onCancel}>
+            Cancel
+          </button>
+          {!isNew && (
+            <button className="team-btn team-btn--danger" onClick={() => onRequestDelete(channel.name)}>
+              <Trash2 size={13} /> Delete
+            </button>
+          )}
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -1,6 +1,6 @@
 import { memo, useCallback, useEffect, useMemo, useRef, useState } from "react";
 import { useShallow } from "zustand/react/shallow";
-import { Mail, MessageSquare, X, ArrowDown, User, Trash2 } from "lucide-react";
+import { Mail, MessageSquare, X, ArrowDown, User, Trash2, HelpCircle, History } from "lucide-react";
 import { invoke } from "../../lib/transport";
 import { useLayoutStore } from "../../stores/layoutStore";
 import { useChatStore } from "../../stores/chatStore";
@@ -8,9 +8,9 @@ import { useConductorStore } from "../../stores/conductorStore";
 import { useAgentStore } from "../../stores/agentStore";
 import { Tooltip } from "../shared/Tooltip";
 import { ChatPanel } from "../layout/chat-panel";
-import type { TeamMessage } from "../../types/team";
+import type { ApprovalDecision, ApprovalRequest, TeamMessage } from "../../types/team";
 
-type PanelTab = "mailbox" | "chats";
+type PanelTab = "mailbox" | "approvals" | "chats";
 
 /* ── Types ── */
 
@@ -35,6 +35,8 @@ export const TeamMailboxPanel = memo(function TeamMailboxPanel() {
   const projectPath = useChatStore((s) => s.projectPath);
 
   const [messages, setMessages] = useState<TeamMessage[]>([]);
+  const [approvals, setApprovals] = useState<ApprovalRequest[]>([]);
+  const [decisions, setDecisions] = useState<ApprovalDecision[]>([]);
   const [teamSlug, setTeamSlug] = useState<string | null>(null);
   const agentRoles = useConductorStore(useShallow((s) => s.agentRoles));
   const agents = useAgentStore(useShallow((s) => s.agents));
@@ -58,6 +60,9 @@ export const TeamMailboxPanel = memo(function TeamMailboxPanel() {
       invoke<TeamMessage[]>("team_read_all_messages", { team: teamSlug })
         .then(setMessages)
         .catch(console.error);
+      invoke<ApprovalRequest[]>("team_pending_approvals", { team: teamSlug })
+        .then(setApprovals)
+        .catch(console.error);
     };
 
     fetchMessages();
@@ -65,6 +70,21 @@ export const TeamMailboxPanel = memo(function TeamMailboxPanel() {
     return () => clearInterval(interval);
   }, [teamSlug]);
 
+  // Poll the approval decision log while its tab is open
+  useEffect(() => {
+    if (!teamSlug || activeTab !== "approvals") return;
+
+    const fetchDecisions = () => {
+      invoke<ApprovalDecision[]>("team_approval_log", { team: teamSlug })
+        .then(setDecisions)
+        .catch(console.error);
+    };
+
+    fetchDecisions();
+    const interval = setInterval(fetchDecisions, 4000);
+    return () => clearInterval(interval);
+  }, [teamSlug, activeTab]);
+
   // Build agent name map from conductor role assignments
   const agentNameMap = useMemo(() => {
     const map = new Map<string, { name: string; canManage: boolean }>();
@@ -82,9 +102,10 @@ export const TeamMailboxPanel = memo(function TeamMailboxPanel() {
     const items: FeedMessage[] = messages.map((msg) => {
       const isUser = msg.from === "user";
       const info = agentNameMap.get(msg.from);
+      const name = isUser ? "You" : (info?.name ?? "Agent");
       return {
         id: msg.id,
-        sender: isUser ? "You" : (info?.name ?? "Agent"),
+        sender: msg.channel ? `${name} #${msg.channel}` : name,
         fromId: msg.from,
         text: msg.text,
         timestamp: new Date(msg.timestamp).getTime(),
@@ -144,6 +165,12 @@ export const TeamMailboxPanel = memo(function TeamMailboxPanel() {
     el.scrollTo({ top: el.scrollHeight, behavior: "smooth" });
   }, []);
 
+  const handleAnswerApproval = useCallback((id: string, answer: string) => {
+    invoke("team_answer_approval", { id, answer })
+      .catch(console.error)
+      .finally(() => setApprovals((prev) => prev.filter((a) => a.id !== id)));
+  }, []);
+
   const handleClearMessages = useCallback(() => {
     if (!teamSlug) return;
     invoke("team_clear_messages", { team: teamSlug })
@@ -211,6 +238,13 @@ export const TeamMailboxPanel = memo(function TeamMailboxPanel() {
             <Mail size={14} />
             <span>Mailbox</span>
           </button>
+          <button
+            className={`tm-tab ${activeTab === "approvals" ? "tm-tab--active" : ""}`}
+            onClick={() => setActiveTab("approvals")}
+          >
+            <History size={14} />
+            <span>Approvals</span>
+          </button>
           <button
             className={`tm-tab ${activeTab === "chats" ? "tm-tab--active" : ""}`}
             onClick={() => setActiveTab("chats")}
@@ -233,6 +267,19 @@ export const TeamMailboxPanel = memo(function TeamMailboxPanel() {
         </Tooltip>
       </div>
 
+      {activeTab === "mailbox" && approvals.length > 0 && (
+        <div className="team-mailbox__approvals">
+          {approvals.map((req) => (
+            <ApprovalCard
+              key={req.id}
+              req={req}
+              sender={agentNameMap.get(req.agent_id)?.name ?? "Agent"}
+              onAnswer={handleAnswerApproval}
+            />
+          ))}
+        </div>
+      )}
+
       {activeTab === "mailbox" ? (
         <div className="team-mailbox__feed" ref={scrollRef} onScroll={handleScroll}>
           {feed.length === 0 ? (
@@ -255,6 +302,22 @@ export const TeamMailboxPanel = memo(function TeamMailboxPanel() {
             </Tooltip>
           )}
         </div>
+      ) : activeTab === "approvals" ? (
+        <div className="team-mailbox__feed">
+          {decisions.length === 0 ? (
+            <div className="team-mailbox__empty">
+              No approval decisions yet
+            </div>
+          ) : (
+            decisions.map((decision) => (
+              <DecisionItem
+                key={decision.id}
+                decision={decision}
+                sender={agentNameMap.get(decision.agent_id)?.name ?? "Agent"}
+              />
+            ))
+          )}
+        </div>
       ) : (
         <ChatPanel />
       )}
@@ -262,6 +325,69 @@ export const TeamMailboxPanel = memo(function TeamMailboxPanel() {
   );
 });
 
+/* ── Pending approval request ── */
+
+function ApprovalCard({
+  req,
+  sender,
+  onAnswer,
+}: {
+  req: ApprovalRequest;
+  sender: string;
+  onAnswer: (id: string, answer: string) => void;
+}) {
+  return (
+    <div className="team-mailbox__approval">
+      <div className="team-mailbox__msg-header">
+        <HelpCircle size={12} className="team-mailbox__msg-icon" />
+        <span className="team-mailbox__msg-sender">{sender} asks</span>
+        <span className="team-mailbox__msg-time">
+          until {new Date(req.expires_at).toLocaleTimeString()}
+        </span>
+      </div>
+      <div className="team-mailbox__msg-text">{req.question}</div>
+      <div className="team-mailbox__approval-options">
+        {req.options.map((opt) => (
+          <button
+            key={opt}
+            className="team-mailbox__approval-btn"
+            onClick={() => onAnswer(req.id, opt)}
+          >
+            {opt}
+          </button>
+        ))}
+      </div>
+    </div>
+  );
+}
+
+/* ── Logged approval decision ── */
+
+function DecisionItem({
+  decision,
+  sender,
+}: {
+  decision: ApprovalDecision;
+  sender: string;
+}) {
+  const outcome = decision.answer
+    ? `${decision.answer} (via ${decision.source})`
+    : "No answer, timed out";
+  return (
+    <div className="team-mailbox__decision">
+      <div className="team-mailbox__msg-header">
+        <HelpCircle size={12} className="team-mailbox__msg-icon" />
+        <span className="team-mailbox__msg-sender">{sender} asked</span>
+        <span className="team-mailbox__msg-time">
+          {new Date(decision.decided_at).toLocaleString()}
+        </span>
+      </div>
+      <div className="team-mailbox__msg-text">{decision.question}</div>
+      <div className="team-mailbox__decision-answer">{outcome}</div>
+    </div>
+  );
+}
+
 /* ── Single message bubble ── */
 
 function getRoleIconClass(sender: string, fromId: string): string {
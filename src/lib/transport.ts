@@ -13,12 +13,19 @@ type DialogOptions = {
   title?: string;
 };
 
+type SaveDialogOptions = {
+  defaultPath?: string;
+  filters?: Array<{ name: string; extensions: string[] }>;
+  title?: string;
+};
+
 // ── Lazy imports for Tauri APIs ──────────────────────────────────────
 
 let _tauriInvoke: ((cmd: string, args?: Record<string, unknown>) => Promise<unknown>) | null = null;
 let _tauriListen: ((event: string, handler: (e: { payload: unknown }) => void) => Promise<() => void>) | null = null;
 let _tauriConvertFileSrc: ((path: string) => string) | null = null;
 let _tauriOpenDialog: ((options: DialogOptions) => Promise<string | string[] | null>) | null = null;
+let _tauriSaveDialog: ((options: SaveDialogOptions) => Promise<string | null>) | null = null;
 let _tauriOpenUrl: ((url: string) => Promise<void>) | null = null;
 let _tauriGetCurrentWindow: (() => Window) | null = null;
 
@@ -34,6 +41,7 @@ const tauriReady: Promise<void> = (async () => {
   _tauriConvertFileSrc = core.convertFileSrc;
   _tauriListen = event.listen;
   _tauriOpenDialog = dialog.open;
+  _tauriSaveDialog = dialog.save;
   _tauriOpenUrl = opener.openUrl;
   _tauriGetCurrentWindow = win.getCurrentWindow;
 })();
@@ -70,6 +78,12 @@ export async function openDialog(options?: DialogOptions): Promise<string | stri
   return _tauriOpenDialog!(options ?? {});
 }
 
+/** Open a native save-file dialog. Returns the chosen path, or null if cancelled. */
+export async function saveDialog(options?: SaveDialogOptions): Promise<string | null> {
+  await tauriReady;
+  return _tauriSaveDialog!(options ?? {});
+}
+
 /** Open a URL in the default browser. Only http/https allowed. */
 export async function openUrl(url: string): Promise<void> {
   if (!/^https?:\/\//i.test(url)) {
@@ -348,7 +348,11 @@ export async function launchTeam(
       setAgentRole(agentIds[i], match);
 
       // Add start_message as user message in agent chat history
-      const startMsg = match.start_message;
+      // Show it as sent: template variables are rendered by the backend
+      const startMsg = match.start_message
+        ? await invoke<string>("prompt_template_preview", { template: match.start_message, projectPath })
+            .catch(() => match.start_message)
+        : undefined;
       if (startMsg) {
         const userMsg = { id: crypto.randomUUID(), role: "user" as const, text: startMsg, timestamp: Date.now() };
         const existing = agentStates.get(agentIds[i]);
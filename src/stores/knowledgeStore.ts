@@ -1,11 +1,11 @@
 import { create } from "zustand";
 import { invoke, listen } from "../lib/transport";
-import type { KnowledgeBase, KnowledgeDocument, RagSettings, SearchResult } from "../types/knowledge";
+import type { BaseKind, DocumentLabels, KnowledgeBase, KnowledgeDocument, RagSettings, SearchMode, SearchResult } from "../types/knowledge";
 
 // --- Progress types ---
 
 export interface BgOperation {
-  type: "add" | "playlist" | "reindex" | "url" | "youtube";
+  type: "add" | "playlist" | "reindex" | "url" | "youtube" | "migrate" | "export" | "import";
   processed: number;
   total: number;
   label: string;
@@ -13,7 +13,7 @@ export interface BgOperation {
 }
 
 export interface BgResult {
-  type: "add" | "playlist" | "reindex" | "url" | "youtube";
+  type: "add" | "playlist" | "reindex" | "url" | "youtube" | "migrate" | "export" | "import";
   message: string;
   baseId: string;
 }
@@ -44,6 +44,21 @@ export interface PlaylistSummary {
   isPlaylist: boolean;
 }
 
+export interface MigrateSummary {
+  documents: number;
+  chunks: number;
+  model: string;
+}
+
+export interface ArchiveSummary {
+  baseId: string;
+  name: string;
+  documents: number;
+  chunks: number;
+  model: string;
+  reembedded: boolean;
+}
+
 export interface ReindexSummary {
   reindexed: number;
   skipped: number;
@@ -70,7 +85,13 @@ interface KnowledgeState {
   _bgResultTimer: ReturnType<typeof setTimeout> | null;
 
   loadBases: () => Promise<void>;
-  createBase: (name: string, description: string) => Promise<void>;
+  createBase: (
+    name: string,
+    description: string,
+    embeddingModel?: string,
+    kind?: BaseKind,
+    root?: string,
+  ) => Promise<void>;
   deleteBase: (baseId: string) => Promise<void>;
   selectBase: (baseId: string | null) => void;
   loadDocuments: (baseId: string) => Promise<void>;
@@ -81,13 +102,21 @@ interface KnowledgeState {
   /** Fire-and-forget. */
   addYoutube: (baseId: string, url: string) => void;
   removeDocument: (baseId: string, documentId: string) => Promise<void>;
+  setDocumentLabels: (baseId: string, documentId: string, labels: DocumentLabels) => Promise<void>;
   /** Fire-and-forget. */
   reindexBase: (baseId: string) => void;
-  search: (baseId: string, query: string) => Promise<void>;
+  /** Fire-and-forget: re-embed the base with another model. */
+  migrateBase: (baseId: string, model: string) => void;
+  /** `filter` restricts results by tags/metadata, e.g. `tag:rust lang=en`. */
+  /** Fire-and-forget: write the base to an archive at `path`. */
+  exportBase: (baseId: string, path: string) => void;
+  /** Fire-and-forget: restore an archive as a new base, re-embedding with `model` if given. */
+  importBase: (path: string, model?: string) => void;
+  search: (baseId: string, query: string, mode?: SearchMode, filter?: string) => Promise<void>;
   clearError: () => void;
   clearBgResult: () => void;
   loadRagSettings: () => Promise<void>;
-  saveRagSettings: (settings: RagSettings) => Promise<boolean>;
+  saveRagSettings: (settings: RagSettings) => Promise<void>;
 }
 
 function errorMessage(e: unknown): string {
@@ -156,9 +185,9 @@ export const useKnowledgeStore = create<KnowledgeState>((set, get) => ({
     }
   },
 
-  createBase: async (name, description) => {
+  createBase: async (name, description, embeddingModel, kind, root) => {
     try {
-      await invoke("rag_create_base", { name, description });
+      await invoke("rag_create_base", { name, description, embeddingModel, kind, root });
       await get().loadBases();
     } catch (e) {
       console.error("Failed to create knowledge base:", e);
@@ -308,6 +337,16 @@ export const useKnowledgeStore = create<KnowledgeState>((set, get) => ({
     }
   },
 
+  setDocumentLabels: async (baseId, documentId, labels) => {
+    try {
+      await invoke("rag_set_document_labels", { baseId, documentId, labels });
+      await get().loadDocuments(baseId);
+    } catch (e) {
+      console.error("Failed to update document labels:", e);
+      setErrorWithAutoClear(set, get, `Failed to update document labels: ${errorMessage(e)}`);
+    }
+  },
+
   reindexBase: (baseId) => {
     set({
       bgOperation: { type: "reindex", processed: 0, total: 0, label: "Starting reindex…", baseId },
@@ -345,10 +384,107 @@ export const useKnowledgeStore = create<KnowledgeState>((set, get) => ({
     })();
   },
 
-  search: async (baseId, query) => {
+  migrateBase: (baseId, model) => {
+    set({
+      bgOperation: { type: "migrate", processed: 0, total: 0, label: `Re-embedding with ${model}…`, baseId },
+    });
+
+    (async () => {
+      let unlisten: (() => void) | null = null;
+      try {
+        unlisten = await listen<ReindexProgressEvent>("rag-migrate-progress", (event) => {
+          const p = event.payload;
+          set({
+            bgOperation: {
+              type: "migrate",
+              processed: p.processed,
+              total: p.total,
+              label: p.currentFilename || `Re-embedding with ${model}…`,
+              baseId,
+            },
+          });
+        });
+
+        const summary = await invoke<MigrateSummary>("rag_migrate_base_model", { baseId, model });
+        await get().loadBases();
+
+        const msg = `Re-embedded ${summary.chunks} chunks from ${summary.documents} documents with ${summary.model}`;
+        setBgResult(set, get, "migrate", msg, baseId);
+      } catch (e) {
+        console.error("Failed to change embedding model:", e);
+        set({ bgOperation: null });
+        setErrorWithAutoClear(set, get, `Failed to change embedding model: ${errorMessage(e)}`);
+      } finally {
+        if (unlisten) unlisten();
+      }
+    })();
+  },
+
+  exportBase: (baseId, path) => {
+    set({
+      bgOperation: { type: "export", processed: 0, total: 0, label: "Exporting…", baseId },
+    });
+
+    (async () => {
+      try {
+        const summary = await invoke<ArchiveSummary>("rag_export_base", { baseId, path });
+        setBgResult(set, get, "export", `Exported ${summary.chunks} chunks to ${path}`, baseId);
+      } catch (e) {
+        console.error("Failed to export knowledge base:", e);
+        set({ bgOperation: null });
+        setErrorWithAutoClear(set, get, `Failed to export knowledge base: ${errorMessage(e)}`);
+      }
+    })();
+  },
+
+  importBase: (path, model) => {
+    // The base gets its id when the import finishes
+    set({
+      bgOperation: { type: "import", processed: 0, total: 0, label: "Importing…", baseId: "" },
+    });
+
+    (async () => {
+      let unlisten: (() => void) | null = null;
+      try {
+        unlisten = await listen<ReindexProgressEvent>("rag-import-progress", (event) => {
+          const p = event.payload;
+          set({
+            bgOperation: {
+              type: "import",
+              processed: p.processed,
+              total: p.total,
+              label: p.currentFilename || "Importing…",
+              baseId: "",
+            },
+          });
+        });
+
+        const summary = await invoke<ArchiveSummary>("rag_import_base", { path, model });
+        await get().loadBases();
+        get().selectBase(summary.baseId);
+
+        const how = summary.reembedded ? `re-embedded with ${summary.model}` : summary.model;
+        const msg = `Imported ${summary.documents} documents, ${summary.chunks} chunks (${how})`;
+        setBgResult(set, get, "import", msg, summary.baseId);
+      } catch (e) {
+        console.error("Failed to import knowledge base:", e);
+        set({ bgOperation: null });
+        setErrorWithAutoClear(set, get, `Failed to import knowledge base: ${errorMessage(e)}`);
+      } finally {
+        if (unlisten) unlisten();
+      }
+    })();
+  },
+
+  search: async (baseId, query, mode, filter) => {
     set({ searchQuery: query, isSearching: true });
     try {
-      const searchResults = await invoke<SearchResult[]>("rag_search", { baseId, query });
+      const searchResults = await invoke<SearchResult[]>("rag_search", {
+        baseId,
+        query,
+        mode,
+        filter: filter || null,
+      });
       set({ searchResults, isSearching: false });
     } catch (e) {
       console.error("Failed to search:", e);
@@ -369,13 +505,11 @@ export const useKnowledgeStore = create<KnowledgeState>((set, get) => ({
 
   saveRagSettings: async (settings) => {
     try {
-      const modelChanged = await invoke<boolean>("rag_save_settings", { settings });
+      await invoke("rag_save_settings", { settings });
       set({ ragSettings: settings });
-      return modelChanged;
     } catch (e) {
       console.error("Failed to save RAG settings:", e);
       setErrorWithAutoClear(set, get, `Failed to save settings: ${errorMessage(e)}`);
-      return false;
     }
   },
 }));
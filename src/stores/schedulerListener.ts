@@ -1,11 +1,13 @@
 /**
- * Side-effect module: registers a listener for scheduler:create-agent events.
- * Import this file to activate the listener (same pattern as chatStreamHandler).
+ * Side-effect module: registers listeners for scheduler:create-agent and
+ * scheduler:notification events.
+ * Import this file to activate the listeners (same pattern as chatStreamHandler).
  */
 import { listen } from "../lib/transport";
 import { useAgentStore } from "./agentStore";
 import { agentStates } from "./chatStore";
 import type { AgentEntry } from "../types/agents";
+import type { SchedulerNotification } from "../types/scheduler";
 
 interface SchedulerAgentEvent {
   agentId: string;
@@ -48,6 +50,17 @@ if (!(globalThis as Record<string, unknown>).__schedulerListenerRegistered) {
       error: null,
     });
   }).catch(console.error);
+
+  // Desktop notifications of finished scheduled runs
+  listen<SchedulerNotification>("scheduler:notification", async (event) => {
+    if (typeof Notification === "undefined") return;
+    if (Notification.permission === "default") {
+      await Notification.requestPermission();
+    }
+    if (Notification.permission !== "granted") return;
+    const { title, body } = event.payload;
+    new Notification(title, { body: body || undefined });
+  }).catch(console.error);
 }
 
 export {};
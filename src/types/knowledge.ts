@@ -1,8 +1,14 @@
+/** "codebase" bases index a project's source tree. */
+export type BaseKind = "documents" | "codebase";
+
 export interface KnowledgeBase {
   id: string;
   name: string;
   description: string;
   createdAt: number;
+  kind: BaseKind;
+  /** Model the base's chunks are embedded with. */
+  embeddingModel: string;
   documentCount: number;
 }
 
@@ -13,6 +19,62 @@ export interface KnowledgeDocument {
   sizeBytes: number;
   chunkCount: number;
   addedAt: number;
+  /** Folder source that added the document, if any. */
+  sourceId?: string;
+  tags?: string[];
+  metadata?: Record<string, string>;
+}
+
+/** User-defined tags and key/value metadata of a document. */
+export interface DocumentLabels {
+  tags: string[];
+  metadata: Record<string, string>;
+}
+
+/** One side of a pair of duplicate chunks. */
+export interface DuplicateChunk {
+  documentId: string;
+  documentName: string;
+  chunkIndex: number;
+  /** Start of the chunk text. */
+  text: string;
+}
+
+export interface DuplicatePair {
+  /** Cosine similarity; 1 for identical text. */
+  score: number;
+  exact: boolean;
+  first: DuplicateChunk;
+  second: DuplicateChunk;
+}
+
+export interface SourceSyncResult {
+  finishedAt: number;
+  added: number;
+  updated: number;
+  removed: number;
+  failed: number;
+  errors: string[];
+}
+
+/** Folder whose files are kept in sync with a knowledge base. */
+export interface FolderSource {
+  id: string;
+  path: string;
+  include: string[];
+  exclude: string[];
+  lastSync?: SourceSyncResult | null;
+}
+
+export interface BaseSyncStatus {
+  syncing: boolean;
+  sources: FolderSource[];
+}
+
+export interface EmbeddingModelInfo {
+  id: string;
+  label: string;
+  dimension: number;
 }
 
 export interface RagSettings {
@@ -21,12 +83,52 @@ export interface RagSettings {
   chunkOverlap: number;
   searchResultsLimit: number;
   knowledgeMcpEnabled: boolean;
+  rerankerEnabled: boolean;
+  rerankerModel: string;
+  rerankCandidates: number;
+}
+
+/** vector: meaning only; keyword: exact terms (BM25); hybrid: both, rank-fused */
+export type SearchMode = "hybrid" | "vector" | "keyword";
+
+/** 1-based, inclusive. */
+export interface LineRange {
+  start: number;
+  end: number;
+}
+
+/** Byte range in the document's parsed text, end exclusive. */
+export interface ByteRange {
+  start: number;
+  end: number;
+}
+
+/** Where a chunk starts in its source; which fields are set depends on the format. */
+export interface ChunkLocation {
+  page: number | null;
+  chapter: string | null;
+  /** Markdown heading path, e.g. "Setup › Linux". */
+  heading: string | null;
+  /** Seconds into a video. */
+  timestamp: number | null;
 }
 
 export interface SearchResult {
   chunkText: string;
+  baseId: string;
   documentId: string;
   documentName: string;
   chunkIndex: number;
+  /** Set for source code chunks. */
+  lines?: LineRange | null;
+  bytes?: ByteRange | null;
+  location?: ChunkLocation;
+  /** `path:line` of a code chunk, otherwise e.g. "p. 42, § Setup" or "12:34". */
+  citation?: string | null;
+  /** Video URL at the chunk's timestamp. */
+  link?: string | null;
+  /** Ranking score; rank-based in keyword and hybrid mode, so not a percentage. */
   score: number;
+  /** Cosine similarity to the query; null for keyword-only hits. */
+  similarity?: number | null;
 }
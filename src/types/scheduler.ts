@@ -5,14 +5,107 @@ export interface ScheduledTask {
   project_path: string;
   schedule: TaskSchedule;
   enabled: boolean;
+  /** Legacy success message to Telegram; moved into notifications on save */
   notify_telegram: boolean;
   created_at: string;
   last_run: string | null;
-  last_status: "success" | "error" | "running" | null;
+  last_status: TaskRunStatus | null;
+  /** IANA timezone name; null = local time */
+  timezone: string | null;
+  catch_up: CatchUpPolicy;
+  last_scheduled: string | null;
+  model: string | null;
+  effort: "low" | "medium" | "high" | null;
+  permission_mode: "default" | "acceptEdits" | "plan" | "bypassPermissions" | null;
+  /** Agent role name (system prompt + tools) */
+  role: string | null;
+  /** Replaces the role's allowed tools when non-empty */
+  allowed_tools: string[];
+  resume_previous: boolean;
+  timeout_minutes: number | null;
+  last_session_id: string | null;
+  /** Extra attempts after a failed run */
+  max_retries: number;
+  /** Wait before the first retry; doubles per attempt */
+  retry_delay_secs: number;
+  /** Commit a git_commit trigger saw last */
+  last_seen_commit: string | null;
+  /** Tasks started when a run ends */
+  follow_ups: FollowUp[];
+  /** Where to report finished runs */
+  notifications: NotifyRule[];
 }
 
+export interface FollowUp {
+  task_id: string;
+  /** failure = error or timeout */
+  when: "success" | "failure" | "always";
+  /** Case-sensitive checks on the run's final message; empty = any */
+  condition: { contains: string | null; not_contains: string | null };
+}
+
+export interface NotifyRule {
+  /** failure = error or timeout */
+  when: "success" | "failure" | "timeout" | "always";
+  sink: { type: "telegram" } | { type: "desktop" } | { type: "webhook"; url: string };
+  /** Message template; empty = summary, error and final message */
+  template: string;
+}
+
+/** Payload of the scheduler:notification event */
+export interface SchedulerNotification {
+  title: string;
+  body: string;
+}
+
+export type TaskRunStatus = "success" | "error" | "running" | "timeout" | "queued";
+
+export type CatchUpPolicy = "skip" | "once" | "all";
+
 export type TaskSchedule =
   | { type: "interval"; minutes: number }
   | { type: "daily"; hour: number; minute: number }
   | { type: "weekly"; day: number; hour: number; minute: number }
-  | { type: "cron"; expression: string };
+  | { type: "cron"; expression: string }
+  /** Glob patterns relative to the project */
+  | { type: "file_change"; patterns: string[] }
+  | { type: "git_commit"; branch: string }
+  | { type: "webhook" }
+  /** Run Now or follow-ups only */
+  | { type: "manual" };
+
+export interface TaskRun {
+  id: string;
+  task_id: string;
+  task_name: string;
+  started_at: string;
+  finished_at: string;
+  duration_ms: number;
+  status: TaskRunStatus;
+  exit_code: number | null;
+  final_message: string | null;
+  cost_usd: number | null;
+  error: string | null;
+  session_id: string | null;
+  /** 0 for the first try, then the retry number */
+  attempt: number;
+  /** What fired the run, for event-triggered tasks */
+  trigger: string | null;
+}
+
+/** One fire time from scheduler_preview */
+export interface UpcomingRun {
+  task_id: string;
+  task_name: string;
+  fire_time: string;
+}
+
+export interface SchedulerSettings {
+  history_max_runs: number;
+  history_max_days: number;
+  /** 0 = unlimited */
+  max_concurrent_runs: number;
+  /** Local webhook server port; 0 = off. Applies on restart */
+  webhook_port: number;
+  webhook_token: string;
+}
@@ -4,10 +4,33 @@ export interface AgentRole {
   allowed_tools: string[];
   can_manage: boolean;
   start_message?: string;
+  /** Parent role: its system prompt and tools are inherited, this role's are appended */
+  extends?: string;
 }
 
 export interface RoleEntry extends AgentRole {
   is_default: boolean;
+  /** Stored (editable) form of a role that extends another; top-level fields are resolved */
+  definition?: AgentRole;
+}
+
+export interface RoleVersion {
+  version: number;
+  saved_at: string;
+  role: AgentRole;
+}
+
+export interface RoleImportReport {
+  imported: string[];
+  skipped: string[];
+}
+
+/** Named link that lets agents of the listed projects message each other */
+export interface TeamChannel {
+  name: string;
+  /** Project paths allowed to send and receive on the channel */
+  projects: string[];
+  enabled: boolean;
 }
 
 export interface TeamMessage {
@@ -18,5 +41,34 @@ export interface TeamMessage {
   timestamp: string;
   read: boolean;
   broadcast_id?: string;
+  /** Cross-project channel the message came through */
+  channel?: string;
+  /** Sender's project path (channel messages only) */
+  from_project?: string;
 }
 
+
+/** A question an agent is waiting on (request_approval MCP tool) */
+export interface ApprovalRequest {
+  id: string;
+  team: string;
+  agent_id: string;
+  question: string;
+  options: string[];
+  created_at: string;
+  expires_at: string;
+}
+
+/** A logged answer to an approval request (team_approval_log) */
+export interface ApprovalDecision {
+  id: string;
+  agent_id: string;
+  question: string;
+  options: string[];
+  /** Chosen option; missing if the request timed out */
+  answer?: string | null;
+  /** "ui", "telegram" or "timeout" */
+  source: string;
+  requested_at: string;
+  decided_at: string;
+}